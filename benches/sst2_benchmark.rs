@@ -3,7 +3,9 @@ extern crate criterion;
 
 use criterion::Criterion;
 use rust_bert::pipelines::sentiment::SentimentModel;
-use rust_bert::pipelines::sequence_classification::SequenceClassificationConfig;
+use rust_bert::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
 use serde::Deserialize;
 use std::error::Error;
 use std::path::PathBuf;
@@ -21,6 +23,17 @@ fn create_sentiment_model() -> SentimentModel {
     SentimentModel::new(config).unwrap()
 }
 
+fn create_half_precision_model(pad_to_multiple_of: Option<usize>) -> SequenceClassificationModel {
+    let config = SequenceClassificationConfig {
+        device: Device::Cuda(0),
+        pad_to_multiple_of,
+        ..Default::default()
+    };
+    let mut model = SequenceClassificationModel::new(config).unwrap();
+    model.half().unwrap();
+    model
+}
+
 fn sst2_forward_pass(iters: u64, model: &SentimentModel, sst2_data: &[String]) -> Duration {
     let mut duration = Duration::new(0, 0);
     let batch_size = BATCH_SIZE;
@@ -43,6 +56,31 @@ fn sst2_forward_pass(iters: u64, model: &SentimentModel, sst2_data: &[String]) -
     duration
 }
 
+fn sst2_classification_forward_pass(
+    iters: u64,
+    model: &SequenceClassificationModel,
+    sst2_data: &[String],
+) -> Duration {
+    let mut duration = Duration::new(0, 0);
+    let mut output = vec![];
+    for _i in 0..iters {
+        let start = Instant::now();
+        for batch in sst2_data.chunks(BATCH_SIZE) {
+            output.push(
+                model.predict(
+                    batch
+                        .iter()
+                        .map(|v| v.as_str())
+                        .collect::<Vec<&str>>()
+                        .as_slice(),
+                ),
+            );
+        }
+        duration = duration.checked_add(start.elapsed()).unwrap();
+    }
+    duration
+}
+
 #[derive(Debug, Deserialize)]
 struct Record {
     sentence: String,
@@ -95,6 +133,18 @@ fn bench_sst2(c: &mut Criterion) {
     });
 
     c.bench_function("Load model", |b| b.iter_custom(sst2_load_model));
+
+    //    Half precision, with and without padding to a multiple of 8 (tensor cores)
+    if tch::Cuda::is_available() {
+        let model = create_half_precision_model(None);
+        c.bench_function("SST2 forward pass (fp16)", |b| {
+            b.iter_custom(|iters| sst2_classification_forward_pass(iters, &model, &inputs))
+        });
+        let model = create_half_precision_model(Some(8));
+        c.bench_function("SST2 forward pass (fp16, padded to a multiple of 8)", |b| {
+            b.iter_custom(|iters| sst2_classification_forward_pass(iters, &model, &inputs))
+        });
+    }
 }
 
 criterion_group! {
@@ -2,6 +2,8 @@
 extern crate criterion;
 
 use criterion::{black_box, Criterion};
+use rust_bert::pipelines::sentence_embeddings::layers::{l2_normalize, Pooling, PoolingConfig};
+use std::convert::TryFrom;
 use std::time::{Duration, Instant};
 use tch::kind::Kind;
 use tch::{Device, Tensor};
@@ -16,6 +18,103 @@ fn matrix_multiply(iters: u64, input: &Tensor, weights: &Tensor) -> Duration {
     duration
 }
 
+fn top_label_selection_on_cpu(iters: u64, logits: &Tensor) -> Duration {
+    let mut duration = Duration::new(0, 0);
+    for _i in 0..iters {
+        let start = Instant::now();
+        let output = logits.softmax(-1, Kind::Float).to(Device::Cpu);
+        let label_indices = output.argmax(-1, true).squeeze_dim(1);
+        let scores = output
+            .gather(1, &label_indices.unsqueeze(-1), false)
+            .squeeze_dim(1);
+        let _ = label_indices.iter::<i64>().unwrap().collect::<Vec<i64>>();
+        let _ = scores.iter::<f64>().unwrap().collect::<Vec<f64>>();
+        duration = duration.checked_add(start.elapsed()).unwrap();
+    }
+    duration
+}
+
+fn top_label_selection_on_device(iters: u64, logits: &Tensor) -> Duration {
+    let mut duration = Duration::new(0, 0);
+    for _i in 0..iters {
+        let start = Instant::now();
+        let (scores, label_indices) = logits.softmax(-1, Kind::Float).max_dim(-1, false);
+        let _ = label_indices
+            .to(Device::Cpu)
+            .iter::<i64>()
+            .unwrap()
+            .collect::<Vec<i64>>();
+        let _ = scores
+            .to(Device::Cpu)
+            .iter::<f64>()
+            .unwrap()
+            .collect::<Vec<f64>>();
+        duration = duration.checked_add(start.elapsed()).unwrap();
+    }
+    duration
+}
+
+fn mean_pooling_on_cpu(iters: u64, token_embeddings: &Tensor, attention_mask: &Tensor) -> Duration {
+    let (batch_size, sequence_length, hidden_size) = token_embeddings.size3().unwrap();
+    let (batch_size, sequence_length, hidden_size) = (
+        batch_size as usize,
+        sequence_length as usize,
+        hidden_size as usize,
+    );
+    let mut duration = Duration::new(0, 0);
+    for _i in 0..iters {
+        let start = Instant::now();
+        let values = Vec::<f32>::try_from(token_embeddings.to(Device::Cpu).flatten(0, -1)).unwrap();
+        let mask = Vec::<i64>::try_from(attention_mask.to(Device::Cpu).flatten(0, -1)).unwrap();
+        let mut embeddings = vec![vec![0f32; hidden_size]; batch_size];
+        for (sentence, embedding) in embeddings.iter_mut().enumerate() {
+            let mut num_tokens = 0f32;
+            for position in 0..sequence_length {
+                if mask[sentence * sequence_length + position] == 0 {
+                    continue;
+                }
+                num_tokens += 1.0;
+                let offset = (sentence * sequence_length + position) * hidden_size;
+                for (value, token_value) in embedding
+                    .iter_mut()
+                    .zip(&values[offset..offset + hidden_size])
+                {
+                    *value += token_value;
+                }
+            }
+            let num_tokens = num_tokens.max(1e-9);
+            embedding.iter_mut().for_each(|value| *value /= num_tokens);
+            let norm = embedding
+                .iter()
+                .map(|value| value * value)
+                .sum::<f32>()
+                .sqrt()
+                .max(1e-12);
+            embedding.iter_mut().for_each(|value| *value /= norm);
+        }
+        let _ = black_box(embeddings);
+        duration = duration.checked_add(start.elapsed()).unwrap();
+    }
+    duration
+}
+
+fn mean_pooling_on_device(
+    iters: u64,
+    pooling: &Pooling,
+    token_embeddings: &Tensor,
+    attention_mask: &Tensor,
+) -> Duration {
+    let mut duration = Duration::new(0, 0);
+    for _i in 0..iters {
+        let start = Instant::now();
+        let embeddings =
+            l2_normalize(&pooling.forward(token_embeddings.shallow_clone(), attention_mask));
+        let _ = Vec::<Vec<f32>>::try_from(embeddings.to(Device::Cpu)).unwrap();
+        duration = duration.checked_add(start.elapsed()).unwrap();
+    }
+    duration
+}
+
 fn bench_tensor_ops(c: &mut Criterion) {
     //    Set-up summarization model
     unsafe {
@@ -28,6 +127,47 @@ fn bench_tensor_ops(c: &mut Criterion) {
     c.bench_function("Matrix multiply ", |b| {
         b.iter_custom(|iters| black_box(matrix_multiply(iters, &input, &weights)))
     });
+
+    let logits = Tensor::rand([4096, 1000], (Kind::Float, Device::cuda_if_available()));
+    c.bench_function("Top label selection (CPU)", |b| {
+        b.iter_custom(|iters| black_box(top_label_selection_on_cpu(iters, &logits)))
+    });
+    c.bench_function("Top label selection (device)", |b| {
+        b.iter_custom(|iters| black_box(top_label_selection_on_device(iters, &logits)))
+    });
+
+    let token_embeddings = Tensor::rand([32, 128, 768], (Kind::Float, Device::cuda_if_available()));
+    let attention_mask = Tensor::arange(128, (Kind::Int64, Device::cuda_if_available()))
+        .lt(96)
+        .to_kind(Kind::Int64)
+        .unsqueeze(0)
+        .expand([32, 128], true);
+    let pooling = Pooling::new(PoolingConfig {
+        word_embedding_dimension: 768,
+        pooling_mode_cls_token: false,
+        pooling_mode_max_tokens: false,
+        pooling_mode_mean_tokens: true,
+        pooling_mode_mean_sqrt_len_tokens: false,
+    });
+    c.bench_function("Mean pooling and normalization (CPU)", |b| {
+        b.iter_custom(|iters| {
+            black_box(mean_pooling_on_cpu(
+                iters,
+                &token_embeddings,
+                &attention_mask,
+            ))
+        })
+    });
+    c.bench_function("Mean pooling and normalization (device)", |b| {
+        b.iter_custom(|iters| {
+            black_box(mean_pooling_on_device(
+                iters,
+                &pooling,
+                &token_embeddings,
+                &attention_mask,
+            ))
+        })
+    });
 }
 
 criterion_group! {
@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::f64::consts::PI;
-use tch::Tensor;
+use tch::{TchError, Tensor};
 
 pub fn _gelu(x: &Tensor) -> Tensor {
-    x * 0.5 * (1.0 + (x / ((2.0_f64).sqrt())).erf())
+    _f_gelu(x).unwrap()
 }
 
 pub fn _relu(x: &Tensor) -> Tensor {
@@ -11,15 +11,15 @@ pub fn _relu(x: &Tensor) -> Tensor {
 }
 
 pub fn _swish(x: &Tensor) -> Tensor {
-    x * x.sigmoid()
+    _f_swish(x).unwrap()
 }
 
 pub fn _mish(x: &Tensor) -> Tensor {
-    x * (x.softplus().tanh())
+    _f_mish(x).unwrap()
 }
 
 pub fn _gelu_new(x: &Tensor) -> Tensor {
-    x * 0.5 * (((x.pow_tensor_scalar(3.0f64) * 0.044715 + x) * ((2f64 / PI).sqrt())).tanh() + 1)
+    _f_gelu_new(x).unwrap()
 }
 
 pub fn _tanh(x: &Tensor) -> Tensor {
@@ -30,6 +30,32 @@ pub fn _identity(x: &Tensor) -> Tensor {
     x.shallow_clone()
 }
 
+fn _f_gelu(x: &Tensor) -> Result<Tensor, TchError> {
+    x.f_mul_scalar(0.5)?.f_mul(
+        &x.f_div_scalar((2.0_f64).sqrt())?
+            .f_erf()?
+            .f_add_scalar(1.0)?,
+    )
+}
+
+fn _f_swish(x: &Tensor) -> Result<Tensor, TchError> {
+    x.f_mul(&x.f_sigmoid()?)
+}
+
+fn _f_mish(x: &Tensor) -> Result<Tensor, TchError> {
+    x.f_mul(&x.f_softplus()?.f_tanh()?)
+}
+
+fn _f_gelu_new(x: &Tensor) -> Result<Tensor, TchError> {
+    let inner = x
+        .f_pow_tensor_scalar(3.0f64)?
+        .f_mul_scalar(0.044715)?
+        .f_add(x)?
+        .f_mul_scalar((2f64 / PI).sqrt())?;
+    x.f_mul_scalar(0.5)?
+        .f_mul(&inner.f_tanh()?.f_add_scalar(1)?)
+}
+
 pub struct TensorFunction(Box<fn(&Tensor) -> Tensor>);
 
 impl TensorFunction {
@@ -78,6 +104,20 @@ impl Activation {
             Activation::identity => _identity,
         }))
     }
+
+    /// Fallible application of the activation function, returning the errors of the tensor operations instead of
+    /// panicking
+    pub fn f_apply(&self, x: &Tensor) -> Result<Tensor, TchError> {
+        match self {
+            Activation::gelu => _f_gelu(x),
+            Activation::relu => x.f_relu(),
+            Activation::swish => _f_swish(x),
+            Activation::gelu_new => _f_gelu_new(x),
+            Activation::mish => _f_mish(x),
+            Activation::tanh => x.f_tanh(),
+            Activation::identity => Ok(x.shallow_clone()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -9,6 +9,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::RustBertError;
 use serde::Deserialize;
 use std::fs::File;
 use std::io::BufReader;
@@ -42,4 +43,35 @@ where
         let config: Self = serde_json::from_reader(br).expect("could not parse configuration");
         config
     }
+
+    /// Loads a `Config` object from a JSON string. This allows building a configuration without
+    /// filesystem access (e.g. when the configuration is stored in a database or an environment variable).
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - `&str` containing the JSON configuration.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self, RustBertError>` containing the configuration, or an `InvalidConfigurationError` if it could not be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::gpt2::Gpt2Config;
+    /// use rust_bert::Config;
+    ///
+    /// let config_json = std::env::var("MODEL_CONFIG")?;
+    /// let config = Gpt2Config::from_json_str(&config_json)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn from_json_str(json: &str) -> Result<Self, RustBertError> {
+        serde_json::from_str(json).map_err(|error| {
+            RustBertError::InvalidConfigurationError(format!(
+                "Could not parse configuration: {error}"
+            ))
+        })
+    }
 }
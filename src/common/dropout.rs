@@ -10,7 +10,7 @@
 // limitations under the License.
 
 use tch::nn::ModuleT;
-use tch::{Kind, Tensor};
+use tch::{Kind, TchError, Tensor};
 
 #[derive(Debug)]
 pub struct Dropout {
@@ -21,6 +21,11 @@ impl Dropout {
     pub fn new(p: f64) -> Dropout {
         Dropout { dropout_prob: p }
     }
+
+    /// Fallible version of `forward_t`, returning the errors of the tensor operations instead of panicking
+    pub fn f_forward_t(&self, input: &Tensor, train: bool) -> Result<Tensor, TchError> {
+        input.f_dropout(self.dropout_prob, train)
+    }
 }
 
 impl ModuleT for Dropout {
@@ -6,6 +6,10 @@ use rust_tokenizers::error::TokenizerError;
 use tch::TchError;
 use thiserror::Error;
 
+/// # Error variants returned by the library
+/// Variants sharing the same message prefix (e.g. `IOError` and `FileNotFoundError`) refine a more
+/// generic error category and allow handling specific failures programmatically, for example retrying
+/// a `FileDownloadError` while failing fast on a `ConfigurationMismatchError`.
 #[derive(Error, Debug)]
 pub enum RustBertError {
     #[cfg(feature = "remote")]
@@ -15,15 +19,33 @@ pub enum RustBertError {
     #[error("IO error: {0}")]
     IOError(String),
 
+    #[error("IO error: {0}")]
+    FileNotFoundError(String),
+
     #[error("Tch tensor error: {0}")]
     TchError(String),
 
+    #[error("Tch tensor error: {0}")]
+    TensorShapeError(String),
+
+    #[error("Tch tensor error: {0}")]
+    WeightsMismatchError(String),
+
+    #[error("Tch tensor error: {0}")]
+    OutOfMemoryError(String),
+
     #[error("Tokenizer error: {0}")]
     TokenizerError(String),
 
+    #[error("Tokenizer error: {0}")]
+    VocabularyError(String),
+
     #[error("Invalid configuration error: {0}")]
     InvalidConfigurationError(String),
 
+    #[error("Invalid configuration error: {0}")]
+    ConfigurationMismatchError(String),
+
     #[error("Value error: {0}")]
     ValueError(String),
 
@@ -41,19 +63,34 @@ pub enum RustBertError {
 
 impl From<std::io::Error> for RustBertError {
     fn from(error: std::io::Error) -> Self {
-        RustBertError::IOError(error.to_string())
+        match error.kind() {
+            std::io::ErrorKind::NotFound => RustBertError::FileNotFoundError(error.to_string()),
+            _ => RustBertError::IOError(error.to_string()),
+        }
     }
 }
 
 impl From<TokenizerError> for RustBertError {
     fn from(error: TokenizerError) -> Self {
-        RustBertError::TokenizerError(error.to_string())
+        match error {
+            TokenizerError::FileNotFound(_) => RustBertError::FileNotFoundError(error.to_string()),
+            TokenizerError::VocabularyParsingError(_)
+            | TokenizerError::TokenNotFound(_)
+            | TokenizerError::IndexNotFound(_) => RustBertError::VocabularyError(error.to_string()),
+            _ => RustBertError::TokenizerError(error.to_string()),
+        }
     }
 }
 
 impl From<TchError> for RustBertError {
     fn from(error: TchError) -> Self {
-        RustBertError::TchError(error.to_string())
+        match error {
+            TchError::Shape(_) => RustBertError::TensorShapeError(error.to_string()),
+            TchError::TensorNameNotFound(_, _) => {
+                RustBertError::WeightsMismatchError(error.to_string())
+            }
+            _ => RustBertError::TchError(error.to_string()),
+        }
     }
 }
 
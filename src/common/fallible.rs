@@ -0,0 +1,53 @@
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fallible forward passes of the `tch` layers, returning the errors of the tensor operations (e.g. a device
+//! running out of memory) instead of panicking.
+
+use tch::{nn, TchError, Tensor};
+
+/// Fallible version of the `nn::Linear` forward pass
+pub(crate) fn f_linear(linear: &nn::Linear, input: &Tensor) -> Result<Tensor, TchError> {
+    input.f_linear(&linear.ws, linear.bs.as_ref())
+}
+
+/// Fallible version of the `nn::LayerNorm` forward pass. The epsilon of the layer configuration (private in `tch`)
+/// is provided by the caller.
+pub(crate) fn f_layer_norm(
+    layer_norm: &nn::LayerNorm,
+    input: &Tensor,
+    eps: f64,
+) -> Result<Tensor, TchError> {
+    input.f_layer_norm(
+        layer_norm.normalized_shape.as_slice(),
+        layer_norm.ws.as_ref(),
+        layer_norm.bs.as_ref(),
+        eps,
+        true,
+    )
+}
+
+/// Fallible version of the `nn::Embedding` forward pass. The padding index of the layer configuration (private in
+/// `tch`, excluding the padding embedding from the gradient updates) is provided by the caller, -1 if not set.
+pub(crate) fn f_embedding(
+    embedding: &nn::Embedding,
+    input_ids: &Tensor,
+    padding_idx: i64,
+) -> Result<Tensor, TchError> {
+    Tensor::f_embedding(&embedding.ws, input_ids, padding_idx, false, false)
+}
+
+/// Fallible version of `Tensor::copy`
+pub(crate) fn f_copy(tensor: &Tensor) -> Result<Tensor, TchError> {
+    let mut copy = tensor.f_zeros_like()?;
+    copy.f_copy_(tensor)?;
+    Ok(copy)
+}
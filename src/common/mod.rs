@@ -2,6 +2,7 @@ pub(crate) mod activations;
 pub mod config;
 pub(crate) mod dropout;
 pub(crate) mod embeddings;
+pub(crate) mod fallible;
 pub mod error;
 pub(crate) mod kind;
 pub(crate) mod linear;
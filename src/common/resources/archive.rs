@@ -0,0 +1,211 @@
+use super::remote::CACHE;
+use super::*;
+use crate::common::error::RustBertError;
+use cached_path::Options;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the configuration file expected at the root of a model archive
+const CONFIG_FILE: &str = "config.json";
+
+/// Weights files following the Hugging Face naming conventions. A model archive should contain at least one of them
+const WEIGHTS_FILES: [&str; 4] = [
+    "rust_model.ot",
+    "model.safetensors",
+    "model.onnx",
+    "pytorch_model.bin",
+];
+
+/// # Resource stored in an archive (`.tar.gz` or `.zip`) of model files
+/// The archive packages the files of a model (configuration, vocabulary, merges and weights) following the
+/// Hugging Face directory layout, either at the root of the archive or in a single top-level directory. It is
+/// extracted to the cache directory on first use: later accesses read the extracted files directly.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ArchiveResource {
+    /// Remote url or local path of the archive
+    pub archive: String,
+    /// Path of the resource file relative to the root of the model directory in the archive
+    pub file_name: String,
+    /// Local subdirectory of the cache root where the archive is extracted
+    pub cache_subdir: String,
+}
+
+impl ArchiveResource {
+    /// Creates a new ArchiveResource pointing to a file of a model archive. Note that this does not
+    /// download or extract the archive (only declares the archive and file locations)
+    ///
+    /// # Arguments
+    ///
+    /// * `archive` - `&str` Remote url or local path of the `.tar.gz` or `.zip` archive
+    /// * `file_name` - `&str` Name of the file in the model directory of the archive (e.g. `config.json`)
+    /// * `cache_subdir` - `&str` Local subdirectory of the cache root to extract the archive to
+    ///
+    /// # Returns
+    ///
+    /// * `ArchiveResource` ArchiveResource object
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::resources::ArchiveResource;
+    /// let config_resource = ArchiveResource::new(
+    ///     "https://example.com/my-fine-tuned-model.tar.gz",
+    ///     "config.json",
+    ///     "my-fine-tuned-model",
+    /// );
+    /// ```
+    pub fn new(archive: &str, file_name: &str, cache_subdir: &str) -> ArchiveResource {
+        ArchiveResource {
+            archive: archive.to_string(),
+            file_name: file_name.to_string(),
+            cache_subdir: cache_subdir.to_string(),
+        }
+    }
+
+    /// Creates a new ArchiveResource for another file of the same model archive
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - `&str` Name of the file in the model directory of the archive (e.g. `vocab.txt`)
+    ///
+    /// # Returns
+    ///
+    /// * `ArchiveResource` ArchiveResource object sharing the archive and cache location
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::resources::ArchiveResource;
+    /// let config_resource = ArchiveResource::new(
+    ///     "https://example.com/my-fine-tuned-model.tar.gz",
+    ///     "config.json",
+    ///     "my-fine-tuned-model",
+    /// );
+    /// let vocab_resource = config_resource.with_file_name("vocab.txt");
+    /// let weights_resource = config_resource.with_file_name("rust_model.ot");
+    /// ```
+    pub fn with_file_name(&self, file_name: &str) -> ArchiveResource {
+        ArchiveResource {
+            archive: self.archive.clone(),
+            file_name: file_name.to_string(),
+            cache_subdir: self.cache_subdir.clone(),
+        }
+    }
+
+    /// Downloads (if remote) and extracts the archive, returning the path to the model directory.
+    ///
+    /// The model directory is the root of the archive, or its single top-level directory if the files are
+    /// packaged in a folder. It must follow the Hugging Face layout: a `config.json` file and at least one weights
+    /// file (`rust_model.ot`, `model.safetensors`, `model.onnx` or `pytorch_model.bin`).
+    ///
+    /// # Returns
+    ///
+    /// * `PathBuf` pointing to the extracted model directory
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::resources::ArchiveResource;
+    /// let config_resource = ArchiveResource::new(
+    ///     "path/to/my-fine-tuned-model.zip",
+    ///     "config.json",
+    ///     "my-fine-tuned-model",
+    /// );
+    /// let model_directory = config_resource.get_model_directory();
+    /// ```
+    pub fn get_model_directory(&self) -> Result<PathBuf, RustBertError> {
+        let options = Options::default().subdir(&self.cache_subdir).extract();
+        let extracted_path = CACHE.cached_path_with_options(&self.archive, &options)?;
+        let model_directory = if extracted_path.join(CONFIG_FILE).is_file() {
+            extracted_path
+        } else {
+            let mut entries = fs::read_dir(&extracted_path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<PathBuf>, _>>()?;
+            match entries.len() {
+                1 if entries[0].is_dir() => entries.remove(0),
+                _ => extracted_path,
+            }
+        };
+        validate_model_directory(&model_directory, &self.archive)?;
+        Ok(model_directory)
+    }
+}
+
+/// Checks that an extracted model directory follows the Hugging Face layout
+fn validate_model_directory(model_directory: &Path, archive: &str) -> Result<(), RustBertError> {
+    if !model_directory.join(CONFIG_FILE).is_file() {
+        return Err(RustBertError::InvalidConfigurationError(format!(
+            "Archive {archive} does not follow the Hugging Face model layout: {CONFIG_FILE} not found"
+        )));
+    }
+    if !WEIGHTS_FILES
+        .iter()
+        .any(|weights_file| model_directory.join(weights_file).is_file())
+    {
+        return Err(RustBertError::InvalidConfigurationError(format!(
+            "Archive {archive} does not follow the Hugging Face model layout: no weights file found (expected one of {WEIGHTS_FILES:?})"
+        )));
+    }
+    Ok(())
+}
+
+impl ResourceProvider for ArchiveResource {
+    /// Gets the local path for a file of a model archive.
+    ///
+    /// The archive is downloaded (if remote), extracted and cached. Then the path
+    /// to the extracted file is returned.
+    ///
+    /// # Returns
+    ///
+    /// * `PathBuf` pointing to the resource file
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::resources::{ArchiveResource, ResourceProvider};
+    /// let config_resource = ArchiveResource::new(
+    ///     "https://example.com/my-fine-tuned-model.tar.gz",
+    ///     "config.json",
+    ///     "my-fine-tuned-model",
+    /// );
+    /// let config_path = config_resource.get_local_path();
+    /// ```
+    fn get_local_path(&self) -> Result<PathBuf, RustBertError> {
+        let local_path = self.get_model_directory()?.join(&self.file_name);
+        if local_path.is_file() {
+            Ok(local_path)
+        } else {
+            Err(RustBertError::FileNotFoundError(format!(
+                "{} not found in archive {}",
+                self.file_name, self.archive
+            )))
+        }
+    }
+
+    /// Gets a wrapper around the local path for a file of a model archive.
+    ///
+    /// # Returns
+    ///
+    /// * `Resource` wrapping a `PathBuf` pointing to the resource file
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::resources::{ArchiveResource, ResourceProvider};
+    /// let config_resource = ArchiveResource::new(
+    ///     "https://example.com/my-fine-tuned-model.tar.gz",
+    ///     "config.json",
+    ///     "my-fine-tuned-model",
+    /// );
+    /// let config_path = config_resource.get_resource();
+    /// ```
+    fn get_resource(&self) -> Result<Resource<'_>, RustBertError> {
+        Ok(Resource::PathBuf(self.get_local_path()?))
+    }
+
+    /// Identifies a file of a model archive by the archive location and the file name
+    fn identifier(&self) -> String {
+        format!("{}#{}", self.archive, self.file_name)
+    }
+}
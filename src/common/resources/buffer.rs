@@ -37,6 +37,11 @@ impl ResourceProvider for BufferResource {
     fn get_resource(&self) -> Result<Resource, RustBertError> {
         Ok(Resource::Buffer(self.data.write().unwrap()))
     }
+
+    /// Identifies an in-memory resource by its size (the data itself is not included)
+    fn identifier(&self) -> String {
+        format!("buffer ({} bytes)", self.data.read().unwrap().len())
+    }
 }
 
 impl From<Vec<u8>> for BufferResource {
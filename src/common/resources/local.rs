@@ -49,6 +49,11 @@ impl ResourceProvider for LocalResource {
     fn get_resource(&self) -> Result<Resource, RustBertError> {
         Ok(Resource::PathBuf(self.local_path.clone()))
     }
+
+    /// Identifies a local resource by its path
+    fn identifier(&self) -> String {
+        self.local_path.display().to_string()
+    }
 }
 
 impl From<PathBuf> for LocalResource {
@@ -11,6 +11,7 @@
 //! resource location. Two types of resources are pre-defined:
 //! - LocalResource: points to a local file
 //! - RemoteResource: points to a remote file via a URL
+//! - ArchiveResource: points to a file of a (local or remote) `.tar.gz` or `.zip` model archive
 //! - BufferResource: refers to a buffer that contains file contents for a resource (currently only
 //!                   usable for weights)
 //!
@@ -25,11 +26,15 @@ mod local;
 use crate::common::error::RustBertError;
 pub use buffer::BufferResource;
 pub use local::LocalResource;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::fs::File;
+use std::io::Read;
 use std::ops::DerefMut;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::RwLockWriteGuard;
 use tch::nn::VarStore;
+use tch::{no_grad, Tensor};
 
 pub enum Resource<'a> {
     PathBuf(PathBuf),
@@ -69,6 +74,27 @@ pub trait ResourceProvider: Debug + Send + Sync {
     /// use rust_bert::resources::{BufferResource, LocalResource, ResourceProvider};
     /// ```
     fn get_resource(&self) -> Result<Resource, RustBertError>;
+
+    /// Provides an identifier of the resource (e.g. its path or URL), used to record the resources a model was
+    /// loaded from. Defaults to the `Debug` representation of the resource.
+    ///
+    /// # Returns
+    ///
+    /// * `String` identifying the resource
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::resources::{LocalResource, ResourceProvider};
+    /// use std::path::PathBuf;
+    /// let config_resource = LocalResource {
+    ///     local_path: PathBuf::from("path/to/config.json"),
+    /// };
+    /// let config_identifier = config_resource.identifier();
+    /// ```
+    fn identifier(&self) -> String {
+        format!("{self:?}")
+    }
 }
 
 impl<T: ResourceProvider + ?Sized> ResourceProvider for Box<T> {
@@ -78,23 +104,207 @@ impl<T: ResourceProvider + ?Sized> ResourceProvider for Box<T> {
     fn get_resource(&self) -> Result<Resource, RustBertError> {
         T::get_resource(self)
     }
+    fn identifier(&self) -> String {
+        T::identifier(self)
+    }
 }
 
-/// Load the provided `VarStore` with model weights from the provided `ResourceProvider`
+/// Suffixes of the output projection variables that may be tied to the input embeddings (and therefore
+/// absent from weights files saved without duplicate tensors)
+const TIED_OUTPUT_EMBEDDINGS: [&str; 5] = [
+    "lm_head.weight",
+    "lm_head.decoder.weight",
+    "predictions.decoder.weight",
+    "vocab_projector.weight",
+    "generator_lm_head.weight",
+];
+
+/// Suffixes of the input embeddings variables the output projections may be tied to
+const INPUT_EMBEDDINGS: [&str; 5] = [
+    "word_embeddings.weight",
+    "wte.weight",
+    "shared.weight",
+    "embed_tokens.weight",
+    "tokens_embed.weight",
+];
+
+/// Load the provided `VarStore` with model weights from the provided `ResourceProvider`.
+/// Output projections missing from the weights are tied to the input embeddings
+/// (see `load_weights_with_tied_embeddings`).
 pub fn load_weights(
     rp: &(impl ResourceProvider + ?Sized),
     vs: &mut VarStore,
 ) -> Result<(), RustBertError> {
+    load_weights_with_tied_embeddings(rp, vs, true)
+}
+
+/// Load the provided `VarStore` with model weights from the provided `ResourceProvider`.
+///
+/// If `tie_word_embeddings` is true, output projection variables (e.g. `lm_head.weight`) missing from the weights
+/// are initialized from the input embeddings with the same shape, as for weights saved without the duplicate tied tensors.
+/// Any other missing variable results in a `WeightsMismatchError`, and the `VarStore` is left unchanged.
+pub fn load_weights_with_tied_embeddings(
+    rp: &(impl ResourceProvider + ?Sized),
+    vs: &mut VarStore,
+    tie_word_embeddings: bool,
+) -> Result<(), RustBertError> {
+    if !tie_word_embeddings {
+        return match rp.get_resource()? {
+            Resource::Buffer(mut data) => {
+                vs.load_from_stream(std::io::Cursor::new(data.deref_mut()))?;
+                Ok(())
+            }
+            Resource::PathBuf(path) => Ok(vs.load(path)?),
+        };
+    }
+    load_variables(rp, &mut vs.variables(), &[], false).map(|_| ())
+}
+
+/// Load the provided `VarStore` with the model weights from the provided `ResourceProvider` matching its variables,
+/// e.g. to fine-tune a pretrained model on a task with a different number of labels.
+///
+/// Variables missing from the weights or whose shape differs from the weights (e.g. a classification head) keep
+/// their (random) initial value: a warning listing them is logged (with the `log` crate), and their names are returned.
+/// Output projections missing from the weights are tied to the input embeddings (see `load_weights_with_tied_embeddings`).
+pub fn load_weights_partial(
+    rp: &(impl ResourceProvider + ?Sized),
+    vs: &mut VarStore,
+) -> Result<Vec<String>, RustBertError> {
+    let reinitialized_variables = load_variables(rp, &mut vs.variables(), &[], true)?;
+    if !reinitialized_variables.is_empty() {
+        log::warn!(
+            "Variables missing from the weights or with a different shape, randomly initialized: {}",
+            reinitialized_variables.join(", ")
+        );
+    }
+    Ok(reinitialized_variables)
+}
+
+/// Load the provided variables (e.g. a subset of the variables of a `VarStore`) with model weights from the provided
+/// `ResourceProvider`, tying the missing output projections to the input embeddings (see `load_weights_with_tied_embeddings`).
+///
+/// Variables ending with one of the `optional_suffixes` (e.g. `pooler.dense.weight`) may be missing from the weights:
+/// they keep their initial value and their names are returned. If `partial` is true, this applies to any variable
+/// missing from the weights or whose shape differs from the weights.
+pub(crate) fn load_variables(
+    rp: &(impl ResourceProvider + ?Sized),
+    variables: &mut HashMap<String, Tensor>,
+    optional_suffixes: &[&str],
+    partial: bool,
+) -> Result<Vec<String>, RustBertError> {
+    let named_tensors = load_named_tensors(rp)?
+        .into_iter()
+        .collect::<HashMap<String, Tensor>>();
+    let input_embeddings = named_tensors
+        .iter()
+        .filter(|(name, _)| INPUT_EMBEDDINGS.iter().any(|suffix| name.ends_with(suffix)))
+        .map(|(_, tensor)| tensor)
+        .collect::<Vec<&Tensor>>();
+
+    let mut sources: HashMap<String, &Tensor> = HashMap::with_capacity(variables.len());
+    let mut missing_variables = Vec::new();
+    for (name, variable) in variables.iter() {
+        let is_tied_output = TIED_OUTPUT_EMBEDDINGS
+            .iter()
+            .any(|suffix| name.ends_with(suffix));
+        let source = match named_tensors.get(name) {
+            Some(tensor) if partial && tensor.size() != variable.size() => {
+                missing_variables.push(name.clone());
+                continue;
+            }
+            Some(tensor) => tensor,
+            None if optional_suffixes
+                .iter()
+                .any(|suffix| name.ends_with(suffix)) =>
+            {
+                missing_variables.push(name.clone());
+                continue;
+            }
+            None => {
+                let tied_embeddings = input_embeddings
+                    .iter()
+                    .find(|tensor| is_tied_output && tensor.size() == variable.size())
+                    .copied();
+                match tied_embeddings {
+                    Some(tensor) => tensor,
+                    None if partial => {
+                        missing_variables.push(name.clone());
+                        continue;
+                    }
+                    None if is_tied_output => {
+                        return Err(RustBertError::WeightsMismatchError(format!(
+                            "Variable {name} not found in the provided weights, and no input embeddings to tie it to"
+                        )));
+                    }
+                    None => {
+                        return Err(RustBertError::WeightsMismatchError(format!(
+                            "Variable {name} not found in the provided weights"
+                        )));
+                    }
+                }
+            }
+        };
+        if source.size() != variable.size() {
+            return Err(RustBertError::TensorShapeError(format!(
+                "Shape mismatch for variable {name}: expected {:?}, got {:?}",
+                variable.size(),
+                source.size()
+            )));
+        }
+        sources.insert(name.clone(), source);
+    }
+    no_grad(|| {
+        for (name, variable) in variables.iter_mut() {
+            if let Some(source) = sources.get(name) {
+                variable.f_copy_(source)?;
+            }
+        }
+        Ok::<(), RustBertError>(())
+    })?;
+    Ok(missing_variables)
+}
+
+/// Read the named tensors stored in the weights file or buffer of the provided `ResourceProvider`,
+/// without loading them into a `VarStore`
+pub(crate) fn load_named_tensors(
+    rp: &(impl ResourceProvider + ?Sized),
+) -> Result<Vec<(String, Tensor)>, RustBertError> {
     match rp.get_resource()? {
-        Resource::Buffer(mut data) => {
-            vs.load_from_stream(std::io::Cursor::new(data.deref_mut()))?;
-            Ok(())
+        Resource::Buffer(mut data) => Ok(Tensor::load_multi_from_stream(std::io::Cursor::new(
+            data.deref_mut(),
+        ))?),
+        Resource::PathBuf(path) => {
+            if is_safetensors(&path) {
+                Ok(Tensor::read_safetensors(path)?)
+            } else {
+                Ok(Tensor::load_multi(path)?)
+            }
+        }
+    }
+}
+
+/// Checks if a weights file is in the safetensors format, either from its extension or, for files without
+/// extension (e.g. downloaded to the cache), from its header: a little-endian `u64` header length followed
+/// by a JSON object.
+fn is_safetensors(path: &Path) -> bool {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("safetensors") => true,
+        Some(_) => false,
+        None => {
+            let mut header = [0u8; 9];
+            File::open(path)
+                .and_then(|mut file| file.read_exact(&mut header))
+                .map(|_| header[8] == b'{')
+                .unwrap_or(false)
         }
-        Resource::PathBuf(path) => Ok(vs.load(path)?),
     }
 }
 
+#[cfg(feature = "remote")]
+mod archive;
 #[cfg(feature = "remote")]
 mod remote;
 #[cfg(feature = "remote")]
-pub use remote::RemoteResource;
+pub use archive::ArchiveResource;
+#[cfg(feature = "remote")]
+pub use remote::{RemoteResource, RetryPolicy};
@@ -4,6 +4,80 @@ use cached_path::{Cache, Options, ProgressBar};
 use dirs::cache_dir;
 use lazy_static::lazy_static;
 use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+/// # Retry policy for the download of remote resources
+/// Transient failures (timeouts and server errors) are retried with an exponential backoff:
+/// the delay before the n-th retry is `base_delay * 2^(n-1)`. Other errors (e.g. resource not found)
+/// are returned immediately.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// Maximum number of download attempts (including the first one)
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for every subsequent retry
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates a new `RetryPolicy`
+    ///
+    /// # Arguments
+    ///
+    /// * `max_attempts` - `u32` maximum number of download attempts (including the first one)
+    /// * `base_delay` - `Duration` delay before the first retry
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::resources::RetryPolicy;
+    /// use std::time::Duration;
+    /// let retry_policy = RetryPolicy::new(5, Duration::from_millis(200));
+    /// ```
+    pub fn new(max_attempts: u32, base_delay: Duration) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay,
+        }
+    }
+
+    /// Policy performing a single download attempt
+    pub fn no_retry() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            base_delay: Duration::ZERO,
+        }
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+    }
+
+    fn is_transient(error: &cached_path::Error) -> bool {
+        match error {
+            cached_path::Error::HttpError(error) => match error.status() {
+                Some(status) => Self::is_transient_status(status.as_u16()),
+                None => error.is_timeout(),
+            },
+            _ => false,
+        }
+    }
+
+    fn is_transient_status(status_code: u16) -> bool {
+        (500..600).contains(&status_code)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// 3 download attempts, with a delay of 1 second before the first retry
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(1),
+        }
+    }
+}
 
 /// # Remote resource that will be downloaded and cached locally on demand
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -12,6 +86,7 @@ pub struct RemoteResource {
     pub url: String,
     /// Local subdirectory of the cache root where this resource is saved
     pub cache_subdir: String,
+    retry_policy: RetryPolicy,
 }
 
 impl RemoteResource {
@@ -37,6 +112,7 @@ impl RemoteResource {
         RemoteResource {
             url: url.to_string(),
             cache_subdir: cache_subdir.to_string(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 
@@ -64,7 +140,70 @@ impl RemoteResource {
     pub fn from_pretrained(name_url_tuple: (&str, &str)) -> RemoteResource {
         let cache_subdir = name_url_tuple.0.to_string();
         let url = name_url_tuple.1.to_string();
-        RemoteResource { url, cache_subdir }
+        RemoteResource {
+            url,
+            cache_subdir,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Creates a new RemoteResource for a file of a model hosted on the Hugging Face Hub, following the Hub URL
+    /// conventions (`https://huggingface.co/{model_id}/resolve/main/{filename}`). The resource is cached under
+    /// a subdirectory named after the model id. Note that this does not download the resource (only declares the
+    /// remote and local locations)
+    ///
+    /// # Arguments
+    ///
+    /// * `model_id` - `&str` Model id on the Hub (e.g. `distilbert-base-uncased-finetuned-sst-2-english`)
+    /// * `filename` - `&str` Name of the file in the model repository (e.g. `rust_model.ot`, `model.safetensors` or `config.json`)
+    ///
+    /// # Returns
+    ///
+    /// * `RemoteResource` RemoteResource object
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::resources::RemoteResource;
+    /// let model_resource = RemoteResource::from_hub(
+    ///     "distilbert-base-uncased-finetuned-sst-2-english",
+    ///     "rust_model.ot",
+    /// );
+    /// ```
+    pub fn from_hub(model_id: &str, filename: &str) -> RemoteResource {
+        RemoteResource {
+            url: format!("https://huggingface.co/{model_id}/resolve/main/{filename}"),
+            cache_subdir: model_id.to_string(),
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Sets the retry policy used when downloading the resource
+    ///
+    /// # Arguments
+    ///
+    /// * `retry_policy` - `RetryPolicy` applied to transient download failures
+    ///
+    /// # Returns
+    ///
+    /// * `RemoteResource` RemoteResource object
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::resources::{RemoteResource, RetryPolicy};
+    /// use std::time::Duration;
+    /// let config_resource = RemoteResource::new("http://config_json_location", "configs")
+    ///     .with_retry_policy(RetryPolicy::new(5, Duration::from_millis(200)));
+    /// ```
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> RemoteResource {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Returns the retry policy used when downloading the resource
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
     }
 }
 
@@ -89,9 +228,21 @@ impl ResourceProvider for RemoteResource {
     /// let config_path = config_resource.get_local_path();
     /// ```
     fn get_local_path(&self) -> Result<PathBuf, RustBertError> {
-        let cached_path = CACHE
-            .cached_path_with_options(&self.url, &Options::default().subdir(&self.cache_subdir))?;
-        Ok(cached_path)
+        let options = Options::default().subdir(&self.cache_subdir);
+        let mut attempt = 1;
+        loop {
+            match CACHE.cached_path_with_options(&self.url, &options) {
+                Ok(cached_path) => return Ok(cached_path),
+                Err(error)
+                    if attempt < self.retry_policy.max_attempts
+                        && RetryPolicy::is_transient(&error) =>
+                {
+                    thread::sleep(self.retry_policy.delay(attempt));
+                    attempt += 1;
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
     }
 
     /// Gets a wrapper around the local path for a remote resource.
@@ -110,6 +261,11 @@ impl ResourceProvider for RemoteResource {
     fn get_resource(&self) -> Result<Resource, RustBertError> {
         Ok(Resource::PathBuf(self.get_local_path()?))
     }
+
+    /// Identifies a remote resource by its URL
+    fn identifier(&self) -> String {
+        self.url.clone()
+    }
 }
 
 lazy_static! {
@@ -118,9 +274,11 @@ lazy_static! {
 /// If the environment variable `RUSTBERT_CACHE` is set, will save the cache model files at that
 /// location. Otherwise defaults to `$XDG_CACHE_HOME/.rustbert`, or corresponding user cache for
 /// the current system.
+/// Downloads are attempted once by the cache, retries being handled by the `RetryPolicy` of each resource.
     pub static ref CACHE: Cache = Cache::builder()
         .dir(_get_cache_directory())
         .progress_bar(Some(ProgressBar::Light))
+        .max_retries(0)
         .build().unwrap();
 }
 
@@ -134,3 +292,41 @@ fn _get_cache_directory() -> PathBuf {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn retry_policy_delay() {
+        let retry_policy = RetryPolicy::new(5, Duration::from_millis(200));
+        assert_eq!(retry_policy.delay(1), Duration::from_millis(200));
+        assert_eq!(retry_policy.delay(2), Duration::from_millis(400));
+        assert_eq!(retry_policy.delay(4), Duration::from_millis(1600));
+        assert_eq!(RetryPolicy::no_retry().delay(1), Duration::ZERO);
+    }
+
+    #[test]
+    fn retry_policy_is_transient() {
+        for status_code in [500, 502, 503, 504] {
+            assert!(RetryPolicy::is_transient_status(status_code));
+        }
+        for status_code in [400, 401, 403, 404] {
+            assert!(!RetryPolicy::is_transient_status(status_code));
+        }
+        assert!(!RetryPolicy::is_transient(
+            &cached_path::Error::ResourceNotFound(String::from("config.json"))
+        ));
+        assert!(!RetryPolicy::is_transient(&cached_path::Error::InvalidUrl(
+            String::from("config.json")
+        )));
+    }
+
+    #[test]
+    fn remote_resource_retry_policy() {
+        let resource = RemoteResource::new("http://config_json_location", "configs");
+        assert_eq!(resource.retry_policy(), RetryPolicy::default());
+        let resource = resource.with_retry_policy(RetryPolicy::no_retry());
+        assert_eq!(resource.retry_policy(), RetryPolicy::no_retry());
+    }
+}
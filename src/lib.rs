@@ -57,6 +57,7 @@
 //!DeBERTa|✅|✅|✅| | | |✅| |
 //!DeBERTa (v2)|✅|✅|✅| | | |✅| |
 //!FNet|✅|✅|✅| | | |✅| |
+//!Funnel Transformer|✅| | | | | | | |
 //!BERT|✅|✅|✅| | | |✅| ✅|
 //!RoBERTa|✅|✅|✅| | | |✅| ✅|
 //!GPT| | | |✅ | | | |  |
@@ -701,6 +702,15 @@
 //!
 //! It is recommended to spawn a separate thread for the models. The `async-sentiment` example displays a possible solution you could use to integrate models into async code.
 //!
+//! ## Concurrent GPU inference
+//!
+//! The models can be shared between threads (e.g. behind an `Arc`) and `predict` called concurrently. On GPU, all
+//! the kernels are however submitted to the default CUDA stream of the device, and concurrent requests are executed
+//! sequentially by the device. The `tch` bindings this crate builds on do not expose CUDA streams (creation of
+//! per-thread streams or stream guards), so overlapping independent requests on a single model is not supported.
+//! To increase throughput, prefer batching concurrent requests together (e.g. with a queue collecting inputs for a
+//! single `predict` call), or load one model per GPU when several devices are available.
+//!
 //!
 //! ## Citation
 //!
@@ -735,7 +745,7 @@ pub use common::error::RustBertError;
 pub use common::resources;
 pub use common::{Activation, Config};
 pub use models::{
-    albert, bart, bert, deberta, deberta_v2, distilbert, electra, fnet, gpt2, gpt_j, gpt_neo,
-    longformer, longt5, m2m_100, marian, mbart, mobilebert, nllb, openai_gpt, pegasus, prophetnet,
-    reformer, roberta, t5, xlnet,
+    albert, bart, bert, deberta, deberta_v2, distilbert, electra, fnet, funnel, gpt2, gpt_j,
+    gpt_neo, longformer, longt5, m2m_100, marian, mbart, mobilebert, nllb, openai_gpt, pegasus,
+    prophetnet, reformer, roberta, t5, xlnet,
 };
@@ -611,6 +611,7 @@ impl AlbertForSequenceClassification {
             .unwrap();
         let logits = base_model_output
             .pooled_output
+            .to_kind(self.classifier.ws.kind())
             .apply_t(&self.dropout, train)
             .apply(&self.classifier);
         AlbertSequenceClassificationOutput {
@@ -177,8 +177,8 @@ impl AlbertTransformer {
         );
 
         let mut layers: Vec<AlbertLayerGroup> = vec![];
-        for layer_index in 0..config.inner_group_num {
-            layers.push(AlbertLayerGroup::new(&p_layers / layer_index, config));
+        for group_index in 0..config.num_hidden_groups {
+            layers.push(AlbertLayerGroup::new(&p_layers / group_index, config));
         }
 
         AlbertTransformer {
@@ -211,7 +211,9 @@ impl AlbertTransformer {
         };
 
         for i in 0..self.num_hidden_layers {
-            let group_idx = i / (self.num_hidden_layers / self.num_hidden_groups);
+            // Layers are evenly distributed across the shared groups (matching the reference implementation when
+            // the number of layers is not a multiple of the number of groups)
+            let group_idx = i * self.num_hidden_groups / self.num_hidden_layers;
             let layer = &self.layers[group_idx as usize];
 
             if let Some(hidden_states) = all_hidden_states.borrow_mut() {
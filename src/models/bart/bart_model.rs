@@ -862,20 +862,15 @@ impl BartForSequenceClassification {
             None,
             train,
         );
-        let eos_mask = input_ids.eq(self.eos_token_id);
-        let reshape = eos_mask.sum_dim_intlist([1].as_slice(), true, input_ids.kind());
+        let hidden_size = *base_model_output.decoder_output.size().last().unwrap();
+        let representation_positions = self
+            .representation_positions(input_ids, attention_mask)
+            .view([-1, 1, 1])
+            .expand([-1, 1, hidden_size], true);
         let sentence_representation = base_model_output
             .decoder_output
-            .permute([2, 0, 1])
-            .masked_select(&eos_mask)
-            .view((-1, reshape.size()[0] * reshape.int64_value(&[0, 0])))
-            .transpose(0, 1)
-            .view((
-                base_model_output.decoder_output.size()[0],
-                -1,
-                *base_model_output.decoder_output.size().last().unwrap(),
-            ))
-            .select(1, -1);
+            .gather(1, &representation_positions, false)
+            .squeeze_dim(1);
 
         let logits = self
             .classification_head
@@ -890,6 +885,39 @@ impl BartForSequenceClassification {
             all_encoder_attentions: base_model_output.all_encoder_attentions,
         }
     }
+
+    /// Returns the id of the EOS token whose hidden state is used as the sentence representation
+    pub fn eos_token_id(&self) -> i64 {
+        self.eos_token_id
+    }
+
+    /// Positions of the token used as sentence representation for each input: the last EOS token, or the
+    /// last non-padded token for inputs without EOS (e.g. truncated without special tokens).
+    fn representation_positions(
+        &self,
+        input_ids: &Tensor,
+        attention_mask: Option<&Tensor>,
+    ) -> Tensor {
+        let (batch_size, sequence_length) = (input_ids.size()[0], input_ids.size()[1]);
+        let eos_mask = input_ids.eq(self.eos_token_id).to_kind(Kind::Int64);
+        let has_eos = eos_mask
+            .sum_dim_intlist([1].as_slice(), false, Kind::Int64)
+            .gt(0);
+        let last_eos_positions = (eos_mask
+            * Tensor::arange(sequence_length, (Kind::Int64, input_ids.device())))
+        .argmax(1, false);
+        let last_token_positions = match attention_mask {
+            Some(attention_mask) => {
+                attention_mask.sum_dim_intlist([1].as_slice(), false, Kind::Int64) - 1
+            }
+            None => Tensor::full(
+                [batch_size],
+                sequence_length - 1,
+                (Kind::Int64, input_ids.device()),
+            ),
+        };
+        last_eos_positions.where_self(&has_eos, &last_token_positions)
+    }
 }
 
 /// Container holding a BART model output. The decoder output may hold the hidden state of
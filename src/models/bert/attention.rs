@@ -12,10 +12,14 @@
 // limitations under the License.
 
 use crate::bert::bert_model::BertConfig;
-use crate::common::activations::TensorFunction;
+use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
+use crate::common::fallible::{f_layer_norm, f_linear};
 use std::borrow::Borrow;
-use tch::{nn, Tensor};
+use tch::{nn, TchError, Tensor};
+
+/// Epsilon of the layer normalizations of BERT models
+pub(crate) const LAYER_NORM_EPS: f64 = 1e-12;
 
 #[derive(Debug)]
 pub struct BertSelfAttention {
@@ -74,34 +78,38 @@ impl BertSelfAttention {
         }
     }
 
-    fn split_heads(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Tensor {
-        x.view((bs, -1, self.num_attention_heads, dim_per_head))
-            .transpose(1, 2)
+    fn split_heads(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Result<Tensor, TchError> {
+        x.f_view((bs, -1, self.num_attention_heads, dim_per_head))?
+            .f_transpose(1, 2)
     }
 
-    fn flatten(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Tensor {
-        x.transpose(1, 2)
-            .contiguous()
-            .view((bs, -1, self.num_attention_heads * dim_per_head))
+    fn flatten(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Result<Tensor, TchError> {
+        x.f_transpose(1, 2)?.f_contiguous()?.f_view((
+            bs,
+            -1,
+            self.num_attention_heads * dim_per_head,
+        ))
     }
 
-    pub fn forward_t(
+    /// Forward pass, returning the errors of the tensor operations (e.g. a device running out of
+    /// memory) instead of panicking
+    pub fn f_forward_t(
         &self,
         hidden_states: &Tensor,
         mask: Option<&Tensor>,
         encoder_hidden_states: Option<&Tensor>,
         encoder_mask: Option<&Tensor>,
         train: bool,
-    ) -> (Tensor, Option<Tensor>) {
+    ) -> Result<(Tensor, Option<Tensor>), TchError> {
         let (key_layer, value_layer, mask) = match encoder_hidden_states {
             Some(encoder_hidden_state_values) => (
-                encoder_hidden_state_values.apply(&self.key),
-                encoder_hidden_state_values.apply(&self.value),
+                f_linear(&self.key, encoder_hidden_state_values)?,
+                f_linear(&self.value, encoder_hidden_state_values)?,
                 encoder_mask,
             ),
             None => (
-                hidden_states.apply(&self.key),
-                hidden_states.apply(&self.value),
+                f_linear(&self.key, hidden_states)?,
+                f_linear(&self.value, hidden_states)?,
                 mask,
             ),
         };
@@ -109,29 +117,34 @@ impl BertSelfAttention {
         let bs = hidden_states.size()[0];
 
         let query_layer = self.split_heads(
-            hidden_states.apply(&self.query),
+            f_linear(&self.query, hidden_states)?,
             bs,
             self.attention_head_size,
-        );
-        let key_layer = self.split_heads(key_layer, bs, self.attention_head_size);
-        let value_layer = self.split_heads(value_layer, bs, self.attention_head_size);
-        let query_layer: Tensor = query_layer / (self.attention_head_size as f64).sqrt();
+        )?;
+        let key_layer = self.split_heads(key_layer, bs, self.attention_head_size)?;
+        let value_layer = self.split_heads(value_layer, bs, self.attention_head_size)?;
+        let query_layer = query_layer.f_div_scalar((self.attention_head_size as f64).sqrt())?;
 
+        let scores = query_layer.f_matmul(&key_layer.f_transpose(-1, -2)?)?;
         let scores = if let Some(mask) = mask {
-            query_layer.matmul(&key_layer.transpose(-1, -2)) + mask
+            scores.f_add(mask)?
         } else {
-            query_layer.matmul(&key_layer.transpose(-1, -2))
+            scores
         };
 
-        let weights = scores
-            .softmax(-1, scores.kind())
-            .apply_t(&self.dropout, train);
-        let context = self.flatten(weights.matmul(&value_layer), bs, self.attention_head_size);
+        let weights = self
+            .dropout
+            .f_forward_t(&scores.f_softmax(-1, scores.kind())?, train)?;
+        let context = self.flatten(
+            weights.f_matmul(&value_layer)?,
+            bs,
+            self.attention_head_size,
+        )?;
 
         if !self.output_attentions {
-            (context, None)
+            Ok((context, None))
         } else {
-            (context, Some(weights))
+            Ok((context, Some(weights)))
         }
     }
 }
@@ -157,7 +170,7 @@ impl BertSelfOutput {
             Default::default(),
         );
         let layer_norm_config = nn::LayerNormConfig {
-            eps: 1e-12,
+            eps: LAYER_NORM_EPS,
             ..Default::default()
         };
         let layer_norm =
@@ -171,12 +184,21 @@ impl BertSelfOutput {
         }
     }
 
-    pub fn forward_t(&self, hidden_states: &Tensor, input_tensor: &Tensor, train: bool) -> Tensor {
-        let hidden_states: Tensor = input_tensor
-            + hidden_states
-                .apply(&self.linear)
-                .apply_t(&self.dropout, train);
-        hidden_states.apply(&self.layer_norm)
+    /// Forward pass, returning the errors of the tensor operations instead of panicking
+    pub fn f_forward_t(
+        &self,
+        hidden_states: &Tensor,
+        input_tensor: &Tensor,
+        train: bool,
+    ) -> Result<Tensor, TchError> {
+        let hidden_states = self
+            .dropout
+            .f_forward_t(&f_linear(&self.linear, hidden_states)?, train)?;
+        f_layer_norm(
+            &self.layer_norm,
+            &input_tensor.f_add(&hidden_states)?,
+            LAYER_NORM_EPS,
+        )
     }
 }
 
@@ -198,30 +220,33 @@ impl BertAttention {
         BertAttention { _self, output }
     }
 
-    pub fn forward_t(
+    /// Forward pass, returning the errors of the tensor operations instead of panicking
+    pub fn f_forward_t(
         &self,
         hidden_states: &Tensor,
         mask: Option<&Tensor>,
         encoder_hidden_states: Option<&Tensor>,
         encoder_mask: Option<&Tensor>,
         train: bool,
-    ) -> (Tensor, Option<Tensor>) {
-        let (self_output, attention_weights) = self._self.forward_t(
+    ) -> Result<(Tensor, Option<Tensor>), TchError> {
+        let (self_output, attention_weights) = self._self.f_forward_t(
             hidden_states,
             mask,
             encoder_hidden_states,
             encoder_mask,
             train,
-        );
+        )?;
 
-        let self_output = self.output.forward_t(&self_output, hidden_states, train);
-        (self_output, attention_weights)
+        let self_output = self
+            .output
+            .f_forward_t(&self_output, hidden_states, train)?;
+        Ok((self_output, attention_weights))
     }
 }
 
 pub struct BertIntermediate {
     lin: nn::Linear,
-    activation: TensorFunction,
+    activation: Activation,
 }
 
 impl BertIntermediate {
@@ -237,12 +262,14 @@ impl BertIntermediate {
             config.intermediate_size,
             Default::default(),
         );
-        let activation = config.hidden_act.get_function();
+        let activation = config.hidden_act;
         BertIntermediate { lin, activation }
     }
 
-    pub fn forward(&self, hidden_states: &Tensor) -> Tensor {
-        (self.activation.get_fn())(&hidden_states.apply(&self.lin))
+    /// Forward pass, returning the errors of the tensor operations instead of panicking
+    pub fn f_forward(&self, hidden_states: &Tensor) -> Result<Tensor, TchError> {
+        self.activation
+            .f_apply(&f_linear(&self.lin, hidden_states)?)
     }
 }
 
@@ -266,7 +293,7 @@ impl BertOutput {
             Default::default(),
         );
         let layer_norm_config = nn::LayerNormConfig {
-            eps: 1e-12,
+            eps: LAYER_NORM_EPS,
             ..Default::default()
         };
         let layer_norm =
@@ -280,9 +307,20 @@ impl BertOutput {
         }
     }
 
-    pub fn forward_t(&self, hidden_states: &Tensor, input_tensor: &Tensor, train: bool) -> Tensor {
-        let hidden_states: Tensor =
-            input_tensor + hidden_states.apply(&self.lin).apply_t(&self.dropout, train);
-        hidden_states.apply(&self.layer_norm)
+    /// Forward pass, returning the errors of the tensor operations instead of panicking
+    pub fn f_forward_t(
+        &self,
+        hidden_states: &Tensor,
+        input_tensor: &Tensor,
+        train: bool,
+    ) -> Result<Tensor, TchError> {
+        let hidden_states = self
+            .dropout
+            .f_forward_t(&f_linear(&self.lin, hidden_states)?, train)?;
+        f_layer_norm(
+            &self.layer_norm,
+            &input_tensor.f_add(&hidden_states)?,
+            LAYER_NORM_EPS,
+        )
     }
 }
@@ -15,7 +15,9 @@ use crate::bert::encoder::{BertEncoder, BertPooler};
 use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
 use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::fallible::{f_copy, f_linear};
 use crate::common::linear::{linear_no_bias, LinearNoBias};
+use crate::pipelines::sequence_classification::RepresentationStrategy;
 use crate::{
     bert::embeddings::{BertEmbedding, BertEmbeddings},
     common::activations::TensorFunction,
@@ -293,6 +295,17 @@ impl<T: BertEmbedding> BertModel<T> {
         }
     }
 
+    /// Computes the output of the embeddings layer (input of the first encoder layer) for the input ids,
+    /// with the default token type and position ids.
+    pub(crate) fn embeddings_forward_t(
+        &self,
+        input_ids: &Tensor,
+        train: bool,
+    ) -> Result<Tensor, RustBertError> {
+        self.embeddings
+            .forward_t(Some(input_ids), None, None, None, train)
+    }
+
     /// Forward pass through the model
     ///
     /// # Arguments
@@ -362,23 +375,27 @@ impl<T: BertEmbedding> BertModel<T> {
         let (input_shape, device) =
             get_shape_and_device_from_ids_embeddings_pair(input_ids, input_embeds)?;
 
-        let calc_mask = Tensor::ones(&input_shape, (Kind::Int8, device));
-        let mask = mask.unwrap_or(&calc_mask);
+        let calc_mask = match mask {
+            Some(_) => None,
+            None => Some(Tensor::f_ones(&input_shape, (Kind::Int8, device))?),
+        };
+        let mask = mask.unwrap_or_else(|| calc_mask.as_ref().unwrap());
 
         let extended_attention_mask = match mask.dim() {
-            3 => mask.unsqueeze(1),
+            3 => mask.f_unsqueeze(1)?,
             2 => {
                 if self.is_decoder {
-                    let seq_ids = Tensor::arange(input_shape[1], (Kind::Int8, device));
-                    let causal_mask = seq_ids.unsqueeze(0).unsqueeze(0).repeat([
+                    let seq_ids = Tensor::f_arange(input_shape[1], (Kind::Int8, device))?;
+                    let causal_mask = seq_ids.f_unsqueeze(0)?.f_unsqueeze(0)?.f_repeat([
                         input_shape[0],
                         input_shape[1],
                         1,
-                    ]);
-                    let causal_mask = causal_mask.le_tensor(&seq_ids.unsqueeze(0).unsqueeze(-1));
-                    causal_mask * mask.unsqueeze(1).unsqueeze(1)
+                    ])?;
+                    let causal_mask =
+                        causal_mask.f_le_tensor(&seq_ids.f_unsqueeze(0)?.f_unsqueeze(-1)?)?;
+                    causal_mask.f_mul(&mask.f_unsqueeze(1)?.f_unsqueeze(1)?)?
                 } else {
-                    mask.unsqueeze(1).unsqueeze(1)
+                    mask.f_unsqueeze(1)?.f_unsqueeze(1)?
                 }
             }
             _ => {
@@ -396,27 +413,29 @@ impl<T: BertEmbedding> BertModel<T> {
             train,
         )?;
 
-        let extended_attention_mask: Tensor =
-            ((extended_attention_mask.ones_like() - extended_attention_mask) * -10000.0)
-                .to_kind(embedding_output.kind());
+        let extended_attention_mask = extended_attention_mask
+            .f_ones_like()?
+            .f_sub(&extended_attention_mask)?
+            .f_mul_scalar(-10000.0)?
+            .f_to_kind(embedding_output.kind())?;
 
         let encoder_extended_attention_mask: Option<Tensor> =
             if self.is_decoder & encoder_hidden_states.is_some() {
                 let encoder_hidden_states = encoder_hidden_states.as_ref().unwrap();
                 let encoder_hidden_states_shape = encoder_hidden_states.size();
                 let encoder_mask = match encoder_mask {
-                    Some(value) => value.copy(),
-                    None => Tensor::ones(
+                    Some(value) => f_copy(value)?,
+                    None => Tensor::f_ones(
                         [
                             encoder_hidden_states_shape[0],
                             encoder_hidden_states_shape[1],
                         ],
                         (Kind::Int8, device),
-                    ),
+                    )?,
                 };
                 match encoder_mask.dim() {
-                    2 => Some(encoder_mask.unsqueeze(1).unsqueeze(1)),
-                    3 => Some(encoder_mask.unsqueeze(1)),
+                    2 => Some(encoder_mask.f_unsqueeze(1)?.f_unsqueeze(1)?),
+                    3 => Some(encoder_mask.f_unsqueeze(1)?),
                     _ => {
                         return Err(RustBertError::ValueError(
                             "Invalid attention mask dimension, must be 2 or 3".into(),
@@ -427,18 +446,19 @@ impl<T: BertEmbedding> BertModel<T> {
                 None
             };
 
-        let encoder_output = self.encoder.forward_t(
+        let encoder_output = self.encoder.f_forward_t(
             &embedding_output,
             Some(&extended_attention_mask),
             encoder_hidden_states,
             encoder_extended_attention_mask.as_ref(),
             train,
-        );
+        )?;
 
         let pooled_output = self
             .pooler
             .as_ref()
-            .map(|pooler| pooler.forward(&encoder_output.hidden_state));
+            .map(|pooler| pooler.f_forward(&encoder_output.hidden_state))
+            .transpose()?;
 
         Ok(BertModelOutput {
             hidden_state: encoder_output.hidden_state,
@@ -656,10 +676,12 @@ impl BertForMaskedLM {
 /// It is made of the following blocks:
 /// - `bert`: Base BertModel
 /// - `classifier`: BERT linear layer for classification
+/// - `token_pooling`: Optional strategy selecting the token hidden states the classifier is applied to instead of the pooled output
 pub struct BertForSequenceClassification {
     bert: BertModel<BertEmbeddings>,
     dropout: Dropout,
     classifier: nn::Linear,
+    token_pooling: Option<RepresentationStrategy>,
 }
 
 impl BertForSequenceClassification {
@@ -715,9 +737,23 @@ impl BertForSequenceClassification {
             bert,
             dropout,
             classifier,
+            token_pooling: None,
         })
     }
 
+    /// Sets if the classifier is applied to the hidden state of the first (*CLS*) token instead of the pooled
+    /// output, for checkpoints saved without the pooler weights. The pooler layer is then unused.
+    pub fn set_cls_pooling(&mut self, cls_pooling: bool) {
+        self.token_pooling = cls_pooling.then_some(RepresentationStrategy::First);
+    }
+
+    /// Sets the strategy selecting the token hidden states the classifier is applied to instead of the pooled
+    /// output (first token, last non-padding token or mean of the non-padding tokens). The pooler layer is unused
+    /// if a strategy is set.
+    pub fn set_token_pooling(&mut self, token_pooling: Option<RepresentationStrategy>) {
+        self.token_pooling = token_pooling;
+    }
+
     /// Forward pass through the model
     ///
     /// # Arguments
@@ -775,30 +811,52 @@ impl BertForSequenceClassification {
         input_embeds: Option<&Tensor>,
         train: bool,
     ) -> BertSequenceClassificationOutput {
-        let base_model_output = self
-            .bert
-            .forward_t(
-                input_ids,
-                mask,
-                token_type_ids,
-                position_ids,
-                input_embeds,
-                None,
-                None,
-                train,
-            )
-            .unwrap();
+        self.f_forward_t(
+            input_ids,
+            mask,
+            token_type_ids,
+            position_ids,
+            input_embeds,
+            train,
+        )
+        .unwrap()
+    }
 
-        let logits = base_model_output
-            .pooled_output
-            .unwrap()
-            .apply_t(&self.dropout, train)
-            .apply(&self.classifier);
-        BertSequenceClassificationOutput {
+    /// Fallible version of `forward_t`, returning the errors of the model and of the tensor operations (e.g. a device
+    /// running out of memory) instead of panicking
+    pub fn f_forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        position_ids: Option<&Tensor>,
+        input_embeds: Option<&Tensor>,
+        train: bool,
+    ) -> Result<BertSequenceClassificationOutput, RustBertError> {
+        let base_model_output = self.bert.forward_t(
+            input_ids,
+            mask,
+            token_type_ids,
+            position_ids,
+            input_embeds,
+            None,
+            None,
+            train,
+        )?;
+
+        let pooled_output = match self.token_pooling {
+            Some(strategy) => strategy.pool(&base_model_output.hidden_state, mask)?,
+            None => base_model_output.pooled_output.unwrap(),
+        };
+        let pooled_output = self
+            .dropout
+            .f_forward_t(&pooled_output.f_to_kind(self.classifier.ws.kind())?, train)?;
+        let logits = f_linear(&self.classifier, &pooled_output)?;
+        Ok(BertSequenceClassificationOutput {
             logits,
             all_hidden_states: base_model_output.all_hidden_states,
             all_attentions: base_model_output.all_attentions,
-        }
+        })
     }
 }
 
@@ -11,14 +11,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::bert::attention::LAYER_NORM_EPS;
 use crate::bert::bert_model::BertConfig;
 use crate::common::dropout::Dropout;
-use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::fallible::{f_embedding, f_layer_norm};
 use crate::RustBertError;
 use std::borrow::Borrow;
 use tch::nn::{embedding, EmbeddingConfig};
 use tch::{nn, Kind, Tensor};
 
+/// Padding index of the BERT word embeddings
+const WORD_EMBEDDINGS_PADDING_INDEX: i64 = 0;
+
 /// # BertEmbedding trait (for use in BertModel or RoBERTaModel)
 /// Defines an interface for the embedding layers in BERT-based models
 pub trait BertEmbedding {
@@ -76,7 +81,7 @@ impl BertEmbedding for BertEmbeddings {
         let p = p.borrow();
 
         let embedding_config = EmbeddingConfig {
-            padding_idx: 0,
+            padding_idx: WORD_EMBEDDINGS_PADDING_INDEX,
             ..Default::default()
         };
 
@@ -102,7 +107,7 @@ impl BertEmbedding for BertEmbeddings {
         );
 
         let layer_norm_config = nn::LayerNormConfig {
-            eps: 1e-12,
+            eps: LAYER_NORM_EPS,
             ..Default::default()
         };
         let layer_norm: nn::LayerNorm =
@@ -171,8 +176,17 @@ impl BertEmbedding for BertEmbeddings {
         input_embeds: Option<&Tensor>,
         train: bool,
     ) -> Result<Tensor, RustBertError> {
-        let (calc_input_embeddings, input_shape, _) =
-            process_ids_embeddings_pair(input_ids, input_embeds, &self.word_embeddings)?;
+        let (input_shape, device) =
+            get_shape_and_device_from_ids_embeddings_pair(input_ids, input_embeds)?;
+        let calc_input_embeddings = input_ids
+            .map(|input_ids| {
+                f_embedding(
+                    &self.word_embeddings,
+                    input_ids,
+                    WORD_EMBEDDINGS_PADDING_INDEX,
+                )
+            })
+            .transpose()?;
 
         let input_embeddings =
             input_embeds.unwrap_or_else(|| calc_input_embeddings.as_ref().unwrap());
@@ -180,19 +194,16 @@ impl BertEmbedding for BertEmbeddings {
 
         let calc_position_ids = if position_ids.is_none() {
             Some(
-                Tensor::arange(seq_length, (Kind::Int64, input_embeddings.device()))
-                    .unsqueeze(0)
-                    .expand(&input_shape, true),
+                Tensor::f_arange(seq_length, (Kind::Int64, device))?
+                    .f_unsqueeze(0)?
+                    .f_expand(&input_shape, true)?,
             )
         } else {
             None
         };
 
         let calc_token_type_ids = if token_type_ids.is_none() {
-            Some(Tensor::zeros(
-                &input_shape,
-                (Kind::Int64, input_embeddings.device()),
-            ))
+            Some(Tensor::f_zeros(&input_shape, (Kind::Int64, device))?)
         } else {
             None
         };
@@ -201,13 +212,15 @@ impl BertEmbedding for BertEmbeddings {
         let token_type_ids =
             token_type_ids.unwrap_or_else(|| calc_token_type_ids.as_ref().unwrap());
 
-        let position_embeddings = position_ids.apply(&self.position_embeddings);
-        let token_type_embeddings = token_type_ids.apply(&self.token_type_embeddings);
+        let position_embeddings = f_embedding(&self.position_embeddings, position_ids, -1)?;
+        let token_type_embeddings = f_embedding(&self.token_type_embeddings, token_type_ids, -1)?;
 
-        let input_embeddings: Tensor =
-            input_embeddings + position_embeddings + token_type_embeddings;
-        Ok(input_embeddings
-            .apply(&self.layer_norm)
-            .apply_t(&self.dropout, train))
+        let input_embeddings = input_embeddings
+            .f_add(&position_embeddings)?
+            .f_add(&token_type_embeddings)?;
+        Ok(self.dropout.f_forward_t(
+            &f_layer_norm(&self.layer_norm, &input_embeddings, LAYER_NORM_EPS)?,
+            train,
+        )?)
     }
 }
@@ -13,8 +13,9 @@
 
 use crate::bert::attention::{BertAttention, BertIntermediate, BertOutput};
 use crate::bert::bert_model::BertConfig;
+use crate::common::fallible::{f_copy, f_linear};
 use std::borrow::{Borrow, BorrowMut};
-use tch::{nn, Tensor};
+use tch::{nn, TchError, Tensor};
 
 /// # BERT Layer
 /// Layer used in BERT encoders.
@@ -133,33 +134,53 @@ impl BertLayer {
         encoder_mask: Option<&Tensor>,
         train: bool,
     ) -> BertLayerOutput {
+        self.f_forward_t(
+            hidden_states,
+            mask,
+            encoder_hidden_states,
+            encoder_mask,
+            train,
+        )
+        .unwrap()
+    }
+
+    /// Fallible version of `forward_t`, returning the errors of the tensor operations (e.g. a device running out of
+    /// memory) instead of panicking
+    pub fn f_forward_t(
+        &self,
+        hidden_states: &Tensor,
+        mask: Option<&Tensor>,
+        encoder_hidden_states: Option<&Tensor>,
+        encoder_mask: Option<&Tensor>,
+        train: bool,
+    ) -> Result<BertLayerOutput, TchError> {
         let (attention_output, attention_weights) =
             self.attention
-                .forward_t(hidden_states, mask, None, None, train);
+                .f_forward_t(hidden_states, mask, None, None, train)?;
 
         let (attention_output, attention_scores, cross_attention_scores) =
             if self.is_decoder & encoder_hidden_states.is_some() {
                 let (attention_output, cross_attention_weights) =
-                    self.cross_attention.as_ref().unwrap().forward_t(
+                    self.cross_attention.as_ref().unwrap().f_forward_t(
                         &attention_output,
                         mask,
                         encoder_hidden_states,
                         encoder_mask,
                         train,
-                    );
+                    )?;
                 (attention_output, attention_weights, cross_attention_weights)
             } else {
                 (attention_output, attention_weights, None)
             };
 
-        let output = self.intermediate.forward(&attention_output);
-        let output = self.output.forward_t(&output, &attention_output, train);
+        let output = self.intermediate.f_forward(&attention_output)?;
+        let output = self.output.f_forward_t(&output, &attention_output, train)?;
 
-        BertLayerOutput {
+        Ok(BertLayerOutput {
             hidden_state: output,
             attention_weights: attention_scores,
             cross_attention_weights: cross_attention_scores,
-        }
+        })
     }
 }
 
@@ -262,6 +283,20 @@ impl BertEncoder {
         encoder_mask: Option<&Tensor>,
         train: bool,
     ) -> BertEncoderOutput {
+        self.f_forward_t(input, mask, encoder_hidden_states, encoder_mask, train)
+            .unwrap()
+    }
+
+    /// Fallible version of `forward_t`, returning the errors of the tensor operations (e.g. a device running out of
+    /// memory) instead of panicking
+    pub fn f_forward_t(
+        &self,
+        input: &Tensor,
+        mask: Option<&Tensor>,
+        encoder_hidden_states: Option<&Tensor>,
+        encoder_mask: Option<&Tensor>,
+        train: bool,
+    ) -> Result<BertEncoderOutput, TchError> {
         let mut all_hidden_states: Option<Vec<Tensor>> = if self.output_hidden_states {
             Some(vec![])
         } else {
@@ -278,15 +313,15 @@ impl BertEncoder {
 
         for layer in &self.layers {
             let layer_output = if let Some(hidden_state) = &hidden_state {
-                layer.forward_t(
+                layer.f_forward_t(
                     hidden_state,
                     mask,
                     encoder_hidden_states,
                     encoder_mask,
                     train,
-                )
+                )?
             } else {
-                layer.forward_t(input, mask, encoder_hidden_states, encoder_mask, train)
+                layer.f_forward_t(input, mask, encoder_hidden_states, encoder_mask, train)?
             };
 
             hidden_state = Some(layer_output.hidden_state);
@@ -295,15 +330,15 @@ impl BertEncoder {
                 attentions.push(std::mem::take(&mut attention_weights.unwrap()));
             };
             if let Some(hidden_states) = all_hidden_states.borrow_mut() {
-                hidden_states.push(hidden_state.as_ref().unwrap().copy());
+                hidden_states.push(f_copy(hidden_state.as_ref().unwrap())?);
             };
         }
 
-        BertEncoderOutput {
+        Ok(BertEncoderOutput {
             hidden_state: hidden_state.unwrap(),
             all_hidden_states,
             all_attentions,
-        }
+        })
     }
 }
 
@@ -382,7 +417,12 @@ impl BertPooler {
     /// let pooler_output = no_grad(|| pooler.forward(&input_tensor));
     /// ```
     pub fn forward(&self, hidden_states: &Tensor) -> Tensor {
-        hidden_states.select(1, 0).apply(&self.lin).tanh()
+        self.f_forward(hidden_states).unwrap()
+    }
+
+    /// Fallible version of `forward`, returning the errors of the tensor operations instead of panicking
+    pub fn f_forward(&self, hidden_states: &Tensor) -> Result<Tensor, TchError> {
+        f_linear(&self.lin, &hidden_states.f_select(1, 0)?)?.f_tanh()
     }
 }
 
@@ -11,9 +11,10 @@
 // limitations under the License.
 
 use crate::common::dropout::Dropout;
+use crate::common::fallible::f_linear;
 use crate::distilbert::distilbert_model::DistilBertConfig;
 use std::borrow::Borrow;
-use tch::{nn, Tensor};
+use tch::{nn, TchError, Tensor};
 
 #[derive(Debug)]
 pub struct MultiHeadSelfAttention {
@@ -53,54 +54,58 @@ impl MultiHeadSelfAttention {
         }
     }
 
-    fn split_heads(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Tensor {
-        x.view((bs, -1, self.n_heads, dim_per_head)).transpose(1, 2)
+    fn split_heads(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Result<Tensor, TchError> {
+        x.f_view((bs, -1, self.n_heads, dim_per_head))?
+            .f_transpose(1, 2)
     }
 
-    fn flatten(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Tensor {
-        x.transpose(1, 2)
-            .contiguous()
-            .view((bs, -1, self.n_heads * dim_per_head))
+    fn flatten(&self, x: Tensor, bs: i64, dim_per_head: i64) -> Result<Tensor, TchError> {
+        x.f_transpose(1, 2)?
+            .f_contiguous()?
+            .f_view((bs, -1, self.n_heads * dim_per_head))
     }
 
-    pub fn forward_t(
+    /// Forward pass, returning the errors of the tensor operations (e.g. a device running out of
+    /// memory) instead of panicking
+    pub fn f_forward_t(
         &self,
         query: &Tensor,
         key: &Tensor,
         value: &Tensor,
         mask: Option<&Tensor>,
         train: bool,
-    ) -> (Tensor, Option<Tensor>) {
+    ) -> Result<(Tensor, Option<Tensor>), TchError> {
         let bs = query.size()[0];
         let k_length = key.size()[1];
 
-        let q = self.split_heads(query.apply(&self.q_lin), bs, self.dim_per_head);
-        let k = self.split_heads(key.apply(&self.k_lin), bs, self.dim_per_head);
-        let v = self.split_heads(value.apply(&self.v_lin), bs, self.dim_per_head);
-        let q: Tensor = q / (self.dim_per_head as f64).sqrt();
+        let q = self.split_heads(f_linear(&self.q_lin, query)?, bs, self.dim_per_head)?;
+        let k = self.split_heads(f_linear(&self.k_lin, key)?, bs, self.dim_per_head)?;
+        let v = self.split_heads(f_linear(&self.v_lin, value)?, bs, self.dim_per_head)?;
+        let q = q.f_div_scalar((self.dim_per_head as f64).sqrt())?;
 
+        let scores = q.f_matmul(&k.f_transpose(2, 3)?)?;
         let scores = if let Some(mask) = mask {
-            let unmasked_scores = q.matmul(&k.transpose(2, 3));
             let mask = mask
-                .le_tensor(&(mask.zeros_like() + 0.1))
-                .view((bs, 1i64, 1i64, k_length))
-                .expand_as(&unmasked_scores);
-            unmasked_scores.masked_fill(&mask, f64::NEG_INFINITY)
+                .f_le_tensor(&mask.f_zeros_like()?.f_add_scalar(0.1)?)?
+                .f_view((bs, 1i64, 1i64, k_length))?
+                .f_expand_as(&scores)?;
+            scores.f_masked_fill(&mask, f64::NEG_INFINITY)?
         } else {
-            q.matmul(&k.transpose(2, 3))
+            scores
         };
 
-        let weights = scores
-            .softmax(-1, scores.kind())
-            .apply_t(&self.dropout, train);
-        let context = self
-            .flatten(weights.matmul(&v), bs, self.dim_per_head)
-            .apply(&self.out_lin);
+        let weights = self
+            .dropout
+            .f_forward_t(&scores.f_softmax(-1, scores.kind())?, train)?;
+        let context = f_linear(
+            &self.out_lin,
+            &self.flatten(weights.f_matmul(&v)?, bs, self.dim_per_head)?,
+        )?;
 
         if !self.output_attentions {
-            (context, None)
+            Ok((context, None))
         } else {
-            (context, Some(weights))
+            Ok((context, Some(weights)))
         }
     }
 }
@@ -15,6 +15,7 @@ extern crate tch;
 use self::tch::{nn, Tensor};
 use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
+use crate::common::fallible::f_linear;
 use crate::distilbert::embeddings::DistilBertEmbedding;
 use crate::distilbert::transformer::{DistilBertTransformerOutput, Transformer};
 use crate::{Config, RustBertError};
@@ -200,6 +201,15 @@ impl DistilBertModel {
         }
     }
 
+    /// Computes the output of the embeddings layer (input of the first transformer layer) for the input ids.
+    pub(crate) fn embeddings_forward_t(
+        &self,
+        input_ids: &Tensor,
+        train: bool,
+    ) -> Result<Tensor, RustBertError> {
+        self.embeddings.forward_t(Some(input_ids), None, train)
+    }
+
     /// Forward pass through the model
     ///
     /// # Arguments
@@ -248,7 +258,9 @@ impl DistilBertModel {
         train: bool,
     ) -> Result<DistilBertTransformerOutput, RustBertError> {
         let input_embeddings = self.embeddings.forward_t(input, input_embeds, train)?;
-        let transformer_output = self.transformer.forward_t(&input_embeddings, mask, train);
+        let transformer_output = self
+            .transformer
+            .f_forward_t(&input_embeddings, mask, train)?;
         Ok(transformer_output)
     }
 }
@@ -380,13 +392,15 @@ impl DistilBertModelClassifier {
             self.distil_bert_model
                 .forward_t(input, mask, input_embeds, train)?;
 
-        let logits = base_model_output
+        let hidden_state = base_model_output
             .hidden_state
-            .select(1, 0)
-            .apply(&self.pre_classifier)
-            .relu()
-            .apply_t(&self.dropout, train)
-            .apply(&self.classifier);
+            .f_select(1, 0)?
+            .f_to_kind(self.pre_classifier.ws.kind())?;
+        let hidden_state = self.dropout.f_forward_t(
+            &f_linear(&self.pre_classifier, &hidden_state)?.f_relu()?,
+            train,
+        )?;
+        let logits = f_linear(&self.classifier, &hidden_state)?;
 
         Ok(DistilBertSequenceClassificationOutput {
             logits,
@@ -11,14 +11,19 @@
 // limitations under the License.
 
 use crate::common::dropout::Dropout;
-use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::fallible::{f_embedding, f_layer_norm};
 use crate::distilbert::distilbert_model::DistilBertConfig;
+use crate::distilbert::transformer::LAYER_NORM_EPS;
 use crate::RustBertError;
 use std::borrow::Borrow;
 use tch::kind::Kind::Float;
 use tch::nn::{embedding, EmbeddingConfig, Init, VarStore};
 use tch::{nn, Device, Kind, Tensor};
 
+/// Padding index of the DistilBERT word and position embeddings
+const PADDING_INDEX: i64 = 0;
+
 fn create_sinusoidal_embeddings<'p, P>(
     config: &DistilBertConfig,
     p: P,
@@ -60,7 +65,7 @@ where
     });
 
     let embedding_config = EmbeddingConfig {
-        padding_idx: 0,
+        padding_idx: PADDING_INDEX,
         ..Default::default()
     };
     let mut embeddings = embedding(
@@ -90,7 +95,7 @@ impl DistilBertEmbedding {
         let p = p.borrow();
 
         let embedding_config = EmbeddingConfig {
-            padding_idx: 0,
+            padding_idx: PADDING_INDEX,
             ..Default::default()
         };
 
@@ -110,7 +115,7 @@ impl DistilBertEmbedding {
             true => create_sinusoidal_embeddings(config, p / "position_embeddings", p.device()),
         };
         let layer_norm_config = nn::LayerNormConfig {
-            eps: 1e-12,
+            eps: LAYER_NORM_EPS,
             ..Default::default()
         };
         let layer_norm: nn::LayerNorm =
@@ -138,20 +143,24 @@ impl DistilBertEmbedding {
         input_embeds: Option<&Tensor>,
         train: bool,
     ) -> Result<Tensor, RustBertError> {
-        let (calc_input_embeddings, input_size, device) =
-            process_ids_embeddings_pair(input_ids, input_embeds, &self.word_embeddings)?;
+        let (input_size, device) =
+            get_shape_and_device_from_ids_embeddings_pair(input_ids, input_embeds)?;
+        let calc_input_embeddings = input_ids
+            .map(|input_ids| f_embedding(&self.word_embeddings, input_ids, PADDING_INDEX))
+            .transpose()?;
         let word_embeds = input_embeds.unwrap_or_else(|| calc_input_embeddings.as_ref().unwrap());
 
         let seq_length = input_size[1];
-        let position_ids = Tensor::arange(seq_length, (Kind::Int64, device));
+        let position_ids = Tensor::f_arange(seq_length, (Kind::Int64, device))?;
         let position_ids = position_ids
-            .unsqueeze(0)
-            .expand(input_size.as_slice(), true);
-        let position_embed = position_ids.apply(&self.position_embeddings);
+            .f_unsqueeze(0)?
+            .f_expand(input_size.as_slice(), true)?;
+        let position_embed = f_embedding(&self.position_embeddings, &position_ids, PADDING_INDEX)?;
 
-        let embeddings = word_embeds + position_embed;
-        Ok(embeddings
-            .apply(&self.layer_norm)
-            .apply_t(&self.dropout, train))
+        let embeddings = word_embeds.f_add(&position_embed)?;
+        Ok(self.dropout.f_forward_t(
+            &f_layer_norm(&self.layer_norm, &embeddings, LAYER_NORM_EPS)?,
+            train,
+        )?)
     }
 }
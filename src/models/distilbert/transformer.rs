@@ -10,19 +10,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::common::activations::TensorFunction;
+use crate::common::activations::Activation;
 use crate::common::dropout::Dropout;
+use crate::common::fallible::{f_copy, f_layer_norm, f_linear};
 use crate::distilbert::attention::MultiHeadSelfAttention;
 use crate::distilbert::distilbert_model::DistilBertConfig;
 use std::borrow::{Borrow, BorrowMut};
 use tch::nn::LayerNorm;
-use tch::{nn, Tensor};
+use tch::{nn, TchError, Tensor};
+
+/// Epsilon of the DistilBERT layer normalizations
+pub(crate) const LAYER_NORM_EPS: f64 = 1e-12;
 
 pub struct FeedForwardNetwork {
     lin1: nn::Linear,
     lin2: nn::Linear,
     dropout: Dropout,
-    activation: TensorFunction,
+    activation: Activation,
 }
 
 impl FeedForwardNetwork {
@@ -44,7 +48,7 @@ impl FeedForwardNetwork {
             Default::default(),
         );
         let dropout = Dropout::new(config.dropout);
-        let activation = config.activation.get_function();
+        let activation = config.activation;
         FeedForwardNetwork {
             lin1,
             lin2,
@@ -53,10 +57,11 @@ impl FeedForwardNetwork {
         }
     }
 
-    pub fn forward_t(&self, input: &Tensor, train: bool) -> Tensor {
-        (self.activation.get_fn())(&input.apply(&self.lin1))
-            .apply(&self.lin2)
-            .apply_t(&self.dropout, train)
+    /// Forward pass, returning the errors of the tensor operations instead of panicking
+    pub fn f_forward_t(&self, input: &Tensor, train: bool) -> Result<Tensor, TchError> {
+        let hidden_states = self.activation.f_apply(&f_linear(&self.lin1, input)?)?;
+        self.dropout
+            .f_forward_t(&f_linear(&self.lin2, &hidden_states)?, train)
     }
 }
 
@@ -76,7 +81,7 @@ impl TransformerBlock {
 
         let attention = MultiHeadSelfAttention::new(p / "attention", config);
         let layer_norm_config = nn::LayerNormConfig {
-            eps: 1e-12,
+            eps: LAYER_NORM_EPS,
             ..Default::default()
         };
         let sa_layer_norm =
@@ -93,16 +98,24 @@ impl TransformerBlock {
         }
     }
 
-    pub fn forward_t(
+    /// Forward pass, returning the errors of the tensor operations (e.g. a device running out of
+    /// memory) instead of panicking
+    pub fn f_forward_t(
         &self,
         input: &Tensor,
         mask: Option<&Tensor>,
         train: bool,
-    ) -> (Tensor, Option<Tensor>) {
-        let (output, sa_weights) = self.attention.forward_t(input, input, input, mask, train);
-        let output = (input + &output).apply(&self.sa_layer_norm);
-        let output = (&output + self.ffn.forward_t(&output, train)).apply(&self.output_layer_norm);
-        (output, sa_weights)
+    ) -> Result<(Tensor, Option<Tensor>), TchError> {
+        let (output, sa_weights) = self
+            .attention
+            .f_forward_t(input, input, input, mask, train)?;
+        let output = f_layer_norm(&self.sa_layer_norm, &input.f_add(&output)?, LAYER_NORM_EPS)?;
+        let output = f_layer_norm(
+            &self.output_layer_norm,
+            &output.f_add(&self.ffn.f_forward_t(&output, train)?)?,
+            LAYER_NORM_EPS,
+        )?;
+        Ok((output, sa_weights))
     }
 }
 
@@ -133,12 +146,14 @@ impl Transformer {
         }
     }
 
-    pub fn forward_t(
+    /// Forward pass, returning the errors of the tensor operations (e.g. a device running out of
+    /// memory) instead of panicking
+    pub fn f_forward_t(
         &self,
         input: &Tensor,
         mask: Option<&Tensor>,
         train: bool,
-    ) -> DistilBertTransformerOutput {
+    ) -> Result<DistilBertTransformerOutput, TchError> {
         let mut all_hidden_states: Option<Vec<Tensor>> = if self.output_hidden_states {
             Some(vec![])
         } else {
@@ -156,9 +171,9 @@ impl Transformer {
 
         for layer in &self.layers {
             let temp = if let Some(hidden_state) = &hidden_state {
-                layer.forward_t(hidden_state, mask, train)
+                layer.f_forward_t(hidden_state, mask, train)?
             } else {
-                layer.forward_t(input, mask, train)
+                layer.f_forward_t(input, mask, train)?
             };
 
             hidden_state = Some(temp.0);
@@ -167,15 +182,15 @@ impl Transformer {
                 attentions.push(std::mem::take(&mut attention_weights.unwrap()));
             };
             if let Some(hidden_states) = all_hidden_states.borrow_mut() {
-                hidden_states.push(hidden_state.as_ref().unwrap().copy());
+                hidden_states.push(f_copy(hidden_state.as_ref().unwrap())?);
             };
         }
 
-        DistilBertTransformerOutput {
+        Ok(DistilBertTransformerOutput {
             hidden_state: hidden_state.unwrap(),
             all_hidden_states,
             all_attentions,
-        }
+        })
     }
 }
 
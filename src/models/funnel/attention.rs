@@ -0,0 +1,582 @@
+// Copyright 2020-present, the HuggingFace Inc. team, Funnel Transformer Authors
+// Copyright 2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::dropout::Dropout;
+use crate::funnel::funnel_model::{FunnelAttentionType, FunnelPoolingType};
+use crate::funnel::FunnelConfig;
+use std::borrow::Borrow;
+use tch::nn::LayerNormConfig;
+use tch::{nn, Device, Kind, Tensor};
+
+const INF: f64 = 1e6;
+const CLS_TOKEN_TYPE_ID: i64 = 2;
+
+#[derive(Clone, Copy, Debug)]
+enum PoolingMode {
+    Mean,
+    Max,
+    Min,
+}
+
+impl From<FunnelPoolingType> for PoolingMode {
+    fn from(pooling_type: FunnelPoolingType) -> Self {
+        match pooling_type {
+            FunnelPoolingType::Mean => PoolingMode::Mean,
+            FunnelPoolingType::Max => PoolingMode::Max,
+        }
+    }
+}
+
+/// Positional embeddings used by the relative attention, depending on the attention type
+pub enum FunnelPositionEmbeddings {
+    /// For each block, embeddings of the relative positions without and with pooling (the latter is
+    /// not defined for the first block, that is never pooled).
+    RelativeShift(Vec<(Tensor, Option<Tensor>)>),
+    /// Sinusoidal components `(phi, pi, psi, omega)` of the factorized attention.
+    Factorized(Tensor, Tensor, Tensor, Tensor),
+}
+
+/// Attention inputs shared by all layers of the encoder (or decoder), updated at each pooling step
+pub struct FunnelAttentionInputs {
+    pub position_embeds: FunnelPositionEmbeddings,
+    pub token_type_mat: Option<Tensor>,
+    pub attention_mask: Option<Tensor>,
+    pub cls_mask: Option<Tensor>,
+}
+
+/// Computes the attention inputs (position embeddings, token type matrix, masks) and handles
+/// their pooling as the sequence length is reduced along the encoder blocks.
+pub struct FunnelAttentionStructure {
+    d_model: i64,
+    num_blocks: usize,
+    attention_type: FunnelAttentionType,
+    pooling_mode: PoolingMode,
+    separate_cls: bool,
+    truncate_seq: bool,
+    pool_q_only: bool,
+    sin_dropout: Dropout,
+    cos_dropout: Dropout,
+}
+
+impl FunnelAttentionStructure {
+    pub fn new(config: &FunnelConfig) -> FunnelAttentionStructure {
+        FunnelAttentionStructure {
+            d_model: config.d_model,
+            num_blocks: config.block_sizes.len(),
+            attention_type: config.attention_type,
+            pooling_mode: config.pooling_type.into(),
+            separate_cls: config.separate_cls,
+            truncate_seq: config.truncate_seq,
+            pool_q_only: config.pool_q_only,
+            sin_dropout: Dropout::new(config.hidden_dropout),
+            cos_dropout: Dropout::new(config.hidden_dropout),
+        }
+    }
+
+    pub fn init_attention_inputs(
+        &self,
+        input_embeddings: &Tensor,
+        attention_mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        train: bool,
+    ) -> FunnelAttentionInputs {
+        let seq_len = input_embeddings.size()[1];
+        let (kind, device) = (input_embeddings.kind(), input_embeddings.device());
+        let position_embeds = self.get_position_embeds(seq_len, kind, device, train);
+        let token_type_mat = token_type_ids.map(Self::token_type_ids_to_mat);
+        let cls_mask = if self.separate_cls {
+            Some(
+                Tensor::ones([seq_len - 1, seq_len - 1], (kind, device))
+                    .constant_pad_nd([1, 0, 1, 0]),
+            )
+        } else {
+            None
+        };
+        FunnelAttentionInputs {
+            position_embeds,
+            token_type_mat,
+            attention_mask: attention_mask.map(|mask| mask.shallow_clone()),
+            cls_mask,
+        }
+    }
+
+    fn token_type_ids_to_mat(token_type_ids: &Tensor) -> Tensor {
+        let token_type_mat = token_type_ids
+            .unsqueeze(-1)
+            .eq_tensor(&token_type_ids.unsqueeze(1));
+        let cls_ids = token_type_ids.eq(CLS_TOKEN_TYPE_ID);
+        let cls_mat = cls_ids
+            .unsqueeze(-1)
+            .bitwise_or_tensor(&cls_ids.unsqueeze(1));
+        cls_mat.bitwise_or_tensor(&token_type_mat)
+    }
+
+    fn get_position_embeds(
+        &self,
+        seq_len: i64,
+        kind: Kind,
+        device: Device,
+        train: bool,
+    ) -> FunnelPositionEmbeddings {
+        let half_d_model = self.d_model / 2;
+        let freq_seq = Tensor::arange(half_d_model, (kind, device));
+        let inv_freq = (freq_seq * (-(10000f64.ln()) / half_d_model as f64)).exp();
+
+        match self.attention_type {
+            FunnelAttentionType::Factorized => {
+                let pos_seq = Tensor::arange(seq_len, (kind, device));
+                let sinusoid = pos_seq.unsqueeze(-1) * inv_freq.unsqueeze(0);
+                let sin_embed = sinusoid.sin();
+                let sin_embed_d = sin_embed.apply_t(&self.sin_dropout, train);
+                let cos_embed = sinusoid.cos();
+                let cos_embed_d = cos_embed.apply_t(&self.cos_dropout, train);
+                let phi = Tensor::cat(&[&sin_embed_d, &sin_embed_d], -1);
+                let psi = Tensor::cat(&[&cos_embed, &sin_embed], -1);
+                let pi = Tensor::cat(&[&cos_embed_d, &cos_embed_d], -1);
+                let omega = Tensor::cat(&[&(-&sin_embed), &cos_embed], -1);
+                FunnelPositionEmbeddings::Factorized(phi, pi, psi, omega)
+            }
+            FunnelAttentionType::RelativeShift => {
+                let zero_offset = seq_len * 2;
+                let rel_pos_id = Tensor::arange_start(-seq_len * 2, seq_len * 2, (kind, device));
+                let sinusoid = rel_pos_id.unsqueeze(-1) * inv_freq.unsqueeze(0);
+                let sin_embed = sinusoid.sin().apply_t(&self.sin_dropout, train);
+                let cos_embed = sinusoid.cos().apply_t(&self.cos_dropout, train);
+                let pos_embed = Tensor::cat(&[sin_embed, cos_embed], -1);
+
+                let gather_positions = |rel_pos: Vec<i64>| {
+                    let index = Tensor::from_slice(&rel_pos).to_device(device) + zero_offset;
+                    pos_embed.index_select(0, &index)
+                };
+
+                let mut pos = (0..seq_len).collect::<Vec<i64>>();
+                let mut position_embeds_list = Vec::with_capacity(self.num_blocks);
+                for block_index in 0..self.num_blocks {
+                    let position_embeds_pooling = if block_index == 0 {
+                        None
+                    } else {
+                        let pooled_pos = self.stride_pool_pos(&pos, block_index);
+                        let stride = 2i64.pow(block_index as u32 - 1);
+                        let rel_pos =
+                            Self::relative_pos(&pos, stride, Some(pooled_pos.as_slice()), 2);
+                        pos = pooled_pos;
+                        Some(gather_positions(rel_pos))
+                    };
+                    let stride = 2i64.pow(block_index as u32);
+                    let rel_pos = Self::relative_pos(&pos, stride, None, 1);
+                    let position_embeds_no_pooling = gather_positions(rel_pos);
+                    position_embeds_list
+                        .push((position_embeds_no_pooling, position_embeds_pooling));
+                }
+                FunnelPositionEmbeddings::RelativeShift(position_embeds_list)
+            }
+        }
+    }
+
+    fn stride_pool_pos(&self, pos_id: &[i64], block_index: usize) -> Vec<i64> {
+        if self.separate_cls {
+            let cls_pos = 1 - 2i64.pow(block_index as u32);
+            let pooled_pos_id = if self.truncate_seq {
+                &pos_id[1..pos_id.len().max(2) - 1]
+            } else {
+                &pos_id[1..]
+            };
+            std::iter::once(cls_pos)
+                .chain(pooled_pos_id.iter().step_by(2).copied())
+                .collect()
+        } else {
+            pos_id.iter().step_by(2).copied().collect()
+        }
+    }
+
+    fn relative_pos(pos: &[i64], stride: i64, pooled_pos: Option<&[i64]>, shift: i64) -> Vec<i64> {
+        let pooled_pos = pooled_pos.unwrap_or(pos);
+        let ref_point = pooled_pos[0] - pos[0];
+        let num_remove = shift * pooled_pos.len() as i64;
+        let max_dist = ref_point + num_remove * stride;
+        let min_dist = pooled_pos[0] - pos[pos.len() - 1];
+        let mut output = Vec::new();
+        let mut value = max_dist;
+        while value > min_dist - 1 {
+            output.push(value);
+            value -= stride;
+        }
+        output
+    }
+
+    fn stride_pool(&self, tensor: &Tensor, axis: i64) -> Tensor {
+        let axis = axis % tensor.dim() as i64;
+        let tensor = if self.separate_cls {
+            Tensor::cat(&[&tensor.slice(axis, 0, 1, 1), tensor], axis)
+        } else {
+            tensor.shallow_clone()
+        };
+        let end = if self.separate_cls && self.truncate_seq {
+            tensor.size()[axis as usize] - 1
+        } else {
+            tensor.size()[axis as usize]
+        };
+        tensor.slice(axis, 0, end, 2)
+    }
+
+    fn pool_tensor(&self, tensor: &Tensor, mode: PoolingMode) -> Tensor {
+        let tensor = if self.separate_cls {
+            let suffix = if self.truncate_seq {
+                tensor.slice(1, 0, -1, 1)
+            } else {
+                tensor.shallow_clone()
+            };
+            Tensor::cat(&[tensor.slice(1, 0, 1, 1), suffix], 1)
+        } else {
+            tensor.shallow_clone()
+        };
+        let ndim = tensor.dim();
+        let tensor = match ndim {
+            2 => tensor.unsqueeze(1).unsqueeze(-1),
+            3 => tensor.unsqueeze(1),
+            _ => tensor,
+        };
+        let stride = [2, 1];
+        let tensor = match mode {
+            PoolingMode::Mean => tensor.avg_pool2d(stride, stride, [0, 0], true, true, None::<i64>),
+            PoolingMode::Max => tensor.max_pool2d(stride, stride, [0, 0], [1, 1], true),
+            PoolingMode::Min => -(-tensor).max_pool2d(stride, stride, [0, 0], [1, 1], true),
+        };
+        match ndim {
+            2 => tensor.select(1, 0).select(-1, 0),
+            3 => tensor.select(1, 0),
+            _ => tensor,
+        }
+    }
+
+    fn stride_pool_factorized(
+        &self,
+        position_embeds: FunnelPositionEmbeddings,
+        pool_phi_pi: bool,
+        pool_psi_omega: bool,
+    ) -> FunnelPositionEmbeddings {
+        match position_embeds {
+            FunnelPositionEmbeddings::Factorized(phi, pi, psi, omega) => {
+                let pool = |tensor: Tensor, flag: bool| {
+                    if flag {
+                        self.stride_pool(&tensor, 0)
+                    } else {
+                        tensor
+                    }
+                };
+                FunnelPositionEmbeddings::Factorized(
+                    pool(phi, pool_phi_pi),
+                    pool(pi, pool_phi_pi),
+                    pool(psi, pool_psi_omega),
+                    pool(omega, pool_psi_omega),
+                )
+            }
+            relative_shift => relative_shift,
+        }
+    }
+
+    pub fn pre_attention_pooling(
+        &self,
+        output: &Tensor,
+        attention_inputs: FunnelAttentionInputs,
+    ) -> (Tensor, FunnelAttentionInputs) {
+        let FunnelAttentionInputs {
+            position_embeds,
+            token_type_mat,
+            attention_mask,
+            cls_mask,
+        } = attention_inputs;
+
+        let attention_inputs = if self.pool_q_only {
+            FunnelAttentionInputs {
+                position_embeds: self.stride_pool_factorized(position_embeds, true, false),
+                token_type_mat: token_type_mat.map(|value| self.stride_pool(&value, 1)),
+                attention_mask,
+                cls_mask: cls_mask.map(|value| self.stride_pool(&value, 0)),
+            }
+        } else {
+            FunnelAttentionInputs {
+                position_embeds: self.stride_pool_factorized(position_embeds, true, true),
+                token_type_mat: token_type_mat
+                    .map(|value| self.stride_pool(&self.stride_pool(&value, 1), 2)),
+                attention_mask: attention_mask
+                    .map(|value| self.pool_tensor(&value, PoolingMode::Min)),
+                cls_mask: cls_mask.map(|value| self.stride_pool(&self.stride_pool(&value, 1), 2)),
+            }
+        };
+        let output = self.pool_tensor(output, self.pooling_mode);
+        (output, attention_inputs)
+    }
+
+    pub fn post_attention_pooling(
+        &self,
+        attention_inputs: FunnelAttentionInputs,
+    ) -> FunnelAttentionInputs {
+        if self.pool_q_only {
+            let FunnelAttentionInputs {
+                position_embeds,
+                token_type_mat,
+                attention_mask,
+                cls_mask,
+            } = attention_inputs;
+            FunnelAttentionInputs {
+                position_embeds: self.stride_pool_factorized(position_embeds, false, true),
+                token_type_mat: token_type_mat.map(|value| self.stride_pool(&value, 2)),
+                attention_mask: attention_mask
+                    .map(|value| self.pool_tensor(&value, PoolingMode::Min)),
+                cls_mask: cls_mask.map(|value| self.stride_pool(&value, 1)),
+            }
+        } else {
+            attention_inputs
+        }
+    }
+}
+
+fn relative_shift_gather(positional_attn: &Tensor, context_len: i64, shift: i64) -> Tensor {
+    let (batch_size, n_head, seq_len, max_rel_len) = positional_attn.size4().unwrap();
+    positional_attn
+        .reshape([batch_size, n_head, max_rel_len, seq_len])
+        .slice(2, shift, max_rel_len, 1)
+        .reshape([batch_size, n_head, seq_len, max_rel_len - shift])
+        .slice(3, 0, context_len, 1)
+}
+
+pub struct FunnelRelMultiheadAttention {
+    block_index: usize,
+    n_head: i64,
+    d_head: i64,
+    q_head: nn::Linear,
+    k_head: nn::Linear,
+    v_head: nn::Linear,
+    r_w_bias: Tensor,
+    r_r_bias: Tensor,
+    r_kernel: Tensor,
+    r_s_bias: Tensor,
+    seg_embed: Tensor,
+    post_proj: nn::Linear,
+    layer_norm: nn::LayerNorm,
+    hidden_dropout: Dropout,
+    attention_dropout: Dropout,
+    scale: f64,
+    output_attentions: bool,
+}
+
+impl FunnelRelMultiheadAttention {
+    pub fn new<'p, P>(
+        p: P,
+        config: &FunnelConfig,
+        block_index: usize,
+    ) -> FunnelRelMultiheadAttention
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+        let (d_model, n_head, d_head) = (config.d_model, config.n_head, config.d_head);
+
+        let q_head = nn::linear(
+            p / "q_head",
+            d_model,
+            n_head * d_head,
+            nn::LinearConfig {
+                bias: false,
+                ..Default::default()
+            },
+        );
+        let k_head = nn::linear(p / "k_head", d_model, n_head * d_head, Default::default());
+        let v_head = nn::linear(p / "v_head", d_model, n_head * d_head, Default::default());
+        let r_w_bias = p.var("r_w_bias", &[n_head, d_head], nn::Init::Const(0.));
+        let r_r_bias = p.var("r_r_bias", &[n_head, d_head], nn::Init::Const(0.));
+        let r_kernel = p.var("r_kernel", &[d_model, n_head, d_head], nn::Init::Const(0.));
+        let r_s_bias = p.var("r_s_bias", &[n_head, d_head], nn::Init::Const(0.));
+        let seg_embed = p.var("seg_embed", &[2, n_head, d_head], nn::Init::Const(0.));
+        let post_proj = nn::linear(
+            p / "post_proj",
+            n_head * d_head,
+            d_model,
+            Default::default(),
+        );
+        let layer_norm_config = LayerNormConfig {
+            eps: config.layer_norm_eps.unwrap_or(1e-9),
+            ..Default::default()
+        };
+        let layer_norm = nn::layer_norm(p / "layer_norm", vec![d_model], layer_norm_config);
+
+        FunnelRelMultiheadAttention {
+            block_index,
+            n_head,
+            d_head,
+            q_head,
+            k_head,
+            v_head,
+            r_w_bias,
+            r_r_bias,
+            r_kernel,
+            r_s_bias,
+            seg_embed,
+            post_proj,
+            layer_norm,
+            hidden_dropout: Dropout::new(config.hidden_dropout),
+            attention_dropout: Dropout::new(config.attention_dropout),
+            scale: 1.0 / (d_head as f64).sqrt(),
+            output_attentions: config.output_attentions.unwrap_or(false),
+        }
+    }
+
+    fn relative_positional_attention(
+        &self,
+        position_embeds: &FunnelPositionEmbeddings,
+        q_head: &Tensor,
+        context_len: i64,
+        cls_mask: Option<&Tensor>,
+    ) -> Tensor {
+        let positional_attn = match position_embeds {
+            FunnelPositionEmbeddings::Factorized(phi, pi, psi, omega) => {
+                let u = &self.r_r_bias * self.scale;
+                let q_r_attention = Tensor::einsum(
+                    "binh,dnh->bind",
+                    &[&(q_head + u), &self.r_kernel],
+                    None::<i64>,
+                );
+                let q_r_attention_1 = &q_r_attention * phi.unsqueeze(1);
+                let q_r_attention_2 = &q_r_attention * pi.unsqueeze(1);
+                Tensor::einsum("bind,jd->bnij", &[&q_r_attention_1, psi], None::<i64>)
+                    + Tensor::einsum("bind,jd->bnij", &[&q_r_attention_2, omega], None::<i64>)
+            }
+            FunnelPositionEmbeddings::RelativeShift(position_embeds_list) => {
+                let shift = if q_head.size()[1] != context_len {
+                    2
+                } else {
+                    1
+                };
+                let (no_pooling, pooling) = &position_embeds_list[self.block_index];
+                let r = if shift == 1 {
+                    no_pooling
+                } else {
+                    pooling.as_ref().unwrap()
+                };
+                let v = &self.r_r_bias * self.scale;
+                let r_head = Tensor::einsum("td,dnh->tnh", &[r, &self.r_kernel], None::<i64>);
+                let positional_attn =
+                    Tensor::einsum("binh,tnh->bnit", &[&(q_head + v), &r_head], None::<i64>);
+                relative_shift_gather(&positional_attn, context_len, shift)
+            }
+        };
+        match cls_mask {
+            Some(cls_mask) => positional_attn * cls_mask,
+            None => positional_attn,
+        }
+    }
+
+    fn relative_token_type_attention(
+        &self,
+        token_type_mat: Option<&Tensor>,
+        q_head: &Tensor,
+        cls_mask: Option<&Tensor>,
+    ) -> Option<Tensor> {
+        let token_type_mat = token_type_mat?;
+        let (batch_size, seq_len, context_len) = token_type_mat.size3().unwrap();
+        let expanded_shape = [batch_size, self.n_head, seq_len, context_len];
+        let r_s_bias = &self.r_s_bias * self.scale;
+        let token_type_bias = Tensor::einsum(
+            "bind,snd->bnis",
+            &[&(q_head + r_s_bias), &self.seg_embed],
+            None::<i64>,
+        );
+        let token_type_mat = token_type_mat.unsqueeze(1).expand(expanded_shape, true);
+        let diff_token_type = token_type_bias.slice(-1, 0, 1, 1);
+        let same_token_type = token_type_bias.slice(-1, 1, 2, 1);
+        let token_type_attn = same_token_type.expand(expanded_shape, true).where_self(
+            &token_type_mat,
+            &diff_token_type.expand(expanded_shape, true),
+        );
+        Some(match cls_mask {
+            Some(cls_mask) => token_type_attn * cls_mask,
+            None => token_type_attn,
+        })
+    }
+
+    pub fn forward_t(
+        &self,
+        query: &Tensor,
+        key: &Tensor,
+        value: &Tensor,
+        attention_inputs: &FunnelAttentionInputs,
+        train: bool,
+    ) -> (Tensor, Option<Tensor>) {
+        let (batch_size, seq_len, _) = query.size3().unwrap();
+        let context_len = key.size()[1];
+
+        let q_head =
+            query
+                .apply(&self.q_head)
+                .view([batch_size, seq_len, self.n_head, self.d_head])
+                * self.scale;
+        let k_head =
+            key.apply(&self.k_head)
+                .view([batch_size, context_len, self.n_head, self.d_head]);
+        let v_head =
+            value
+                .apply(&self.v_head)
+                .view([batch_size, context_len, self.n_head, self.d_head]);
+
+        let r_w_bias = &self.r_w_bias * self.scale;
+        let content_score = Tensor::einsum(
+            "bind,bjnd->bnij",
+            &[&(&q_head + r_w_bias), &k_head],
+            None::<i64>,
+        );
+        let positional_attn = self.relative_positional_attention(
+            &attention_inputs.position_embeds,
+            &q_head,
+            context_len,
+            attention_inputs.cls_mask.as_ref(),
+        );
+        let mut attention_score = content_score + positional_attn;
+        if let Some(token_type_attn) = self.relative_token_type_attention(
+            attention_inputs.token_type_mat.as_ref(),
+            &q_head,
+            attention_inputs.cls_mask.as_ref(),
+        ) {
+            attention_score = attention_score + token_type_attn;
+        }
+
+        let kind = attention_score.kind();
+        let mut attention_score = attention_score.to_kind(Kind::Float);
+        if let Some(attention_mask) = &attention_inputs.attention_mask {
+            attention_score = attention_score
+                + (attention_mask
+                    .unsqueeze(1)
+                    .unsqueeze(1)
+                    .to_kind(Kind::Float)
+                    - 1)
+                    * INF;
+        }
+        let attention_probs = attention_score
+            .softmax(-1, kind)
+            .apply_t(&self.attention_dropout, train);
+
+        let attention_vector =
+            Tensor::einsum("bnij,bjnd->bind", &[&attention_probs, &v_head], None::<i64>);
+        let attention_output = attention_vector
+            .reshape([batch_size, seq_len, self.n_head * self.d_head])
+            .apply(&self.post_proj)
+            .apply_t(&self.hidden_dropout, train);
+        let output = (query + attention_output).apply(&self.layer_norm);
+
+        let attention_probs = if self.output_attentions {
+            Some(attention_probs)
+        } else {
+            None
+        };
+        (output, attention_probs)
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2020-present, the HuggingFace Inc. team, Funnel Transformer Authors
+// Copyright 2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::dropout::Dropout;
+use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::funnel::FunnelConfig;
+use crate::RustBertError;
+use std::borrow::Borrow;
+use tch::nn::{EmbeddingConfig, LayerNormConfig};
+use tch::{nn, Tensor};
+
+/// Funnel embeddings: word embeddings only (the position information is provided by the
+/// relative attention mechanism and the segment information by the token type attention bias).
+pub struct FunnelEmbeddings {
+    word_embeddings: nn::Embedding,
+    layer_norm: nn::LayerNorm,
+    dropout: Dropout,
+}
+
+impl FunnelEmbeddings {
+    pub fn new<'p, P>(p: P, config: &FunnelConfig) -> FunnelEmbeddings
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let word_embeddings_config = EmbeddingConfig {
+            padding_idx: config.pad_token_id.unwrap_or(0),
+            ..Default::default()
+        };
+        let word_embeddings = nn::embedding(
+            p / "word_embeddings",
+            config.vocab_size,
+            config.d_model,
+            word_embeddings_config,
+        );
+
+        let layer_norm_config = LayerNormConfig {
+            eps: config.layer_norm_eps.unwrap_or(1e-9),
+            ..Default::default()
+        };
+        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], layer_norm_config);
+        let dropout = Dropout::new(config.hidden_dropout);
+
+        FunnelEmbeddings {
+            word_embeddings,
+            layer_norm,
+            dropout,
+        }
+    }
+
+    pub fn forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        input_embeddings: Option<&Tensor>,
+        train: bool,
+    ) -> Result<Tensor, RustBertError> {
+        let (calc_input_embeddings, _, _) =
+            process_ids_embeddings_pair(input_ids, input_embeddings, &self.word_embeddings)?;
+
+        let input_embeddings =
+            input_embeddings.unwrap_or_else(|| calc_input_embeddings.as_ref().unwrap());
+
+        Ok(input_embeddings
+            .apply(&self.layer_norm)
+            .apply_t(&self.dropout, train))
+    }
+}
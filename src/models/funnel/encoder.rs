@@ -0,0 +1,389 @@
+// Copyright 2020-present, the HuggingFace Inc. team, Funnel Transformer Authors
+// Copyright 2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::activations::TensorFunction;
+use crate::common::dropout::Dropout;
+use crate::funnel::attention::{
+    FunnelAttentionInputs, FunnelAttentionStructure, FunnelRelMultiheadAttention,
+};
+use crate::funnel::FunnelConfig;
+use std::borrow::{Borrow, BorrowMut};
+use tch::nn::LayerNormConfig;
+use tch::{nn, Tensor};
+
+pub struct FunnelPositionwiseFFN {
+    linear_1: nn::Linear,
+    activation: TensorFunction,
+    activation_dropout: Dropout,
+    linear_2: nn::Linear,
+    dropout: Dropout,
+    layer_norm: nn::LayerNorm,
+}
+
+impl FunnelPositionwiseFFN {
+    pub fn new<'p, P>(p: P, config: &FunnelConfig) -> FunnelPositionwiseFFN
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let linear_1 = nn::linear(
+            p / "linear_1",
+            config.d_model,
+            config.d_inner,
+            Default::default(),
+        );
+        let linear_2 = nn::linear(
+            p / "linear_2",
+            config.d_inner,
+            config.d_model,
+            Default::default(),
+        );
+        let layer_norm_config = LayerNormConfig {
+            eps: config.layer_norm_eps.unwrap_or(1e-9),
+            ..Default::default()
+        };
+        let layer_norm = nn::layer_norm(p / "layer_norm", vec![config.d_model], layer_norm_config);
+
+        FunnelPositionwiseFFN {
+            linear_1,
+            activation: config.hidden_act.get_function(),
+            activation_dropout: Dropout::new(config.activation_dropout),
+            linear_2,
+            dropout: Dropout::new(config.hidden_dropout),
+            layer_norm,
+        }
+    }
+
+    pub fn forward_t(&self, hidden_states: &Tensor, train: bool) -> Tensor {
+        let intermediate = self.activation.get_fn()(&hidden_states.apply(&self.linear_1));
+        let output = intermediate
+            .apply_t(&self.activation_dropout, train)
+            .apply(&self.linear_2)
+            .apply_t(&self.dropout, train);
+        (hidden_states + output).apply(&self.layer_norm)
+    }
+}
+
+pub struct FunnelLayer {
+    attention: FunnelRelMultiheadAttention,
+    ffn: FunnelPositionwiseFFN,
+}
+
+impl FunnelLayer {
+    pub fn new<'p, P>(p: P, config: &FunnelConfig, block_index: usize) -> FunnelLayer
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let attention = FunnelRelMultiheadAttention::new(p / "attention", config, block_index);
+        let ffn = FunnelPositionwiseFFN::new(p / "ffn", config);
+
+        FunnelLayer { attention, ffn }
+    }
+
+    pub fn forward_t(
+        &self,
+        query: &Tensor,
+        key: &Tensor,
+        value: &Tensor,
+        attention_inputs: &FunnelAttentionInputs,
+        train: bool,
+    ) -> (Tensor, Option<Tensor>) {
+        let (attention_output, attention_weights) =
+            self.attention
+                .forward_t(query, key, value, attention_inputs, train);
+        let output = self.ffn.forward_t(&attention_output, train);
+        (output, attention_weights)
+    }
+}
+
+pub struct FunnelEncoder {
+    attention_structure: FunnelAttentionStructure,
+    blocks: Vec<Vec<FunnelLayer>>,
+    block_repeats: Vec<i64>,
+    first_block_size: i64,
+    separate_cls: bool,
+    pool_q_only: bool,
+    output_attentions: bool,
+    output_hidden_states: bool,
+}
+
+impl FunnelEncoder {
+    pub fn new<'p, P>(p: P, config: &FunnelConfig) -> FunnelEncoder
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow() / "blocks";
+
+        let blocks = config
+            .block_sizes
+            .iter()
+            .enumerate()
+            .map(|(block_index, &block_size)| {
+                let p_block = &p / block_index;
+                (0..block_size)
+                    .map(|layer_index| {
+                        FunnelLayer::new(&p_block / layer_index, config, block_index)
+                    })
+                    .collect::<Vec<FunnelLayer>>()
+            })
+            .collect::<Vec<Vec<FunnelLayer>>>();
+
+        FunnelEncoder {
+            attention_structure: FunnelAttentionStructure::new(config),
+            blocks,
+            block_repeats: config.get_block_repeats(),
+            first_block_size: config.block_sizes.first().copied().unwrap_or(0),
+            separate_cls: config.separate_cls,
+            pool_q_only: config.pool_q_only,
+            output_attentions: config.output_attentions.unwrap_or(false),
+            output_hidden_states: config.output_hidden_states.unwrap_or(false),
+        }
+    }
+
+    pub fn forward_t(
+        &self,
+        input_embeddings: &Tensor,
+        attention_mask: &Tensor,
+        token_type_ids: &Tensor,
+        train: bool,
+    ) -> FunnelEncoderOutput {
+        let attention_mask = attention_mask.to_kind(input_embeddings.kind());
+        let mut attention_inputs = self.attention_structure.init_attention_inputs(
+            input_embeddings,
+            Some(&attention_mask),
+            Some(token_type_ids),
+            train,
+        );
+
+        let mut all_hidden_states: Option<Vec<Tensor>> = if self.output_hidden_states {
+            Some(vec![input_embeddings.shallow_clone()])
+        } else {
+            None
+        };
+        let mut all_attentions: Option<Vec<Tensor>> = if self.output_attentions {
+            Some(vec![])
+        } else {
+            None
+        };
+
+        let mut hidden_state = input_embeddings.shallow_clone();
+        let mut first_block_hidden_state = if self.first_block_size == 0 {
+            Some(hidden_state.shallow_clone())
+        } else {
+            None
+        };
+        let mut num_layer_applications = 0;
+        for (block_index, block) in self.blocks.iter().enumerate() {
+            let min_length = if self.separate_cls { 2 } else { 1 };
+            let pooling_flag = (hidden_state.size()[1] > min_length) && (block_index > 0);
+            let mut pooled_hidden_state = None;
+            if pooling_flag {
+                let (pooled, pooled_attention_inputs) = self
+                    .attention_structure
+                    .pre_attention_pooling(&hidden_state, attention_inputs);
+                pooled_hidden_state = Some(pooled);
+                attention_inputs = pooled_attention_inputs;
+            }
+            for (layer_index, layer) in block.iter().enumerate() {
+                for repeat_index in 0..self.block_repeats[block_index] {
+                    let do_pooling = (repeat_index == 0) && (layer_index == 0) && pooling_flag;
+                    let (layer_output, attention_weights) = if do_pooling {
+                        let query = pooled_hidden_state.as_ref().unwrap();
+                        let key_value = if self.pool_q_only {
+                            &hidden_state
+                        } else {
+                            query
+                        };
+                        layer.forward_t(query, key_value, key_value, &attention_inputs, train)
+                    } else {
+                        layer.forward_t(
+                            &hidden_state,
+                            &hidden_state,
+                            &hidden_state,
+                            &attention_inputs,
+                            train,
+                        )
+                    };
+                    hidden_state = layer_output;
+                    if do_pooling {
+                        attention_inputs = self
+                            .attention_structure
+                            .post_attention_pooling(attention_inputs);
+                    }
+
+                    num_layer_applications += 1;
+                    if num_layer_applications == self.first_block_size {
+                        first_block_hidden_state = Some(hidden_state.shallow_clone());
+                    }
+                    if let Some(attentions) = all_attentions.borrow_mut() {
+                        attentions.push(attention_weights.unwrap());
+                    };
+                    if let Some(hidden_states) = all_hidden_states.borrow_mut() {
+                        hidden_states.push(hidden_state.shallow_clone());
+                    };
+                }
+            }
+        }
+
+        FunnelEncoderOutput {
+            hidden_state,
+            first_block_hidden_state: first_block_hidden_state.unwrap(),
+            all_hidden_states,
+            all_attentions,
+        }
+    }
+}
+
+/// Upsamples the final encoder hidden states to the length of the first block hidden states
+fn upsample(
+    hidden_states: &Tensor,
+    stride: i64,
+    target_length: i64,
+    separate_cls: bool,
+    truncate_seq: bool,
+) -> Tensor {
+    if stride == 1 {
+        return hidden_states.shallow_clone();
+    }
+    if separate_cls {
+        let cls = hidden_states.slice(1, 0, 1, 1);
+        let mut output = hidden_states
+            .slice(1, 1, hidden_states.size()[1], 1)
+            .repeat_interleave_self_int(stride, 1, None::<i64>);
+        if truncate_seq {
+            output = output.constant_pad_nd([0, 0, 0, stride - 1, 0, 0]);
+        }
+        let output = output.slice(1, 0, target_length - 1, 1);
+        Tensor::cat(&[cls, output], 1)
+    } else {
+        hidden_states
+            .repeat_interleave_self_int(stride, 1, None::<i64>)
+            .slice(1, 0, target_length, 1)
+    }
+}
+
+pub struct FunnelDecoder {
+    attention_structure: FunnelAttentionStructure,
+    layers: Vec<FunnelLayer>,
+    num_blocks: usize,
+    separate_cls: bool,
+    truncate_seq: bool,
+    output_attentions: bool,
+    output_hidden_states: bool,
+}
+
+impl FunnelDecoder {
+    pub fn new<'p, P>(p: P, config: &FunnelConfig) -> FunnelDecoder
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow() / "layers";
+
+        let layers = (0..config.num_decoder_layers)
+            .map(|layer_index| FunnelLayer::new(&p / layer_index, config, 0))
+            .collect::<Vec<FunnelLayer>>();
+
+        FunnelDecoder {
+            attention_structure: FunnelAttentionStructure::new(config),
+            layers,
+            num_blocks: config.block_sizes.len(),
+            separate_cls: config.separate_cls,
+            truncate_seq: config.truncate_seq,
+            output_attentions: config.output_attentions.unwrap_or(false),
+            output_hidden_states: config.output_hidden_states.unwrap_or(false),
+        }
+    }
+
+    pub fn forward_t(
+        &self,
+        final_hidden_state: &Tensor,
+        first_block_hidden_state: &Tensor,
+        attention_mask: &Tensor,
+        token_type_ids: &Tensor,
+        train: bool,
+    ) -> FunnelDecoderOutput {
+        let upsampled_hidden_state = upsample(
+            final_hidden_state,
+            2i64.pow(self.num_blocks.saturating_sub(1) as u32),
+            first_block_hidden_state.size()[1],
+            self.separate_cls,
+            self.truncate_seq,
+        );
+        let mut hidden_state = upsampled_hidden_state + first_block_hidden_state;
+        let attention_mask = attention_mask.to_kind(hidden_state.kind());
+        let attention_inputs = self.attention_structure.init_attention_inputs(
+            &hidden_state,
+            Some(&attention_mask),
+            Some(token_type_ids),
+            train,
+        );
+
+        let mut all_hidden_states: Option<Vec<Tensor>> = if self.output_hidden_states {
+            Some(vec![hidden_state.shallow_clone()])
+        } else {
+            None
+        };
+        let mut all_attentions: Option<Vec<Tensor>> = if self.output_attentions {
+            Some(vec![])
+        } else {
+            None
+        };
+
+        for layer in &self.layers {
+            let (layer_output, attention_weights) = layer.forward_t(
+                &hidden_state,
+                &hidden_state,
+                &hidden_state,
+                &attention_inputs,
+                train,
+            );
+            hidden_state = layer_output;
+            if let Some(attentions) = all_attentions.borrow_mut() {
+                attentions.push(attention_weights.unwrap());
+            };
+            if let Some(hidden_states) = all_hidden_states.borrow_mut() {
+                hidden_states.push(hidden_state.shallow_clone());
+            };
+        }
+
+        FunnelDecoderOutput {
+            hidden_state,
+            all_hidden_states,
+            all_attentions,
+        }
+    }
+}
+
+/// Container for the Funnel encoder output.
+pub struct FunnelEncoderOutput {
+    /// Last hidden states from the encoder (pooled sequence)
+    pub hidden_state: Tensor,
+    /// Hidden states after the first block (full sequence length), used as a residual by the decoder
+    pub first_block_hidden_state: Tensor,
+    /// Hidden states for all intermediate layers
+    pub all_hidden_states: Option<Vec<Tensor>>,
+    /// Attention weights for all intermediate layers
+    pub all_attentions: Option<Vec<Tensor>>,
+}
+
+/// Container for the Funnel decoder output.
+pub struct FunnelDecoderOutput {
+    /// Last hidden states from the decoder (full sequence length)
+    pub hidden_state: Tensor,
+    /// Hidden states for all intermediate layers
+    pub all_hidden_states: Option<Vec<Tensor>>,
+    /// Attention weights for all intermediate layers
+    pub all_attentions: Option<Vec<Tensor>>,
+}
@@ -0,0 +1,601 @@
+// Copyright 2020-present, the HuggingFace Inc. team, Funnel Transformer Authors
+// Copyright 2023 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::common::dropout::Dropout;
+use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::funnel::embeddings::FunnelEmbeddings;
+use crate::funnel::encoder::{FunnelDecoder, FunnelEncoder};
+use crate::{Activation, Config, RustBertError};
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use tch::{nn, Kind, Tensor};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+/// # Pooling type used to reduce the sequence length between blocks
+pub enum FunnelPoolingType {
+    /// Mean pooling
+    Mean,
+    /// Max pooling
+    Max,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+/// # Relative attention variant
+pub enum FunnelAttentionType {
+    /// Relative positional attention using a relative shift (faster on GPU)
+    RelativeShift,
+    /// Factorized relative positional attention (faster on TPU in the original implementation)
+    Factorized,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+/// # Funnel Transformer model configuration
+/// Defines the Funnel Transformer model architecture (e.g. number of blocks, hidden size, label mapping...)
+pub struct FunnelConfig {
+    pub vocab_size: i64,
+    pub block_sizes: Vec<i64>,
+    pub block_repeats: Option<Vec<i64>>,
+    pub num_decoder_layers: i64,
+    pub d_model: i64,
+    pub n_head: i64,
+    pub d_head: i64,
+    pub d_inner: i64,
+    pub hidden_act: Activation,
+    pub hidden_dropout: f64,
+    pub attention_dropout: f64,
+    pub activation_dropout: f64,
+    pub initializer_range: f64,
+    pub initializer_std: Option<f64>,
+    pub layer_norm_eps: Option<f64>,
+    pub pooling_type: FunnelPoolingType,
+    pub attention_type: FunnelAttentionType,
+    pub separate_cls: bool,
+    pub truncate_seq: bool,
+    pub pool_q_only: bool,
+    pub pad_token_id: Option<i64>,
+    pub id2label: Option<HashMap<i64, String>>,
+    pub label2id: Option<HashMap<String, i64>>,
+    pub output_attentions: Option<bool>,
+    pub output_hidden_states: Option<bool>,
+}
+
+impl FunnelConfig {
+    /// Number of repetitions of each block, defaulting to a single pass per block.
+    pub fn get_block_repeats(&self) -> Vec<i64> {
+        self.block_repeats
+            .clone()
+            .unwrap_or_else(|| vec![1; self.block_sizes.len()])
+    }
+}
+
+impl Config for FunnelConfig {}
+
+impl Default for FunnelConfig {
+    fn default() -> Self {
+        FunnelConfig {
+            vocab_size: 30522,
+            block_sizes: vec![4, 4, 4],
+            block_repeats: None,
+            num_decoder_layers: 2,
+            d_model: 768,
+            n_head: 12,
+            d_head: 64,
+            d_inner: 3072,
+            hidden_act: Activation::gelu_new,
+            hidden_dropout: 0.1,
+            attention_dropout: 0.1,
+            activation_dropout: 0.0,
+            initializer_range: 0.1,
+            initializer_std: None,
+            layer_norm_eps: Some(1e-9),
+            pooling_type: FunnelPoolingType::Mean,
+            attention_type: FunnelAttentionType::RelativeShift,
+            separate_cls: true,
+            truncate_seq: true,
+            pool_q_only: true,
+            pad_token_id: Some(0),
+            id2label: None,
+            label2id: None,
+            output_attentions: None,
+            output_hidden_states: None,
+        }
+    }
+}
+
+/// # Funnel Transformer Base model
+/// Base architecture for the Funnel Transformer: embeddings followed by the pooling encoder blocks,
+/// returning a compressed sequence representation. This is the model used by the sequence-level heads.
+/// It is made of the following blocks:
+/// - `embeddings`: `token` embeddings
+/// - `encoder`: blocks of relative attention layers, with the sequence length halved between blocks
+pub struct FunnelBaseModel {
+    embeddings: FunnelEmbeddings,
+    encoder: FunnelEncoder,
+}
+
+impl FunnelBaseModel {
+    /// Build a new `FunnelBaseModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the Funnel model
+    /// * `config` - `FunnelConfig` object defining the model architecture
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::funnel::{FunnelBaseModel, FunnelConfig};
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = FunnelConfig::from_file(config_path);
+    /// let funnel: FunnelBaseModel = FunnelBaseModel::new(&p.root() / "funnel", &config);
+    /// ```
+    pub fn new<'p, P>(p: P, config: &FunnelConfig) -> FunnelBaseModel
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let embeddings = FunnelEmbeddings::new(p / "embeddings", config);
+        let encoder = FunnelEncoder::new(p / "encoder", config);
+
+        FunnelBaseModel {
+            embeddings,
+            encoder,
+        }
+    }
+
+    /// Forward pass through the model
+    ///
+    /// # Arguments
+    ///
+    /// * `input_ids` - Optional input tensor of shape (*batch size*, *sequence_length*). If None, pre-computed embeddings must be provided (see `input_embeds`)
+    /// * `attention_mask` - Optional mask of shape (*batch size*, *sequence_length*). Masked position have value 0, non-masked value 1. If None set to 1
+    /// * `token_type_ids` - Optional segment id of shape (*batch size*, *sequence_length*). Convention is value of 0 for the first sentence (incl. *SEP*), 1 for the second sentence and 2 for the *CLS* token. If None set to 0.
+    /// * `input_embeds` - Optional pre-computed input embeddings of shape (*batch size*, *sequence_length*, *d_model*). If None, input ids must be provided (see `input_ids`)
+    /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
+    ///
+    /// # Returns
+    ///
+    /// * `FunnelBaseModelOutput` containing:
+    ///   - `hidden_state` - `Tensor` of shape (*batch size*, *pooled_sequence_length*, *d_model*)
+    ///   - `all_hidden_states` - `Option<Vec<Tensor>>` of length *num_layers + 1* with shape (*batch size*, *sequence_length*, *d_model*)
+    ///   - `all_attentions` - `Option<Vec<Tensor>>` of length *num_layers* with shape (*batch size*, *n_head*, *sequence_length*, *sequence_length*)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use tch::{nn, Device, Tensor, no_grad};
+    /// # use rust_bert::Config;
+    /// # use std::path::Path;
+    /// # use tch::kind::Kind::Int64;
+    /// use rust_bert::funnel::{FunnelBaseModel, FunnelConfig};
+    /// # let config_path = Path::new("path/to/config.json");
+    /// # let device = Device::Cpu;
+    /// # let vs = nn::VarStore::new(device);
+    /// # let config = FunnelConfig::from_file(config_path);
+    /// let model = FunnelBaseModel::new(&vs.root(), &config);
+    /// let (batch_size, sequence_length) = (64, 128);
+    /// let input_tensor = Tensor::rand(&[batch_size, sequence_length], (Int64, device));
+    /// let mask = Tensor::ones(&[batch_size, sequence_length], (Int64, device));
+    /// let token_type_ids = Tensor::zeros(&[batch_size, sequence_length], (Int64, device));
+    ///
+    /// let model_output = no_grad(|| {
+    ///     model
+    ///         .forward_t(
+    ///             Some(&input_tensor),
+    ///             Some(&mask),
+    ///             Some(&token_type_ids),
+    ///             None,
+    ///             false,
+    ///         )
+    ///         .unwrap()
+    /// });
+    /// ```
+    pub fn forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        attention_mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        input_embeddings: Option<&Tensor>,
+        train: bool,
+    ) -> Result<FunnelBaseModelOutput, RustBertError> {
+        let (input_shape, device) =
+            get_shape_and_device_from_ids_embeddings_pair(input_ids, input_embeddings)?;
+
+        let calc_attention_mask = if attention_mask.is_none() {
+            Some(Tensor::ones(input_shape.as_slice(), (Kind::Int64, device)))
+        } else {
+            None
+        };
+        let attention_mask =
+            attention_mask.unwrap_or_else(|| calc_attention_mask.as_ref().unwrap());
+        let calc_token_type_ids = if token_type_ids.is_none() {
+            Some(Tensor::zeros(input_shape.as_slice(), (Kind::Int64, device)))
+        } else {
+            None
+        };
+        let token_type_ids =
+            token_type_ids.unwrap_or_else(|| calc_token_type_ids.as_ref().unwrap());
+
+        let embedding_output = self
+            .embeddings
+            .forward_t(input_ids, input_embeddings, train)?;
+
+        let encoder_output =
+            self.encoder
+                .forward_t(&embedding_output, attention_mask, token_type_ids, train);
+
+        Ok(FunnelBaseModelOutput {
+            hidden_state: encoder_output.hidden_state,
+            all_hidden_states: encoder_output.all_hidden_states,
+            all_attentions: encoder_output.all_attentions,
+        })
+    }
+}
+
+/// # Funnel Transformer model
+/// Full Funnel Transformer architecture, where the compressed sequence returned by the encoder is
+/// upsampled back to the input length by a decoder. This is the model used by the token-level heads.
+/// It is made of the following blocks:
+/// - `embeddings`: `token` embeddings
+/// - `encoder`: blocks of relative attention layers, with the sequence length halved between blocks
+/// - `decoder`: relative attention layers applied to the upsampled encoder output
+pub struct FunnelModel {
+    embeddings: FunnelEmbeddings,
+    encoder: FunnelEncoder,
+    decoder: FunnelDecoder,
+}
+
+impl FunnelModel {
+    /// Build a new `FunnelModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the Funnel model
+    /// * `config` - `FunnelConfig` object defining the model architecture
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::funnel::{FunnelConfig, FunnelModel};
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = FunnelConfig::from_file(config_path);
+    /// let funnel: FunnelModel = FunnelModel::new(&p.root() / "funnel", &config);
+    /// ```
+    pub fn new<'p, P>(p: P, config: &FunnelConfig) -> FunnelModel
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let embeddings = FunnelEmbeddings::new(p / "embeddings", config);
+        let encoder = FunnelEncoder::new(p / "encoder", config);
+        let decoder = FunnelDecoder::new(p / "decoder", config);
+
+        FunnelModel {
+            embeddings,
+            encoder,
+            decoder,
+        }
+    }
+
+    /// Forward pass through the model
+    ///
+    /// # Arguments
+    ///
+    /// * `input_ids` - Optional input tensor of shape (*batch size*, *sequence_length*). If None, pre-computed embeddings must be provided (see `input_embeds`)
+    /// * `attention_mask` - Optional mask of shape (*batch size*, *sequence_length*). Masked position have value 0, non-masked value 1. If None set to 1
+    /// * `token_type_ids` - Optional segment id of shape (*batch size*, *sequence_length*). Convention is value of 0 for the first sentence (incl. *SEP*), 1 for the second sentence and 2 for the *CLS* token. If None set to 0.
+    /// * `input_embeds` - Optional pre-computed input embeddings of shape (*batch size*, *sequence_length*, *d_model*). If None, input ids must be provided (see `input_ids`)
+    /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
+    ///
+    /// # Returns
+    ///
+    /// * `FunnelModelOutput` containing:
+    ///   - `hidden_state` - `Tensor` of shape (*batch size*, *sequence_length*, *d_model*)
+    ///   - `all_hidden_states` - `Option<Vec<Tensor>>` of length *num_layers + 2* with shape (*batch size*, *sequence_length*, *d_model*)
+    ///   - `all_attentions` - `Option<Vec<Tensor>>` of length *num_layers* with shape (*batch size*, *n_head*, *sequence_length*, *sequence_length*)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use tch::{nn, Device, Tensor, no_grad};
+    /// # use rust_bert::Config;
+    /// # use std::path::Path;
+    /// # use tch::kind::Kind::Int64;
+    /// use rust_bert::funnel::{FunnelConfig, FunnelModel};
+    /// # let config_path = Path::new("path/to/config.json");
+    /// # let device = Device::Cpu;
+    /// # let vs = nn::VarStore::new(device);
+    /// # let config = FunnelConfig::from_file(config_path);
+    /// let model = FunnelModel::new(&vs.root(), &config);
+    /// let (batch_size, sequence_length) = (64, 128);
+    /// let input_tensor = Tensor::rand(&[batch_size, sequence_length], (Int64, device));
+    ///
+    /// let model_output = no_grad(|| {
+    ///     model
+    ///         .forward_t(Some(&input_tensor), None, None, None, false)
+    ///         .unwrap()
+    /// });
+    /// ```
+    pub fn forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        attention_mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        input_embeddings: Option<&Tensor>,
+        train: bool,
+    ) -> Result<FunnelModelOutput, RustBertError> {
+        let (input_shape, device) =
+            get_shape_and_device_from_ids_embeddings_pair(input_ids, input_embeddings)?;
+
+        let calc_attention_mask = if attention_mask.is_none() {
+            Some(Tensor::ones(input_shape.as_slice(), (Kind::Int64, device)))
+        } else {
+            None
+        };
+        let attention_mask =
+            attention_mask.unwrap_or_else(|| calc_attention_mask.as_ref().unwrap());
+        let calc_token_type_ids = if token_type_ids.is_none() {
+            Some(Tensor::zeros(input_shape.as_slice(), (Kind::Int64, device)))
+        } else {
+            None
+        };
+        let token_type_ids =
+            token_type_ids.unwrap_or_else(|| calc_token_type_ids.as_ref().unwrap());
+
+        let embedding_output = self
+            .embeddings
+            .forward_t(input_ids, input_embeddings, train)?;
+
+        let encoder_output =
+            self.encoder
+                .forward_t(&embedding_output, attention_mask, token_type_ids, train);
+        let decoder_output = self.decoder.forward_t(
+            &encoder_output.hidden_state,
+            &encoder_output.first_block_hidden_state,
+            attention_mask,
+            token_type_ids,
+            train,
+        );
+
+        let all_hidden_states = match (
+            encoder_output.all_hidden_states,
+            decoder_output.all_hidden_states,
+        ) {
+            (Some(mut encoder_hidden_states), Some(decoder_hidden_states)) => {
+                encoder_hidden_states.extend(decoder_hidden_states);
+                Some(encoder_hidden_states)
+            }
+            _ => None,
+        };
+        let all_attentions = match (encoder_output.all_attentions, decoder_output.all_attentions) {
+            (Some(mut encoder_attentions), Some(decoder_attentions)) => {
+                encoder_attentions.extend(decoder_attentions);
+                Some(encoder_attentions)
+            }
+            _ => None,
+        };
+
+        Ok(FunnelModelOutput {
+            hidden_state: decoder_output.hidden_state,
+            all_hidden_states,
+            all_attentions,
+        })
+    }
+}
+
+struct FunnelClassificationHead {
+    linear_hidden: nn::Linear,
+    dropout: Dropout,
+    linear_out: nn::Linear,
+}
+
+impl FunnelClassificationHead {
+    pub fn new<'p, P>(p: P, config: &FunnelConfig, num_labels: i64) -> FunnelClassificationHead
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let linear_hidden = nn::linear(
+            p / "linear_hidden",
+            config.d_model,
+            config.d_model,
+            Default::default(),
+        );
+        let dropout = Dropout::new(config.hidden_dropout);
+        let linear_out = nn::linear(
+            p / "linear_out",
+            config.d_model,
+            num_labels,
+            Default::default(),
+        );
+
+        FunnelClassificationHead {
+            linear_hidden,
+            dropout,
+            linear_out,
+        }
+    }
+
+    pub fn forward_t(&self, hidden_states: &Tensor, train: bool) -> Tensor {
+        hidden_states
+            .apply(&self.linear_hidden)
+            .tanh()
+            .apply_t(&self.dropout, train)
+            .apply(&self.linear_out)
+    }
+}
+
+/// # Funnel Transformer for sequence classification
+/// Base Funnel Transformer model with a classifier head to perform sentence or document-level classification.
+/// The classification head is applied to the first (*CLS*) position of the compressed sequence.
+/// It is made of the following blocks:
+/// - `funnel`: Base `FunnelBaseModel`
+/// - `classifier`: Two-layer classification head
+pub struct FunnelForSequenceClassification {
+    funnel: FunnelBaseModel,
+    classifier: FunnelClassificationHead,
+}
+
+impl FunnelForSequenceClassification {
+    /// Build a new `FunnelForSequenceClassification`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the Funnel model
+    /// * `config` - `FunnelConfig` object defining the model architecture
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::funnel::{FunnelConfig, FunnelForSequenceClassification};
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = FunnelConfig::from_file(config_path);
+    /// let funnel = FunnelForSequenceClassification::new(&p.root(), &config).unwrap();
+    /// ```
+    pub fn new<'p, P>(
+        p: P,
+        config: &FunnelConfig,
+    ) -> Result<FunnelForSequenceClassification, RustBertError>
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+
+        let funnel = FunnelBaseModel::new(p / "funnel", config);
+        let num_labels = config
+            .id2label
+            .as_ref()
+            .ok_or_else(|| {
+                RustBertError::InvalidConfigurationError(
+                    "num_labels not provided in configuration".to_string(),
+                )
+            })?
+            .len() as i64;
+        let classifier = FunnelClassificationHead::new(p / "classifier", config, num_labels);
+
+        Ok(FunnelForSequenceClassification { funnel, classifier })
+    }
+
+    /// Forward pass through the model
+    ///
+    /// # Arguments
+    ///
+    /// * `input_ids` - Optional input tensor of shape (*batch size*, *sequence_length*). If None, pre-computed embeddings must be provided (see `input_embeds`)
+    /// * `attention_mask` - Optional mask of shape (*batch size*, *sequence_length*). Masked position have value 0, non-masked value 1. If None set to 1
+    /// * `token_type_ids` - Optional segment id of shape (*batch size*, *sequence_length*). Convention is value of 0 for the first sentence (incl. *SEP*), 1 for the second sentence and 2 for the *CLS* token. If None set to 0.
+    /// * `input_embeds` - Optional pre-computed input embeddings of shape (*batch size*, *sequence_length*, *d_model*). If None, input ids must be provided (see `input_ids`)
+    /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
+    ///
+    /// # Returns
+    ///
+    /// * `FunnelSequenceClassificationOutput` containing:
+    ///   - `logits` - `Tensor` of shape (*batch size*, *num_labels*)
+    ///   - `all_hidden_states` - `Option<Vec<Tensor>>` of length *num_layers + 1* with shape (*batch size*, *sequence_length*, *d_model*)
+    ///   - `all_attentions` - `Option<Vec<Tensor>>` of length *num_layers* with shape (*batch size*, *n_head*, *sequence_length*, *sequence_length*)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use tch::{nn, Device, Tensor, no_grad};
+    /// # use rust_bert::Config;
+    /// # use std::path::Path;
+    /// # use tch::kind::Kind::Int64;
+    /// use rust_bert::funnel::{FunnelConfig, FunnelForSequenceClassification};
+    /// # let config_path = Path::new("path/to/config.json");
+    /// # let device = Device::Cpu;
+    /// # let vs = nn::VarStore::new(device);
+    /// # let config = FunnelConfig::from_file(config_path);
+    /// let model = FunnelForSequenceClassification::new(&vs.root(), &config).unwrap();
+    /// let (batch_size, sequence_length) = (64, 128);
+    /// let input_tensor = Tensor::rand(&[batch_size, sequence_length], (Int64, device));
+    /// let mask = Tensor::ones(&[batch_size, sequence_length], (Int64, device));
+    ///
+    /// let model_output = no_grad(|| {
+    ///     model
+    ///         .forward_t(Some(&input_tensor), Some(&mask), None, None, false)
+    ///         .unwrap()
+    /// });
+    /// ```
+    pub fn forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        attention_mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        input_embeddings: Option<&Tensor>,
+        train: bool,
+    ) -> Result<FunnelSequenceClassificationOutput, RustBertError> {
+        let base_model_output = self.funnel.forward_t(
+            input_ids,
+            attention_mask,
+            token_type_ids,
+            input_embeddings,
+            train,
+        )?;
+
+        let logits = self
+            .classifier
+            .forward_t(&base_model_output.hidden_state.select(1, 0), train);
+
+        Ok(FunnelSequenceClassificationOutput {
+            logits,
+            all_hidden_states: base_model_output.all_hidden_states,
+            all_attentions: base_model_output.all_attentions,
+        })
+    }
+}
+
+/// Container for the Funnel base model output.
+pub struct FunnelBaseModelOutput {
+    /// Last hidden states (pooled sequence for the base model, full sequence for the model with a decoder)
+    pub hidden_state: Tensor,
+    /// Hidden states for all intermediate layers
+    pub all_hidden_states: Option<Vec<Tensor>>,
+    /// Attention weights for all intermediate layers
+    pub all_attentions: Option<Vec<Tensor>>,
+}
+
+/// Container for the Funnel model output.
+pub type FunnelModelOutput = FunnelBaseModelOutput;
+
+/// Container for the Funnel sequence classification model output.
+pub struct FunnelSequenceClassificationOutput {
+    /// Logits for each input (sequence) for each target class
+    pub logits: Tensor,
+    /// Hidden states for all intermediate layers
+    pub all_hidden_states: Option<Vec<Tensor>>,
+    /// Attention weights for all intermediate layers
+    pub all_attentions: Option<Vec<Tensor>>,
+}
@@ -0,0 +1,61 @@
+//! # Funnel-Transformer: Filtering out Sequential Redundancy for Efficient Language Processing (Dai et al.)
+//!
+//! Implementation of the Funnel Transformer language model ([https://arxiv.org/abs/2006.03236](https://arxiv.org/abs/2006.03236) Dai, Lai, Yang, Le, 2020).
+//! The encoder progressively compresses the sequence of hidden states by pooling between blocks of relative attention layers.
+//! The base model is implemented in the `funnel_model::FunnelBaseModel` struct (encoder only, used for sequence-level tasks),
+//! and the full model including the upsampling decoder in `funnel_model::FunnelModel`. The following language model head has been implemented:
+//! - Sequence classification: `funnel_model::FunnelForSequenceClassification`
+//!
+//! # Model set-up and pre-trained weights loading
+//!
+//! All models expect the following resources:
+//! - Configuration file expected to have a structure following the [Transformers library](https://github.com/huggingface/transformers)
+//! - Model weights are expected to have a structure and parameter names following the [Transformers library](https://github.com/huggingface/transformers). A conversion using the Python utility scripts is required to convert the `.bin` weights to the `.ot` format.
+//! - `BertTokenizer` using a `vocab.txt` vocabulary
+//!
+//! The Funnel tokenizer uses a token type id of 2 for the *CLS* token, the sequence classification pipeline sets it automatically.
+//!
+//! ```no_run
+//! # fn main() -> anyhow::Result<()> {
+//! #
+//! use tch::{nn, Device};
+//! # use std::path::PathBuf;
+//! use rust_bert::funnel::{FunnelConfig, FunnelForSequenceClassification};
+//! use rust_bert::resources::{LocalResource, ResourceProvider};
+//! use rust_bert::Config;
+//! use rust_tokenizers::tokenizer::BertTokenizer;
+//!
+//! let config_resource = LocalResource {
+//!     local_path: PathBuf::from("path/to/config.json"),
+//! };
+//! let vocab_resource = LocalResource {
+//!     local_path: PathBuf::from("path/to/vocab.txt"),
+//! };
+//! let weights_resource = LocalResource {
+//!     local_path: PathBuf::from("path/to/model.ot"),
+//! };
+//! let config_path = config_resource.get_local_path()?;
+//! let vocab_path = vocab_resource.get_local_path()?;
+//! let weights_path = weights_resource.get_local_path()?;
+//! let device = Device::cuda_if_available();
+//! let mut vs = nn::VarStore::new(device);
+//! let tokenizer: BertTokenizer =
+//!     BertTokenizer::from_file(vocab_path.to_str().unwrap(), true, true)?;
+//! let config = FunnelConfig::from_file(config_path);
+//! let funnel_model = FunnelForSequenceClassification::new(&vs.root(), &config)?;
+//! vs.load(weights_path)?;
+//!
+//! # Ok(())
+//! # }
+//! ```
+
+mod attention;
+mod embeddings;
+mod encoder;
+mod funnel_model;
+
+pub use funnel_model::{
+    FunnelAttentionType, FunnelBaseModel, FunnelBaseModelOutput, FunnelConfig,
+    FunnelForSequenceClassification, FunnelModel, FunnelModelOutput, FunnelPoolingType,
+    FunnelSequenceClassificationOutput,
+};
@@ -165,6 +165,7 @@ impl From<&LongT5Config> for T5Config {
             task_specific_params: val.task_specific_params.clone(),
             output_attentions: val.output_attentions,
             output_hidden_states: val.output_hidden_states,
+            id2label: None,
         }
     }
 }
@@ -595,7 +596,11 @@ impl LongT5Generator {
 
         let config = LongT5Config::from_file(config_path);
         let model = LongT5ForConditionalGeneration::new(var_store.root(), &config);
-        crate::resources::load_weights(&generate_config.model_resource, &mut var_store)?;
+        crate::resources::load_weights_with_tied_embeddings(
+            &generate_config.model_resource,
+            &mut var_store,
+            config.tie_word_embeddings.unwrap_or(true),
+        )?;
 
         let bos_token_id = config.bos_token_id;
         let eos_token_ids = Some(match config.eos_token_id {
@@ -8,6 +8,7 @@ pub mod deberta_v2;
 pub mod distilbert;
 pub mod electra;
 pub mod fnet;
+pub mod funnel;
 pub mod gpt2;
 pub mod gpt_j;
 pub mod gpt_neo;
@@ -13,11 +13,15 @@
 
 use crate::bert::{BertConfig, BertEmbedding};
 use crate::common::dropout::Dropout;
-use crate::common::embeddings::process_ids_embeddings_pair;
+use crate::common::embeddings::get_shape_and_device_from_ids_embeddings_pair;
+use crate::common::fallible::{f_embedding, f_layer_norm};
 use crate::RustBertError;
 use std::borrow::Borrow;
 use tch::nn::{embedding, EmbeddingConfig};
-use tch::{nn, Kind, Tensor};
+use tch::{nn, Kind, TchError, Tensor};
+
+/// Epsilon of the embeddings layer normalization
+const LAYER_NORM_EPS: f64 = 1e-12;
 
 #[derive(Debug)]
 /// # BertEmbeddings implementation for RoBERTa model
@@ -32,20 +36,22 @@ pub struct RobertaEmbeddings {
 }
 
 impl RobertaEmbeddings {
-    fn create_position_ids_from_input_ids(&self, x: &Tensor) -> Tensor {
-        let mask: Tensor = x.ne(self.padding_index).to_kind(Kind::Int64);
-        mask.cumsum(1, Kind::Int64) * mask + self.padding_index
+    fn create_position_ids_from_input_ids(&self, x: &Tensor) -> Result<Tensor, TchError> {
+        let mask = x.f_ne(self.padding_index)?.f_to_kind(Kind::Int64)?;
+        mask.f_cumsum(1, Kind::Int64)?
+            .f_mul(&mask)?
+            .f_add_scalar(self.padding_index)
     }
 
-    fn create_position_ids_from_embeddings(&self, x: &Tensor) -> Tensor {
+    fn create_position_ids_from_embeddings(&self, x: &Tensor) -> Result<Tensor, TchError> {
         let input_shape = x.size();
         let input_shape = vec![input_shape[0], input_shape[1]];
-        let position_ids: Tensor = Tensor::arange_start(
+        let position_ids = Tensor::f_arange_start(
             self.padding_index + 1,
             input_shape[0],
             (Kind::Int64, x.device()),
-        );
-        position_ids.unsqueeze(0).expand(&input_shape, true)
+        )?;
+        position_ids.f_unsqueeze(0)?.f_expand(&input_shape, true)
     }
 }
 
@@ -105,7 +111,7 @@ impl BertEmbedding for RobertaEmbeddings {
         );
 
         let layer_norm_config = nn::LayerNormConfig {
-            eps: 1e-12,
+            eps: LAYER_NORM_EPS,
             ..Default::default()
         };
         let layer_norm: nn::LayerNorm =
@@ -177,26 +183,26 @@ impl BertEmbedding for RobertaEmbeddings {
         input_embeds: Option<&Tensor>,
         train: bool,
     ) -> Result<Tensor, RustBertError> {
-        let (calc_input_embeddings, input_shape, _) =
-            process_ids_embeddings_pair(input_ids, input_embeds, &self.word_embeddings)?;
+        let (input_shape, device) =
+            get_shape_and_device_from_ids_embeddings_pair(input_ids, input_embeds)?;
+        let calc_input_embeddings = input_ids
+            .map(|input_ids| f_embedding(&self.word_embeddings, input_ids, self.padding_index))
+            .transpose()?;
 
         let input_embeddings =
             input_embeds.unwrap_or_else(|| calc_input_embeddings.as_ref().unwrap());
 
         let calc_position_ids = if position_ids.is_none() {
             Some(match input_ids {
-                Some(value) => self.create_position_ids_from_input_ids(value),
-                None => self.create_position_ids_from_embeddings(input_embeds.unwrap()),
+                Some(value) => self.create_position_ids_from_input_ids(value)?,
+                None => self.create_position_ids_from_embeddings(input_embeds.unwrap())?,
             })
         } else {
             None
         };
 
         let calc_token_type_ids = if token_type_ids.is_none() {
-            Some(Tensor::zeros(
-                input_shape,
-                (Kind::Int64, input_embeddings.device()),
-            ))
+            Some(Tensor::f_zeros(input_shape, (Kind::Int64, device))?)
         } else {
             None
         };
@@ -205,13 +211,15 @@ impl BertEmbedding for RobertaEmbeddings {
         let token_type_ids =
             token_type_ids.unwrap_or_else(|| calc_token_type_ids.as_ref().unwrap());
 
-        let position_embeddings = position_ids.apply(&self.position_embeddings);
-        let token_type_embeddings = token_type_ids.apply(&self.token_type_embeddings);
+        let position_embeddings = f_embedding(&self.position_embeddings, position_ids, -1)?;
+        let token_type_embeddings = f_embedding(&self.token_type_embeddings, token_type_ids, -1)?;
 
-        let input_embeddings: Tensor =
-            input_embeddings + position_embeddings + token_type_embeddings;
-        Ok(input_embeddings
-            .apply(&self.layer_norm)
-            .apply_t(&self.dropout, train))
+        let input_embeddings = input_embeddings
+            .f_add(&position_embeddings)?
+            .f_add(&token_type_embeddings)?;
+        Ok(self.dropout.f_forward_t(
+            &f_layer_norm(&self.layer_norm, &input_embeddings, LAYER_NORM_EPS)?,
+            train,
+        )?)
     }
 }
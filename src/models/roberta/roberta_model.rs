@@ -14,12 +14,13 @@
 use crate::bert::{BertConfig, BertModel};
 use crate::common::activations::_gelu;
 use crate::common::dropout::Dropout;
+use crate::common::fallible::f_linear;
 use crate::common::linear::{linear_no_bias, LinearNoBias};
 use crate::roberta::embeddings::RobertaEmbeddings;
 use crate::RustBertError;
 use std::borrow::Borrow;
 use tch::nn::init::DEFAULT_KAIMING_UNIFORM;
-use tch::{nn, Tensor};
+use tch::{nn, TchError, Tensor};
 
 /// # RoBERTa Pretrained model weight files
 pub struct RobertaModelResources;
@@ -457,14 +458,18 @@ impl RobertaClassificationHead {
         })
     }
 
-    pub fn forward_t(&self, hidden_states: &Tensor, train: bool) -> Tensor {
-        hidden_states
-            .select(1, 0)
-            .apply_t(&self.dropout, train)
-            .apply(&self.dense)
-            .tanh()
-            .apply_t(&self.dropout, train)
-            .apply(&self.out_proj)
+    /// Forward pass, returning the errors of the tensor operations instead of panicking
+    pub fn f_forward_t(&self, hidden_states: &Tensor, train: bool) -> Result<Tensor, TchError> {
+        let hidden_states = self.dropout.f_forward_t(
+            &hidden_states
+                .f_select(1, 0)?
+                .f_to_kind(self.dense.ws.kind())?,
+            train,
+        )?;
+        let hidden_states = self
+            .dropout
+            .f_forward_t(&f_linear(&self.dense, &hidden_states)?.f_tanh()?, train)?;
+        f_linear(&self.out_proj, &hidden_states)
     }
 }
 
@@ -579,28 +584,47 @@ impl RobertaForSequenceClassification {
         input_embeds: Option<&Tensor>,
         train: bool,
     ) -> RobertaSequenceClassificationOutput {
-        let base_model_output = self
-            .roberta
-            .forward_t(
-                input_ids,
-                mask,
-                token_type_ids,
-                position_ids,
-                input_embeds,
-                None,
-                None,
-                train,
-            )
-            .unwrap();
+        self.f_forward_t(
+            input_ids,
+            mask,
+            token_type_ids,
+            position_ids,
+            input_embeds,
+            train,
+        )
+        .unwrap()
+    }
+
+    /// Fallible version of `forward_t`, returning the errors of the model and of the tensor operations (e.g. a device
+    /// running out of memory) instead of panicking
+    pub fn f_forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        position_ids: Option<&Tensor>,
+        input_embeds: Option<&Tensor>,
+        train: bool,
+    ) -> Result<RobertaSequenceClassificationOutput, RustBertError> {
+        let base_model_output = self.roberta.forward_t(
+            input_ids,
+            mask,
+            token_type_ids,
+            position_ids,
+            input_embeds,
+            None,
+            None,
+            train,
+        )?;
 
         let logits = self
             .classifier
-            .forward_t(&base_model_output.hidden_state, train);
-        RobertaSequenceClassificationOutput {
+            .f_forward_t(&base_model_output.hidden_state, train)?;
+        Ok(RobertaSequenceClassificationOutput {
             logits,
             all_hidden_states: base_model_output.all_hidden_states,
             all_attentions: base_model_output.all_attentions,
-        }
+        })
     }
 }
 
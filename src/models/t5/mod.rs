@@ -59,7 +59,7 @@ pub(crate) use encoder::{T5Block, T5BlockOutput, T5LayerFF, T5StackOutput};
 pub(crate) use layer_norm::T5LayerNorm;
 pub(crate) use t5_model::{FeedForwardProj, TaskSpecificParams};
 pub use t5_model::{
-    T5Config, T5ConfigResources, T5ForConditionalGeneration, T5ForSentenceEmbeddings, T5Generator,
-    T5Model, T5ModelOutput, T5ModelResources, T5Prefix, T5SourceLanguages, T5TargetLanguages,
-    T5VocabResources,
+    T5Config, T5ConfigResources, T5ForConditionalGeneration, T5ForSentenceEmbeddings,
+    T5ForTextClassification, T5Generator, T5Model, T5ModelOutput, T5ModelResources, T5Prefix,
+    T5SourceLanguages, T5TargetLanguages, T5VocabResources,
 };
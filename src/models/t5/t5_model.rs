@@ -11,10 +11,11 @@
 // limitations under the License.
 
 use std::borrow::Borrow;
+use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 use tch::nn::{embedding, LinearConfig};
-use tch::{nn, Device, Tensor};
+use tch::{nn, Device, Kind, Tensor};
 
 use crate::pipelines::common::{ModelType, TokenizerOption};
 use crate::pipelines::generation_utils::private_generation_utils::{
@@ -148,6 +149,7 @@ pub struct T5Config {
     pub task_specific_params: Option<TaskSpecificParams>,
     pub output_attentions: Option<bool>,
     pub output_hidden_states: Option<bool>,
+    pub id2label: Option<HashMap<i64, String>>,
 }
 
 /// # T5 task-specific configurations
@@ -228,6 +230,7 @@ impl Default for T5Config {
             task_specific_params: None,
             output_attentions: None,
             output_hidden_states: None,
+            id2label: None,
         }
     }
 }
@@ -622,6 +625,200 @@ impl T5ForConditionalGeneration {
     }
 }
 
+/// # T5 for text-to-text classification
+/// T5 frames classification as the generation of the label text (e.g. `sst2 sentence: ...` -> `positive`).
+/// This model scores the likelihood of each candidate label sequence with the conditional generation head,
+/// constraining the output to the valid label set. The output is equivalent to an exhaustive constrained decoding
+/// and can be used in place of the logits of a classification head.
+/// It is made of the following blocks:
+/// - `model`: `T5ForConditionalGeneration` encoder-decoder model with language modeling head
+/// - `label_ids`: token ids of the candidate labels, padded to a common length
+pub struct T5ForTextClassification {
+    model: T5ForConditionalGeneration,
+    label_ids: Tensor,
+    label_mask: Tensor,
+    decoder_input_ids: Tensor,
+    pad_token_id: i64,
+}
+
+impl T5ForTextClassification {
+    /// Build a new `T5ForTextClassification`
+    ///
+    /// # Arguments
+    ///
+    /// * `p` - Variable store path for the root of the T5 model
+    /// * `config` - `T5Config` object defining the model architecture
+    /// * `label_token_ids` - Token ids of each candidate label (including the end of sequence token), ordered by label id
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::t5::{T5Config, T5ForTextClassification};
+    /// use rust_bert::Config;
+    /// use std::path::Path;
+    /// use tch::{nn, Device};
+    ///
+    /// let config_path = Path::new("path/to/config.json");
+    /// let device = Device::Cpu;
+    /// let p = nn::VarStore::new(device);
+    /// let config = T5Config::from_file(config_path);
+    /// let label_token_ids = vec![vec![2841, 1], vec![1465, 1]];
+    /// let t5 = T5ForTextClassification::new(&p.root(), &config, &label_token_ids).unwrap();
+    /// ```
+    pub fn new<'p, P>(
+        p: P,
+        config: &T5Config,
+        label_token_ids: &[Vec<i64>],
+    ) -> Result<T5ForTextClassification, RustBertError>
+    where
+        P: Borrow<nn::Path<'p>>,
+    {
+        let p = p.borrow();
+        let label_length = label_token_ids
+            .iter()
+            .map(|label| label.len())
+            .max()
+            .unwrap_or(0);
+        if label_length == 0 {
+            return Err(RustBertError::InvalidConfigurationError(
+                "At least one non-empty label must be provided for T5 text classification"
+                    .to_string(),
+            ));
+        }
+        let pad_token_id = config.pad_token_id.unwrap_or(0);
+        let decoder_start_token_id = config.decoder_start_token_id.unwrap_or(pad_token_id);
+
+        let mut label_ids = Vec::with_capacity(label_token_ids.len());
+        let mut label_mask = Vec::with_capacity(label_token_ids.len());
+        let mut decoder_input_ids = Vec::with_capacity(label_token_ids.len());
+        for label in label_token_ids {
+            let padding_length = label_length - label.len();
+            let mut ids = label.clone();
+            ids.extend(vec![pad_token_id; padding_length]);
+            let mut mask = vec![1i64; label.len()];
+            mask.extend(vec![0; padding_length]);
+            let mut shifted_ids = vec![decoder_start_token_id];
+            shifted_ids.extend_from_slice(&ids[..label_length - 1]);
+            label_ids.push(Tensor::from_slice(&ids));
+            label_mask.push(Tensor::from_slice(&mask));
+            decoder_input_ids.push(Tensor::from_slice(&shifted_ids));
+        }
+
+        let model = T5ForConditionalGeneration::new(p, config);
+        Ok(T5ForTextClassification {
+            model,
+            label_ids: Tensor::stack(&label_ids, 0).to(p.device()),
+            label_mask: Tensor::stack(&label_mask, 0).to(p.device()),
+            decoder_input_ids: Tensor::stack(&decoder_input_ids, 0).to(p.device()),
+            pad_token_id,
+        })
+    }
+
+    /// Forward pass through the model
+    ///
+    /// # Arguments
+    ///
+    /// * `input_ids` - Input tensor of shape (*batch size*, *source_sequence_length*).
+    /// * `attention_mask` - Optional attention mask of shape (*batch size*, *source_sequence_length*). Positions with a mask with value 0 will be masked. If not provided, padding positions are masked.
+    /// * `train` - boolean flag to turn on/off the dropout layers in the model. Should be set to false for inference.
+    ///
+    /// # Returns
+    ///
+    /// * `Tensor` of shape (*batch size*, *num_labels*) containing the log-likelihood of each label sequence
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use tch::{nn, Device, Tensor, no_grad};
+    /// # use rust_bert::Config;
+    /// # use std::path::Path;
+    /// # use tch::kind::Kind::Int64;
+    /// use rust_bert::t5::{T5Config, T5ForTextClassification};
+    /// # let config_path = Path::new("path/to/config.json");
+    /// # let device = Device::Cpu;
+    /// # let vs = nn::VarStore::new(device);
+    /// # let config = T5Config::from_file(config_path);
+    /// # let t5_model = T5ForTextClassification::new(&vs.root(), &config, &[vec![2841, 1], vec![1465, 1]]).unwrap();
+    /// let (batch_size, sequence_length) = (64, 128);
+    /// let input_tensor = Tensor::rand(&[batch_size, sequence_length], (Int64, device));
+    /// let attention_mask = Tensor::ones(&[batch_size, sequence_length], (Int64, device));
+    ///
+    /// let label_scores =
+    ///     no_grad(|| t5_model.forward_t(&input_tensor, Some(&attention_mask), false));
+    /// ```
+    pub fn forward_t(
+        &self,
+        input_ids: &Tensor,
+        attention_mask: Option<&Tensor>,
+        train: bool,
+    ) -> Tensor {
+        let (batch_size, sequence_length) = input_ids.size2().unwrap();
+        let (num_labels, label_length) = self.label_ids.size2().unwrap();
+        let attention_mask = attention_mask
+            .map(|mask| mask.shallow_clone())
+            .unwrap_or_else(|| input_ids.ne(self.pad_token_id).to_kind(Kind::Int64));
+
+        let encoder_hidden_state = self
+            .model
+            .base_model
+            .encoder
+            .forward_t(
+                Some(input_ids),
+                Some(&attention_mask),
+                None,
+                None,
+                None,
+                &self.model.base_model.embeddings,
+                None,
+                train,
+            )
+            .unwrap()
+            .hidden_state;
+        let hidden_size = encoder_hidden_state.size()[2];
+
+        let expanded_hidden_state = encoder_hidden_state
+            .unsqueeze(1)
+            .expand([batch_size, num_labels, sequence_length, hidden_size], true)
+            .reshape([batch_size * num_labels, sequence_length, hidden_size]);
+        let expanded_attention_mask = attention_mask
+            .unsqueeze(1)
+            .expand([batch_size, num_labels, sequence_length], true)
+            .reshape([batch_size * num_labels, sequence_length]);
+        let expand_labels = |labels: &Tensor| {
+            labels
+                .unsqueeze(0)
+                .expand([batch_size, num_labels, label_length], true)
+                .reshape([batch_size * num_labels, label_length])
+        };
+        let decoder_input_ids = expand_labels(&self.decoder_input_ids);
+        let label_ids = expand_labels(&self.label_ids);
+        let label_mask = expand_labels(&self.label_mask);
+
+        let lm_logits = self
+            .model
+            .forward_t(
+                None,
+                Some(&expanded_attention_mask),
+                Some(&expanded_hidden_state),
+                Some(&decoder_input_ids),
+                None,
+                None,
+                None,
+                None,
+                train,
+            )
+            .decoder_output;
+
+        lm_logits
+            .log_softmax(-1, Kind::Float)
+            .gather(2, &label_ids.unsqueeze(-1), false)
+            .squeeze_dim(-1)
+            .multiply(&label_mask.to_kind(Kind::Float))
+            .sum_dim_intlist([-1].as_slice(), false, Kind::Float)
+            .view([batch_size, num_labels])
+    }
+}
+
 /// # T5 for sentence embeddings
 /// Transformer usable in [`SentenceEmbeddingsModel`](crate::pipelines::sentence_embeddings::SentenceEmbeddingsModel).
 pub struct T5ForSentenceEmbeddings {
@@ -763,7 +960,11 @@ impl T5Generator {
 
         let config = T5Config::from_file(config_path);
         let model = T5ForConditionalGeneration::new(var_store.root(), &config);
-        crate::resources::load_weights(&generate_config.model_resource, &mut var_store)?;
+        crate::resources::load_weights_with_tied_embeddings(
+            &generate_config.model_resource,
+            &mut var_store,
+            config.tie_word_embeddings.unwrap_or(true),
+        )?;
 
         let bos_token_id = Some(config.bos_token_id.unwrap_or(-1));
         let eos_token_ids = Some(match config.eos_token_id {
@@ -25,6 +25,7 @@ use crate::deberta_v2::DebertaV2Config;
 use crate::distilbert::DistilBertConfig;
 use crate::electra::ElectraConfig;
 use crate::fnet::FNetConfig;
+use crate::funnel::FunnelConfig;
 use crate::gpt2::Gpt2Config;
 use crate::gpt_j::GptJConfig;
 use crate::gpt_neo::GptNeoConfig;
@@ -52,12 +53,13 @@ use rust_tokenizers::tokenizer::{
     XLNetTokenizer,
 };
 use rust_tokenizers::vocab::Vocab;
-use rust_tokenizers::{TokenIdsWithOffsets, TokenizedInput, TokensWithOffsets};
+use rust_tokenizers::{Mask, TokenIdsWithOffsets, TokenizedInput, TokensWithOffsets};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tch::{Device, Kind, Tensor};
 
 #[cfg(feature = "onnx")]
@@ -97,6 +99,21 @@ impl ResourceProvider for ModelResource {
             ModelResource::ONNX(_) => Err(RustBertError::UnsupportedError),
         }
     }
+    fn identifier(&self) -> String {
+        match self {
+            ModelResource::Torch(ref resource) => resource.identifier(),
+            #[cfg(feature = "onnx")]
+            ModelResource::ONNX(onnx_model_resources) => [
+                &onnx_model_resources.encoder_resource,
+                &onnx_model_resources.decoder_resource,
+                &onnx_model_resources.decoder_with_past_resource,
+            ]
+            .iter()
+            .filter_map(|resource| resource.as_ref().map(|resource| resource.identifier()))
+            .collect::<Vec<String>>()
+            .join(", "),
+        }
+    }
 }
 
 pub struct ONNXLocalPaths {
@@ -189,10 +206,141 @@ pub enum ModelType {
     #[serde(alias = "m2m100")]
     NLLB,
     FNet,
+    FunnelTransformer,
     #[cfg(feature = "onnx")]
     ONNX,
 }
 
+/// Architecture name prefixes (e.g. `BertForSequenceClassification`) and the corresponding model types,
+/// used when the configuration does not provide a known `model_type`.
+/// Prefixes shared by several architectures are listed after the longer, more specific ones.
+const ARCHITECTURE_PREFIXES: [(&str, ModelType); 27] = [
+    ("DistilBert", ModelType::DistilBert),
+    ("MobileBert", ModelType::MobileBert),
+    ("Bert", ModelType::Bert),
+    ("DebertaV2", ModelType::DebertaV2),
+    ("Deberta", ModelType::Deberta),
+    ("XLMRoberta", ModelType::XLMRoberta),
+    ("Roberta", ModelType::Roberta),
+    ("MBart", ModelType::MBart),
+    ("Bart", ModelType::Bart),
+    ("Electra", ModelType::Electra),
+    ("Marian", ModelType::Marian),
+    ("LongT5", ModelType::LongT5),
+    ("T5", ModelType::T5),
+    ("Albert", ModelType::Albert),
+    ("XLNet", ModelType::XLNet),
+    ("GPT2", ModelType::GPT2),
+    ("GPTJ", ModelType::GPTJ),
+    ("GPTNeo", ModelType::GPTNeo),
+    ("OpenAIGPT", ModelType::OpenAiGpt),
+    ("Reformer", ModelType::Reformer),
+    ("ProphetNet", ModelType::ProphetNet),
+    ("Longformer", ModelType::Longformer),
+    ("Pegasus", ModelType::Pegasus),
+    ("M2M100", ModelType::M2M100),
+    ("FNet", ModelType::FNet),
+    ("Funnel", ModelType::FunnelTransformer),
+    ("Camembert", ModelType::Roberta),
+];
+
+impl ModelType {
+    /// Infers the model type from a configuration file (e.g. config.json), reading its `model_type` field
+    /// or, if missing or unknown, its `architectures` field.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - `&Path` to the model configuration file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<ModelType, RustBertError>` the model type, or an `InvalidConfigurationError` if it could not be inferred
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::ModelType;
+    /// use std::path::Path;
+    ///
+    /// let model_type = ModelType::from_config(Path::new("path/to/config.json"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_config(path: &Path) -> Result<ModelType, RustBertError> {
+        #[derive(Deserialize)]
+        struct ModelTypeConfig {
+            model_type: Option<String>,
+            architectures: Option<Vec<String>>,
+        }
+
+        let config: ModelTypeConfig = serde_json::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|error| {
+                RustBertError::InvalidConfigurationError(format!(
+                    "Could not parse configuration: {error}"
+                ))
+            })?;
+        config
+            .model_type
+            .as_deref()
+            .and_then(Self::from_model_type_name)
+            .or_else(|| {
+                config
+                    .architectures
+                    .iter()
+                    .flatten()
+                    .find_map(|architecture| Self::from_architecture_name(architecture))
+            })
+            .ok_or_else(|| {
+                RustBertError::InvalidConfigurationError(format!(
+                    "Could not infer a supported model type from {path:?} (model_type: {:?}, architectures: {:?})",
+                    config.model_type, config.architectures
+                ))
+            })
+    }
+
+    /// Returns the model type matching a `model_type` configuration value (e.g. `bert` or `xlm-roberta`)
+    fn from_model_type_name(name: &str) -> Option<ModelType> {
+        Some(match name {
+            "bart" => ModelType::Bart,
+            "bert" => ModelType::Bert,
+            "distilbert" => ModelType::DistilBert,
+            "deberta" => ModelType::Deberta,
+            "deberta-v2" => ModelType::DebertaV2,
+            "roberta" | "camembert" => ModelType::Roberta,
+            "xlm-roberta" => ModelType::XLMRoberta,
+            "electra" => ModelType::Electra,
+            "marian" => ModelType::Marian,
+            "mobilebert" => ModelType::MobileBert,
+            "t5" => ModelType::T5,
+            "longt5" => ModelType::LongT5,
+            "albert" => ModelType::Albert,
+            "xlnet" => ModelType::XLNet,
+            "gpt2" => ModelType::GPT2,
+            "gptj" => ModelType::GPTJ,
+            "gpt_neo" => ModelType::GPTNeo,
+            "openai-gpt" => ModelType::OpenAiGpt,
+            "reformer" => ModelType::Reformer,
+            "prophetnet" => ModelType::ProphetNet,
+            "longformer" => ModelType::Longformer,
+            "pegasus" => ModelType::Pegasus,
+            "mbart" => ModelType::MBart,
+            "m2m_100" => ModelType::M2M100,
+            "fnet" => ModelType::FNet,
+            "funnel" => ModelType::FunnelTransformer,
+            _ => return None,
+        })
+    }
+
+    /// Returns the model type matching an architecture name (e.g. `BertForSequenceClassification`)
+    fn from_architecture_name(name: &str) -> Option<ModelType> {
+        ARCHITECTURE_PREFIXES
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix))
+            .map(|(_, model_type)| *model_type)
+    }
+}
+
 /// # Abstraction that holds a model configuration, can be of any of the supported models
 pub enum ConfigOption {
     /// Bart configuration
@@ -243,6 +391,8 @@ pub enum ConfigOption {
     M2M100(M2M100Config),
     /// FNet configuration
     FNet(FNetConfig),
+    /// Funnel Transformer configuration
+    FunnelTransformer(FunnelConfig),
     /// ONNX Model configuration
     #[cfg(feature = "onnx")]
     ONNX(ONNXModelConfig),
@@ -322,11 +472,66 @@ impl ConfigOption {
                 ConfigOption::M2M100(M2M100Config::from_file(path))
             }
             ModelType::FNet => ConfigOption::FNet(FNetConfig::from_file(path)),
+            ModelType::FunnelTransformer => {
+                ConfigOption::FunnelTransformer(FunnelConfig::from_file(path))
+            }
             #[cfg(feature = "onnx")]
             ModelType::ONNX => ConfigOption::ONNX(ONNXModelConfig::from_file(path)),
         }
     }
 
+    /// Interface method to load a configuration from a JSON string
+    ///
+    /// # Arguments
+    ///
+    /// * `model_type` - `ModelType` of the configuration to parse
+    /// * `json` - `&str` containing the JSON configuration
+    pub fn from_json_str(model_type: ModelType, json: &str) -> Result<Self, RustBertError> {
+        Ok(match model_type {
+            ModelType::Bart => ConfigOption::Bart(BartConfig::from_json_str(json)?),
+            ModelType::Bert => ConfigOption::Bert(BertConfig::from_json_str(json)?),
+            ModelType::Deberta => ConfigOption::Deberta(DebertaConfig::from_json_str(json)?),
+            ModelType::DebertaV2 => ConfigOption::DebertaV2(DebertaV2Config::from_json_str(json)?),
+            ModelType::DistilBert => {
+                ConfigOption::DistilBert(DistilBertConfig::from_json_str(json)?)
+            }
+            ModelType::Electra => ConfigOption::Electra(ElectraConfig::from_json_str(json)?),
+            ModelType::Marian => ConfigOption::Marian(MarianConfig::from_json_str(json)?),
+            ModelType::MobileBert => {
+                ConfigOption::MobileBert(MobileBertConfig::from_json_str(json)?)
+            }
+            ModelType::T5 => ConfigOption::T5(T5Config::from_json_str(json)?),
+            ModelType::LongT5 => ConfigOption::LongT5(LongT5Config::from_json_str(json)?),
+            ModelType::Albert => ConfigOption::Albert(AlbertConfig::from_json_str(json)?),
+            ModelType::XLNet => ConfigOption::XLNet(XLNetConfig::from_json_str(json)?),
+            ModelType::GPT2 => ConfigOption::GPT2(Gpt2Config::from_json_str(json)?),
+            ModelType::GPTJ => ConfigOption::GPTJ(GptJConfig::from_json_str(json)?),
+            ModelType::GPTNeo => ConfigOption::GPTNeo(GptNeoConfig::from_json_str(json)?),
+            ModelType::OpenAiGpt => ConfigOption::OpenAiGpt(OpenAiGptConfig::from_json_str(json)?),
+            ModelType::Reformer => ConfigOption::Reformer(ReformerConfig::from_json_str(json)?),
+            ModelType::ProphetNet => {
+                ConfigOption::ProphetNet(ProphetNetConfig::from_json_str(json)?)
+            }
+            ModelType::Longformer => {
+                ConfigOption::Longformer(LongformerConfig::from_json_str(json)?)
+            }
+            ModelType::Pegasus => ConfigOption::Pegasus(PegasusConfig::from_json_str(json)?),
+            ModelType::Roberta | ModelType::XLMRoberta => {
+                ConfigOption::Roberta(RobertaConfig::from_json_str(json)?)
+            }
+            ModelType::MBart => ConfigOption::MBart(MBartConfig::from_json_str(json)?),
+            ModelType::M2M100 | ModelType::NLLB => {
+                ConfigOption::M2M100(M2M100Config::from_json_str(json)?)
+            }
+            ModelType::FNet => ConfigOption::FNet(FNetConfig::from_json_str(json)?),
+            ModelType::FunnelTransformer => {
+                ConfigOption::FunnelTransformer(FunnelConfig::from_json_str(json)?)
+            }
+            #[cfg(feature = "onnx")]
+            ModelType::ONNX => ConfigOption::ONNX(ONNXModelConfig::from_json_str(json)?),
+        })
+    }
+
     pub fn get_label_mapping(&self) -> &HashMap<i64, String> {
         match self {
             Self::Bart(config) => config
@@ -393,6 +598,10 @@ impl ConfigOption {
                 .id2label
                 .as_ref()
                 .expect("No label dictionary (id2label) provided in configuration file"),
+            Self::FunnelTransformer(config) => config
+                .id2label
+                .as_ref()
+                .expect("No label dictionary (id2label) provided in configuration file"),
             Self::Roberta(config) => config
                 .id2label
                 .as_ref()
@@ -402,7 +611,10 @@ impl ConfigOption {
                 .id2label
                 .as_ref()
                 .expect("No label dictionary (id2label) provided in configuration file"),
-            Self::T5(_) => panic!("T5 does not use a label mapping"),
+            Self::T5(config) => config
+                .id2label
+                .as_ref()
+                .expect("No label dictionary (id2label) provided in configuration file"),
             Self::LongT5(_) => panic!("LongT5 does not use a label mapping"),
             Self::OpenAiGpt(_) => panic!("OpenAI GPT does not use a label mapping"),
             Self::GPT2(_) => panic!("GPT2 does not use a label mapping"),
@@ -412,6 +624,106 @@ impl ConfigOption {
         }
     }
 
+    /// Returns the label ids of the configuration in the order of the model outputs. The classification heads have one
+    /// output per label (`num_labels` is the size of `id2label`): the labels are assigned to the outputs by increasing
+    /// id, so that configurations with ids that do not start from 0 or have gaps (e.g. `{"2": "A", "5": "B"}`) have
+    /// their labels at contiguous output indices (`[2, 5]`, the label 2 being the output 0).
+    pub fn get_output_label_ids(&self) -> Vec<i64> {
+        let mut ids = self.get_label_mapping().keys().copied().collect::<Vec<i64>>();
+        ids.sort_unstable();
+        if ids
+            .iter()
+            .enumerate()
+            .any(|(position, id)| *id != position as i64)
+        {
+            log::warn!(
+                "The label ids of the configuration ({ids:?}) are not contiguous from 0, labels are assigned to the model outputs by increasing id"
+            );
+        }
+        ids
+    }
+
+    /// Overrides the label mapping (`id2label`) of the configuration, e.g. with labels read from a separate file.
+    /// Returns an `InvalidConfigurationError` for models that do not use a label mapping.
+    pub fn set_label_mapping(
+        &mut self,
+        id2label: HashMap<i64, String>,
+    ) -> Result<(), RustBertError> {
+        match self {
+            Self::Bart(config) => config.id2label = Some(id2label),
+            Self::Bert(config) => config.id2label = Some(id2label),
+            Self::Deberta(config) => config.id2label = Some(id2label),
+            Self::DebertaV2(config) => config.id2label = Some(id2label),
+            Self::DistilBert(config) => config.id2label = Some(id2label),
+            Self::Electra(config) => config.id2label = Some(id2label),
+            Self::Marian(config) => config.id2label = Some(id2label),
+            Self::MobileBert(config) => config.id2label = Some(id2label),
+            Self::Albert(config) => config.id2label = Some(id2label),
+            Self::XLNet(config) => config.id2label = Some(id2label),
+            Self::Reformer(config) => config.id2label = Some(id2label),
+            Self::ProphetNet(config) => config.id2label = Some(id2label),
+            Self::Longformer(config) => config.id2label = Some(id2label),
+            Self::MBart(config) => config.id2label = Some(id2label),
+            Self::M2M100(config) => config.id2label = Some(id2label),
+            Self::FNet(config) => config.id2label = Some(id2label),
+            Self::FunnelTransformer(config) => config.id2label = Some(id2label),
+            Self::Roberta(config) => config.id2label = Some(id2label),
+            #[cfg(feature = "onnx")]
+            Self::ONNX(config) => config.id2label = Some(id2label),
+            Self::T5(config) => config.id2label = Some(id2label),
+            Self::LongT5(_)
+            | Self::OpenAiGpt(_)
+            | Self::GPT2(_)
+            | Self::GPTJ(_)
+            | Self::GPTNeo(_)
+            | Self::Pegasus(_) => {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "This model does not use a label mapping".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Enables or disables the output of the attention weights of each layer by the model built from this
+    /// configuration. Returns an `InvalidConfigurationError` for models not supporting it.
+    pub fn set_output_attentions(&mut self, output_attentions: bool) -> Result<(), RustBertError> {
+        match self {
+            Self::Bert(config) | Self::Roberta(config) => {
+                config.output_attentions = Some(output_attentions)
+            }
+            Self::DistilBert(config) => config.output_attentions = Some(output_attentions),
+            Self::MobileBert(config) => config.output_attentions = Some(output_attentions),
+            _ => {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "Attentions output not supported for this model".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Enables or disables the output of the hidden states of each layer by the model built from this
+    /// configuration. Returns an `InvalidConfigurationError` for models not supporting it.
+    pub fn set_output_hidden_states(
+        &mut self,
+        output_hidden_states: bool,
+    ) -> Result<(), RustBertError> {
+        match self {
+            Self::Bert(config) | Self::Roberta(config) => {
+                config.output_hidden_states = Some(output_hidden_states)
+            }
+            Self::DistilBert(config) => config.output_hidden_states = Some(output_hidden_states),
+            Self::Albert(config) => config.output_hidden_states = Some(output_hidden_states),
+            _ => {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "Hidden states output not supported for this model".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_max_len(&self) -> Option<i64> {
         match self {
             Self::Bart(config) => Some(config.max_position_embeddings),
@@ -437,6 +749,7 @@ impl ConfigOption {
             Self::MBart(config) => Some(config.max_position_embeddings),
             Self::M2M100(config) => Some(config.max_position_embeddings),
             Self::FNet(config) => Some(config.max_position_embeddings),
+            Self::FunnelTransformer(_) => None,
             Self::Roberta(config) => Some(config.max_position_embeddings),
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.max_position_embeddings,
@@ -468,12 +781,63 @@ impl ConfigOption {
             Self::MBart(config) => config.vocab_size,
             Self::M2M100(config) => config.vocab_size,
             Self::FNet(config) => config.vocab_size,
+            Self::FunnelTransformer(config) => config.vocab_size,
             Self::Roberta(config) => config.vocab_size,
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.vocab_size,
         }
     }
 
+    /// Returns the number of token types (segments) supported by the model, if the model uses token type ids.
+    pub fn get_type_vocab_size(&self) -> Option<i64> {
+        match self {
+            Self::Bert(config) => Some(config.type_vocab_size),
+            Self::Deberta(config) => Some(config.type_vocab_size),
+            Self::DebertaV2(config) => Some(config.type_vocab_size),
+            Self::Electra(config) => Some(config.type_vocab_size),
+            Self::MobileBert(config) => Some(config.type_vocab_size),
+            Self::Albert(config) => Some(config.type_vocab_size),
+            Self::Longformer(config) => Some(config.type_vocab_size),
+            Self::FNet(config) => Some(config.type_vocab_size),
+            Self::Roberta(config) => Some(config.type_vocab_size),
+            _ => None,
+        }
+    }
+
+    /// Returns the dimension of the input embeddings (the input of the encoder, that may differ from the hidden size),
+    /// if the model accepts input embeddings.
+    pub fn get_embedding_size(&self) -> Option<i64> {
+        match self {
+            Self::Bart(config) => Some(config.d_model),
+            Self::Bert(config) => Some(config.hidden_size),
+            Self::Deberta(config) => Some(config.embedding_size.unwrap_or(config.hidden_size)),
+            Self::DebertaV2(config) => Some(config.embedding_size.unwrap_or(config.hidden_size)),
+            Self::DistilBert(config) => Some(config.dim),
+            Self::Electra(config) => Some(config.embedding_size),
+            Self::MobileBert(config) => Some(config.embedding_size),
+            Self::Albert(config) => Some(config.embedding_size),
+            Self::XLNet(config) => Some(config.d_model),
+            Self::Longformer(config) => Some(config.hidden_size),
+            Self::FNet(config) => Some(config.hidden_size),
+            Self::FunnelTransformer(config) => Some(config.d_model),
+            Self::Roberta(config) => Some(config.hidden_size),
+            Self::T5(config) => Some(config.d_model),
+            Self::LongT5(config) => Some(config.d_model),
+            Self::Marian(_)
+            | Self::OpenAiGpt(_)
+            | Self::GPT2(_)
+            | Self::GPTJ(_)
+            | Self::Reformer(_)
+            | Self::ProphetNet(_)
+            | Self::Pegasus(_)
+            | Self::GPTNeo(_)
+            | Self::MBart(_)
+            | Self::M2M100(_) => None,
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => None,
+        }
+    }
+
     pub fn get_decoder_start_token_id(&self) -> Option<i64> {
         match self {
             Self::Bart(config) => config.decoder_start_token_id,
@@ -499,6 +863,7 @@ impl ConfigOption {
             Self::MBart(config) => config.decoder_start_token_id,
             Self::M2M100(config) => config.decoder_start_token_id,
             Self::FNet(config) => config.decoder_start_token_id,
+            Self::FunnelTransformer(_) => None,
             Self::Roberta(_) => None,
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.decoder_start_token_id,
@@ -530,6 +895,7 @@ impl ConfigOption {
             Self::MBart(config) => config.forced_bos_token_id,
             Self::M2M100(config) => config.forced_bos_token_id,
             Self::FNet(_) => None,
+            Self::FunnelTransformer(_) => None,
             Self::Roberta(_) => None,
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.forced_bos_token_id,
@@ -561,6 +927,7 @@ impl ConfigOption {
             Self::MBart(config) => config.forced_eos_token_id,
             Self::M2M100(config) => config.forced_eos_token_id,
             Self::FNet(_) => None,
+            Self::FunnelTransformer(_) => None,
             Self::Roberta(_) => None,
             #[cfg(feature = "onnx")]
             Self::ONNX(config) => config.forced_eos_token_id,
@@ -574,7 +941,7 @@ impl TryFrom<&ConfigOption> for BertConfig {
     fn try_from(config: &ConfigOption) -> Result<Self, Self::Error> {
         match config {
             ConfigOption::Bert(config) | ConfigOption::Roberta(config) => Ok(config.clone()),
-            _ => Err(RustBertError::InvalidConfigurationError(
+            _ => Err(RustBertError::ConfigurationMismatchError(
                 "You can only supply a BertConfig for Bert or a RobertaConfig for Roberta!"
                     .to_string(),
             )),
@@ -589,7 +956,7 @@ impl TryFrom<&ConfigOption> for DistilBertConfig {
         if let ConfigOption::DistilBert(config) = config {
             Ok(config.clone())
         } else {
-            Err(RustBertError::InvalidConfigurationError(
+            Err(RustBertError::ConfigurationMismatchError(
                 "You can only supply a DistilBertConfig for DistilBert!".to_string(),
             ))
         }
@@ -603,7 +970,7 @@ impl TryFrom<&ConfigOption> for AlbertConfig {
         if let ConfigOption::Albert(config) = config {
             Ok(config.clone())
         } else {
-            Err(RustBertError::InvalidConfigurationError(
+            Err(RustBertError::ConfigurationMismatchError(
                 "You can only supply an AlbertConfig for Albert!".to_string(),
             ))
         }
@@ -617,13 +984,30 @@ impl TryFrom<&ConfigOption> for T5Config {
         if let ConfigOption::T5(config) = config {
             Ok(config.clone())
         } else {
-            Err(RustBertError::InvalidConfigurationError(
+            Err(RustBertError::ConfigurationMismatchError(
                 "You can only supply a T5Config for T5!".to_string(),
             ))
         }
     }
 }
 
+/// Converts token ids to their string representation in a vocabulary
+fn ids_to_tokens<V: Vocab>(vocab: &V, token_ids: &[i64]) -> Vec<String> {
+    token_ids
+        .iter()
+        .map(|token_id| vocab.id_to_token(token_id))
+        .collect()
+}
+
+/// Returns the largest token id of a vocabulary + 1
+fn vocab_size<V: Vocab>(vocab: &V) -> i64 {
+    vocab
+        .values()
+        .values()
+        .max()
+        .map_or(0, |max_token_id| max_token_id + 1)
+}
+
 impl TokenizerOption {
     /// Interface method to load a tokenizer from file
     pub fn from_file(
@@ -641,7 +1025,8 @@ impl TokenizerOption {
             ModelType::Bert
             | ModelType::DistilBert
             | ModelType::Electra
-            | ModelType::MobileBert => {
+            | ModelType::MobileBert
+            | ModelType::FunnelTransformer => {
                 if add_prefix_space.is_some() {
                     return Err(RustBertError::InvalidConfigurationError(
                         format!("Optional input `add_prefix_space` set to value {} but cannot be used by {:?}",
@@ -1739,6 +2124,69 @@ impl TokenizerOption {
         }
     }
 
+    /// Interface method to convert ids to tokens. Ids missing from the vocabulary map to the unknown token.
+    pub fn convert_ids_to_tokens(&self, token_ids: &[i64]) -> Vec<String> {
+        match *self {
+            Self::Bert(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::Deberta(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::DebertaV2(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::Roberta(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::Bart(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::Marian(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::T5(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::XLMRoberta(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::Albert(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::XLNet(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::GPT2(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::OpenAiGpt(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::Reformer(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::ProphetNet(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::Pegasus(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::MBart50(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::M2M100(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::NLLB(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+            Self::FNet(ref tokenizer) => {
+                ids_to_tokens(MultiThreadedTokenizer::vocab(tokenizer), token_ids)
+            }
+        }
+    }
+
     /// Interface method
     pub fn get_unk_id(&self) -> i64 {
         match *self {
@@ -1821,6 +2269,31 @@ impl TokenizerOption {
         }
     }
 
+    /// Returns the size of the vocabulary (largest token id + 1), including the special and added tokens
+    pub fn get_vocab_size(&self) -> i64 {
+        match *self {
+            Self::Bert(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::Deberta(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::DebertaV2(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::Roberta(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::Bart(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::XLMRoberta(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::Marian(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::T5(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::Albert(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::XLNet(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::GPT2(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::OpenAiGpt(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::Reformer(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::ProphetNet(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::Pegasus(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::MBart50(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::M2M100(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::NLLB(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+            Self::FNet(ref tokenizer) => vocab_size(MultiThreadedTokenizer::vocab(tokenizer)),
+        }
+    }
+
     /// Interface method
     pub fn get_pad_id(&self) -> Option<i64> {
         match *self {
@@ -1958,6 +2431,58 @@ impl TokenizerOption {
         }
     }
 
+    /// Interface method
+    pub fn get_cls_id(&self) -> Option<i64> {
+        match *self {
+            Self::Bert(ref tokenizer) => {
+                let vocab = MultiThreadedTokenizer::vocab(tokenizer);
+                Some(vocab.token_to_id(vocab.get_cls_value()))
+            }
+            Self::Deberta(ref tokenizer) => {
+                let vocab = MultiThreadedTokenizer::vocab(tokenizer);
+                Some(vocab.token_to_id(vocab.get_cls_value()))
+            }
+            Self::DebertaV2(ref tokenizer) => {
+                let vocab = MultiThreadedTokenizer::vocab(tokenizer);
+                Some(vocab.token_to_id(vocab.get_cls_value()))
+            }
+            Self::Roberta(ref tokenizer) => {
+                let vocab = MultiThreadedTokenizer::vocab(tokenizer);
+                Some(vocab.token_to_id(vocab.get_cls_value()))
+            }
+            Self::Bart(ref tokenizer) => {
+                let vocab = MultiThreadedTokenizer::vocab(tokenizer);
+                Some(vocab.token_to_id(vocab.get_cls_value()))
+            }
+            Self::XLMRoberta(ref tokenizer) => {
+                let vocab = MultiThreadedTokenizer::vocab(tokenizer);
+                Some(vocab.token_to_id(vocab.get_cls_value()))
+            }
+            Self::Albert(ref tokenizer) => {
+                let vocab = MultiThreadedTokenizer::vocab(tokenizer);
+                Some(vocab.token_to_id(vocab.get_cls_value()))
+            }
+            Self::XLNet(ref tokenizer) => {
+                let vocab = MultiThreadedTokenizer::vocab(tokenizer);
+                Some(vocab.token_to_id(vocab.get_cls_value()))
+            }
+            Self::FNet(ref tokenizer) => {
+                let vocab = MultiThreadedTokenizer::vocab(tokenizer);
+                Some(vocab.token_to_id(vocab.get_cls_value()))
+            }
+            Self::Marian(_) => None,
+            Self::T5(_) => None,
+            Self::GPT2(_) => None,
+            Self::OpenAiGpt(_) => None,
+            Self::Reformer(_) => None,
+            Self::ProphetNet(_) => None,
+            Self::Pegasus(_) => None,
+            Self::MBart50(_) => None,
+            Self::M2M100(_) => None,
+            Self::NLLB(_) => None,
+        }
+    }
+
     /// Interface method
     pub fn get_mask_id(&self) -> Option<i64> {
         match *self {
@@ -2202,44 +2727,167 @@ impl TokenizerOption {
     where
         S: AsRef<[&'a str]>,
     {
-        let mut tokenized_input: Vec<TokenizedInput> = self.encode_list(
+        let tokenized_input: Vec<TokenizedInput> = self.encode_list(
             input.as_ref(),
             max_length,
             &TruncationStrategy::LongestFirst,
             0,
         );
-        let max_len = tokenized_input
+        self.pad_tokenized_input(tokenized_input, device)
+    }
+
+    /// Encodes a list of texts without adding the special tokens (e.g. *CLS* and *SEP*), for models trained
+    /// on inputs without special tokens. The token ids are truncated to `max_len`.
+    pub fn encode_list_without_special_tokens<S>(
+        &self,
+        text_list: &[S],
+        max_len: usize,
+    ) -> Vec<TokenizedInput>
+    where
+        S: AsRef<str>,
+    {
+        text_list
             .iter()
-            .map(|input| input.token_ids.len())
-            .max()
-            .unwrap();
+            .map(|text| {
+                let mut tokens = self.tokenize_with_offsets(text.as_ref());
+                let mut token_ids = self.convert_tokens_to_ids(&tokens.tokens);
+                let num_truncated_tokens = token_ids.len().saturating_sub(max_len);
+                token_ids.truncate(max_len);
+                tokens.offsets.truncate(max_len);
+                tokens.reference_offsets.truncate(max_len);
+                tokens.masks.truncate(max_len);
+                let num_tokens = token_ids.len();
+                TokenizedInput {
+                    token_ids,
+                    segment_ids: vec![0; num_tokens],
+                    special_tokens_mask: vec![0; num_tokens],
+                    overflowing_tokens: vec![],
+                    num_truncated_tokens,
+                    token_offsets: tokens.offsets,
+                    reference_offsets: tokens.reference_offsets,
+                    mask: tokens.masks,
+                }
+            })
+            .collect()
+    }
+
+    /// Tokenize sentence pairs (e.g. for cross-encoders), returning the padded token ids and the token type ids
+    /// identifying the first and second sentence of each pair.
+    pub fn tokenize_pair_and_pad(
+        &self,
+        input: &[(&str, &str)],
+        max_length: usize,
+        device: Device,
+    ) -> (Tensor, Tensor) {
+        let tokenized_input: Vec<TokenizedInput> =
+            self.encode_pair_list(input, max_length, &TruncationStrategy::LongestFirst, 0);
+        self.pad_tokenized_input(tokenized_input, device)
+    }
+
+    /// Encodes an input made of several segments with the BERT layout (`[CLS] A [SEP] B [SEP] C [SEP]`), the token
+    /// type id of each segment being its position in the input (0 for the classification token and first segment).
+    /// Tokens are removed from the end of the longest segment until the input fits in `max_len` tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - `&[&str]` Segments of the input.
+    /// * `max_len` - Maximum length of the encoded input, including the special tokens.
+    ///
+    /// # Returns
+    ///
+    /// * `TokenizedInput` for the input, or a `ValueError` if the tokenizer does not define classification and
+    ///   separator tokens or if the input contains more than 127 segments.
+    pub fn encode_segments(
+        &self,
+        segments: &[&str],
+        max_len: usize,
+    ) -> Result<TokenizedInput, RustBertError> {
+        let (cls_id, sep_id) = self.get_cls_id().zip(self.get_sep_id()).ok_or_else(|| {
+            RustBertError::ValueError(
+                "Encoding segments requires classification and separator tokens".to_string(),
+            )
+        })?;
+        if segments.len() > i8::MAX as usize {
+            return Err(RustBertError::ValueError(format!(
+                "Inputs are limited to {} segments, got {}",
+                i8::MAX,
+                segments.len()
+            )));
+        }
+        let mut encoded_segments = self.encode_list_without_special_tokens(segments, usize::MAX);
+        let special_tokens_count = segments.len() + 1;
+        let mut num_tokens = encoded_segments
+            .iter()
+            .map(|segment| segment.token_ids.len())
+            .sum::<usize>()
+            + special_tokens_count;
+        let mut num_truncated_tokens = 0;
+        while num_tokens > max_len {
+            let longest_segment = encoded_segments
+                .iter_mut()
+                .max_by_key(|segment| segment.token_ids.len())
+                .filter(|segment| !segment.token_ids.is_empty());
+            let longest_segment = match longest_segment {
+                Some(longest_segment) => longest_segment,
+                None => break,
+            };
+            longest_segment.token_ids.pop();
+            longest_segment.token_offsets.pop();
+            longest_segment.reference_offsets.pop();
+            longest_segment.mask.pop();
+            num_tokens -= 1;
+            num_truncated_tokens += 1;
+        }
+
+        let mut tokenized_input = TokenizedInput {
+            token_ids: Vec::with_capacity(num_tokens),
+            segment_ids: Vec::with_capacity(num_tokens),
+            special_tokens_mask: Vec::with_capacity(num_tokens),
+            overflowing_tokens: vec![],
+            num_truncated_tokens,
+            token_offsets: Vec::with_capacity(num_tokens),
+            reference_offsets: Vec::with_capacity(num_tokens),
+            mask: Vec::with_capacity(num_tokens),
+        };
+        fn push_special_token(tokenized_input: &mut TokenizedInput, token_id: i64, segment_id: i8) {
+            tokenized_input.token_ids.push(token_id);
+            tokenized_input.segment_ids.push(segment_id);
+            tokenized_input.special_tokens_mask.push(1);
+            tokenized_input.token_offsets.push(None);
+            tokenized_input.reference_offsets.push(vec![]);
+            tokenized_input.mask.push(Mask::Special);
+        }
+        push_special_token(&mut tokenized_input, cls_id, 0);
+        for (segment_id, segment) in encoded_segments.into_iter().enumerate() {
+            let segment_id = segment_id as i8;
+            let segment_length = segment.token_ids.len();
+            tokenized_input.token_ids.extend(segment.token_ids);
+            tokenized_input
+                .segment_ids
+                .extend(vec![segment_id; segment_length]);
+            tokenized_input
+                .special_tokens_mask
+                .extend(vec![0; segment_length]);
+            tokenized_input.token_offsets.extend(segment.token_offsets);
+            tokenized_input
+                .reference_offsets
+                .extend(segment.reference_offsets);
+            tokenized_input.mask.extend(segment.mask);
+            push_special_token(&mut tokenized_input, sep_id, segment_id);
+        }
+        Ok(tokenized_input)
+    }
+
+    /// Pads already tokenized inputs to the longest input length, returning the token ids and token type ids tensors
+    pub(crate) fn pad_tokenized_input(
+        &self,
+        tokenized_input: Vec<TokenizedInput>,
+        device: Device,
+    ) -> (Tensor, Tensor) {
         let pad_id = self
             .get_pad_id()
             .expect("The Tokenizer used for sequence classification should contain a PAD id");
-        let tokenized_input_tensors: Vec<Tensor> = tokenized_input
-            .iter_mut()
-            .map(|input| {
-                input.token_ids.resize(max_len, pad_id);
-                Tensor::from_slice(&(input.token_ids))
-            })
-            .collect::<Vec<_>>();
-
-        let token_type_ids: Vec<Tensor> = tokenized_input
-            .iter_mut()
-            .map(|input| {
-                input
-                    .segment_ids
-                    .resize(max_len, *input.segment_ids.last().unwrap_or(&0));
-                Tensor::from_slice(&(input.segment_ids))
-            })
-            .collect::<Vec<_>>();
-
-        (
-            Tensor::stack(tokenized_input_tensors.as_slice(), 0).to(device),
-            Tensor::stack(token_type_ids.as_slice(), 0)
-                .to(device)
-                .to_kind(Kind::Int64),
-        )
+        pad_tokenized_input(tokenized_input, pad_id, device, None)
     }
 
     /// Interface method
@@ -2292,3 +2940,177 @@ impl TokenizerOption {
         }
     }
 }
+
+/// Pads already tokenized inputs to the longest input length with the provided padding token id,
+/// returning the token ids and token type ids tensors. If `pad_to_multiple_of` is provided, the padded
+/// length is rounded up to a multiple of this value.
+pub(crate) fn pad_tokenized_input(
+    mut tokenized_input: Vec<TokenizedInput>,
+    pad_id: i64,
+    device: Device,
+    pad_to_multiple_of: Option<usize>,
+) -> (Tensor, Tensor) {
+    let max_len = tokenized_input
+        .iter()
+        .map(|input| input.token_ids.len())
+        .max()
+        .unwrap();
+    let max_len = match pad_to_multiple_of {
+        Some(multiple) if multiple > 1 => max_len.next_multiple_of(multiple),
+        _ => max_len,
+    };
+    let tokenized_input_tensors: Vec<Tensor> = tokenized_input
+        .iter_mut()
+        .map(|input| {
+            input.token_ids.resize(max_len, pad_id);
+            Tensor::from_slice(&(input.token_ids))
+        })
+        .collect::<Vec<_>>();
+
+    let token_type_ids: Vec<Tensor> = tokenized_input
+        .iter_mut()
+        .map(|input| {
+            input
+                .segment_ids
+                .resize(max_len, *input.segment_ids.last().unwrap_or(&0));
+            Tensor::from_slice(&(input.segment_ids))
+        })
+        .collect::<Vec<_>>();
+
+    (
+        Tensor::stack(tokenized_input_tensors.as_slice(), 0).to(device),
+        Tensor::stack(token_type_ids.as_slice(), 0)
+            .to(device)
+            .to_kind(Kind::Int64),
+    )
+}
+
+/// # Tokenizer interface for pipelines
+/// Allows plugging a custom tokenizer implementation (e.g. a domain-specific tokenizer) into pipelines accepting
+/// a `Box<dyn Tokenize>`, such as the `SequenceClassificationModel`. It is implemented by `TokenizerOption`.
+pub trait Tokenize {
+    /// Encodes a list of texts, adding the special tokens expected by the model and truncating the inputs to `max_len`
+    fn encode_list(
+        &self,
+        text_list: &[&str],
+        max_len: usize,
+        truncation_strategy: &TruncationStrategy,
+        stride: usize,
+    ) -> Vec<TokenizedInput>;
+
+    /// Returns the padding token id, if defined
+    fn get_pad_id(&self) -> Option<i64>;
+
+    /// Decodes a sequence of token ids back to a text
+    fn decode(
+        &self,
+        token_ids: &[i64],
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+    ) -> String;
+
+    /// Converts token ids to their string representation. Defaults to decoding each token id separately.
+    fn convert_ids_to_tokens(&self, token_ids: &[i64]) -> Vec<String> {
+        token_ids
+            .iter()
+            .map(|token_id| self.decode(&[*token_id], false, false))
+            .collect()
+    }
+
+    /// Returns the underlying `TokenizerOption`, if any. Pipeline features relying on tokenizer specifics
+    /// (e.g. offsets or sentence pairs encoding) are only available for tokenizers returning `Some`.
+    fn as_tokenizer_option(&self) -> Option<&TokenizerOption> {
+        None
+    }
+
+    /// Returns a mutable reference to the underlying `TokenizerOption`, if any
+    fn as_tokenizer_option_mut(&mut self) -> Option<&mut TokenizerOption> {
+        None
+    }
+}
+
+impl Tokenize for TokenizerOption {
+    fn encode_list(
+        &self,
+        text_list: &[&str],
+        max_len: usize,
+        truncation_strategy: &TruncationStrategy,
+        stride: usize,
+    ) -> Vec<TokenizedInput> {
+        TokenizerOption::encode_list(self, text_list, max_len, truncation_strategy, stride)
+    }
+
+    fn get_pad_id(&self) -> Option<i64> {
+        TokenizerOption::get_pad_id(self)
+    }
+
+    fn decode(
+        &self,
+        token_ids: &[i64],
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+    ) -> String {
+        TokenizerOption::decode(
+            self,
+            token_ids,
+            skip_special_tokens,
+            clean_up_tokenization_spaces,
+        )
+    }
+
+    fn convert_ids_to_tokens(&self, token_ids: &[i64]) -> Vec<String> {
+        TokenizerOption::convert_ids_to_tokens(self, token_ids)
+    }
+
+    fn as_tokenizer_option(&self) -> Option<&TokenizerOption> {
+        Some(self)
+    }
+
+    fn as_tokenizer_option_mut(&mut self) -> Option<&mut TokenizerOption> {
+        Some(self)
+    }
+}
+
+/// Tokenizer shared across several pipelines (e.g. models fine-tuned from the same base checkpoint),
+/// loading the vocabulary in memory once. The tokenizer can only be modified while it is not shared.
+impl Tokenize for Arc<TokenizerOption> {
+    fn encode_list(
+        &self,
+        text_list: &[&str],
+        max_len: usize,
+        truncation_strategy: &TruncationStrategy,
+        stride: usize,
+    ) -> Vec<TokenizedInput> {
+        TokenizerOption::encode_list(self, text_list, max_len, truncation_strategy, stride)
+    }
+
+    fn get_pad_id(&self) -> Option<i64> {
+        TokenizerOption::get_pad_id(self)
+    }
+
+    fn decode(
+        &self,
+        token_ids: &[i64],
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+    ) -> String {
+        TokenizerOption::decode(
+            self,
+            token_ids,
+            skip_special_tokens,
+            clean_up_tokenization_spaces,
+        )
+    }
+
+    fn convert_ids_to_tokens(&self, token_ids: &[i64]) -> Vec<String> {
+        TokenizerOption::convert_ids_to_tokens(self, token_ids)
+    }
+
+    fn as_tokenizer_option(&self) -> Option<&TokenizerOption> {
+        Some(self.as_ref())
+    }
+
+    fn as_tokenizer_option_mut(&mut self) -> Option<&mut TokenizerOption> {
+        Arc::get_mut(self)
+    }
+}
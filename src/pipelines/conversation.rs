@@ -57,7 +57,7 @@ use crate::common::error::RustBertError;
 use crate::gpt2::GPT2Generator;
 use crate::pipelines::common::{ModelResource, ModelType, TokenizerOption};
 use crate::pipelines::generation_utils::private_generation_utils::PrivateLanguageGenerator;
-use crate::pipelines::generation_utils::{GenerateConfig, LanguageGenerator};
+use crate::pipelines::generation_utils::{GenerateConfig, LanguageGenerator, LogitsProcessor};
 use crate::resources::ResourceProvider;
 use std::collections::HashMap;
 use tch::{Device, Kind, Tensor};
@@ -115,6 +115,8 @@ pub struct ConversationConfig {
     pub diversity_penalty: Option<f64>,
     /// Device to place the model on (default: CUDA/GPU when available)
     pub device: Device,
+    /// Logits processors applied in order to the next token scores at each generation step (default: none)
+    pub logits_processors: Vec<Box<dyn LogitsProcessor>>,
 }
 
 #[cfg(feature = "remote")]
@@ -150,6 +152,7 @@ impl Default for ConversationConfig {
             num_beam_groups: None,
             diversity_penalty: None,
             device: Device::cuda_if_available(),
+            logits_processors: Vec::new(),
         }
     }
 }
@@ -177,6 +180,7 @@ impl From<ConversationConfig> for GenerateConfig {
             num_beam_groups: config.num_beam_groups,
             diversity_penalty: config.diversity_penalty,
             device: config.device,
+            logits_processors: config.logits_processors,
         }
     }
 }
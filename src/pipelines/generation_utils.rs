@@ -66,6 +66,7 @@
 //! # ;
 //! ```
 
+use std::sync::atomic::AtomicBool;
 use tch::kind::Kind::Int64;
 use tch::{no_grad, Device, Tensor};
 
@@ -74,7 +75,7 @@ use crate::common::resources::ResourceProvider;
 use crate::gpt_j::LayerState as GPTJLayerState;
 use crate::gpt_neo::LayerState as GPTNeoLayerState;
 use crate::pipelines::generation_utils::private_generation_utils::{
-    InternalGenerateOptions, PrivateLanguageGenerator,
+    force_token_id_generation, InternalGenerateOptions, PrivateLanguageGenerator,
 };
 use crate::prophetnet::LayerState as ProphetNetLayerState;
 use crate::reformer::LayerState as ReformerLayerState;
@@ -136,6 +137,8 @@ pub struct GenerateConfig {
     pub diversity_penalty: Option<f64>,
     /// Device to place the model on (default: CUDA/GPU when available)
     pub device: Device,
+    /// Logits processors applied in order to the next token scores at each generation step (default: none)
+    pub logits_processors: Vec<Box<dyn LogitsProcessor>>,
 }
 
 #[cfg(feature = "remote")]
@@ -166,6 +169,7 @@ impl Default for GenerateConfig {
             num_beam_groups: None,
             diversity_penalty: None,
             device: Device::cuda_if_available(),
+            logits_processors: Vec::new(),
         }
     }
 }
@@ -219,6 +223,151 @@ impl GenerateConfig {
     }
 }
 
+/// # Logits processor applied at each generation step
+/// Modifies the scores of the next token (e.g. to ban or force tokens) before its selection. The processors listed
+/// in the `logits_processors` of the `GenerateConfig` are applied in order at each step of greedy, sampling and
+/// beam search decoding.
+pub trait LogitsProcessor: Send + Sync {
+    /// Processes the next token scores in place
+    ///
+    /// # Arguments
+    ///
+    /// * `input_ids` - `Tensor` of shape (*batch size*, *current_length*) containing the tokens generated so far (one row per beam)
+    /// * `scores` - `Tensor` of shape (*batch size*, *vocab_size*) containing the next token scores
+    /// * `current_length` - `i64` current length of the generated sequences
+    /// * `max_length` - `Option<i64>` maximum length of the generated sequences
+    fn process(
+        &self,
+        input_ids: &Tensor,
+        scores: &mut Tensor,
+        current_length: i64,
+        max_length: Option<i64>,
+    );
+}
+
+/// # Logits processor banning token sequences
+/// The last token of a banned sequence is given a score of negative infinity when the previously generated tokens
+/// match the rest of the sequence (single tokens are always banned).
+pub struct BadWordsLogitsProcessor {
+    bad_words_ids: Vec<Vec<i64>>,
+}
+
+impl BadWordsLogitsProcessor {
+    /// Creates a new `BadWordsLogitsProcessor`
+    ///
+    /// # Arguments
+    ///
+    /// * `bad_words_ids` - `Vec<Vec<i64>>` token ids sequences to ban. Empty sequences are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use rust_bert::pipelines::generation_utils::{BadWordsLogitsProcessor, GenerateConfig};
+    ///
+    /// let generate_config = GenerateConfig {
+    ///     logits_processors: vec![Box::new(BadWordsLogitsProcessor::new(vec![
+    ///         vec![464],
+    ///         vec![3290, 318],
+    ///     ]))],
+    ///     ..Default::default()
+    /// };
+    /// ```
+    pub fn new(bad_words_ids: Vec<Vec<i64>>) -> BadWordsLogitsProcessor {
+        BadWordsLogitsProcessor {
+            bad_words_ids: bad_words_ids
+                .into_iter()
+                .filter(|bad_word_ids| !bad_word_ids.is_empty())
+                .collect(),
+        }
+    }
+}
+
+impl LogitsProcessor for BadWordsLogitsProcessor {
+    fn process(
+        &self,
+        input_ids: &Tensor,
+        scores: &mut Tensor,
+        _current_length: i64,
+        _max_length: Option<i64>,
+    ) {
+        let context_length = self
+            .bad_words_ids
+            .iter()
+            .map(|bad_word_ids| bad_word_ids.len() as i64 - 1)
+            .max()
+            .unwrap_or(0);
+        let start = (input_ids.size()[1] - context_length).max(0);
+        let previous_tokens = input_ids.slice(1, start, None, 1);
+        for sequence_index in 0..input_ids.size()[0] {
+            let sequence_tokens = previous_tokens
+                .get(sequence_index)
+                .iter::<i64>()
+                .unwrap()
+                .collect::<Vec<i64>>();
+            let banned_tokens = self
+                .bad_words_ids
+                .iter()
+                .filter(|bad_word_ids| {
+                    sequence_tokens.ends_with(&bad_word_ids[..bad_word_ids.len() - 1])
+                })
+                .map(|bad_word_ids| *bad_word_ids.last().unwrap())
+                .collect::<Vec<i64>>();
+            if !banned_tokens.is_empty() {
+                let _ = scores.get(sequence_index).index_fill_(
+                    0,
+                    &Tensor::from_slice(&banned_tokens).to_device(scores.device()),
+                    f64::NEG_INFINITY,
+                );
+            }
+        }
+    }
+}
+
+/// # Logits processor forcing the first generated token
+/// Forces the generation of a token at the first generation step, e.g. the target language token of
+/// multilingual models.
+pub struct ForcedBosTokenLogitsProcessor {
+    /// Token id to generate first
+    pub token_id: i64,
+}
+
+impl LogitsProcessor for ForcedBosTokenLogitsProcessor {
+    fn process(
+        &self,
+        _input_ids: &Tensor,
+        scores: &mut Tensor,
+        current_length: i64,
+        _max_length: Option<i64>,
+    ) {
+        if current_length == 1 {
+            let vocab_size = scores.size()[1];
+            force_token_id_generation(scores, &[self.token_id], vocab_size);
+        }
+    }
+}
+
+/// # Logits processor forcing the last generated token
+/// Forces the generation of a token (e.g. EOS) when the generated sequences reach the maximum length.
+pub struct ForcedEosTokenLogitsProcessor {
+    /// Token id to generate last
+    pub token_id: i64,
+}
+
+impl LogitsProcessor for ForcedEosTokenLogitsProcessor {
+    fn process(
+        &self,
+        _input_ids: &Tensor,
+        scores: &mut Tensor,
+        current_length: i64,
+        max_length: Option<i64>,
+    ) {
+        if max_length == Some(current_length + 1) {
+            let vocab_size = scores.size()[1];
+            force_token_id_generation(scores, &[self.token_id], vocab_size);
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Cache {
     GPT2Cache(Option<Vec<Tensor>>),
@@ -241,13 +390,14 @@ pub(crate) mod private_generation_utils {
     use std::collections::HashMap;
     use std::convert::TryFrom;
     use std::mem;
+    use std::sync::atomic::{AtomicBool, Ordering};
 
     use rust_tokenizers::tokenizer::{truncate_sequences, TruncationStrategy};
     use tch::{nn, Device, Kind, Tensor};
 
     use crate::pipelines::common::TokenizerOption;
     use crate::pipelines::generation_utils::{
-        BeamHypotheses, Cache, GenerateConfig, LMModelOutput, PrefixAllowedFunction,
+        BeamHypotheses, Cache, GenerateConfig, LMModelOutput, PrefixAllowedFunction, TokenCallback,
     };
 
     use super::ordered_float::OrderedFloat;
@@ -288,6 +438,12 @@ pub(crate) mod private_generation_utils {
         pub indices: Tensor,
         pub scores: Option<Vec<f64>>,
         pub token_scores: Option<Vec<Vec<f64>>>,
+        pub cancelled: bool,
+    }
+
+    /// Checks if the generation was cancelled through its (optional) cancellation token
+    pub fn is_cancelled(cancellation_token: Option<&AtomicBool>) -> bool {
+        matches!(cancellation_token, Some(token) if token.load(Ordering::Relaxed))
     }
 
     pub trait PrivateLanguageGenerator {
@@ -816,6 +972,47 @@ pub(crate) mod private_generation_utils {
             }
         }
 
+        /// Calls the token callback with the text generated at the current step for each unfinished sequence.
+        /// The new text is the difference between the decoded generated tokens and the previously streamed
+        /// text, and is held back while the last token is an incomplete character.
+        fn stream_generated_text(
+            &self,
+            token_callback: TokenCallback,
+            input_ids: &Tensor,
+            unfinished_sentences: &Tensor,
+            start_length: i64,
+            streamed_texts: &mut [String],
+        ) {
+            let unfinished_sentences = unfinished_sentences
+                .to(Device::Cpu)
+                .iter::<i64>()
+                .unwrap()
+                .collect::<Vec<i64>>();
+            for (sequence_index, streamed_text) in streamed_texts.iter_mut().enumerate() {
+                if unfinished_sentences[sequence_index] == 0 {
+                    continue;
+                }
+                let generated_ids = input_ids
+                    .get(sequence_index as i64)
+                    .slice(0, start_length, None, 1)
+                    .iter::<i64>()
+                    .unwrap()
+                    .collect::<Vec<i64>>();
+                let generated_text = self._get_tokenizer().decode(&generated_ids, true, false);
+                if generated_text.ends_with(char::REPLACEMENT_CHARACTER) {
+                    continue;
+                }
+                let new_text = match generated_text.strip_prefix(streamed_text.as_str()) {
+                    Some(new_text) => new_text,
+                    None => generated_text.as_str(),
+                };
+                if !new_text.is_empty() {
+                    token_callback(sequence_index as i64, new_text, &generated_text);
+                }
+                *streamed_text = generated_text;
+            }
+        }
+
         fn generate_no_beam_search(
             &self,
             input_ids: Tensor,
@@ -825,8 +1022,11 @@ pub(crate) mod private_generation_utils {
             attention_mask: Tensor,
             gen_opt: InternalGenerateOptions,
             prefix_allowed_tokens_fn: Option<PrefixAllowedFunction>,
+            token_callback: Option<TokenCallback>,
+            cancellation_token: Option<&AtomicBool>,
             output_scores: bool,
         ) -> GeneratedOutputWithScores {
+            let mut streamed_texts = vec![String::new(); batch_size as usize];
             let mut unfinished_sentences =
                 Tensor::ones([batch_size], (Kind::Int64, self.get_device()));
             let mut sentence_lengths: Tensor =
@@ -841,8 +1041,20 @@ pub(crate) mod private_generation_utils {
             let mut current_length = cur_len;
             let mut token_scores_output: Option<Vec<Tensor>> =
                 if output_scores { Some(vec![]) } else { None };
+            let mut cancelled = false;
 
             loop {
+                // The cancellation is checked before each step after the first one, returning the tokens generated so far
+                if current_length > cur_len && is_cancelled(cancellation_token) {
+                    let _ = sentence_lengths.masked_fill_(
+                        &unfinished_sentences
+                            .to_kind(Kind::Bool)
+                            .to_device(sentence_lengths.device()),
+                        current_length,
+                    );
+                    cancelled = true;
+                    break;
+                }
                 let prepared_input = self.prepare_inputs_for_generation(
                     input_ids.copy(),
                     encoder_outputs.as_ref(),
@@ -941,6 +1153,14 @@ pub(crate) mod private_generation_utils {
                     gen_opt.max_length,
                     gen_opt.forced_bos_token_id,
                 );
+                for logits_processor in self.get_config().logits_processors.iter() {
+                    logits_processor.process(
+                        &input_ids,
+                        &mut next_token_logits,
+                        current_length,
+                        gen_opt.max_length,
+                    );
+                }
 
                 // Top-k and top-p sampling
                 let next_token = if gen_opt.do_sample {
@@ -980,6 +1200,15 @@ pub(crate) mod private_generation_utils {
                 };
 
                 input_ids = Tensor::cat(&[input_ids, tokens_to_add.unsqueeze(-1)], -1);
+                if let Some(token_callback) = token_callback {
+                    self.stream_generated_text(
+                        token_callback,
+                        &input_ids,
+                        &unfinished_sentences,
+                        cur_len,
+                        &mut streamed_texts,
+                    );
+                }
                 if gen_opt.eos_token_ids.is_some() {
                     for eos_token_id in gen_opt.eos_token_ids.as_ref().unwrap() {
                         let sentence_with_eos =
@@ -1050,6 +1279,7 @@ pub(crate) mod private_generation_utils {
                 indices: input_ids,
                 scores: scores_output,
                 token_scores: token_scores_output,
+                cancelled,
             }
         }
 
@@ -1062,6 +1292,7 @@ pub(crate) mod private_generation_utils {
             mut attention_mask: Tensor,
             gen_opt: InternalGenerateOptions,
             prefix_allowed_tokens_fn: Option<PrefixAllowedFunction>,
+            cancellation_token: Option<&AtomicBool>,
             output_scores: bool,
         ) -> GeneratedOutputWithScores {
             let num_beam_groups = gen_opt.num_beam_groups.unwrap_or(1);
@@ -1110,8 +1341,15 @@ pub(crate) mod private_generation_utils {
             let mut outputs: Tensor;
             let mut encoder_outputs = encoder_outputs;
             let mut current_length = cur_len;
+            let mut cancelled = false;
 
             loop {
+                // The cancellation is checked before each step after the first one: the unfinished hypotheses are
+                // completed with the current beams
+                if current_length > cur_len && is_cancelled(cancellation_token) {
+                    cancelled = true;
+                    break;
+                }
                 if num_beam_groups > 1 {
                     current_tokens = Tensor::zeros(
                         [batch_size * gen_opt.num_beams],
@@ -1192,6 +1430,14 @@ pub(crate) mod private_generation_utils {
                         gen_opt.max_length,
                         gen_opt.forced_bos_token_id,
                     );
+                    for logits_processor in self.get_config().logits_processors.iter() {
+                        logits_processor.process(
+                            group_input_ids.as_ref().unwrap_or(&input_ids),
+                            &mut next_token_logits,
+                            current_length,
+                            gen_opt.max_length,
+                        );
+                    }
 
                     let mut scores = next_token_logits.log_softmax(-1, next_token_logits.kind());
 
@@ -1568,6 +1814,7 @@ pub(crate) mod private_generation_utils {
                 indices: decoded,
                 scores: scores_output,
                 token_scores: token_scores_output,
+                cancelled,
             }
         }
 
@@ -1605,6 +1852,9 @@ pub(crate) mod private_generation_utils {
 pub struct GeneratedTextOutput {
     pub text: String,
     pub score: Option<f64>,
+    /// Flag indicating if the generation was cancelled (see `GenerateOptions::cancellation_token`): the text is
+    /// then the partial output generated before the cancellation
+    pub cancelled: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -1614,8 +1864,16 @@ pub struct GeneratedIndicesOutput {
     pub indices: Vec<i64>,
     pub score: Option<f64>,
     pub token_scores: Option<Vec<f64>>,
+    /// Flag indicating if the generation was cancelled (see `GenerateOptions::cancellation_token`): the indices are
+    /// then the partial output generated before the cancellation
+    pub cancelled: bool,
 }
 
+/// Type alias for a function receiving the generated text as it is produced (streaming), called at each
+/// generation step with the index of the generated sequence, the newly generated text (usually a single token)
+/// and the text generated so far. Only called for greedy and sampling decoding (`num_beams` = 1).
+pub type TokenCallback<'a> = &'a dyn Fn(i64, &str, &str);
+
 pub type PrefixAllowedFunction<'a> = &'a dyn Fn(i64, &Tensor) -> Vec<i64>;
 /// Type alias for a function defining allowed tokens based on current tokens generated.
 /// This function should take a `batch_id` and associated tensor of already generated tokens and
@@ -1669,6 +1927,12 @@ pub struct GenerateOptions<'a> {
     pub bad_word_ids: Option<&'a Vec<Vec<i64>>>,
     /// Flag indicating if text generation scores should be returned
     pub output_scores: bool,
+    /// Function called with the generated text as it is produced, for greedy and sampling decoding (not beam search). The function takes the index of the generated sequence (`i64`), the new text and the text generated so far.
+    pub token_callback: Option<TokenCallback<'a>>,
+    /// Cancellation token checked before each generation step (e.g. set from another thread when the client of a
+    /// server disconnects). When set, the generation stops and returns the partial output generated so far, flagged
+    /// as cancelled. At least one token is generated, and a beam search returns its current best beams.
+    pub cancellation_token: Option<&'a AtomicBool>,
 }
 
 macro_rules! unpack_config {
@@ -1784,6 +2048,7 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
                     ._get_tokenizer()
                     .decode(&generated_sequence.indices, true, true),
                 score: generated_sequence.score,
+                cancelled: generated_sequence.cancelled,
             });
         }
         output
@@ -1986,6 +2251,8 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
         let bad_word_ids = generate_options.and_then(|opts| opts.bad_word_ids);
         let prefix_allowed_tokens_fn =
             generate_options.and_then(|opts| opts.prefix_allowed_tokens_fn);
+        let token_callback = generate_options.and_then(|opts| opts.token_callback);
+        let cancellation_token = generate_options.and_then(|opts| opts.cancellation_token);
         let output_scores = generate_options.map_or(false, |opts| opts.output_scores);
 
         let pad_token_id = match self.get_pad_id() {
@@ -2136,6 +2403,7 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
                     attention_mask,
                     gen_opt,
                     prefix_allowed_tokens_fn,
+                    cancellation_token,
                     output_scores,
                 )
             } else {
@@ -2147,14 +2415,17 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
                     attention_mask,
                     gen_opt,
                     prefix_allowed_tokens_fn,
+                    token_callback,
+                    cancellation_token,
                     output_scores,
                 )
             }
         });
-        let (decoded, scores, mut token_scores) = (
+        let (decoded, scores, mut token_scores, cancelled) = (
             generated_output_with_scores.indices,
             generated_output_with_scores.scores,
             generated_output_with_scores.token_scores,
+            generated_output_with_scores.cancelled,
         );
         let num_sequences = *decoded.size().first().unwrap();
         let mut output = Vec::with_capacity(num_sequences as usize);
@@ -2177,6 +2448,7 @@ pub trait LanguageGenerator: PrivateLanguageGenerator {
                 indices,
                 score,
                 token_scores,
+                cancelled,
             });
         }
         output
@@ -222,6 +222,55 @@ impl<'a> KeywordExtractionModel<'a> {
     where
         S: AsRef<str> + Sync,
     {
+        self.predict_top_n(inputs, self.num_keywords)
+    }
+
+    /// Extract the `top_n` keywords from a single input text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - input text to extract keywords from
+    /// * `top_n` - maximum number of keywords to return (overrides the `num_keywords` configuration value)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<(String, f32)>, RustBertError>` containing keywords and their score, ranked by decreasing score
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::keywords_extraction::KeywordExtractionModel;
+    ///
+    /// let keyword_extraction_model = KeywordExtractionModel::new(Default::default())?;
+    /// let output = keyword_extraction_model.extract(
+    ///     "Rust is a multi-paradigm, general-purpose programming language.",
+    ///     3,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extract(&self, text: &str, top_n: usize) -> Result<Vec<(String, f32)>, RustBertError> {
+        Ok(self
+            .predict_top_n(&[text], top_n)?
+            .pop()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|keyword| (keyword.text, keyword.score))
+            .collect())
+    }
+
+    fn predict_top_n<S>(
+        &self,
+        inputs: &[S],
+        top_n: usize,
+    ) -> Result<Vec<Vec<Keyword>>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        if top_n == 0 {
+            return Ok(vec![Vec::new(); inputs.len()]);
+        }
         let words = self.tokenizer.tokenize_list(inputs, self.ngram_range);
         let (flat_word_list, document_boundaries) =
             KeywordExtractionModel::flatten_word_list(&words);
@@ -238,13 +287,17 @@ impl<'a> KeywordExtractionModel<'a> {
         let mut output_keywords: Vec<Vec<Keyword>> = Vec::new();
         for (document_index, (start, end)) in document_boundaries.into_iter().enumerate() {
             let mut document_keywords = Vec::new();
+            if start == end {
+                output_keywords.push(document_keywords);
+                continue;
+            }
             let document_embedding = document_embeddings
                 .select(0, document_index as i64)
                 .unsqueeze(0);
             let word_embeddings = word_embeddings
                 .embeddings
                 .slice(0, start as i64, end as i64, 1);
-            let num_keywords = min(self.num_keywords, word_embeddings.size()[0] as usize);
+            let num_keywords = min(top_n, word_embeddings.size()[0] as usize);
             let local_top_word_indices = self.scorer_type.score_keywords(
                 document_embedding,
                 word_embeddings,
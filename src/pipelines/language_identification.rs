@@ -0,0 +1,328 @@
+// Copyright 2019-present, the HuggingFace Inc. team, The Google AI Language Team and Facebook, Inc.
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Language identification pipeline
+//! Detects the language of texts with a multilingual sequence classification model (e.g. XLM-RoBERTa
+//! fine-tuned for language detection), whose labels (`id2label` of the model configuration or labels resource)
+//! are language codes. The pipeline returns the detected language code and its confidence for each input,
+//! and can be used to route texts to language-specific models.
+//!
+//! No default resources are provided for this pipeline: the configuration resources should point to a language
+//! detection checkpoint converted to the `.ot` format (e.g. an XLM-RoBERTa base model fine-tuned for language
+//! detection). A `SequenceClassificationConfig` can also be converted into a `LanguageIdentificationConfig`.
+//!
+//! ```no_run
+//! use rust_bert::pipelines::common::{ModelResource, ModelType};
+//! use rust_bert::pipelines::language_identification::{
+//!     LanguageIdentificationConfig, LanguageIdentificationModel,
+//! };
+//! use rust_bert::resources::LocalResource;
+//! use std::path::PathBuf;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let config = LanguageIdentificationConfig::new(
+//!     ModelType::XLMRoberta,
+//!     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+//!         "path/to/rust_model.ot",
+//!     )))),
+//!     LocalResource::from(PathBuf::from("path/to/config.json")),
+//!     LocalResource::from(PathBuf::from("path/to/sentencepiece.bpe.model")),
+//!     None,
+//!     false,
+//!     None,
+//!     None,
+//! );
+//! let language_identification_model = LanguageIdentificationModel::new(config)?;
+//! let input = [
+//!     "This is a sentence written in English.",
+//!     "Ceci est une phrase écrite en français.",
+//! ];
+//! let output = language_identification_model.predict(&input);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Output: \
+//! ```no_run
+//! # use rust_bert::pipelines::language_identification::DetectedLanguage;
+//! # let output =
+//! [
+//!     DetectedLanguage {
+//!         code: String::from("en"),
+//!         score: 0.997,
+//!     },
+//!     DetectedLanguage {
+//!         code: String::from("fr"),
+//!         score: 0.995,
+//!     },
+//! ]
+//! # ;
+//! ```
+
+use crate::common::error::RustBertError;
+use crate::pipelines::common::{ModelResource, ModelType, TokenizerOption};
+use crate::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
+use crate::resources::ResourceProvider;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Language detected by the model.
+pub struct DetectedLanguage {
+    /// Language code (label of the model, e.g. `en`)
+    pub code: String,
+    /// Confidence score
+    pub score: f64,
+}
+
+/// # Configuration for LanguageIdentificationModel
+/// Wraps a `SequenceClassificationConfig` pointing to a language detection checkpoint.
+pub struct LanguageIdentificationConfig {
+    sequence_classification_config: SequenceClassificationConfig,
+}
+
+impl LanguageIdentificationConfig {
+    /// Instantiate a new language identification configuration of the supplied type.
+    ///
+    /// # Arguments
+    ///
+    /// * `model_type` - `ModelType` indicating the model type to load (must match with the actual data to be loaded!)
+    /// * model - The `ResourceProvider` pointing to the model to load (e.g.  model.ot)
+    /// * config - The `ResourceProvider` pointing to the model configuration to load (e.g. config.json)
+    /// * vocab - The `ResourceProvider` pointing to the tokenizer's vocabulary to load (e.g.  sentencepiece.bpe.model)
+    /// * merges - An optional `ResourceProvider` pointing to the tokenizer's merge file to load (e.g.  merges.txt), needed only for Roberta.
+    /// * lower_case - A `bool` indicating whether the tokenizer should lower case all input (in case of a lower-cased model)
+    pub fn new<RC, RV>(
+        model_type: ModelType,
+        model_resource: ModelResource,
+        config_resource: RC,
+        vocab_resource: RV,
+        merges_resource: Option<RV>,
+        lower_case: bool,
+        strip_accents: impl Into<Option<bool>>,
+        add_prefix_space: impl Into<Option<bool>>,
+    ) -> LanguageIdentificationConfig
+    where
+        RC: ResourceProvider + Send + 'static,
+        RV: ResourceProvider + Send + 'static,
+    {
+        SequenceClassificationConfig::new(
+            model_type,
+            model_resource,
+            config_resource,
+            vocab_resource,
+            merges_resource,
+            lower_case,
+            strip_accents,
+            add_prefix_space,
+        )
+        .into()
+    }
+}
+
+impl From<SequenceClassificationConfig> for LanguageIdentificationConfig {
+    fn from(sequence_classification_config: SequenceClassificationConfig) -> Self {
+        LanguageIdentificationConfig {
+            sequence_classification_config,
+        }
+    }
+}
+
+impl From<LanguageIdentificationConfig> for SequenceClassificationConfig {
+    fn from(language_identification_config: LanguageIdentificationConfig) -> Self {
+        language_identification_config.sequence_classification_config
+    }
+}
+
+/// # LanguageIdentificationModel to detect the language of texts
+pub struct LanguageIdentificationModel {
+    sequence_classification_model: SequenceClassificationModel,
+}
+
+impl LanguageIdentificationModel {
+    /// Build a new `LanguageIdentificationModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `language_identification_config` - `LanguageIdentificationConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::language_identification::{
+    ///     LanguageIdentificationConfig, LanguageIdentificationModel,
+    /// };
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let resource = |path: &str| LocalResource::from(PathBuf::from(path));
+    /// # let language_identification_config = LanguageIdentificationConfig::new(
+    /// #     ModelType::XLMRoberta,
+    /// #     ModelResource::Torch(Box::new(resource("path/to/rust_model.ot"))),
+    /// #     resource("path/to/config.json"),
+    /// #     resource("path/to/sentencepiece.bpe.model"),
+    /// #     None,
+    /// #     false,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let language_identification_model =
+    ///     LanguageIdentificationModel::new(language_identification_config)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        language_identification_config: LanguageIdentificationConfig,
+    ) -> Result<LanguageIdentificationModel, RustBertError> {
+        let sequence_classification_model =
+            SequenceClassificationModel::new(language_identification_config.into())?;
+        Ok(LanguageIdentificationModel {
+            sequence_classification_model,
+        })
+    }
+
+    /// Build a new `LanguageIdentificationModel` with a provided tokenizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `language_identification_config` - `LanguageIdentificationConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    /// * `tokenizer` - `TokenizerOption` tokenizer to use for language identification.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::{ModelType, TokenizerOption};
+    /// use rust_bert::pipelines::language_identification::{
+    ///     LanguageIdentificationConfig, LanguageIdentificationModel,
+    /// };
+    /// let tokenizer = TokenizerOption::from_file(
+    ///     ModelType::XLMRoberta,
+    ///     "path/to/sentencepiece.bpe.model",
+    ///     None,
+    ///     false,
+    ///     None,
+    ///     None,
+    /// )?;
+    /// # use rust_bert::pipelines::common::ModelResource;
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let resource = |path: &str| LocalResource::from(PathBuf::from(path));
+    /// # let language_identification_config = LanguageIdentificationConfig::new(
+    /// #     ModelType::XLMRoberta,
+    /// #     ModelResource::Torch(Box::new(resource("path/to/rust_model.ot"))),
+    /// #     resource("path/to/config.json"),
+    /// #     resource("path/to/sentencepiece.bpe.model"),
+    /// #     None,
+    /// #     false,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let language_identification_model = LanguageIdentificationModel::new_with_tokenizer(
+    ///     language_identification_config,
+    ///     tokenizer,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_tokenizer(
+        language_identification_config: LanguageIdentificationConfig,
+        tokenizer: TokenizerOption,
+    ) -> Result<LanguageIdentificationModel, RustBertError> {
+        let sequence_classification_model = SequenceClassificationModel::new_with_tokenizer(
+            language_identification_config.into(),
+            tokenizer,
+        )?;
+        Ok(LanguageIdentificationModel {
+            sequence_classification_model,
+        })
+    }
+
+    /// Get a reference to the model tokenizer.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        self.sequence_classification_model.get_tokenizer()
+    }
+
+    /// Get a mutable reference to the model tokenizer.
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        self.sequence_classification_model.get_tokenizer_mut()
+    }
+
+    /// Detect the language of texts
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to detect the language of.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<DetectedLanguage>` Detected language code and confidence for each text.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::language_identification::{
+    ///     LanguageIdentificationConfig, LanguageIdentificationModel,
+    /// };
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let resource = |path: &str| LocalResource::from(PathBuf::from(path));
+    /// # let language_identification_config = LanguageIdentificationConfig::new(
+    /// #     ModelType::XLMRoberta,
+    /// #     ModelResource::Torch(Box::new(resource("path/to/rust_model.ot"))),
+    /// #     resource("path/to/config.json"),
+    /// #     resource("path/to/sentencepiece.bpe.model"),
+    /// #     None,
+    /// #     false,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let language_identification_model =
+    ///     LanguageIdentificationModel::new(language_identification_config)?;
+    /// let input = [
+    ///     "This is a sentence written in English.",
+    ///     "Ceci est une phrase écrite en français.",
+    /// ];
+    /// let output = language_identification_model.predict(&input);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict<'a, S>(&self, input: S) -> Vec<DetectedLanguage>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        self.sequence_classification_model
+            .predict(input)
+            .into_iter()
+            .map(|label| DetectedLanguage {
+                code: label.text,
+                score: label.score,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let config = LanguageIdentificationConfig::from(SequenceClassificationConfig::default());
+        let _: Box<dyn Send> = Box::new(LanguageIdentificationModel::new(config));
+    }
+}
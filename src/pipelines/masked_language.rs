@@ -46,6 +46,21 @@
 //! }
 //! ```
 //!
+//! The masked language model predicts a single token per mask. For tokenizers splitting words into several
+//! sub-word tokens (e.g. WordPiece, "playing" -> "play", "##ing"), `MaskedLanguageModel::predict_whole_words`
+//! implements whole-word masking: each mask stands for an entire word, that may span several tokens.
+//! Candidate words of increasing length are filled token by token and scored by the geometric mean of their
+//! token probabilities, so that words split into several tokens can be predicted. Models pre-trained with
+//! whole-word masking (all the tokens of a word masked together) are best suited for this mode.
+//!
+//! When an input contains several masks, `predict` fills each of them independently by default. Setting the `joint`
+//! flag of the `MaskedLanguageConfig` returns instead the most likely joint filling found by a beam search over the
+//! mask positions (from left to right, each filling conditioned on the previous ones), and
+//! `MaskedLanguageModel::predict_joint` returns the ranked joint candidates. For an input with *M* masks, the beam
+//! search runs *M* forward passes on batches of at most `joint_num_beams` sequences, instead of evaluating the
+//! *V^M* combinations of an exhaustive search (*V* being the vocabulary size). It is approximate: the best joint
+//! filling may be pruned from the beams.
+//!
 use crate::bert::BertForMaskedLM;
 use crate::common::error::RustBertError;
 use crate::deberta::DebertaForMaskedLM;
@@ -56,6 +71,7 @@ use crate::pipelines::common::{
 };
 use crate::resources::ResourceProvider;
 use crate::roberta::RobertaForMaskedLM;
+use rust_tokenizers::tokenizer::TruncationStrategy;
 use std::convert::TryFrom;
 
 #[cfg(feature = "onnx")]
@@ -67,7 +83,7 @@ use crate::{
     resources::RemoteResource,
 };
 use tch::nn::VarStore;
-use tch::{no_grad, Device, Tensor};
+use tch::{no_grad, Device, Kind, Tensor};
 
 #[derive(Debug, Clone)]
 /// Output container for masked language model pipeline.
@@ -76,7 +92,29 @@ pub struct MaskedToken {
     pub text: String,
     /// Vocabulary index for the masked word
     pub id: i64,
-    /// Score for the masked word
+    /// Score for the masked word. `predict` returns by default the raw logit of the token, while with the `joint`
+    /// flag (and in `MaskedFilling`) the score is the log-probability of the token conditioned on the previous fillings
+    pub score: f64,
+}
+
+#[derive(Debug, Clone)]
+/// Output container for the whole-word masked language model pipeline.
+pub struct MaskedWord {
+    /// String representation of the masked word
+    pub text: String,
+    /// Vocabulary indices of the tokens making up the masked word
+    pub token_ids: Vec<i64>,
+    /// Score for the masked word (geometric mean of the probabilities of its tokens)
+    pub score: f64,
+}
+
+#[derive(Debug, Clone)]
+/// Output container for the joint filling of the masks of an input.
+pub struct MaskedFilling {
+    /// Tokens filling each mask of the input, the score of each token being its log-probability
+    /// conditioned on the previous fillings
+    pub tokens: Vec<MaskedToken>,
+    /// Joint log-probability of the filling
     pub score: f64,
 }
 
@@ -103,6 +141,13 @@ pub struct MaskedLanguageConfig {
     pub mask_token: Option<String>,
     /// Device to place the model on (default: CUDA/GPU when available)
     pub device: Device,
+    /// Flag indicating if `predict` should return the most likely joint filling of the masks of an input, rather
+    /// than filling each mask independently (default: false). The token scores are then log-probabilities rather
+    /// than raw logits.
+    pub joint: bool,
+    /// Number of beams of the joint filling search, also the number of candidates returned by
+    /// `MaskedLanguageModel::predict_joint` (default: 5)
+    pub joint_num_beams: usize,
 }
 
 impl MaskedLanguageConfig {
@@ -143,6 +188,8 @@ impl MaskedLanguageConfig {
             add_prefix_space: add_prefix_space.into(),
             mask_token: mask_token.into(),
             device: Device::cuda_if_available(),
+            joint: false,
+            joint_num_beams: 5,
         }
     }
 }
@@ -202,7 +249,6 @@ impl MaskedLanguageOption {
 
     fn new_torch(config: &MaskedLanguageConfig) -> Result<Self, RustBertError> {
         let device = config.device;
-        let weights_path = config.model_resource.get_torch_local_path()?;
         let mut var_store = VarStore::new(device);
         let model_config =
             &ConfigOption::from_file(config.model_type, config.config_resource.get_local_path()?);
@@ -215,7 +261,7 @@ impl MaskedLanguageOption {
                         config,
                     )))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Bert!".to_string(),
                     ))
                 }
@@ -227,7 +273,7 @@ impl MaskedLanguageOption {
                         config,
                     )))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DebertaConfig for DeBERTa!".to_string(),
                     ))
                 }
@@ -239,7 +285,7 @@ impl MaskedLanguageOption {
                         config,
                     )))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DebertaV2Config for DeBERTa V2!".to_string(),
                     ))
                 }
@@ -251,7 +297,7 @@ impl MaskedLanguageOption {
                         config,
                     )))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Roberta!".to_string(),
                     ))
                 }
@@ -263,7 +309,7 @@ impl MaskedLanguageOption {
                         config,
                     )))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Roberta!".to_string(),
                     ))
                 }
@@ -275,7 +321,7 @@ impl MaskedLanguageOption {
                         config,
                     )))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a FNetConfig for FNet!".to_string(),
                     ))
                 }
@@ -284,7 +330,7 @@ impl MaskedLanguageOption {
                 "Masked Language is not implemented for {model_type:?}!",
             ))),
         }?;
-        var_store.load(weights_path)?;
+        crate::resources::load_weights(&config.model_resource, &mut var_store)?;
         Ok(model)
     }
 
@@ -421,6 +467,8 @@ pub struct MaskedLanguageModel {
     mask_token: Option<String>,
     device: Device,
     max_length: usize,
+    joint: bool,
+    joint_num_beams: usize,
 }
 
 impl MaskedLanguageModel {
@@ -504,6 +552,8 @@ impl MaskedLanguageModel {
             mask_token,
             device,
             max_length,
+            joint: config.joint,
+            joint_num_beams: config.joint_num_beams,
         })
     }
 
@@ -573,6 +623,18 @@ impl MaskedLanguageModel {
     where
         S: AsRef<[&'a str]>,
     {
+        if self.joint {
+            return Ok(self
+                .predict_joint(input)?
+                .into_iter()
+                .map(|fillings| {
+                    fillings
+                        .into_iter()
+                        .next()
+                        .map_or_else(Vec::new, |filling| filling.tokens)
+                })
+                .collect());
+        }
         let (input_ids, token_type_ids) = if let Some(mask_token) = &self.mask_token {
             let input_with_replaced_mask = self.replace_mask_token(input.as_ref(), mask_token)?;
             self.tokenizer.tokenize_and_pad(
@@ -629,6 +691,351 @@ impl MaskedLanguageModel {
         }
         Ok(output_tokens)
     }
+
+    /// Predict the masked words of texts with whole-word masking
+    ///
+    /// Each mask stands for an entire word that may span several tokens. For every mask (from left to right),
+    /// candidates of 1 to `max_word_tokens` tokens are filled greedily, token by token, and the candidate with
+    /// the highest geometric mean of its token probabilities is retained. The retained words are used as context
+    /// for the following masks of the same input. The candidates of all lengths are filled in a batch, requiring
+    /// `max_word_tokens` forward passes per mask.
+    ///
+    /// The first token of a word is chosen among the tokens starting a word, and the following tokens among the
+    /// continuation subwords (prefixed with `##`): whole-word masking requires a WordPiece vocabulary (e.g. BERT).
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to fill.
+    /// * `max_word_tokens` - `usize` maximum number of tokens of a predicted word.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<MaskedWord>>` containing the predicted word for each mask of the input texts, or an
+    ///   `InvalidConfigurationError` if the vocabulary has no continuation subwords
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::masked_language::MaskedLanguageModel;
+    ///
+    /// let mask_language_model = MaskedLanguageModel::new(Default::default())?;
+    /// let input = ["The [MASK] is a large marine mammal living in the Arctic."];
+    /// let output = mask_language_model.predict_whole_words(&input, 3)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_whole_words<'a, S>(
+        &self,
+        input: S,
+        max_word_tokens: usize,
+    ) -> Result<Vec<Vec<MaskedWord>>, RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let input = match &self.mask_token {
+            Some(mask_token) => self.replace_mask_token(input.as_ref(), mask_token)?,
+            None => input.as_ref().iter().map(|text| text.to_string()).collect(),
+        };
+        let mask_token_id =
+            self.tokenizer
+                .get_mask_id()
+                .ok_or_else(|| RustBertError::InvalidConfigurationError(
+                    "Tokenizer does not have a mask token id, Please use a tokenizer/model with a mask token.".into(),
+                ))?;
+        let vocab_size = self.tokenizer.get_vocab_size();
+        let continuation_subwords = self
+            .tokenizer
+            .convert_ids_to_tokens(&(0..vocab_size).collect::<Vec<i64>>())
+            .iter()
+            .map(|token| token.starts_with("##"))
+            .collect::<Vec<bool>>();
+        if !continuation_subwords.contains(&true) {
+            return Err(RustBertError::InvalidConfigurationError(
+                "Whole-word masking requires a WordPiece vocabulary with continuation subwords (prefixed with `##`)"
+                    .into(),
+            ));
+        }
+
+        let mut output_words = Vec::with_capacity(input.len());
+        for tokenized_input in self.tokenizer.encode_list(
+            input.as_slice(),
+            self.max_length,
+            &TruncationStrategy::LongestFirst,
+            0,
+        ) {
+            let mut token_ids = tokenized_input.token_ids;
+            let mut sequence_words = vec![];
+            let mut position = 0;
+            while let Some(offset) = token_ids[position..]
+                .iter()
+                .position(|&token_id| token_id == mask_token_id)
+            {
+                position += offset;
+                let max_word_tokens = max_word_tokens
+                    .max(1)
+                    .min(self.max_length.saturating_sub(token_ids.len()) + 1);
+                let best_word = self
+                    .fill_words(
+                        &token_ids,
+                        position,
+                        max_word_tokens,
+                        mask_token_id,
+                        &continuation_subwords,
+                    )?
+                    .into_iter()
+                    .reduce(|best_word, word| {
+                        if word.score > best_word.score {
+                            word
+                        } else {
+                            best_word
+                        }
+                    })
+                    .unwrap();
+                token_ids.splice(position..position + 1, best_word.token_ids.iter().copied());
+                position += best_word.token_ids.len();
+                sequence_words.push(best_word);
+            }
+            output_words.push(sequence_words);
+        }
+        Ok(output_words)
+    }
+
+    /// Predict the joint fillings of the masks of texts
+    ///
+    /// The masks of each input are filled from left to right with a beam search of `joint_num_beams` beams,
+    /// each filling being conditioned on the fillings of the previous masks. For an input with *M* masks, this
+    /// requires *M* forward passes on batches of at most `joint_num_beams` sequences.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to fill.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<MaskedFilling>>` containing the joint fillings of each input, ranked by decreasing joint log-probability (empty for inputs without mask)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::masked_language::MaskedLanguageModel;
+    ///
+    /// let mask_language_model = MaskedLanguageModel::new(Default::default())?;
+    /// let input = ["The [MASK] [MASK] is a famous landmark of Paris."];
+    /// let output = mask_language_model.predict_joint(&input)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_joint<'a, S>(&self, input: S) -> Result<Vec<Vec<MaskedFilling>>, RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let input = match &self.mask_token {
+            Some(mask_token) => self.replace_mask_token(input.as_ref(), mask_token)?,
+            None => input.as_ref().iter().map(|text| text.to_string()).collect(),
+        };
+        let mask_token_id =
+            self.tokenizer
+                .get_mask_id()
+                .ok_or_else(|| RustBertError::InvalidConfigurationError(
+                    "Tokenizer does not have a mask token id, Please use a tokenizer/model with a mask token.".into(),
+                ))?;
+
+        self.tokenizer
+            .encode_list(
+                input.as_slice(),
+                self.max_length,
+                &TruncationStrategy::LongestFirst,
+                0,
+            )
+            .into_iter()
+            .map(|tokenized_input| {
+                let mask_positions = tokenized_input
+                    .token_ids
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &token_id)| token_id == mask_token_id)
+                    .map(|(position, _)| position)
+                    .collect::<Vec<usize>>();
+                if mask_positions.is_empty() {
+                    Ok(vec![])
+                } else {
+                    self.fill_jointly(tokenized_input.token_ids, &mask_positions)
+                }
+            })
+            .collect()
+    }
+
+    /// Fills the masks at `mask_positions` with a beam search, returning the beams ranked by joint log-probability
+    fn fill_jointly(
+        &self,
+        token_ids: Vec<i64>,
+        mask_positions: &[usize],
+    ) -> Result<Vec<MaskedFilling>, RustBertError> {
+        let num_beams = self.joint_num_beams.max(1);
+        let sequence_length = token_ids.len() as i64;
+        let mut beams = vec![(
+            token_ids,
+            MaskedFilling {
+                tokens: vec![],
+                score: 0.0,
+            },
+        )];
+        for &position in mask_positions {
+            let input_ids = Tensor::from_slice(
+                &beams
+                    .iter()
+                    .flat_map(|(token_ids, _)| token_ids.iter().copied())
+                    .collect::<Vec<i64>>(),
+            )
+            .view((-1, sequence_length))
+            .to(self.device);
+            let token_type_ids = input_ids.zeros_like();
+            let output = no_grad(|| {
+                self.language_encode.forward_t(
+                    Some(&input_ids),
+                    None,
+                    Some(&token_type_ids),
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+            });
+            let (log_probabilities, candidate_ids) = output
+                .select(1, position as i64)
+                .log_softmax(-1, Kind::Float)
+                .topk(num_beams as i64, -1, true, true);
+            let log_probabilities = log_probabilities
+                .flatten(0, -1)
+                .iter::<f64>()?
+                .collect::<Vec<f64>>();
+            let candidate_ids = candidate_ids
+                .flatten(0, -1)
+                .iter::<i64>()?
+                .collect::<Vec<i64>>();
+
+            let mut candidates = log_probabilities
+                .into_iter()
+                .zip(candidate_ids)
+                .enumerate()
+                .map(|(index, (log_probability, token_id))| {
+                    let beam_index = index / num_beams;
+                    (
+                        beams[beam_index].1.score + log_probability,
+                        beam_index,
+                        token_id,
+                        log_probability,
+                    )
+                })
+                .collect::<Vec<(f64, usize, i64, f64)>>();
+            candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+            beams = candidates
+                .into_iter()
+                .take(num_beams)
+                .map(|(score, beam_index, token_id, log_probability)| {
+                    let (mut token_ids, mut filling) = beams[beam_index].clone();
+                    token_ids[position] = token_id;
+                    filling.tokens.push(MaskedToken {
+                        text: self.tokenizer.decode(&[token_id], false, true),
+                        id: token_id,
+                        score: log_probability,
+                    });
+                    filling.score = score;
+                    (token_ids, filling)
+                })
+                .collect();
+        }
+        Ok(beams.into_iter().map(|(_, filling)| filling).collect())
+    }
+
+    /// Fills greedily the candidate words of 1 to `max_word_tokens` tokens replacing the mask at `position`, returning
+    /// the candidate words by increasing number of tokens. The candidates are filled in a batch, one token position
+    /// at a time: the first token is restricted to the tokens starting a word, the following tokens to the
+    /// continuation subwords (flagged by `continuation_subwords`, indexed by token id).
+    fn fill_words(
+        &self,
+        token_ids: &[i64],
+        position: usize,
+        max_word_tokens: usize,
+        mask_token_id: i64,
+        continuation_subwords: &[bool],
+    ) -> Result<Vec<MaskedWord>, RustBertError> {
+        let pad_token_id = self.tokenizer.get_pad_id().unwrap_or(mask_token_id);
+        let max_sequence_length = token_ids.len() + max_word_tokens - 1;
+        let mut candidate_ids = Vec::with_capacity(max_word_tokens);
+        let mut attention_masks = Vec::with_capacity(max_word_tokens);
+        for num_tokens in 1..=max_word_tokens {
+            let mut candidate = token_ids.to_vec();
+            candidate.splice(position..position, vec![mask_token_id; num_tokens - 1]);
+            let mut attention_mask = vec![1i64; candidate.len()];
+            candidate.resize(max_sequence_length, pad_token_id);
+            attention_mask.resize(max_sequence_length, 0);
+            candidate_ids.push(candidate);
+            attention_masks.push(attention_mask);
+        }
+        let attention_mask = Tensor::from_slice2(&attention_masks).to(self.device);
+
+        let mut continuation_mask: Option<Tensor> = None;
+        let mut sum_log_probabilities = vec![0f64; max_word_tokens];
+        for token_offset in 0..max_word_tokens {
+            // The candidates of `token_offset` tokens or fewer are complete
+            let input_ids = Tensor::from_slice2(&candidate_ids[token_offset..]).to(self.device);
+            let token_type_ids = input_ids.zeros_like();
+            let output = no_grad(|| {
+                self.language_encode.forward_t(
+                    Some(&input_ids),
+                    Some(&attention_mask.slice(0, token_offset as i64, None, 1)),
+                    Some(&token_type_ids),
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+            });
+            let continuation_mask = continuation_mask.get_or_insert_with(|| {
+                let mut flags = continuation_subwords.to_vec();
+                flags.resize(output.size()[2] as usize, false);
+                Tensor::from_slice(&flags).to(self.device)
+            });
+            let excluded_tokens = if token_offset == 0 {
+                continuation_mask.shallow_clone()
+            } else {
+                continuation_mask.logical_not()
+            };
+            let (log_probabilities, filled_ids) = output
+                .select(1, (position + token_offset) as i64)
+                .log_softmax(-1, Kind::Float)
+                .masked_fill(&excluded_tokens, f64::NEG_INFINITY)
+                .max_dim(-1, false);
+            for (candidate, (log_probability, token_id)) in (token_offset..max_word_tokens).zip(
+                log_probabilities
+                    .iter::<f64>()?
+                    .zip(filled_ids.iter::<i64>()?),
+            ) {
+                candidate_ids[candidate][position + token_offset] = token_id;
+                sum_log_probabilities[candidate] += log_probability;
+            }
+        }
+
+        Ok(candidate_ids
+            .into_iter()
+            .zip(sum_log_probabilities)
+            .enumerate()
+            .map(|(candidate, (candidate_ids, sum_log_probabilities))| {
+                let num_tokens = candidate + 1;
+                let word_ids = candidate_ids[position..position + num_tokens].to_vec();
+                MaskedWord {
+                    text: self.tokenizer.decode(&word_ids, false, true),
+                    token_ids: word_ids,
+                    score: (sum_log_probabilities / num_tokens as f64).exp(),
+                }
+            })
+            .collect())
+    }
 }
 #[cfg(test)]
 mod test {
@@ -478,13 +478,16 @@ pub mod common;
 pub mod conversation;
 pub mod generation_utils;
 pub mod keywords_extraction;
+pub mod language_identification;
 pub mod masked_language;
 pub mod ner;
 pub mod pos_tagging;
 pub mod question_answering;
+pub mod reranking;
 pub mod sentence_embeddings;
 pub mod sentiment;
 pub mod sequence_classification;
+pub mod similarity;
 pub mod summarization;
 pub mod text_generation;
 pub mod token_classification;
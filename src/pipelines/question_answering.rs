@@ -342,7 +342,7 @@ impl QuestionAnsweringOption {
                         BertForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Bert!".to_string(),
                     ))
                 }
@@ -353,7 +353,7 @@ impl QuestionAnsweringOption {
                         DebertaForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DebertaConfig for DeBERTa!".to_string(),
                     ))
                 }
@@ -364,7 +364,7 @@ impl QuestionAnsweringOption {
                         DebertaV2ForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DebertaV2Config for DeBERTa V2!".to_string(),
                     ))
                 }
@@ -376,7 +376,7 @@ impl QuestionAnsweringOption {
                         DistilBertForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DistilBertConfig for DistilBert!".to_string(),
                     ))
                 }
@@ -387,7 +387,7 @@ impl QuestionAnsweringOption {
                         MobileBertForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a MobileBertConfig for MobileBert!".to_string(),
                     ))
                 }
@@ -398,7 +398,7 @@ impl QuestionAnsweringOption {
                         RobertaForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a RobertaConfig for Roberta!".to_string(),
                     ))
                 }
@@ -409,7 +409,7 @@ impl QuestionAnsweringOption {
                         RobertaForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Roberta!".to_string(),
                     ))
                 }
@@ -420,7 +420,7 @@ impl QuestionAnsweringOption {
                         AlbertForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply an AlbertConfig for Albert!".to_string(),
                     ))
                 }
@@ -431,7 +431,7 @@ impl QuestionAnsweringOption {
                         XLNetForQuestionAnswering::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a XLNetConfig for XLNet!".to_string(),
                     ))
                 }
@@ -442,7 +442,7 @@ impl QuestionAnsweringOption {
                         ReformerForQuestionAnswering::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a ReformerConfig for Reformer!".to_string(),
                     ))
                 }
@@ -453,7 +453,7 @@ impl QuestionAnsweringOption {
                         LongformerForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a LongformerConfig for Longformer!".to_string(),
                     ))
                 }
@@ -464,7 +464,7 @@ impl QuestionAnsweringOption {
                         FNetForQuestionAnswering::new(var_store.root(), config),
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a FNetConfig for FNet!".to_string(),
                     ))
                 }
@@ -0,0 +1,250 @@
+// Copyright 2019-present, the HuggingFace Inc. team, The Google AI Language Team and Facebook, Inc.
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Reranking pipeline
+//! Ranks candidate documents by relevance to a query with a cross-encoder, a sequence classification model
+//! scoring (query, document) pairs (e.g. a MiniLM model fine-tuned on MS MARCO). Unlike bi-encoders
+//! (see the sentence embeddings pipeline), the query and document are encoded jointly: cross-encoders are more
+//! accurate but more expensive, and are typically used to rerank the top results of a first retrieval stage.
+//!
+//! The relevance score is the sigmoid of the model output for models with a single output, or the probability of
+//! the last label for models with several labels (e.g. `not relevant` / `relevant`). The outputs are calibrated
+//! with the `temperature` and `apply_softmax` settings of the configuration, as for the sequence classification pipeline.
+//!
+//! No default resources are provided for this pipeline: the configuration resources should point to a
+//! cross-encoder checkpoint converted to the `.ot` format.
+//!
+//! ```no_run
+//! use rust_bert::pipelines::common::{ModelResource, ModelType};
+//! use rust_bert::pipelines::reranking::{RerankingConfig, RerankingModel};
+//! use rust_bert::resources::LocalResource;
+//! use std::path::PathBuf;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let config = RerankingConfig::new(
+//!     ModelType::Bert,
+//!     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+//!         "path/to/rust_model.ot",
+//!     )))),
+//!     LocalResource::from(PathBuf::from("path/to/config.json")),
+//!     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+//!     None,
+//!     true,
+//!     None,
+//!     None,
+//! );
+//! let reranking_model = RerankingModel::new(config)?;
+//! let query = "How many people live in Berlin?";
+//! let candidates = [
+//!     "New York City is famous for the Metropolitan Museum of Art.",
+//!     "Berlin has a population of 3,520,031 registered inhabitants.",
+//! ];
+//! let output = reranking_model.rerank(query, &candidates)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Output: \
+//! ```no_run
+//! # let output =
+//! [(1, 0.998), (0, 0.001)]
+//! # ;
+//! ```
+
+use crate::common::error::RustBertError;
+use crate::pipelines::common::TokenizerOption;
+use crate::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
+use std::cmp::Ordering;
+
+pub type RerankingConfig = SequenceClassificationConfig;
+
+/// # RerankingModel to sort candidate documents by relevance to a query
+pub struct RerankingModel {
+    sequence_classification_model: SequenceClassificationModel,
+}
+
+impl RerankingModel {
+    /// Build a new `RerankingModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `reranking_config` - `RerankingConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::reranking::{RerankingConfig, RerankingModel};
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let resource = |path: &str| LocalResource::from(PathBuf::from(path));
+    /// # let reranking_config = RerankingConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(resource("path/to/rust_model.ot"))),
+    /// #     resource("path/to/config.json"),
+    /// #     resource("path/to/vocab.txt"),
+    /// #     None,
+    /// #     true,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let reranking_model = RerankingModel::new(reranking_config)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(reranking_config: RerankingConfig) -> Result<RerankingModel, RustBertError> {
+        let sequence_classification_model = SequenceClassificationModel::new(reranking_config)?;
+        Ok(RerankingModel {
+            sequence_classification_model,
+        })
+    }
+
+    /// Build a new `RerankingModel` with a provided tokenizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `reranking_config` - `RerankingConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    /// * `tokenizer` - `TokenizerOption` tokenizer to use for reranking.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::{ModelType, TokenizerOption};
+    /// use rust_bert::pipelines::reranking::{RerankingConfig, RerankingModel};
+    /// let tokenizer = TokenizerOption::from_file(
+    ///     ModelType::Bert,
+    ///     "path/to/vocab.txt",
+    ///     None,
+    ///     true,
+    ///     None,
+    ///     None,
+    /// )?;
+    /// # use rust_bert::pipelines::common::ModelResource;
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let resource = |path: &str| LocalResource::from(PathBuf::from(path));
+    /// # let reranking_config = RerankingConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(resource("path/to/rust_model.ot"))),
+    /// #     resource("path/to/config.json"),
+    /// #     resource("path/to/vocab.txt"),
+    /// #     None,
+    /// #     true,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let reranking_model = RerankingModel::new_with_tokenizer(reranking_config, tokenizer)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_tokenizer(
+        reranking_config: RerankingConfig,
+        tokenizer: TokenizerOption,
+    ) -> Result<RerankingModel, RustBertError> {
+        let sequence_classification_model =
+            SequenceClassificationModel::new_with_tokenizer(reranking_config, tokenizer)?;
+        Ok(RerankingModel {
+            sequence_classification_model,
+        })
+    }
+
+    /// Get a reference to the model tokenizer.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        self.sequence_classification_model.get_tokenizer()
+    }
+
+    /// Get a mutable reference to the model tokenizer.
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        self.sequence_classification_model.get_tokenizer_mut()
+    }
+
+    /// Rank candidate documents by relevance to a query. The (query, candidate) pairs are scored in batches fitting
+    /// within the `memory_budget` of the configuration (in a single batch without budget).
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - `&str` Query to rank the candidates against.
+    /// * `candidates` - `&[&str]` Candidate documents to rank.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(usize, f64)>` Index of each candidate in the input and its relevance score, sorted by decreasing relevance.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::reranking::{RerankingConfig, RerankingModel};
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let resource = |path: &str| LocalResource::from(PathBuf::from(path));
+    /// # let reranking_config = RerankingConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(resource("path/to/rust_model.ot"))),
+    /// #     resource("path/to/config.json"),
+    /// #     resource("path/to/vocab.txt"),
+    /// #     None,
+    /// #     true,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let reranking_model = RerankingModel::new(reranking_config)?;
+    /// let query = "How many people live in Berlin?";
+    /// let candidates = [
+    ///     "New York City is famous for the Metropolitan Museum of Art.",
+    ///     "Berlin has a population of 3,520,031 registered inhabitants.",
+    /// ];
+    /// let output = reranking_model.rerank(query, &candidates)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rerank(
+        &self,
+        query: &str,
+        candidates: &[&str],
+    ) -> Result<Vec<(usize, f64)>, RustBertError> {
+        if candidates.is_empty() {
+            return Ok(vec![]);
+        }
+        let pairs = candidates
+            .iter()
+            .map(|candidate| (query, *candidate))
+            .collect::<Vec<(&str, &str)>>();
+        let mut ranked_candidates = self
+            .sequence_classification_model
+            .predict_pairs_last_label_scores(&pairs)?
+            .iter::<f64>()?
+            .enumerate()
+            .collect::<Vec<(usize, f64)>>();
+        ranked_candidates.sort_by(|(_, score_1), (_, score_2)| {
+            score_2.partial_cmp(score_1).unwrap_or(Ordering::Equal)
+        });
+        Ok(ranked_candidates)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let config = RerankingConfig::default();
+        let _: Box<dyn Send> = Box::new(RerankingModel::new(config));
+    }
+}
@@ -4,6 +4,7 @@ use serde::Deserialize;
 use tch::Device;
 
 use crate::pipelines::common::ModelType;
+use crate::pipelines::sentence_embeddings::layers::DimensionReduction;
 use crate::pipelines::sentence_embeddings::{
     SentenceEmbeddingsConfig, SentenceEmbeddingsModel, SentenceEmbeddingsModulesConfig,
 };
@@ -21,6 +22,9 @@ use crate::{
 /// (configuration and weights).
 pub struct SentenceEmbeddingsBuilder<T> {
     device: Device,
+    dimension_reduction: Option<DimensionReduction>,
+    output_hidden_states: bool,
+    add_special_tokens: bool,
     inner: T,
 }
 
@@ -29,6 +33,21 @@ impl<T> SentenceEmbeddingsBuilder<T> {
         self.device = device;
         self
     }
+
+    pub fn with_dimension_reduction(mut self, dimension_reduction: DimensionReduction) -> Self {
+        self.dimension_reduction = Some(dimension_reduction);
+        self
+    }
+
+    pub fn with_hidden_states(mut self) -> Self {
+        self.output_hidden_states = true;
+        self
+    }
+
+    pub fn without_special_tokens(mut self) -> Self {
+        self.add_special_tokens = false;
+        self
+    }
 }
 
 pub struct Local {
@@ -46,6 +65,9 @@ impl SentenceEmbeddingsBuilder<Local> {
     pub fn local<P: Into<PathBuf>>(model_dir: P) -> Self {
         Self {
             device: Device::cuda_if_available(),
+            dimension_reduction: None,
+            output_hidden_states: false,
+            add_special_tokens: true,
             inner: Local {
                 model_dir: model_dir.into(),
             },
@@ -106,6 +128,9 @@ impl SentenceEmbeddingsBuilder<Local> {
             tokenizer_vocab_resource: tokenizer_vocab.into(),
             tokenizer_merges_resource: tokenizer_merges.map(|r| r.into()),
             device: self.device,
+            dimension_reduction: self.dimension_reduction,
+            output_hidden_states: self.output_hidden_states,
+            add_special_tokens: self.add_special_tokens,
         };
 
         SentenceEmbeddingsModel::new(config)
@@ -122,6 +147,9 @@ impl SentenceEmbeddingsBuilder<Remote> {
     pub fn remote(model_type: SentenceEmbeddingsModelType) -> Self {
         Self {
             device: Device::cuda_if_available(),
+            dimension_reduction: None,
+            output_hidden_states: false,
+            add_special_tokens: true,
             inner: Remote {
                 config: SentenceEmbeddingsConfig::from(model_type),
             },
@@ -180,6 +208,9 @@ impl SentenceEmbeddingsBuilder<Remote> {
 
     pub fn create_model(mut self) -> Result<SentenceEmbeddingsModel, RustBertError> {
         self.inner.config.device = self.device;
+        self.inner.config.dimension_reduction = self.dimension_reduction;
+        self.inner.config.output_hidden_states = self.output_hidden_states;
+        self.inner.config.add_special_tokens = self.add_special_tokens;
         SentenceEmbeddingsModel::new(self.inner.config)
     }
 }
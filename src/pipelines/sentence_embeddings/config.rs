@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use tch::Device;
 
 use crate::pipelines::common::ModelType;
+use crate::pipelines::sentence_embeddings::layers::DimensionReduction;
 use crate::resources::ResourceProvider;
 use crate::{Config, RustBertError};
 
@@ -55,6 +56,17 @@ pub struct SentenceEmbeddingsConfig {
     pub tokenizer_merges_resource: Option<Box<dyn ResourceProvider + Send>>,
     /// Device to place the transformer model on
     pub device: Device,
+    /// Optional reduction of the embeddings to a smaller dimension (truncation or projection), applied before
+    /// the normalization of the embeddings (default: None)
+    pub dimension_reduction: Option<DimensionReduction>,
+    /// Flag indicating if the transformer should output the hidden states of all its layers, required to compute the
+    /// embeddings of an intermediate layer with `SentenceEmbeddingsModel::encode_layer` (default: false). Only
+    /// supported for BERT, DistilBERT, RoBERTa and ALBERT transformers.
+    pub output_hidden_states: bool,
+    /// Flag indicating if the special tokens (e.g. *CLS* and *SEP*) should be added to the inputs (default: true).
+    /// To be set to `false` for models trained on inputs without special tokens: the token embeddings are then
+    /// mean-pooled over the input tokens (excluding the padding), whatever the pooling configuration of the model.
+    pub add_special_tokens: bool,
 }
 
 #[cfg(feature = "remote")]
@@ -92,6 +104,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 )),
                 tokenizer_merges_resource: None,
                 device: Device::cuda_if_available(),
+                dimension_reduction: None,
+                output_hidden_states: false,
+                add_special_tokens: true,
             },
 
             SentenceEmbeddingsModelType::BertBaseNliMeanTokens => SentenceEmbeddingsConfig {
@@ -121,6 +136,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 )),
                 tokenizer_merges_resource: None,
                 device: Device::cuda_if_available(),
+                dimension_reduction: None,
+                output_hidden_states: false,
+                add_special_tokens: true,
             },
 
             SentenceEmbeddingsModelType::AllMiniLmL12V2 => SentenceEmbeddingsConfig {
@@ -150,6 +168,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 )),
                 tokenizer_merges_resource: None,
                 device: Device::cuda_if_available(),
+                dimension_reduction: None,
+                output_hidden_states: false,
+                add_special_tokens: true,
             },
 
             SentenceEmbeddingsModelType::AllMiniLmL6V2 => SentenceEmbeddingsConfig {
@@ -179,6 +200,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 )),
                 tokenizer_merges_resource: None,
                 device: Device::cuda_if_available(),
+                dimension_reduction: None,
+                output_hidden_states: false,
+                add_special_tokens: true,
             },
 
             SentenceEmbeddingsModelType::AllDistilrobertaV1 => SentenceEmbeddingsConfig {
@@ -210,6 +234,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                     RobertaMergesResources::ALL_DISTILROBERTA_V1,
                 ))),
                 device: Device::cuda_if_available(),
+                dimension_reduction: None,
+                output_hidden_states: false,
+                add_special_tokens: true,
             },
 
             SentenceEmbeddingsModelType::ParaphraseAlbertSmallV2 => SentenceEmbeddingsConfig {
@@ -239,6 +266,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 )),
                 tokenizer_merges_resource: None,
                 device: Device::cuda_if_available(),
+                dimension_reduction: None,
+                output_hidden_states: false,
+                add_special_tokens: true,
             },
 
             SentenceEmbeddingsModelType::SentenceT5Base => SentenceEmbeddingsConfig {
@@ -272,6 +302,9 @@ impl From<SentenceEmbeddingsModelType> for SentenceEmbeddingsConfig {
                 )),
                 tokenizer_merges_resource: None,
                 device: Device::cuda_if_available(),
+                dimension_reduction: None,
+                output_hidden_states: false,
+                add_special_tokens: true,
             },
         }
     }
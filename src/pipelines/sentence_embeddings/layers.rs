@@ -36,8 +36,15 @@ impl Pooling {
         Pooling { conf }
     }
 
-    pub fn forward(&self, mut token_embeddings: Tensor, attention_mask: &Tensor) -> Tensor {
+    /// Pools token embeddings of shape (*batch size*, *sequence length*, *hidden size*) into sentence embeddings
+    /// of shape (*batch size*, *pooled dimension*), ignoring the padding positions of the attention mask.
+    /// The pooling runs on the device of the token embeddings (e.g. the GPU), from a single mask broadcast over
+    /// the hidden dimension: only the pooled embeddings need to be transferred to the CPU.
+    pub fn forward(&self, token_embeddings: Tensor, attention_mask: &Tensor) -> Tensor {
         let mut output_vectors = Vec::new();
+        let mask = attention_mask
+            .unsqueeze(-1)
+            .to_kind(token_embeddings.kind());
 
         if self.conf.pooling_mode_cls_token {
             let cls_token = token_embeddings.select(1, 0); // Take first token by default
@@ -45,22 +52,19 @@ impl Pooling {
         }
 
         if self.conf.pooling_mode_max_tokens {
-            let input_mask_expanded = attention_mask.unsqueeze(-1).expand_as(&token_embeddings);
             // Set padding tokens to large negative value
-            token_embeddings = token_embeddings.masked_fill_(&input_mask_expanded.eq(0), -1e9);
-            let max_over_time = token_embeddings.max_dim(1, true).0;
+            let max_over_time = token_embeddings
+                .masked_fill(&mask.eq(0), -1e9)
+                .amax([1].as_slice(), false);
             output_vectors.push(max_over_time);
         }
 
         if self.conf.pooling_mode_mean_tokens || self.conf.pooling_mode_mean_sqrt_len_tokens {
-            let input_mask_expanded = attention_mask.unsqueeze(-1).expand_as(&token_embeddings);
-            let sum_embeddings = (token_embeddings * &input_mask_expanded).sum_dim_intlist(
-                [1].as_slice(),
-                false,
-                Kind::Float,
-            );
-            let sum_mask = input_mask_expanded.sum_dim_intlist([1].as_slice(), false, Kind::Float);
-            let sum_mask = sum_mask.clamp_min(10e-9);
+            let sum_embeddings =
+                (&token_embeddings * &mask).sum_dim_intlist([1].as_slice(), false, Kind::Float);
+            let sum_mask = mask
+                .sum_dim_intlist([1].as_slice(), false, Kind::Float)
+                .clamp_min(10e-9);
 
             if self.conf.pooling_mode_mean_tokens {
                 output_vectors.push(&sum_embeddings / &sum_mask);
@@ -74,6 +78,11 @@ impl Pooling {
     }
 }
 
+/// L2-normalizes embeddings of shape (*batch size*, *embeddings dimension*) on their device.
+pub fn l2_normalize(embeddings: &Tensor) -> Tensor {
+    embeddings / embeddings.norm_scalaropt_dim(2, [1], true).clamp_min(1e-12)
+}
+
 /// Configuration for [`Dense`](Dense) layer.
 #[derive(Debug, Deserialize)]
 pub struct DenseConfig {
@@ -151,3 +160,65 @@ impl Dense {
         self.activation.get_fn()(&x.apply(&self.linear))
     }
 }
+
+/// Reduction of the sentence embeddings to a smaller dimension (e.g. for vector databases favoring
+/// 256-dimensional embeddings), applied after the pooling and dense layers.
+pub enum DimensionReduction {
+    /// Keeps the first `dimension` components of the embeddings (suited to models trained with Matryoshka
+    /// representation learning), optionally L2-normalizing the truncated embeddings.
+    Truncation { dimension: i64, normalize: bool },
+    /// Projects the embeddings with a provided matrix of shape (*embeddings dimension*, *target dimension*),
+    /// e.g. a learned projection or the principal components of a PCA.
+    Projection(Tensor),
+}
+
+impl DimensionReduction {
+    /// Validates the reduction for embeddings of dimension `embeddings_dim`, moving the projection matrix
+    /// (if any) to the model device, and returns the reduced dimension.
+    pub(crate) fn prepare(
+        &mut self,
+        embeddings_dim: i64,
+        device: Device,
+    ) -> Result<i64, RustBertError> {
+        match self {
+            DimensionReduction::Truncation { dimension, .. } => {
+                if *dimension < 1 || *dimension > embeddings_dim {
+                    return Err(RustBertError::InvalidConfigurationError(format!(
+                        "Truncation dimension {dimension} must be between 1 and the embeddings dimension {embeddings_dim}"
+                    )));
+                }
+                Ok(*dimension)
+            }
+            DimensionReduction::Projection(projection) => {
+                let size = projection.size();
+                if size.len() != 2 || size[0] != embeddings_dim {
+                    return Err(RustBertError::TensorShapeError(format!(
+                        "Projection matrix must be of shape ({embeddings_dim}, target dimension), got {size:?}"
+                    )));
+                }
+                *projection = projection.to_device(device).to_kind(Kind::Float);
+                Ok(size[1])
+            }
+        }
+    }
+
+    /// Returns `true` if the reduced embeddings should be L2-normalized
+    pub(crate) fn normalize(&self) -> bool {
+        matches!(
+            self,
+            DimensionReduction::Truncation {
+                normalize: true,
+                ..
+            }
+        )
+    }
+
+    pub fn forward(&self, x: &Tensor) -> Tensor {
+        match self {
+            DimensionReduction::Truncation { dimension, .. } => x.narrow(1, 0, *dimension),
+            DimensionReduction::Projection(projection) => {
+                x.to_kind(projection.kind()).matmul(projection)
+            }
+        }
+    }
+}
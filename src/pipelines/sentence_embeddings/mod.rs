@@ -41,8 +41,8 @@ pub use config::{
     SentenceEmbeddingsTokenizerConfig,
 };
 pub use pipeline::{
-    SentenceEmbeddingsModel, SentenceEmbeddingsModelOutput, SentenceEmbeddingsOption,
-    SentenceEmbeddingsTokenizerOutput,
+    ClsRepresentation, SentenceEmbeddingsModel, SentenceEmbeddingsModelOutput,
+    SentenceEmbeddingsOption, SentenceEmbeddingsTokenizerOutput,
 };
 
 pub use resources::{
@@ -2,13 +2,15 @@ use std::borrow::Borrow;
 use std::convert::{TryFrom, TryInto};
 
 use rust_tokenizers::tokenizer::TruncationStrategy;
-use tch::{nn, Tensor};
+use tch::{nn, Kind, Tensor};
 
 use crate::albert::AlbertForSentenceEmbeddings;
 use crate::bert::BertForSentenceEmbeddings;
 use crate::distilbert::DistilBertForSentenceEmbeddings;
 use crate::pipelines::common::{ConfigOption, ModelType, TokenizerOption};
-use crate::pipelines::sentence_embeddings::layers::{Dense, DenseConfig, Pooling, PoolingConfig};
+use crate::pipelines::sentence_embeddings::layers::{
+    l2_normalize, Dense, DenseConfig, DimensionReduction, Pooling, PoolingConfig,
+};
 use crate::pipelines::sentence_embeddings::{
     AttentionHead, AttentionLayer, AttentionOutput, Embedding, SentenceEmbeddingsConfig,
     SentenceEmbeddingsModulesConfig, SentenceEmbeddingsSentenceBertConfig,
@@ -18,6 +20,21 @@ use crate::roberta::RobertaForSentenceEmbeddings;
 use crate::t5::T5ForSentenceEmbeddings;
 use crate::{Config, RustBertError};
 
+/// # Representation of the first (CLS) token returned by `SentenceEmbeddingsModel::encode_cls`
+///
+/// BERT-like models compute a pooler output by applying a dense layer and a tanh activation to the final
+/// hidden state of the first token. This projection is trained for the next sentence prediction (or sentence
+/// order prediction) objective and is often not suited for downstream tasks such as clustering, for which the
+/// raw hidden state is usually preferred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClsRepresentation {
+    /// Final hidden state of the first token, before any pooling or projection
+    HiddenState,
+    /// Output of the model pooler (dense layer and tanh activation applied to the first token hidden state).
+    /// Only available for BERT and ALBERT transformers.
+    PoolerOutput,
+}
+
 /// # Abstraction that holds one particular sentence embeddings model, for any of the supported models
 pub enum SentenceEmbeddingsOption {
     /// Bert for Sentence Embeddings
@@ -148,6 +165,154 @@ impl SentenceEmbeddingsOption {
             Self::T5(transformer) => transformer.forward(tokens_ids, tokens_masks),
         }
     }
+
+    /// Interface method to forward() of the particular transformer models, returning the last hidden states
+    /// and the pooler output (if the transformer has a pooler).
+    pub fn forward_with_pooler(
+        &self,
+        tokens_ids: &Tensor,
+        tokens_masks: &Tensor,
+    ) -> Result<(Tensor, Option<Tensor>), RustBertError> {
+        match self {
+            Self::Bert(transformer) => transformer
+                .forward_t(
+                    Some(tokens_ids),
+                    Some(tokens_masks),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+                .map(|transformer_output| {
+                    (
+                        transformer_output.hidden_state,
+                        transformer_output.pooled_output,
+                    )
+                }),
+            Self::Roberta(transformer) => transformer
+                .forward_t(
+                    Some(tokens_ids),
+                    Some(tokens_masks),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+                .map(|transformer_output| {
+                    (
+                        transformer_output.hidden_state,
+                        transformer_output.pooled_output,
+                    )
+                }),
+            Self::Albert(transformer) => transformer
+                .forward_t(
+                    Some(tokens_ids),
+                    Some(tokens_masks),
+                    None,
+                    None,
+                    None,
+                    false,
+                )
+                .map(|transformer_output| {
+                    (
+                        transformer_output.hidden_state,
+                        Some(transformer_output.pooled_output),
+                    )
+                }),
+            Self::DistilBert(_) | Self::T5(_) => self
+                .forward(tokens_ids, tokens_masks)
+                .map(|(hidden_state, _)| (hidden_state, None)),
+        }
+    }
+
+    /// Interface method to forward() of the particular transformer models, returning the hidden states of all
+    /// layers: the embeddings layer output (index 0) followed by the output of each transformer layer (the last one
+    /// being the final hidden state). The transformer must be built with `output_hidden_states` enabled in its
+    /// configuration. Not supported for T5 transformers.
+    pub fn forward_all_hidden_states(
+        &self,
+        tokens_ids: &Tensor,
+        tokens_masks: &Tensor,
+    ) -> Result<Vec<Tensor>, RustBertError> {
+        let missing_hidden_states = || {
+            RustBertError::InvalidConfigurationError(
+                "The transformer was not configured to output its hidden states".to_string(),
+            )
+        };
+        match self {
+            Self::Bert(transformer) => {
+                let embeddings_output = transformer.embeddings_forward_t(tokens_ids, false)?;
+                let all_hidden_states = transformer
+                    .forward_t(
+                        Some(tokens_ids),
+                        Some(tokens_masks),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        false,
+                    )?
+                    .all_hidden_states
+                    .ok_or_else(missing_hidden_states)?;
+                Ok(std::iter::once(embeddings_output)
+                    .chain(all_hidden_states)
+                    .collect())
+            }
+            Self::Roberta(transformer) => {
+                let embeddings_output = transformer.embeddings_forward_t(tokens_ids, false)?;
+                let all_hidden_states = transformer
+                    .forward_t(
+                        Some(tokens_ids),
+                        Some(tokens_masks),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        false,
+                    )?
+                    .all_hidden_states
+                    .ok_or_else(missing_hidden_states)?;
+                Ok(std::iter::once(embeddings_output)
+                    .chain(all_hidden_states)
+                    .collect())
+            }
+            Self::DistilBert(transformer) => {
+                let embeddings_output = transformer.embeddings_forward_t(tokens_ids, false)?;
+                let all_hidden_states = transformer
+                    .forward_t(Some(tokens_ids), Some(tokens_masks), None, false)?
+                    .all_hidden_states
+                    .ok_or_else(missing_hidden_states)?;
+                Ok(std::iter::once(embeddings_output)
+                    .chain(all_hidden_states)
+                    .collect())
+            }
+            Self::Albert(transformer) => {
+                // ALBERT collects the input of each layer, starting with the projected embeddings
+                let transformer_output = transformer.forward_t(
+                    Some(tokens_ids),
+                    Some(tokens_masks),
+                    None,
+                    None,
+                    None,
+                    false,
+                )?;
+                let mut all_hidden_states = transformer_output
+                    .all_hidden_states
+                    .ok_or_else(missing_hidden_states)?;
+                all_hidden_states.push(transformer_output.hidden_state);
+                Ok(all_hidden_states)
+            }
+            Self::T5(_) => Err(RustBertError::InvalidConfigurationError(
+                "Hidden states output not supported for T5 transformers".to_string(),
+            )),
+        }
+    }
 }
 
 /// # SentenceEmbeddingsModel to perform sentence embeddings
@@ -156,6 +321,7 @@ impl SentenceEmbeddingsOption {
 /// - `transformer`: Base transformer model
 /// - `pooling`: Pooling layer
 /// - `dense` _(optional)_: Linear (feed forward) layer
+/// - `dimension_reduction` _(optional)_: Truncation or projection of the embeddings to a smaller dimension
 /// - `normalization` _(optional)_: Embeddings normalization
 pub struct SentenceEmbeddingsModel {
     sentence_bert_config: SentenceEmbeddingsSentenceBertConfig,
@@ -166,8 +332,12 @@ pub struct SentenceEmbeddingsModel {
     transformer_config: ConfigOption,
     pooling_layer: Pooling,
     dense_layer: Option<Dense>,
+    dimension_reduction: Option<DimensionReduction>,
     normalize_embeddings: bool,
+    pooled_dim: i64,
     embeddings_dim: i64,
+    output_hidden_states: bool,
+    add_special_tokens: bool,
 }
 
 impl SentenceEmbeddingsModel {
@@ -190,6 +360,9 @@ impl SentenceEmbeddingsModel {
             dense_config_resource,
             dense_weights_resource,
             device,
+            dimension_reduction,
+            output_hidden_states,
+            add_special_tokens,
         } = config;
 
         let modules =
@@ -224,17 +397,32 @@ impl SentenceEmbeddingsModel {
 
         // Setup transformer
         let mut var_store = nn::VarStore::new(device);
-        let transformer_config = ConfigOption::from_file(
+        let mut transformer_config = ConfigOption::from_file(
             transformer_type,
             transformer_config_resource.get_local_path()?,
         );
+        if output_hidden_states {
+            transformer_config.set_output_hidden_states(true)?;
+        }
         let transformer =
             SentenceEmbeddingsOption::new(transformer_type, var_store.root(), &transformer_config)?;
         crate::resources::load_weights(&transformer_weights_resource, &mut var_store)?;
 
         // Setup pooling layer
-        let pooling_config = PoolingConfig::from_file(pooling_config_resource.get_local_path()?);
-        let mut embeddings_dim = pooling_config.word_embedding_dimension;
+        let mut pooling_config =
+            PoolingConfig::from_file(pooling_config_resource.get_local_path()?);
+        if !add_special_tokens {
+            // Without special tokens, the first token is not a CLS token: the tokens are mean-pooled
+            pooling_config = PoolingConfig {
+                word_embedding_dimension: pooling_config.word_embedding_dimension,
+                pooling_mode_cls_token: false,
+                pooling_mode_max_tokens: false,
+                pooling_mode_mean_tokens: true,
+                pooling_mode_mean_sqrt_len_tokens: false,
+            };
+        }
+        let pooled_dim = pooling_config.word_embedding_dimension;
+        let mut embeddings_dim = pooled_dim;
         let pooling_layer = Pooling::new(pooling_config);
 
         // Setup dense layer
@@ -251,7 +439,17 @@ impl SentenceEmbeddingsModel {
             None
         };
 
-        let normalize_embeddings = modules.has_normalization();
+        // Setup dimension reduction
+        let dimension_reduction = match dimension_reduction {
+            Some(mut dimension_reduction) => {
+                embeddings_dim = dimension_reduction.prepare(embeddings_dim, device)?;
+                Some(dimension_reduction)
+            }
+            None => None,
+        };
+
+        let normalize_embeddings = modules.has_normalization()
+            || matches!(&dimension_reduction, Some(reduction) if reduction.normalize());
 
         Ok(Self {
             tokenizer,
@@ -262,8 +460,12 @@ impl SentenceEmbeddingsModel {
             transformer_config,
             pooling_layer,
             dense_layer,
+            dimension_reduction,
             normalize_embeddings,
+            pooled_dim,
             embeddings_dim,
+            output_hidden_states,
+            add_special_tokens,
         })
     }
 
@@ -287,17 +489,30 @@ impl SentenceEmbeddingsModel {
         Ok(self.embeddings_dim)
     }
 
-    /// Tokenizes the inputs
+    /// Return the dimension of the pooling layer output, before the optional dense layer and dimension reduction
+    pub fn get_pooled_dim(&self) -> i64 {
+        self.pooled_dim
+    }
+
+    /// Tokenizes the inputs, padded to the longest input. The attention masks are 1 for the tokens of the inputs and
+    /// 0 for the padding.
     pub fn tokenize<S>(&self, inputs: &[S]) -> SentenceEmbeddingsTokenizerOutput
     where
         S: AsRef<str> + Sync,
     {
-        let tokenized_input = self.tokenizer.encode_list(
-            inputs,
-            self.sentence_bert_config.max_seq_length,
-            &self.tokenizer_truncation_strategy,
-            0,
-        );
+        let tokenized_input = if self.add_special_tokens {
+            self.tokenizer.encode_list(
+                inputs,
+                self.sentence_bert_config.max_seq_length,
+                &self.tokenizer_truncation_strategy,
+                0,
+            )
+        } else {
+            self.tokenizer.encode_list_without_special_tokens(
+                inputs,
+                self.sentence_bert_config.max_seq_length,
+            )
+        };
 
         let max_len = tokenized_input
             .iter()
@@ -306,6 +521,18 @@ impl SentenceEmbeddingsModel {
             .unwrap_or(0);
 
         let pad_token_id = self.tokenizer.get_pad_id().unwrap_or(0);
+        // The masks are derived from the input lengths rather than the token ids: the padding token may also
+        // appear in the inputs
+        let tokens_masks = tokenized_input
+            .iter()
+            .map(|input| {
+                let num_tokens = input.token_ids.len();
+                let mut mask = vec![1i64; num_tokens];
+                mask.extend(vec![0i64; max_len - num_tokens]);
+                Tensor::from_slice(&mask)
+            })
+            .collect::<Vec<_>>();
+
         let tokens_ids = tokenized_input
             .into_iter()
             .map(|input| {
@@ -315,18 +542,6 @@ impl SentenceEmbeddingsModel {
             })
             .collect::<Vec<_>>();
 
-        let tokens_masks = tokens_ids
-            .iter()
-            .map(|input| {
-                Tensor::from_slice(
-                    &input
-                        .iter()
-                        .map(|&e| i64::from(e != pad_token_id))
-                        .collect::<Vec<_>>(),
-                )
-            })
-            .collect::<Vec<_>>();
-
         let tokens_ids = tokens_ids
             .into_iter()
             .map(|input| Tensor::from_slice(&(input)))
@@ -346,19 +561,7 @@ impl SentenceEmbeddingsModel {
     where
         S: AsRef<str> + Sync,
     {
-        let SentenceEmbeddingsTokenizerOutput {
-            tokens_ids,
-            tokens_masks,
-        } = self.tokenize(inputs);
-        if tokens_ids.is_empty() {
-            return Err(RustBertError::ValueError(
-                "No n-gram found in the document. \
-                Try allowing smaller n-gram sizes or relax stopword/forbidden characters criteria."
-                    .to_string(),
-            ));
-        }
-        let tokens_ids = Tensor::stack(&tokens_ids, 0).to(self.var_store.device());
-        let tokens_masks = Tensor::stack(&tokens_masks, 0).to(self.var_store.device());
+        let (tokens_ids, tokens_masks) = self.prepare_inputs(inputs)?;
 
         let (tokens_embeddings, all_attentions) =
             tch::no_grad(|| self.transformer.forward(&tokens_ids, &tokens_masks))?;
@@ -370,12 +573,13 @@ impl SentenceEmbeddingsModel {
         } else {
             mean_pool
         };
+        let maybe_linear = if let Some(dimension_reduction) = &self.dimension_reduction {
+            tch::no_grad(|| dimension_reduction.forward(&maybe_linear))
+        } else {
+            maybe_linear
+        };
         let maybe_normalized = if self.normalize_embeddings {
-            let norm = &maybe_linear
-                .norm_scalaropt_dim(2, [1], true)
-                .clamp_min(1e-12)
-                .expand_as(&maybe_linear);
-            maybe_linear / norm
+            l2_normalize(&maybe_linear)
         } else {
             maybe_linear
         };
@@ -395,6 +599,195 @@ impl SentenceEmbeddingsModel {
         Ok(Vec::try_from(embeddings)?)
     }
 
+    /// Computes the representation of the first (CLS) token of the inputs, outputs `Tensor`.
+    ///
+    /// The pooling, dense and normalization modules of the model are not applied: the output is either the raw
+    /// final hidden state of the first token or the transformer pooler output (see `ClsRepresentation`).
+    /// The pooler output is only available for BERT and ALBERT transformers, an `InvalidConfigurationError`
+    /// is returned for other transformers.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sentence_embeddings::{ClsRepresentation, SentenceEmbeddingsBuilder};
+    ///
+    /// let model = SentenceEmbeddingsBuilder::local("local/path/to/bert-base-nli-mean-tokens")
+    ///     .create_model()?;
+    /// let sentences = ["This is an example sentence", "Each sentence is converted"];
+    /// let cls_hidden_states = model.encode_cls_as_tensor(&sentences, ClsRepresentation::HiddenState)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encode_cls_as_tensor<S>(
+        &self,
+        inputs: &[S],
+        representation: ClsRepresentation,
+    ) -> Result<Tensor, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let (tokens_ids, tokens_masks) = self.prepare_inputs(inputs)?;
+        let (hidden_state, pooled_output) = tch::no_grad(|| {
+            self.transformer
+                .forward_with_pooler(&tokens_ids, &tokens_masks)
+        })?;
+        match representation {
+            ClsRepresentation::HiddenState => Ok(hidden_state.select(1, 0)),
+            ClsRepresentation::PoolerOutput => pooled_output.ok_or_else(|| {
+                RustBertError::InvalidConfigurationError(
+                    "The transformer of this model does not have a pooler".to_string(),
+                )
+            }),
+        }
+    }
+
+    /// Computes the representation of the first (CLS) token of the inputs (see `encode_cls_as_tensor`).
+    pub fn encode_cls<S>(
+        &self,
+        inputs: &[S],
+        representation: ClsRepresentation,
+    ) -> Result<Vec<Embedding>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let embeddings = self.encode_cls_as_tensor(inputs, representation)?;
+        Ok(Vec::try_from(embeddings)?)
+    }
+
+    /// Computes the embeddings of the inputs from the hidden states of a transformer layer, outputs `Tensor`
+    /// (e.g. to probe the information captured by each layer).
+    ///
+    /// The layer index ranges from 0 (output of the embeddings layer) to the number of transformer layers (final
+    /// hidden state). The hidden states of the layer are pooled by the pooling layer of the model: the dense and
+    /// normalization modules are not applied. The model must be built with `output_hidden_states` enabled (see
+    /// `SentenceEmbeddingsBuilder::with_hidden_states`), an `InvalidConfigurationError` is returned otherwise. A
+    /// `ValueError` is returned for a layer index greater than the number of transformer layers.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sentence_embeddings::SentenceEmbeddingsBuilder;
+    ///
+    /// let model = SentenceEmbeddingsBuilder::local("local/path/to/bert-base-nli-mean-tokens")
+    ///     .with_hidden_states()
+    ///     .create_model()?;
+    /// let sentences = ["This is an example sentence", "Each sentence is converted"];
+    /// let sixth_layer_embeddings = model.encode_layer_as_tensor(&sentences, 6)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encode_layer_as_tensor<S>(
+        &self,
+        inputs: &[S],
+        layer: usize,
+    ) -> Result<Tensor, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        if !self.output_hidden_states {
+            return Err(RustBertError::InvalidConfigurationError(
+                "The model must be built with `output_hidden_states` enabled to compute the embeddings of a layer"
+                    .to_string(),
+            ));
+        }
+        let num_layers = self.nb_layers();
+        if layer > num_layers {
+            return Err(RustBertError::ValueError(format!(
+                "Layer index {layer} out of range: the transformer has {num_layers} layers (valid indices: 0 to {num_layers})"
+            )));
+        }
+        let (tokens_ids, tokens_masks) = self.prepare_inputs(inputs)?;
+        tch::no_grad(|| {
+            let mut all_hidden_states = self
+                .transformer
+                .forward_all_hidden_states(&tokens_ids, &tokens_masks)?;
+            let hidden_state = all_hidden_states.swap_remove(layer);
+            Ok(self.pooling_layer.forward(hidden_state, &tokens_masks))
+        })
+    }
+
+    /// Computes the embeddings of the inputs from the hidden states of a transformer layer (see
+    /// `encode_layer_as_tensor`).
+    pub fn encode_layer<S>(
+        &self,
+        inputs: &[S],
+        layer: usize,
+    ) -> Result<Vec<Embedding>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let embeddings = self.encode_layer_as_tensor(inputs, layer)?;
+        Ok(Vec::try_from(embeddings)?)
+    }
+
+    /// Computes the pairwise cosine similarity between the embeddings of the inputs, outputs `Tensor`
+    /// (e.g. for clustering or deduplication).
+    ///
+    /// The inputs are encoded in a single batch and the similarities are computed on the model device with one
+    /// matrix product, instead of encoding every pair of inputs. The output is a square `(n, n)` matrix: the
+    /// memory required grows quadratically with the number of inputs (e.g. about 400MB for 10,000 inputs in
+    /// single precision), and all inputs are encoded at once. Large collections should be processed by chunks of
+    /// embeddings computed with `encode_as_tensor`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sentence_embeddings::SentenceEmbeddingsBuilder;
+    ///
+    /// let model = SentenceEmbeddingsBuilder::local("local/path/to/all-MiniLM-L12-v2")
+    ///     .create_model()?;
+    /// let sentences = [
+    ///     "The cat sits on the mat",
+    ///     "A cat is sitting on the mat",
+    ///     "Stock markets fell sharply today",
+    /// ];
+    /// let similarities = model.similarity_matrix_as_tensor(&sentences)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn similarity_matrix_as_tensor<S>(&self, inputs: &[S]) -> Result<Tensor, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let SentenceEmbeddingsModelOutput { embeddings, .. } = self.encode_as_tensor(inputs)?;
+        let embeddings = l2_normalize(&embeddings.to_kind(Kind::Float));
+        Ok(embeddings.matmul(&embeddings.transpose(0, 1)))
+    }
+
+    /// Computes the pairwise cosine similarity between the embeddings of the inputs (see
+    /// `similarity_matrix_as_tensor`).
+    pub fn similarity_matrix<S>(&self, inputs: &[S]) -> Result<Vec<Vec<f32>>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let similarities = self.similarity_matrix_as_tensor(inputs)?;
+        Ok(Vec::try_from(similarities)?)
+    }
+
+    /// Tokenizes the inputs and stacks the token ids and masks, placed on the model device
+    fn prepare_inputs<S>(&self, inputs: &[S]) -> Result<(Tensor, Tensor), RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let SentenceEmbeddingsTokenizerOutput {
+            tokens_ids,
+            tokens_masks,
+        } = self.tokenize(inputs);
+        if tokens_ids.is_empty() {
+            return Err(RustBertError::ValueError(
+                "No n-gram found in the document. \
+                Try allowing smaller n-gram sizes or relax stopword/forbidden characters criteria."
+                    .to_string(),
+            ));
+        }
+        let tokens_ids = Tensor::stack(&tokens_ids, 0).to(self.var_store.device());
+        let tokens_masks = Tensor::stack(&tokens_masks, 0).to(self.var_store.device());
+        Ok((tokens_ids, tokens_masks))
+    }
+
     fn nb_layers(&self) -> usize {
         use SentenceEmbeddingsOption::*;
         match (&self.transformer, &self.transformer_config) {
@@ -402,7 +795,7 @@ impl SentenceEmbeddingsModel {
             (Bert(_), _) => unreachable!(),
             (DistilBert(_), ConfigOption::DistilBert(conf)) => conf.n_layers as usize,
             (DistilBert(_), _) => unreachable!(),
-            (Roberta(_), ConfigOption::Bert(conf)) => conf.num_hidden_layers as usize,
+            (Roberta(_), ConfigOption::Roberta(conf)) => conf.num_hidden_layers as usize,
             (Roberta(_), _) => unreachable!(),
             (Albert(_), ConfigOption::Albert(conf)) => conf.num_hidden_layers as usize,
             (Albert(_), _) => unreachable!(),
@@ -0,0 +1,504 @@
+// Copyright 2019-present, the HuggingFace Inc. team, The Google AI Language Team and Facebook, Inc.
+// Copyright 2019-2020 Guillaume Becquin
+// Copyright 2020 Maarten van Gompel
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Batching of the inputs of the sequence classification pipeline: prepared batches, memory budgets and
+//! accumulation of requests.
+
+use crate::common::error::RustBertError;
+use crate::pipelines::common::pad_tokenized_input;
+use rust_tokenizers::TokenizedInput;
+use std::cmp::max;
+use std::collections::HashMap;
+use std::hash::Hash;
+use tch::Tensor;
+
+use super::{Label, SequenceClassificationModel};
+
+/// # Tokenized input batch for a `SequenceClassificationModel`, placed on the model device
+/// Allows re-using the pre-processing (tokenization and device transfer) across multiple
+/// prediction calls on the same input.
+pub struct PreparedBatch {
+    pub(super) input_ids: Tensor,
+    pub(super) token_type_ids: Tensor,
+    pub(super) attention_mask: Tensor,
+}
+
+impl PreparedBatch {
+    /// Returns the number of inputs in the batch
+    pub fn len(&self) -> usize {
+        self.input_ids.size()[0] as usize
+    }
+
+    /// Returns `true` if the batch contains no input
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the padded token ids of the batch, of shape (*batch size*, *sequence length*)
+    pub fn input_ids(&self) -> &Tensor {
+        &self.input_ids
+    }
+
+    /// Returns the token type ids of the batch, of shape (*batch size*, *sequence length*)
+    pub fn token_type_ids(&self) -> &Tensor {
+        &self.token_type_ids
+    }
+
+    /// Returns the attention mask of the batch, of shape (*batch size*, *sequence length*): 1 for the tokens of the
+    /// inputs and 0 for the padding
+    pub fn attention_mask(&self) -> &Tensor {
+        &self.attention_mask
+    }
+}
+
+/// Factor applied to the embedding size to estimate the memory used per token by the forward pass
+/// (intermediate activations, attention scores and classification head), when no explicit cost is provided.
+const MEMORY_BUDGET_ACTIVATION_FACTOR: u64 = 32;
+
+/// # Memory budget for a forward pass
+/// Instead of a fixed batch size, the inputs are split into batches whose estimated memory usage
+/// (number of sequences x padded sequence length x memory per token) fits within the budget.
+/// Batches of short inputs therefore hold more sequences than batches of long inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBudget {
+    /// Maximum memory (in bytes) to be used by the forward pass of a batch
+    pub bytes: u64,
+    /// Estimated memory (in bytes) used per token. If `None`, estimated from the model embedding size
+    /// (4 bytes per float value, times 32 for the intermediate activations).
+    pub bytes_per_token: Option<u64>,
+}
+
+impl MemoryBudget {
+    /// Creates a memory budget of the given number of bytes
+    pub fn from_bytes(bytes: u64) -> MemoryBudget {
+        MemoryBudget {
+            bytes,
+            bytes_per_token: None,
+        }
+    }
+
+    /// Creates a memory budget of the given number of megabytes (MiB)
+    pub fn from_megabytes(megabytes: u64) -> MemoryBudget {
+        Self::from_bytes(megabytes << 20)
+    }
+
+    /// Creates a memory budget of the given number of gigabytes (GiB)
+    pub fn from_gigabytes(gigabytes: u64) -> MemoryBudget {
+        Self::from_bytes(gigabytes << 30)
+    }
+
+    /// Sets the estimated memory (in bytes) used per token, overriding the estimate derived from the model embedding size
+    pub fn with_bytes_per_token(mut self, bytes_per_token: u64) -> MemoryBudget {
+        self.bytes_per_token = Some(bytes_per_token);
+        self
+    }
+
+    /// Returns the number of leading sequences (of the given lengths) that fit in the budget once padded to the
+    /// longest of them. At least one sequence is always returned for a non-empty input, even if it exceeds the budget.
+    fn batch_size(
+        &self,
+        sequence_lengths: impl Iterator<Item = usize>,
+        bytes_per_token: u64,
+    ) -> usize {
+        let mut max_length = 0;
+        let mut batch_size = 0;
+        for sequence_length in sequence_lengths {
+            let padded_length = max(max_length, sequence_length.max(1));
+            let batch_bytes = (batch_size as u64 + 1)
+                .saturating_mul(padded_length as u64)
+                .saturating_mul(bytes_per_token);
+            if batch_size > 0 && batch_bytes > self.bytes {
+                break;
+            }
+            max_length = padded_length;
+            batch_size += 1;
+        }
+        batch_size
+    }
+}
+
+/// # Accumulator of classification requests for micro-batching servers
+/// Collects texts identified by a request id (e.g. over a short time window) and classifies them in a single batch
+/// when flushed, returning the labels keyed by request id.
+pub struct BatchAccumulator<'a, K> {
+    model: &'a SequenceClassificationModel,
+    requests: Vec<(K, String)>,
+}
+
+impl<'a, K> BatchAccumulator<'a, K>
+where
+    K: Eq + Hash,
+{
+    /// Build a new `BatchAccumulator` classifying the accumulated texts with the provided model
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - `&SequenceClassificationModel` model to classify the texts with
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::{
+    ///     BatchAccumulator, SequenceClassificationModel,
+    /// };
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let mut accumulator = BatchAccumulator::new(&sequence_classification_model);
+    /// accumulator.push(17u64, "This is a great movie.");
+    /// accumulator.push(18u64, "What a waste of time.");
+    /// let labels = accumulator.flush();
+    /// println!("Request 17: {}", labels[&17u64].text);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(model: &'a SequenceClassificationModel) -> BatchAccumulator<'a, K> {
+        BatchAccumulator {
+            model,
+            requests: vec![],
+        }
+    }
+
+    /// Adds a text to classify at the next flush
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - Identifier of the request the text belongs to. If several texts are added with the same
+    ///   identifier before a flush, the label of the last one is returned.
+    /// * `text` - Text to classify
+    pub fn push(&mut self, request_id: K, text: impl Into<String>) {
+        self.requests.push((request_id, text.into()));
+    }
+
+    /// Returns the number of texts accumulated since the last flush
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Returns `true` if no text was accumulated since the last flush
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Classifies the accumulated texts in a single `predict` call and clears the accumulator. Flushing an empty
+    /// accumulator returns an empty map without running the model.
+    ///
+    /// # Returns
+    ///
+    /// * `HashMap<K, Label>` label of each request, keyed by request id. The `sentence` field of the labels is the
+    ///   position of the text in the flushed batch.
+    pub fn flush(&mut self) -> HashMap<K, Label> {
+        if self.requests.is_empty() {
+            return HashMap::new();
+        }
+        let requests = std::mem::take(&mut self.requests);
+        let texts = requests
+            .iter()
+            .map(|(_, text)| text.as_str())
+            .collect::<Vec<&str>>();
+        let labels = self.model.predict(&texts);
+        requests
+            .into_iter()
+            .map(|(request_id, _)| request_id)
+            .zip(labels)
+            .collect()
+    }
+}
+
+impl SequenceClassificationModel {
+    /// Checks that the weights and the projected activations of the largest batch fit within the device memory limit
+    pub(super) fn check_device_memory_limit(
+        &self,
+        device_memory_limit: u64,
+    ) -> Result<(), RustBertError> {
+        let var_store = self.var_store.as_ref().ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(
+                "Device memory limit only supported for Torch models".to_string(),
+            )
+        })?;
+        let weights_bytes: u64 = var_store
+            .variables()
+            .values()
+            .map(|tensor| tensor.numel() as u64 * tensor.kind().elt_size_in_bytes() as u64)
+            .sum();
+        let activations_bytes = match &self.memory_budget {
+            Some(memory_budget) => memory_budget.bytes,
+            None if self.max_length < usize::MAX => {
+                (self.max_length as u64).saturating_mul(self.bytes_per_token())
+            }
+            None => {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "A memory budget is required to apply a device memory limit to models without maximum input length"
+                        .to_string(),
+                ));
+            }
+        };
+        let required_bytes = weights_bytes.saturating_add(activations_bytes);
+        if required_bytes > device_memory_limit {
+            return Err(RustBertError::OutOfMemoryError(format!(
+                "The model requires an estimated {required_bytes} bytes ({weights_bytes} bytes of weights and {activations_bytes} bytes of activations), exceeding the device memory limit of {device_memory_limit} bytes"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Estimated memory (in bytes) used per token by the forward pass
+    fn bytes_per_token(&self) -> u64 {
+        self.memory_budget
+            .and_then(|memory_budget| memory_budget.bytes_per_token)
+            .unwrap_or_else(|| {
+                self.embedding_size.unwrap_or(768) as u64 * 4 * MEMORY_BUDGET_ACTIVATION_FACTOR
+            })
+    }
+
+    /// Splits tokenized inputs into successive batches fitting within the memory budget (a single batch without
+    /// budget), each padded to its own longest input. All the entry points classifying texts go through these batches.
+    fn prepare_budgeted_batches(
+        &self,
+        mut tokenized_input: Vec<TokenizedInput>,
+    ) -> Vec<PreparedBatch> {
+        let memory_budget = match &self.memory_budget {
+            Some(memory_budget) => memory_budget,
+            None => return vec![self.pad_tokenized_input(tokenized_input)],
+        };
+        let bytes_per_token = self.bytes_per_token();
+        let mut batches = Vec::new();
+        while !tokenized_input.is_empty() {
+            let batch_size = memory_budget.batch_size(
+                tokenized_input.iter().map(|input| input.token_ids.len()),
+                bytes_per_token,
+            );
+            let remaining_input = tokenized_input.split_off(batch_size);
+            batches.push(self.pad_tokenized_input(tokenized_input));
+            tokenized_input = remaining_input;
+        }
+        batches
+    }
+
+    /// Returns the logits of tokenized inputs, classified in batches fitting within the memory budget
+    pub(super) fn forward_within_budget(&self, tokenized_input: Vec<TokenizedInput>) -> Tensor {
+        let logits = self
+            .prepare_budgeted_batches(tokenized_input)
+            .iter()
+            .map(|batch| self.forward_logits(batch))
+            .collect::<Vec<Tensor>>();
+        Tensor::cat(&logits, 0)
+    }
+
+    /// Returns the logits of texts, classified in batches fitting within the memory budget
+    pub(super) fn logits_within_budget<'a, S>(&self, input: S) -> Tensor
+    where
+        S: AsRef<[&'a str]>,
+    {
+        self.forward_within_budget(self.encode_inputs(input.as_ref()))
+    }
+
+    /// Returns the label probabilities of texts, classified in batches fitting within the memory budget
+    pub(super) fn probabilities_within_budget<'a, S>(&self, input: S) -> Tensor
+    where
+        S: AsRef<[&'a str]>,
+    {
+        self.calibrate(self.logits_within_budget(input))
+            .softmax(-1, self.score_kind())
+    }
+
+    /// Fallible forward pass of tokenized inputs, returning their logits. The inputs are classified in batches fitting
+    /// within the memory budget.
+    pub(super) fn try_forward_within_budget(
+        &self,
+        tokenized_input: Vec<TokenizedInput>,
+    ) -> Result<Tensor, RustBertError> {
+        let logits = self
+            .prepare_budgeted_batches(tokenized_input)
+            .iter()
+            .map(|batch| self.try_forward_logits(batch))
+            .collect::<Result<Vec<Tensor>, RustBertError>>()?;
+        Ok(Tensor::cat(&logits, 0))
+    }
+
+    /// Tokenize and pad texts, and move the resulting tensors to the model device
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to prepare.
+    ///
+    /// # Returns
+    ///
+    /// * `PreparedBatch` holding the model inputs, that can be passed (repeatedly) to `predict_prepared`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input = [
+    ///     "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+    ///     "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+    /// ];
+    /// let prepared_batch = sequence_classification_model.prepare_batch(&input);
+    /// let output = sequence_classification_model.predict_prepared(&prepared_batch);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn prepare_batch<'a, S>(&self, input: S) -> PreparedBatch
+    where
+        S: AsRef<[&'a str]>,
+    {
+        self.pad_tokenized_input(self.encode_inputs(input.as_ref()))
+    }
+
+    /// Tokenize and pad sentence pairs, and move the resulting tensors to the model device.
+    /// The token type ids distinguish the first and second sentence of each pair, as expected by cross-encoders.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[(&str, &str)]` Array of sentence pairs to prepare.
+    ///
+    /// # Returns
+    ///
+    /// * `PreparedBatch` holding the model inputs, that can be passed (repeatedly) to `predict_prepared`, or an
+    ///   `InvalidConfigurationError` if the model was built with a custom tokenizer not providing a `TokenizerOption`
+    pub fn prepare_pair_batch(
+        &self,
+        input: &[(&str, &str)],
+    ) -> Result<PreparedBatch, RustBertError> {
+        Ok(self.pad_tokenized_input(self.encode_pairs(input)?))
+    }
+
+    /// Tokenize and pad inputs made of several segments (e.g. question, context and answer candidate for models
+    /// trained with more than two token types), and move the resulting tensors to the model device. The segments are
+    /// joined with the BERT layout (`[CLS] A [SEP] B [SEP] C [SEP]`), each segment being identified by its position
+    /// in the input as token type id.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&[&str]]` Array of inputs to prepare, each given as its list of segments.
+    ///
+    /// # Returns
+    ///
+    /// * `PreparedBatch` holding the model inputs, that can be passed (repeatedly) to `predict_prepared`, or a
+    ///   `ValueError` if an input contains more segments than the token types supported by the model
+    ///   (`type_vocab_size`). Models without token type ids only accept a single segment. Returns an
+    ///   `InvalidConfigurationError` if the model was built with a custom tokenizer not providing a `TokenizerOption`.
+    pub fn prepare_segments_batch(
+        &self,
+        input: &[&[&str]],
+    ) -> Result<PreparedBatch, RustBertError> {
+        let max_segments = self.type_vocab_size.unwrap_or(1).max(1) as usize;
+        let tokenizer = self.native_tokenizer()?;
+        let tokenized_input = input
+            .iter()
+            .map(|segments| {
+                if segments.len() > max_segments {
+                    return Err(RustBertError::ValueError(format!(
+                        "Input with {} segments exceeds the {max_segments} token types supported by the model",
+                        segments.len()
+                    )));
+                }
+                let segments = segments
+                    .iter()
+                    .map(|segment| self.preprocess(segment))
+                    .collect::<Vec<_>>();
+                let segments = segments
+                    .iter()
+                    .map(|segment| segment.as_ref())
+                    .collect::<Vec<&str>>();
+                tokenizer.encode_segments(&segments, self.max_length)
+            })
+            .collect::<Result<Vec<TokenizedInput>, RustBertError>>()?;
+        Ok(self.pad_tokenized_input(tokenized_input))
+    }
+
+    /// Returns the raw classification head output (before softmax) for a batch of inputs prepared with
+    /// `prepare_batch` or `prepare_pair_batch`. This is the relevant output for regression heads
+    /// (e.g. a single similarity score). For models applying the softmax in their graph (`apply_softmax` set to
+    /// `false`), the output holds the probabilities computed by the model.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - `&PreparedBatch` tokenized inputs already placed on the model device.
+    ///
+    /// # Returns
+    ///
+    /// * `Tensor` of shape (*batch size*, *number of labels*) containing the logits
+    pub fn predict_prepared_logits(&self, batch: &PreparedBatch) -> Tensor {
+        self.forward_logits(batch)
+    }
+
+    /// Classify a batch of inputs prepared with `prepare_batch`
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - `&PreparedBatch` tokenized inputs already placed on the model device.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Label>` containing labels for input texts
+    pub fn predict_prepared(&self, batch: &PreparedBatch) -> Vec<Label> {
+        let output = self.forward_probabilities(batch);
+        self.get_top_labels(&output)
+    }
+
+    /// Classify a batch of inputs prepared with `prepare_batch`, writing the labels to a caller-provided buffer
+    ///
+    /// The buffer is cleared at the start of the call. Combined with a re-used `PreparedBatch`, this avoids
+    /// the allocations of the pre-processing and of the output in a serving loop.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch` - `&PreparedBatch` tokenized inputs already placed on the model device.
+    /// * `out` - `&mut Vec<Label>` buffer receiving the labels for the batch inputs.
+    pub fn predict_prepared_into(&self, batch: &PreparedBatch, out: &mut Vec<Label>) {
+        out.clear();
+        let output = self.forward_probabilities(batch);
+        self.get_top_labels_into(&output, out);
+    }
+
+    /// Pads the inputs and moves them to the model device. The attention mask is derived from the input lengths, so
+    /// that the padding (including the extra padding of `pad_to_multiple_of`) is ignored by the model.
+    pub(super) fn pad_tokenized_input(
+        &self,
+        tokenized_input: Vec<TokenizedInput>,
+    ) -> PreparedBatch {
+        let pad_id = self
+            .tokenizer
+            .get_pad_id()
+            .expect("The Tokenizer used for sequence classification should contain a PAD id");
+        let input_lengths = tokenized_input
+            .iter()
+            .map(|input| input.token_ids.len())
+            .collect::<Vec<usize>>();
+        let (input_ids, token_type_ids) = pad_tokenized_input(
+            tokenized_input,
+            pad_id,
+            self.device,
+            self.pad_to_multiple_of,
+        );
+        let max_len = input_ids.size()[1] as usize;
+        let attention_mask = input_lengths
+            .into_iter()
+            .map(|input_length| {
+                let mut mask = vec![1i64; input_length];
+                mask.resize(max_len, 0);
+                Tensor::from_slice(&mask)
+            })
+            .collect::<Vec<_>>();
+        let attention_mask = Tensor::stack(attention_mask.as_slice(), 0).to(self.device);
+        PreparedBatch {
+            input_ids,
+            token_type_ids,
+            attention_mask,
+        }
+    }
+}
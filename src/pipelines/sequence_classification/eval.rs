@@ -0,0 +1,644 @@
+// Copyright 2019-present, the HuggingFace Inc. team, The Google AI Language Team and Facebook, Inc.
+// Copyright 2019-2020 Guillaume Becquin
+// Copyright 2020 Maarten van Gompel
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Evaluation of the sequence classification pipeline: benchmarks, calibration, label ranks and comparison of models.
+
+use crate::common::error::RustBertError;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tch::{Device, Kind, Tensor};
+
+use super::{Label, SequenceClassificationModel};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+/// # Approximate cost of an input classified in a batch by a `SequenceClassificationModel`
+/// The cost of the batch is attributed to its inputs in proportion to their number of tokens
+/// (see `SequenceClassificationModel::predict_with_stats`).
+pub struct ExampleStats {
+    /// Sentence index
+    pub sentence: usize,
+    /// Number of tokens (including special tokens) passed to the model
+    pub token_count: usize,
+    /// Share of the batch compute attributed to the input (between 0 and 1, summing to 1 over the batch)
+    pub compute_share: f64,
+    /// Estimated latency of the input: the batch latency (tokenization and forward pass) weighted by the compute share
+    pub latency: Duration,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+/// # Throughput measured by `SequenceClassificationModel::benchmark`
+pub struct BenchmarkReport {
+    /// Number of timed runs
+    pub iterations: usize,
+    /// Number of sample inputs classified at each run
+    pub batch_size: usize,
+    /// Mean latency of a run
+    pub mean_latency: Duration,
+    /// Median latency of a run
+    pub p50_latency: Duration,
+    /// 95th percentile latency of a run
+    pub p95_latency: Duration,
+    /// Number of inputs classified per second over the timed runs
+    pub sequences_per_second: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+/// # Confidence bin of a `CalibrationReport`
+pub struct CalibrationBin {
+    /// Lower bound of the confidence scores of the bin (inclusive)
+    pub lower: f64,
+    /// Upper bound of the confidence scores of the bin (exclusive, inclusive for the last bin)
+    pub upper: f64,
+    /// Number of predictions in the bin
+    pub count: usize,
+    /// Share of the predictions of the bin matching the true label (0 for empty bins)
+    pub accuracy: f64,
+    /// Mean confidence score of the predictions of the bin (0 for empty bins)
+    pub mean_confidence: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+/// # Calibration of the confidence scores on a labelled set (see `SequenceClassificationModel::calibration_report`)
+/// A model is well calibrated if the accuracy of the predictions of each confidence bin matches their mean confidence.
+pub struct CalibrationReport {
+    /// Confidence bins of equal width, covering the scores from 0 to 1
+    pub bins: Vec<CalibrationBin>,
+    /// Expected calibration error: mean absolute difference between the accuracy and mean confidence of the bins,
+    /// weighted by the share of the predictions in each bin
+    pub expected_calibration_error: f64,
+    /// Overall accuracy of the predictions
+    pub accuracy: f64,
+}
+
+/// Number of untimed runs preceding the measurements of `SequenceClassificationModel::benchmark`
+const BENCHMARK_WARMUP_ITERATIONS: usize = 3;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+/// # Difference between the predictions of two models for an input (see `SequenceClassificationModel::compare`)
+pub struct PredictionDiff {
+    /// Sentence index
+    pub sentence: usize,
+    /// Label predicted by the reference model
+    pub label: Label,
+    /// Label predicted by the compared model
+    pub other_label: Label,
+    /// Flag indicating if the compared model predicts a different label
+    pub label_changed: bool,
+    /// Score of the reference label in the compared model minus its score in the reference model
+    pub score_delta: f64,
+    /// Largest absolute difference between the scores of the two models, over all labels
+    pub max_score_delta: f64,
+}
+
+impl SequenceClassificationModel {
+    /// Classify texts, returning for each input its label and the approximate share of the batch cost it incurred
+    /// (e.g. for billing or rate-limiting in multi-tenant serving). The batch latency is measured from tokenization
+    /// to the retrieval of the labels, and attributed to the inputs in proportion to their number of tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(Label, ExampleStats)>` containing the label and cost statistics of each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["A short review.", "A much longer review, that costs more to classify."];
+    /// for (label, stats) in sequence_classification_model.predict_with_stats(&input) {
+    ///     println!(
+    ///         "{}: {} tokens, {:.1}% of the batch, {:?}",
+    ///         label.text,
+    ///         stats.token_count,
+    ///         stats.compute_share * 100.0,
+    ///         stats.latency
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_stats<'a, S>(&self, input: S) -> Vec<(Label, ExampleStats)>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let start = Instant::now();
+        let tokenized_input = self.encode_inputs(input.as_ref());
+        let token_counts = tokenized_input
+            .iter()
+            .map(|tokenized| tokenized.token_ids.len())
+            .collect::<Vec<usize>>();
+        let labels = self.predict_tokenized(tokenized_input);
+        let batch_latency = start.elapsed();
+
+        let total_tokens = token_counts.iter().sum::<usize>().max(1) as f64;
+        labels
+            .into_iter()
+            .zip(token_counts)
+            .enumerate()
+            .map(|(sentence, (label, token_count))| {
+                let compute_share = token_count as f64 / total_tokens;
+                let stats = ExampleStats {
+                    sentence,
+                    token_count,
+                    compute_share,
+                    latency: batch_latency.mul_f64(compute_share),
+                };
+                (label, stats)
+            })
+            .collect()
+    }
+
+    /// Measures the throughput of the model on sample inputs, e.g. to compare configurations (batch size, device,
+    /// precision). The samples are classified with `predict` in `iterations` timed runs, after a few untimed warmup runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `sample_inputs` - `&[&str]` Texts classified (as a single call to `predict`) at each run.
+    /// * `iterations` - `usize` Number of timed runs.
+    ///
+    /// # Returns
+    ///
+    /// * `BenchmarkReport` containing the latency statistics of the runs and the throughput in sequences per second
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let sample_inputs = ["A short review.", "A much longer review, that costs more to classify."];
+    /// let report = sequence_classification_model.benchmark(&sample_inputs, 20);
+    /// println!(
+    ///     "p50: {:?}, p95: {:?}, {:.1} sequences/s",
+    ///     report.p50_latency, report.p95_latency, report.sequences_per_second
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn benchmark(&self, sample_inputs: &[&str], iterations: usize) -> BenchmarkReport {
+        for _ in 0..BENCHMARK_WARMUP_ITERATIONS {
+            self.predict(sample_inputs);
+        }
+        let mut latencies = (0..iterations)
+            .map(|_| {
+                let start = Instant::now();
+                self.predict(sample_inputs);
+                start.elapsed()
+            })
+            .collect::<Vec<Duration>>();
+        latencies.sort();
+        let total_latency: Duration = latencies.iter().sum();
+        let percentile = |percentile: f64| {
+            latencies
+                .get(((latencies.len() as f64 * percentile).ceil() as usize).saturating_sub(1))
+                .copied()
+                .unwrap_or_default()
+        };
+        BenchmarkReport {
+            iterations,
+            batch_size: sample_inputs.len(),
+            mean_latency: total_latency
+                .checked_div(iterations as u32)
+                .unwrap_or_default(),
+            p50_latency: percentile(0.5),
+            p95_latency: percentile(0.95),
+            sequences_per_second: if total_latency.is_zero() {
+                0.0
+            } else {
+                (iterations * sample_inputs.len()) as f64 / total_latency.as_secs_f64()
+            },
+        }
+    }
+
+    /// Assess the calibration of the confidence scores on a labelled validation set: the predictions are grouped in
+    /// bins of equal confidence width, and the accuracy of each bin is compared to its mean confidence. The
+    /// temperature of the configuration is applied, so that the report can be used to validate a fitted temperature.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs` - `&[&str]` Array of texts to classify.
+    /// * `true_labels` - `&[i64]` Id of the true label of each input.
+    /// * `bins` - Number of confidence bins.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<CalibrationReport, RustBertError>` with the accuracy and confidence of each bin and the expected
+    ///   calibration error, or a `ValueError` if the number of true labels differs from the number of inputs or if
+    ///   `bins` is 0
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let inputs = ["This is a great movie.", "What a waste of time.", "An average film."];
+    /// let true_labels = [1, 0, 0];
+    /// let report = sequence_classification_model.calibration_report(&inputs, &true_labels, 10)?;
+    /// println!("ECE: {:.3}", report.expected_calibration_error);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn calibration_report(
+        &self,
+        inputs: &[&str],
+        true_labels: &[i64],
+        bins: usize,
+    ) -> Result<CalibrationReport, RustBertError> {
+        if inputs.len() != true_labels.len() {
+            return Err(RustBertError::ValueError(format!(
+                "One true label must be provided for each input: got {} labels for {} inputs",
+                true_labels.len(),
+                inputs.len()
+            )));
+        }
+        if bins == 0 {
+            return Err(RustBertError::ValueError(
+                "At least one bin is required".to_string(),
+            ));
+        }
+
+        let mut bin_counts = vec![0usize; bins];
+        let mut bin_correct = vec![0usize; bins];
+        let mut bin_confidences = vec![0f64; bins];
+        for (label, true_label) in self.try_predict(inputs)?.iter().zip(true_labels) {
+            let bin = ((label.score * bins as f64) as usize).min(bins - 1);
+            bin_counts[bin] += 1;
+            bin_confidences[bin] += label.score;
+            if label.id == *true_label {
+                bin_correct[bin] += 1;
+            }
+        }
+
+        let num_predictions = inputs.len().max(1) as f64;
+        let mut expected_calibration_error = 0.0;
+        let calibration_bins = (0..bins)
+            .map(|bin| {
+                let count = bin_counts[bin];
+                let (accuracy, mean_confidence) = if count > 0 {
+                    (
+                        bin_correct[bin] as f64 / count as f64,
+                        bin_confidences[bin] / count as f64,
+                    )
+                } else {
+                    (0.0, 0.0)
+                };
+                expected_calibration_error +=
+                    count as f64 / num_predictions * (accuracy - mean_confidence).abs();
+                CalibrationBin {
+                    lower: bin as f64 / bins as f64,
+                    upper: (bin + 1) as f64 / bins as f64,
+                    count,
+                    accuracy,
+                    mean_confidence,
+                }
+            })
+            .collect();
+        Ok(CalibrationReport {
+            bins: calibration_bins,
+            expected_calibration_error,
+            accuracy: bin_correct.iter().sum::<usize>() as f64 / num_predictions,
+        })
+    }
+
+    /// Checks that the model is loaded and functional
+    ///
+    /// Runs the classification of a fixed, short input and verifies the shape of the output probabilities
+    /// and that the predicted labels map to the label dictionary of the model. Intended as a readiness or
+    /// liveness check: an error indicates a failed or corrupted model load. The forward pass runs through the fallible
+    /// path of `try_predict`, returning its errors.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), RustBertError>` - `Ok(())` if the model produced a valid output
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// sequence_classification_model.self_test()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn self_test(&self) -> Result<(), RustBertError> {
+        let input = ["This is a test.", "Another test input."];
+        let logits = self.try_forward_logits(&self.prepare_batch(input))?;
+        let output = self
+            .calibrate(logits)
+            .softmax(-1, self.score_kind())
+            .to(Device::Cpu);
+
+        let expected_size = vec![input.len() as i64, self.label_mapping.len() as i64];
+        if output.size() != expected_size {
+            return Err(RustBertError::TensorShapeError(format!(
+                "Self-test output has shape {:?}, expected {expected_size:?}",
+                output.size()
+            )));
+        }
+        for position in output.argmax(-1, false).iter::<i64>()? {
+            if position as usize >= self.output_label_ids.len() {
+                return Err(RustBertError::ValueError(format!(
+                    "Self-test predicted the output {position}, missing from the label mapping"
+                )));
+            }
+        }
+        let labels = self.get_top_labels(&output);
+        if labels.len() != input.len() {
+            return Err(RustBertError::ValueError(format!(
+                "Self-test returned {} labels for {} inputs",
+                labels.len(),
+                input.len()
+            )));
+        }
+        for (position, label) in labels.iter().enumerate() {
+            if label.sentence != position
+                || self.label_mapping.get(&label.id) != Some(&label.text)
+                || !(0.0..=1.0).contains(&label.score)
+            {
+                return Err(RustBertError::ValueError(format!(
+                    "Self-test returned an invalid label for input {position}: {label:?}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compares the predictions of the model with the ones of another model on the same texts, e.g. to check
+    /// that a new checkpoint does not regress on a golden set. Both models must share the same label mapping.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - `&SequenceClassificationModel` model to compare to (e.g. the new checkpoint).
+    /// * `input` - `&[&str]` Array of texts to classify with both models.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<PredictionDiff>, RustBertError>` labels predicted by both models and score differences for each input,
+    ///   or a `ConfigurationMismatchError` if the models have different label mappings
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// # let new_config = Default::default();
+    /// let reference_model = SequenceClassificationModel::new(Default::default())?;
+    /// let new_model = SequenceClassificationModel::new(new_config)?;
+    /// let golden_set = ["This is a great movie.", "This movie was a waste of time."];
+    /// let regressions = reference_model
+    ///     .compare(&new_model, &golden_set)?
+    ///     .into_iter()
+    ///     .filter(|diff| diff.label_changed)
+    ///     .collect::<Vec<_>>();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compare<'a, S>(
+        &self,
+        other: &SequenceClassificationModel,
+        input: S,
+    ) -> Result<Vec<PredictionDiff>, RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        if self.label_mapping != other.label_mapping {
+            return Err(RustBertError::ConfigurationMismatchError(
+                "The compared models must share the same label mapping".to_string(),
+            ));
+        }
+        let input = input.as_ref();
+        let probabilities = self.probabilities_within_budget(input);
+        let other_probabilities = other.probabilities_within_budget(input);
+        let labels = self.get_top_labels(&probabilities);
+        let other_labels = other.get_top_labels(&other_probabilities);
+        let scores = self.scores_to_maps(probabilities);
+        let other_scores = other.scores_to_maps(other_probabilities);
+
+        Ok(labels
+            .into_iter()
+            .zip(other_labels)
+            .zip(scores.iter().zip(other_scores.iter()))
+            .map(|((label, other_label), (scores, other_scores))| {
+                let max_score_delta = scores
+                    .iter()
+                    .map(|(text, score)| (other_scores[text] - score).abs())
+                    .fold(0.0, f64::max);
+                PredictionDiff {
+                    sentence: label.sentence,
+                    label_changed: label.id != other_label.id,
+                    score_delta: other_scores[&label.text] - label.score,
+                    max_score_delta,
+                    label,
+                    other_label,
+                }
+            })
+            .collect())
+    }
+
+    /// Classify texts and return the Shannon entropy of the predicted class distribution
+    ///
+    /// The entropy (in nats) measures the uncertainty of the prediction: it is 0 for a fully confident
+    /// prediction and reaches `ln(num_labels)` for a uniform distribution over the classes.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(Label, f64)>` containing the label and the entropy of the softmax distribution for each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input = [
+    ///     "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+    ///     "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+    /// ];
+    /// let output = sequence_classification_model.predict_with_uncertainty(&input);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_uncertainty<'a, S>(&self, input: S) -> Vec<(Label, f64)>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let output = self.probabilities_within_budget(input);
+        let entropies = -(&output * output.clamp_min(f64::from(f32::MIN_POSITIVE)).log())
+            .sum_dim_intlist([-1].as_slice(), false, Kind::Double);
+        let entropies = entropies.to(Device::Cpu).iter::<f64>().unwrap();
+        self.get_top_labels(&output)
+            .into_iter()
+            .zip(entropies)
+            .collect()
+    }
+
+    /// Returns an out-of-distribution score for each text, the energy-based score (negative free energy)
+    /// `T * logsumexp(logits / T)` where `T` is the configured temperature. A low score indicates that the input is
+    /// far from the training distribution of the model. The energy score is more reliable than the maximum softmax
+    /// probability, which tends to remain high for inputs unlike the training data.
+    ///
+    /// The scores are not normalized and their range depends on the model: the threshold below which predictions are
+    /// rejected should be selected on held-out data, e.g. as the score keeping 95% of in-distribution validation
+    /// inputs (the 5th percentile of their scores), then checked against a sample of out-of-distribution inputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to score.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<f64>` out-of-distribution score of each input text (higher is more in-distribution)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["This is a great movie.", "SELECT * FROM users WHERE id = 1;"];
+    /// // Threshold selected on validation data
+    /// let threshold = 4.5;
+    /// let scores = sequence_classification_model.ood_score(&input);
+    /// let in_distribution = scores.iter().map(|score| *score >= threshold).collect::<Vec<bool>>();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ood_score(&self, input: &[&str]) -> Vec<f64> {
+        if input.is_empty() {
+            return vec![];
+        }
+        let logits = self.calibrate(self.logits_within_budget(input));
+        (logits
+            .to_kind(Kind::Double)
+            .logsumexp([-1].as_slice(), false)
+            * self.temperature)
+            .to(Device::Cpu)
+            .iter::<f64>()
+            .unwrap()
+            .collect()
+    }
+
+    /// Returns the rank of the true label of a text among the labels sorted by decreasing score (0 if the true label is
+    /// the top prediction), for the computation of evaluation metrics such as the mean reciprocal rank or the top-k
+    /// accuracy. Labels with the same score as the true label are not counted as ranked above it.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&str` text to classify.
+    /// * `true_id` - `i64` id of the true label of the text.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<usize, RustBertError>` number of labels scored higher than the true label, or a `ValueError` if
+    ///   `true_id` is not a label id of the model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let rank = sequence_classification_model.true_label_rank("This is a great movie.", 1)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn true_label_rank(&self, input: &str, true_id: i64) -> Result<usize, RustBertError> {
+        Ok(self.true_label_ranks(&[input], &[true_id])?.remove(0))
+    }
+
+    /// Returns the rank of the true label of each text among the labels sorted by decreasing score (0 if the true label
+    /// is the top prediction). All texts are classified in a single batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `true_ids` - `&[i64]` id of the true label of each text, in the same order as `input`.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<usize>, RustBertError>` number of labels scored higher than the true label for each input text,
+    ///   or a `ValueError` if the number of true label ids differs from the number of texts or if a true label id is
+    ///   not a label id of the model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["This is a great movie.", "What a waste of time."];
+    /// let ranks = sequence_classification_model.true_label_ranks(&input, &[1, 0])?;
+    /// let mean_reciprocal_rank =
+    ///     ranks.iter().map(|rank| 1.0 / (*rank + 1) as f64).sum::<f64>() / ranks.len() as f64;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn true_label_ranks(
+        &self,
+        input: &[&str],
+        true_ids: &[i64],
+    ) -> Result<Vec<usize>, RustBertError> {
+        if input.len() != true_ids.len() {
+            return Err(RustBertError::ValueError(format!(
+                "One true label id must be provided for each input: got {} ids for {} inputs",
+                true_ids.len(),
+                input.len()
+            )));
+        }
+        let true_positions = true_ids
+            .iter()
+            .map(|label_id| {
+                self.output_position(*label_id).ok_or_else(|| {
+                    RustBertError::ValueError(format!(
+                        "True label id {label_id} is not a label of the model"
+                    ))
+                })
+            })
+            .collect::<Result<Vec<i64>, RustBertError>>()?;
+        if input.is_empty() {
+            return Ok(vec![]);
+        }
+        let probabilities = self.probabilities_within_budget(input);
+        let true_positions = Tensor::from_slice(&true_positions)
+            .unsqueeze(-1)
+            .to(probabilities.device());
+        let true_scores = probabilities.gather(-1, &true_positions, false);
+        Ok(probabilities
+            .gt_tensor(&true_scores)
+            .sum_dim_intlist([-1].as_slice(), false, Kind::Int64)
+            .to(Device::Cpu)
+            .iter::<i64>()
+            .unwrap()
+            .map(|rank| rank as usize)
+            .collect())
+    }
+}
@@ -0,0 +1,484 @@
+// Copyright 2019-present, the HuggingFace Inc. team, The Google AI Language Team and Facebook, Inc.
+// Copyright 2019-2020 Guillaume Becquin
+// Copyright 2020 Maarten van Gompel
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Explanation of the predictions of the sequence classification pipeline: occlusion, attention rollout and
+//! masked inputs.
+
+use crate::common::error::RustBertError;
+use serde::{Deserialize, Serialize};
+use tch::{no_grad, Device, Kind, Tensor};
+
+use super::{
+    aggregate_probabilities, split_sentences, Aggregation, Label, SequenceClassificationModel,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+/// # Sentence contributing the most to the label of a multi-sentence input
+/// (see `SequenceClassificationModel::predict_with_top_sentence`)
+pub struct SentenceContribution {
+    /// Index of the input text the sentence belongs to
+    pub sentence: usize,
+    /// Index of the sentence within the input text
+    pub index: usize,
+    /// Sentence text
+    pub text: String,
+    /// Byte offsets (start, end) of the sentence in the input text
+    pub byte_offsets: (usize, usize),
+    /// Confidence score of the aggregate label when classifying the sentence alone
+    pub score: f64,
+}
+
+impl SequenceClassificationModel {
+    /// Classify texts, returning each label with the tokens the model actually saw (after truncation and insertion
+    /// of the special tokens). Useful to diagnose misclassifications caused by the tokenization of an input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(Label, Vec<String>)>` containing the label and tokens of each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["This movie was unwatchable."];
+    /// for (label, tokens) in sequence_classification_model.predict_verbose(&input) {
+    ///     println!("{}: {:?}", label.text, tokens);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_verbose(&self, input: &[&str]) -> Vec<(Label, Vec<String>)> {
+        let tokenized_input = self.encode_inputs(input);
+        let tokens = tokenized_input
+            .iter()
+            .map(|tokenized| self.tokenizer.convert_ids_to_tokens(&tokenized.token_ids))
+            .collect::<Vec<Vec<String>>>();
+
+        let labels = self.predict_tokenized(tokenized_input);
+        labels.into_iter().zip(tokens).collect()
+    }
+
+    /// Classify texts ignoring some character spans of each input (e.g. boilerplate headers and footers of retrieved
+    /// documents). The spans are mapped to token positions with the offsets of the tokens (see `encode_with_offsets`),
+    /// and the attention mask of these tokens is set to 0. The tokens remain in the input, keeping the positions of
+    /// the other tokens unchanged.
+    ///
+    /// A token partially overlapping an ignored span (e.g. a word piece straddling the span boundary) is ignored:
+    /// spans should therefore start and end at word boundaries to keep the surrounding words. Special tokens are never
+    /// ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `ignored_spans` - `&[&[(usize, usize)]]` (start, end) character offsets of the spans to ignore, for each
+    ///   input text. The end offset is exclusive, and offsets refer to the preprocessed text if a preprocessing
+    ///   callback or a normalization is configured.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Label>, RustBertError>` containing labels for the inputs, or a `ValueError` if the number of
+    ///   spans lists differs from the number of inputs
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["Sponsored content. I really enjoyed this movie."];
+    /// let output = sequence_classification_model.predict_with_ignored_spans(&input, &[&[(0, 18)]])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_ignored_spans(
+        &self,
+        input: &[&str],
+        ignored_spans: &[&[(usize, usize)]],
+    ) -> Result<Vec<Label>, RustBertError> {
+        if input.len() != ignored_spans.len() {
+            return Err(RustBertError::ValueError(format!(
+                "Got {} inputs but {} lists of ignored spans",
+                input.len(),
+                ignored_spans.len()
+            )));
+        }
+        let (token_ids, masks): (Vec<Vec<i64>>, Vec<Vec<i64>>) = self
+            .encode_inputs(input)
+            .into_iter()
+            .zip(ignored_spans)
+            .map(|(tokenized_input, spans)| {
+                let mask = tokenized_input
+                    .token_offsets
+                    .iter()
+                    .map(|offset| match offset {
+                        Some(offset)
+                            if spans.iter().any(|(start, end)| {
+                                (offset.begin as usize) < *end && (offset.end as usize) > *start
+                            }) =>
+                        {
+                            0
+                        }
+                        _ => 1,
+                    })
+                    .collect();
+                (tokenized_input.token_ids, mask)
+            })
+            .unzip();
+        self.predict_with_mask(&token_ids, &masks)
+    }
+
+    /// Classify pre-tokenized inputs with a caller-provided attention mask
+    ///
+    /// # Arguments
+    ///
+    /// * `token_ids` - `&[Vec<i64>]` token ids (including special tokens) for each input.
+    /// * `masks` - `&[Vec<i64>]` attention mask for each input (1 for tokens to attend to, 0 for masked tokens).
+    ///   Each mask must have the same length as the corresponding token ids.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Label>, RustBertError>` containing labels for the inputs, or a `ValueError` if the token ids and masks shapes do not match
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let token_ids = [vec![101, 2023, 3185, 2001, 2307, 102]];
+    /// let masks = [vec![1, 1, 1, 0, 1, 1]];
+    /// let output = sequence_classification_model.predict_with_mask(&token_ids, &masks)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_mask(
+        &self,
+        token_ids: &[Vec<i64>],
+        masks: &[Vec<i64>],
+    ) -> Result<Vec<Label>, RustBertError> {
+        if token_ids.len() != masks.len() {
+            return Err(RustBertError::ValueError(format!(
+                "Got {} token ids sequences but {} masks",
+                token_ids.len(),
+                masks.len()
+            )));
+        }
+        for (index, (input_token_ids, mask)) in token_ids.iter().zip(masks).enumerate() {
+            if input_token_ids.len() != mask.len() {
+                return Err(RustBertError::ValueError(format!(
+                    "Length of the token ids ({}) and mask ({}) do not match for input {index}",
+                    input_token_ids.len(),
+                    mask.len()
+                )));
+            }
+        }
+        if token_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let pad_id = self
+            .tokenizer
+            .get_pad_id()
+            .expect("The Tokenizer used for sequence classification should contain a PAD id");
+        let max_len = token_ids.iter().map(|input| input.len()).max().unwrap();
+        let input_ids = token_ids
+            .iter()
+            .map(|input| {
+                let mut input = input.clone();
+                input.resize(max_len, pad_id);
+                Tensor::from_slice(&input)
+            })
+            .collect::<Vec<_>>();
+        let masks = masks
+            .iter()
+            .map(|mask| {
+                let mut mask = mask.clone();
+                mask.resize(max_len, 0);
+                Tensor::from_slice(&mask)
+            })
+            .collect::<Vec<_>>();
+        let input_ids = Tensor::stack(input_ids.as_slice(), 0).to(self.device);
+        let mask = Tensor::stack(masks.as_slice(), 0).to(self.device);
+
+        let output = no_grad(|| {
+            self.sequence_classifier
+                .forward_t(Some(&input_ids), Some(&mask), None, None, None, false)
+                .detach()
+        });
+        Ok(self.get_top_labels(&self.calibrate(output).softmax(-1, self.score_kind())))
+    }
+
+    /// Explains the prediction for a text by occlusion: each span of `window` tokens is masked in turn (its attention
+    /// mask set to 0), and the importance of the span is the resulting drop of the probability of the predicted label.
+    /// Unlike `attention_rollout`, this method does not depend on the model architecture. The unmodified input and all
+    /// occluded variants are classified in a single batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&str` text to explain.
+    /// * `window` - Number of consecutive tokens occluded together (1 to occlude each token separately).
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(String, f32)>` containing each span of the encoded input (excluding special tokens) with its importance.
+    ///   Negative importances indicate spans whose occlusion increases the probability of the predicted label.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let output = sequence_classification_model.explain_occlusion("This is a great movie.", 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn explain_occlusion(&self, input: &str, window: usize) -> Vec<(String, f32)> {
+        let tokenized_input = self.encode_inputs(&[input]).remove(0);
+        let positions = tokenized_input
+            .special_tokens_mask
+            .iter()
+            .enumerate()
+            .filter(|(_, is_special)| **is_special == 0)
+            .map(|(position, _)| position)
+            .collect::<Vec<usize>>();
+        if positions.is_empty() {
+            return vec![];
+        }
+        let spans = positions.chunks(window.max(1)).collect::<Vec<&[usize]>>();
+
+        //    The first variant is the unmodified input, followed by one variant per occluded span
+        let sequence_length = tokenized_input.token_ids.len();
+        let num_variants = spans.len() as i64 + 1;
+        let mut masks = vec![1i64; sequence_length * (spans.len() + 1)];
+        for (variant_mask, span) in masks.chunks_mut(sequence_length).skip(1).zip(&spans) {
+            for position in span.iter() {
+                variant_mask[*position] = 0;
+            }
+        }
+        let input_ids = Tensor::from_slice(&tokenized_input.token_ids)
+            .unsqueeze(0)
+            .repeat([num_variants, 1])
+            .to(self.device);
+        let token_type_ids = Tensor::from_slice(&tokenized_input.segment_ids)
+            .to_kind(Kind::Int64)
+            .unsqueeze(0)
+            .repeat([num_variants, 1])
+            .to(self.device);
+        let masks = Tensor::from_slice(&masks)
+            .view([num_variants, sequence_length as i64])
+            .to(self.device);
+
+        let output = no_grad(|| {
+            self.sequence_classifier
+                .forward_t(
+                    Some(&input_ids),
+                    Some(&masks),
+                    Some(&token_type_ids),
+                    None,
+                    None,
+                    false,
+                )
+                .detach()
+        });
+        let probabilities = self
+            .calibrate(output)
+            .softmax(-1, self.score_kind())
+            .to(Device::Cpu);
+        let predicted_label = probabilities.get(0).argmax(-1, false).int64_value(&[]);
+        let scores = probabilities.select(1, predicted_label);
+        let base_score = scores.double_value(&[0]);
+
+        spans
+            .iter()
+            .enumerate()
+            .map(|(span_index, span)| {
+                let span_token_ids = span
+                    .iter()
+                    .map(|position| tokenized_input.token_ids[*position])
+                    .collect::<Vec<i64>>();
+                let occluded_score = scores.double_value(&[span_index as i64 + 1]);
+                (
+                    self.tokenizer.decode(&span_token_ids, false, false),
+                    (base_score - occluded_score) as f32,
+                )
+            })
+            .collect()
+    }
+
+    /// Computes the attention rollout of a text: the attention weights (averaged over heads, with the residual
+    /// connections accounted for as an identity matrix) are multiplied across layers, and the rolled out attention
+    /// of the CLS token gives the importance of each input token for the classification.
+    /// Requires the `output_attentions` option of the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&str` text to explain.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(String, f32)>` containing each token of the encoded input (including special tokens) with its importance. The importances sum to 1.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::{
+    ///     SequenceClassificationConfig, SequenceClassificationModel,
+    /// };
+    ///
+    /// let config = SequenceClassificationConfig {
+    ///     output_attentions: true,
+    ///     ..Default::default()
+    /// };
+    /// let sequence_classification_model = SequenceClassificationModel::new(config)?;
+    /// let output = sequence_classification_model.attention_rollout("This is a great movie.")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn attention_rollout(&self, input: &str) -> Result<Vec<(String, f32)>, RustBertError> {
+        let tokenized_input = self.encode_inputs(&[input]);
+        let token_ids = tokenized_input[0].token_ids.clone();
+        let batch = self.pad_tokenized_input(tokenized_input);
+        let rollout = no_grad(|| {
+            let (_, all_attentions) = self.sequence_classifier.forward_t_with_attentions(
+                &batch.input_ids,
+                Some(&batch.attention_mask),
+                Some(&batch.token_type_ids),
+                false,
+            )?;
+            let identity = Tensor::eye(batch.input_ids.size()[1], (Kind::Float, self.device));
+            Ok::<Tensor, RustBertError>(all_attentions.iter().fold(
+                identity.copy(),
+                |rollout, layer_attentions| {
+                    let attentions = layer_attentions.squeeze_dim(0).mean_dim(
+                        [0].as_slice(),
+                        false,
+                        Kind::Float,
+                    ) + &identity;
+                    let attentions = &attentions
+                        / attentions.sum_dim_intlist([-1].as_slice(), true, Kind::Float);
+                    attentions.matmul(&rollout)
+                },
+            ))
+        })?;
+        Ok(token_ids
+            .iter()
+            .zip(rollout.select(0, 0).to(Device::Cpu).iter::<f64>()?)
+            .map(|(token_id, importance)| {
+                (
+                    self.tokenizer.decode(&[*token_id], false, false),
+                    importance as f32,
+                )
+            })
+            .collect())
+    }
+
+    /// Classify multi-sentence texts and report the sentence driving the classification
+    ///
+    /// Each input is split on sentence boundaries (`.`, `!` or `?` followed by a white space) and every sentence
+    /// is classified. The sentence logits are aggregated into a single label per input text, and the sentence
+    /// with the highest score for this label is reported as the top contributing sentence.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `aggregation` - `Aggregation` used to combine the logits of the sentences of an input.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(Label, SentenceContribution)>` containing the aggregate label and the top contributing sentence for each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::{Aggregation, SequenceClassificationModel};
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let review = "The cast is great. The plot, however, makes no sense at all! I walked out after an hour.";
+    /// for (label, top_sentence) in
+    ///     sequence_classification_model.predict_with_top_sentence(&[review], Aggregation::Mean)
+    /// {
+    ///     println!("{} ({:.2}), driven by: {}", label.text, label.score, top_sentence.text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_top_sentence(
+        &self,
+        input: &[&str],
+        aggregation: Aggregation,
+    ) -> Vec<(Label, SentenceContribution)> {
+        let mut sentences: Vec<&str> = vec![];
+        let mut sentence_offsets: Vec<(usize, usize)> = vec![];
+        let mut sentence_boundaries: Vec<(usize, usize)> = Vec::with_capacity(input.len());
+        for text in input {
+            let start = sentences.len();
+            for (sentence_start, sentence_end) in split_sentences(text) {
+                sentences.push(&text[sentence_start..sentence_end]);
+                sentence_offsets.push((sentence_start, sentence_end));
+            }
+            sentence_boundaries.push((start, sentences.len()));
+        }
+        if sentences.is_empty() {
+            return vec![];
+        }
+
+        let logits = self.calibrate(self.logits_within_budget(&sentences));
+        let output = aggregate_probabilities(
+            &logits,
+            &sentence_boundaries,
+            aggregation,
+            self.score_kind(),
+        );
+        let sentence_scores = logits.softmax(-1, self.score_kind()).to(Device::Cpu);
+
+        self.get_top_labels(&output)
+            .into_iter()
+            .zip(sentence_boundaries)
+            .map(|(label, (start, end))| {
+                let (top_sentence, score) = (start..end)
+                    .map(|sentence_idx| {
+                        (
+                            sentence_idx,
+                            sentence_scores.double_value(&[
+                                sentence_idx as i64,
+                                self.output_position(label.id).unwrap(),
+                            ]),
+                        )
+                    })
+                    .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .unwrap();
+                let contribution = SentenceContribution {
+                    sentence: label.sentence,
+                    index: top_sentence - start,
+                    text: sentences[top_sentence].to_string(),
+                    byte_offsets: sentence_offsets[top_sentence],
+                    score,
+                };
+                (label, contribution)
+            })
+            .collect()
+    }
+}
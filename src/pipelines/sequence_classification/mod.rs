@@ -0,0 +1,4443 @@
+// Copyright 2019-present, the HuggingFace Inc. team, The Google AI Language Team and Facebook, Inc.
+// Copyright 2019-2020 Guillaume Becquin
+// Copyright 2020 Maarten van Gompel
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! # Sequence classification pipeline (e.g. Sentiment Analysis)
+//! More generic sequence classification pipeline, works with multiple models (Bert, Roberta)
+//!
+//! ```no_run
+//! use rust_bert::pipelines::sequence_classification::SequenceClassificationConfig;
+//! use rust_bert::resources::{RemoteResource};
+//! use rust_bert::distilbert::{DistilBertModelResources, DistilBertVocabResources, DistilBertConfigResources};
+//! use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+//! use rust_bert::pipelines::common::ModelType;
+//! # fn main() -> anyhow::Result<()> {
+//!
+//! //Load a configuration
+//! use rust_bert::pipelines::common::ModelResource;
+//! let config = SequenceClassificationConfig::new(ModelType::DistilBert,
+//!    ModelResource::Torch(Box::new(RemoteResource::from_pretrained(DistilBertModelResources::DISTIL_BERT_SST2))),
+//!    RemoteResource::from_pretrained(DistilBertVocabResources::DISTIL_BERT_SST2),
+//!    RemoteResource::from_pretrained(DistilBertConfigResources::DISTIL_BERT_SST2),
+//!    None, // Merge resources
+//!    true, //lowercase
+//!    None, //strip_accents
+//!    None, //add_prefix_space
+//! );
+//!
+//! //Create the model
+//! let sequence_classification_model = SequenceClassificationModel::new(config)?;
+//!
+//! let input = [
+//!     "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+//!     "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+//!     "If you like original gut wrenching laughter you will like this movie. If you are young or old then you will love this movie, hell even my mom liked it.",
+//! ];
+//! let output = sequence_classification_model.predict(&input);
+//! # Ok(())
+//! # }
+//! ```
+//! (Example courtesy of [IMDb](http://www.imdb.com))
+//!
+//! Output: \
+//! ```no_run
+//! # use rust_bert::pipelines::sequence_classification::Label;
+//! let output =
+//! [
+//!    Label { text: String::from("POSITIVE"), score: 0.9986, id: 1, sentence: 0},
+//!    Label { text: String::from("NEGATIVE"), score: 0.9985, id: 0, sentence: 1},
+//!    Label { text: String::from("POSITIVE"), score: 0.9988, id: 1, sentence: 12},
+//! ]
+//! # ;
+//! ```
+use crate::albert::AlbertForSequenceClassification;
+use crate::bart::BartForSequenceClassification;
+use crate::bert::BertForSequenceClassification;
+use crate::common::error::RustBertError;
+use crate::deberta::DebertaForSequenceClassification;
+use crate::distilbert::DistilBertModelClassifier;
+use crate::fnet::FNetForSequenceClassification;
+use crate::funnel::FunnelForSequenceClassification;
+use crate::longformer::LongformerForSequenceClassification;
+use crate::mobilebert::MobileBertForSequenceClassification;
+use crate::pipelines::common::{
+    get_device, ConfigOption, ModelResource, ModelType, Tokenize, TokenizerOption,
+};
+use crate::reformer::ReformerForSequenceClassification;
+use crate::resources::ResourceProvider;
+use crate::roberta::RobertaForSequenceClassification;
+use crate::t5::T5ForTextClassification;
+use crate::xlnet::XLNetForSequenceClassification;
+use rust_tokenizers::tokenizer::TruncationStrategy;
+use rust_tokenizers::{Mask, Offset, TokenIdsWithOffsets, TokenizedInput};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cmp::{max, min};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::sync::Arc;
+use tch::nn::VarStore;
+use tch::{no_grad, Device, Kind, TchError, Tensor};
+use unicode_normalization::{is_nfc, is_nfd, is_nfkc, is_nfkd, UnicodeNormalization as _};
+
+use crate::deberta_v2::DebertaV2ForSequenceClassification;
+#[cfg(feature = "onnx")]
+use crate::pipelines::onnx::{config::ONNXEnvironmentConfig, ONNXEncoder};
+#[cfg(feature = "remote")]
+use crate::{
+    distilbert::{DistilBertConfigResources, DistilBertModelResources, DistilBertVocabResources},
+    resources::RemoteResource,
+};
+
+mod batching;
+mod eval;
+mod explain;
+mod tracing;
+
+pub use batching::{BatchAccumulator, MemoryBudget, PreparedBatch};
+pub use eval::{BenchmarkReport, CalibrationBin, CalibrationReport, ExampleStats, PredictionDiff};
+pub use explain::SentenceContribution;
+use tracing::TracedModelFingerprint;
+pub use tracing::{TracedModelCache, TracedSequenceClassifier};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+/// # Label generated by a `SequenceClassificationModel`
+pub struct Label {
+    /// Label String representation
+    pub text: String,
+    /// Confidence score
+    pub score: f64,
+    /// Label ID (key of the `id2label` mapping of the model configuration)
+    pub id: i64,
+    /// Sentence index
+    #[serde(default)]
+    pub sentence: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+/// # Label attributed to the model version that produced it (see `SequenceClassificationModel::predict_with_provenance`)
+pub struct LabelWithProvenance {
+    /// Predicted label
+    pub label: Label,
+    /// Identifier of the model version: the configured model tag, or the identifier (path or URL) of the model
+    /// weights resource
+    pub model_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+/// # Truncation report for an input classified by a `SequenceClassificationModel`
+/// Describes which part of an input was retained when it exceeded the maximum model length.
+pub struct TruncationReport {
+    /// Sentence index
+    pub sentence: usize,
+    /// Number of tokens (including special tokens) passed to the model
+    pub retained_tokens: usize,
+    /// Number of tokens removed by the truncation
+    pub truncated_tokens: usize,
+    /// Character range (start, end) of the input text removed by the truncation, if the input was truncated: the end
+    /// of the input for `TruncationSide::Right`, its beginning for `TruncationSide::Left` and the span between the
+    /// retained head and tail for `TruncationSide::HeadTail`
+    pub dropped_char_span: Option<(usize, usize)>,
+    /// Byte range (start, end) of the input text removed by the truncation, if the input was truncated
+    pub dropped_byte_span: Option<(usize, usize)>,
+    /// Text classified by the model, if modified by the preprocessing callback, the normalization or the unmappable
+    /// characters policy. The dropped spans refer to this text if provided, and to the input text otherwise.
+    pub preprocessed_text: Option<String>,
+}
+
+impl TruncationReport {
+    /// Returns `true` if part of the input was not seen by the model
+    pub fn is_truncated(&self) -> bool {
+        self.truncated_tokens > 0
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+/// # Metadata of a configured classification pipeline (see `SequenceClassificationModel::metadata`)
+/// Records the model, resources and settings the pipeline was built with, e.g. to log a fingerprint of the serving
+/// configuration alongside the predictions.
+pub struct PipelineMetadata {
+    /// Model type
+    pub model_type: ModelType,
+    /// Identifier (path or URL) of the model weights resource
+    pub model_resource: String,
+    /// Tag identifying the model version, if configured
+    #[serde(default)]
+    pub model_tag: Option<String>,
+    /// Identifier of the configuration resource
+    pub config_resource: String,
+    /// Identifier of the vocabulary resource. Not used if the pipeline was built with a provided tokenizer.
+    pub vocab_resource: String,
+    /// Identifier of the merges resource, if any
+    pub merges_resource: Option<String>,
+    /// Identifier of the labels resource, if any
+    pub labels_resource: Option<String>,
+    /// Mapping from label id to label name
+    pub label_mapping: BTreeMap<i64, String>,
+    /// Maximum input length (in tokens), longer inputs are truncated
+    pub max_length: usize,
+    /// Device the model is placed on (e.g. `Cpu` or `Cuda(0)`)
+    pub device: String,
+    /// Lower casing of the inputs by the tokenizer
+    pub lower_case: bool,
+    /// Stripping of the accents by the tokenizer, if configured
+    pub strip_accents: Option<bool>,
+    /// Addition of a white space before each input by the tokenizer, if configured
+    pub add_prefix_space: Option<bool>,
+    /// Addition of the special tokens to the inputs
+    pub add_special_tokens: bool,
+    /// Temperature dividing the logits before the softmax
+    pub temperature: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// # Outputs computed by `SequenceClassificationModel::predict_with_options` in addition to the top label
+/// All the outputs are derived from the logits of a single forward pass.
+pub struct PredictOptions {
+    /// Return the decision margin of each prediction: the difference between the softmax scores of the top and
+    /// second labels, or the score of the label for models with a single label (default: false)
+    pub margin: bool,
+    /// Return the softmax probability of every label, keyed by label name (default: false)
+    pub scores: bool,
+    /// Return the log-probability (log-softmax of the logits) of every label, keyed by label name. The
+    /// log-probabilities avoid the underflow of very small probabilities and can be summed (e.g. to combine the
+    /// predictions of several windows or models) without loss of precision (default: false)
+    pub log_scores: bool,
+    /// Return the multi-label set of each input: the labels whose sigmoid score (computed independently for each
+    /// label, as in `predict_multilabel`) is above the threshold, ordered by id (default: None)
+    pub multilabel_threshold: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+/// # Prediction for an input (see `SequenceClassificationModel::predict_with_options`)
+/// The optional outputs are `Some` if requested in the `PredictOptions`.
+pub struct Prediction {
+    /// Top label, with its softmax score (as returned by `predict`)
+    pub label: Label,
+    /// Difference between the scores of the top and second labels
+    pub margin: Option<f64>,
+    /// Softmax probability of every label, keyed by label name
+    pub scores: Option<HashMap<String, f64>>,
+    /// Log-probability of every label, keyed by label name
+    pub log_scores: Option<HashMap<String, f64>>,
+    /// Labels whose sigmoid score is above the multi-label threshold, with their sigmoid scores
+    pub multilabel: Option<Vec<Label>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+/// # Multi-label prediction for an input (see `SequenceClassificationModel::predict_multilabel_with_fallback`)
+pub struct MultiLabelPrediction {
+    /// Labels predicted for the input
+    pub labels: Vec<Label>,
+    /// Flag indicating if no label exceeded the threshold and the top label was returned instead
+    pub fallback: bool,
+}
+
+/// # Aggregation of the logits of overlapping windows for long inputs classification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    /// Average of the window logits
+    Mean,
+    /// Element-wise maximum of the window logits
+    Max,
+    /// Majority vote of the window labels, the score being the fraction of windows voting for the label
+    /// (ties are resolved in favor of the lowest label id)
+    Vote,
+}
+
+/// # Side from which the inputs exceeding the maximum model length are truncated
+/// The special tokens (e.g. *CLS* and *SEP*) are added after the truncation: they are always retained,
+/// at their usual position, whatever the truncation side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TruncationSide {
+    /// Removes the tokens at the end of the input, keeping its beginning
+    Right,
+    /// Removes the tokens at the beginning of the input, keeping its end (e.g. the most recent content of a log line)
+    Left,
+    /// Removes the tokens in the middle of the input, keeping its first `head` tokens and filling the rest of the
+    /// maximum model length with its last tokens. Keeping both the beginning and the end of long documents (e.g.
+    /// the introduction and conclusion of a review) often improves their classification.
+    HeadTail {
+        /// Number of tokens (excluding special tokens) retained at the beginning of the input
+        head: usize,
+    },
+}
+
+/// # Unicode normalization form applied to the inputs before tokenization
+/// Composed (e.g. `é` as a single code point) and decomposed (e.g. `e` followed by a combining acute accent)
+/// characters are tokenized differently: normalizing the inputs to the form used at training time ensures consistent
+/// tokens, in particular for languages using many diacritics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnicodeNormalization {
+    /// Canonical decomposition followed by canonical composition
+    Nfc,
+    /// Canonical decomposition
+    Nfd,
+    /// Compatibility decomposition followed by canonical composition (e.g. maps ligatures and full-width characters
+    /// to their plain equivalents)
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+impl UnicodeNormalization {
+    /// Normalizes a text, returning it unchanged if it is already in the normalization form
+    fn normalize<'a>(&self, text: Cow<'a, str>) -> Cow<'a, str> {
+        let is_normalized = match self {
+            UnicodeNormalization::Nfc => is_nfc(&text),
+            UnicodeNormalization::Nfd => is_nfd(&text),
+            UnicodeNormalization::Nfkc => is_nfkc(&text),
+            UnicodeNormalization::Nfkd => is_nfkd(&text),
+        };
+        if is_normalized {
+            return text;
+        }
+        Cow::Owned(match self {
+            UnicodeNormalization::Nfc => text.nfc().collect(),
+            UnicodeNormalization::Nfd => text.nfd().collect(),
+            UnicodeNormalization::Nfkc => text.nfkc().collect(),
+            UnicodeNormalization::Nfkd => text.nfkd().collect(),
+        })
+    }
+}
+
+/// # Handling of the input characters that cannot be mapped by the tokenizer
+/// A character is unmappable if the tokenizer maps it to its unknown token (e.g. an emoji absent from a WordPiece
+/// vocabulary). Characters removed by the tokenizer normalization (e.g. control characters) are not unmappable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnmappableCharacterPolicy {
+    /// Tokenizes the inputs as is: the tokenizer handles the unmappable characters (e.g. a WordPiece tokenizer maps
+    /// the whole word containing the character to its unknown token)
+    TokenizerDefault,
+    /// Removes the unmappable characters from the inputs before tokenization
+    Skip,
+    /// Replaces each unmappable character by the unknown token, keeping the rest of its word
+    ReplaceWithUnknown,
+}
+
+/// # Handling of a pooler layer missing from the model weights
+/// BERT sequence classification computes its logits from the pooled output (pooler layer applied to the hidden
+/// state of the *CLS* token). Some checkpoints, for example converted from RoBERTa-style models, are saved without the
+/// pooler weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingPoolerPolicy {
+    /// Fails to load the model with a `WeightsMismatchError`
+    Error,
+    /// Keeps the randomly initialized pooler layer (e.g. for a checkpoint to be fine-tuned)
+    Initialize,
+    /// Applies the classifier to the token hidden states selected by the `representation_token` strategy
+    /// (the *CLS* token by default), bypassing the pooler layer
+    ClsPooling,
+}
+
+/// # Token hidden states representing the sequence when the classifier input is pooled manually
+/// Encoder models use the first (*CLS*) token, while decoder-style models adapted for classification attend
+/// to the previous tokens only: their last token is the only one to see the whole sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepresentationStrategy {
+    /// Hidden state of the first token (e.g. *CLS*)
+    First,
+    /// Hidden state of the last non-padding token
+    Last,
+    /// Mean of the hidden states of the non-padding tokens
+    Mean,
+}
+
+impl RepresentationStrategy {
+    /// Pools hidden states of shape (*batch size*, *sequence_length*, *hidden_size*) into a tensor of shape
+    /// (*batch size*, *hidden_size*), ignoring the padding positions of the optional attention mask.
+    pub(crate) fn pool(
+        &self,
+        hidden_state: &Tensor,
+        mask: Option<&Tensor>,
+    ) -> Result<Tensor, TchError> {
+        match (self, mask) {
+            (Self::First, _) => hidden_state.f_select(1, 0),
+            (Self::Last, None) => hidden_state.f_select(1, -1),
+            (Self::Last, Some(mask)) => {
+                let positions = Tensor::f_arange(mask.size()[1], (Kind::Int64, mask.device()))?;
+                let last_positions = mask
+                    .f_to_kind(Kind::Int64)?
+                    .f_mul(&positions)?
+                    .f_argmax(1, false)?;
+                let hidden_size = hidden_state.size()[2];
+                hidden_state
+                    .f_gather(
+                        1,
+                        &last_positions
+                            .f_view([-1, 1, 1])?
+                            .f_expand([-1, 1, hidden_size], true)?,
+                        false,
+                    )?
+                    .f_squeeze_dim(1)
+            }
+            (Self::Mean, None) => {
+                hidden_state.f_mean_dim([1].as_slice(), false, hidden_state.kind())
+            }
+            (Self::Mean, Some(mask)) => {
+                let mask = mask.f_unsqueeze(-1)?.f_to_kind(hidden_state.kind())?;
+                hidden_state
+                    .f_mul(&mask)?
+                    .f_sum_dim_intlist([1].as_slice(), false, hidden_state.kind())?
+                    .f_div(
+                        &mask
+                            .f_sum_dim_intlist([1].as_slice(), false, hidden_state.kind())?
+                            .f_clamp_min(1.0)?,
+                    )
+            }
+        }
+    }
+}
+
+/// Suffixes of the pooler layer variables that may be missing from the weights
+const POOLER_VARIABLES: [&str; 2] = ["pooler.dense.weight", "pooler.dense.bias"];
+
+/// # Delimited text format for the export of predictions (see `SequenceClassificationModel::predict_to_writer`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelimitedFormat {
+    /// Comma-separated values
+    Csv,
+    /// Tab-separated values
+    Tsv,
+}
+
+impl DelimitedFormat {
+    fn delimiter(&self) -> char {
+        match self {
+            DelimitedFormat::Csv => ',',
+            DelimitedFormat::Tsv => '\t',
+        }
+    }
+
+    /// Quotes fields containing the delimiter, quotes or line breaks (doubling the inner quotes)
+    fn escape<'a>(&self, field: &'a str) -> Cow<'a, str> {
+        if field.contains(|c: char| c == self.delimiter() || c == '"' || c == '\n' || c == '\r') {
+            Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+        } else {
+            Cow::Borrowed(field)
+        }
+    }
+}
+
+/// Type alias for an input preprocessing callback, mapping an input text to the text to classify
+pub type PreprocessingFunction = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+/// # Configuration for SequenceClassificationModel
+/// Contains information regarding the model to load and device to place the model on.
+pub struct SequenceClassificationConfig {
+    /// Model type
+    pub model_type: ModelType,
+    /// Model weights resource (default: pretrained BERT model on CoNLL)
+    pub model_resource: ModelResource,
+    /// Config resource (default: pretrained BERT model on CoNLL)
+    pub config_resource: Box<dyn ResourceProvider + Send>,
+    /// Vocab resource (default: pretrained BERT model on CoNLL)
+    pub vocab_resource: Box<dyn ResourceProvider + Send>,
+    /// Merges resource (default: None)
+    pub merges_resource: Option<Box<dyn ResourceProvider + Send>>,
+    /// Automatically lower case all input upon tokenization (assumes a lower-cased model)
+    pub lower_case: bool,
+    /// Flag indicating if the tokenizer should strip accents (normalization). Only used for BERT / ALBERT models
+    pub strip_accents: Option<bool>,
+    /// Flag indicating if the tokenizer should add a white space before each tokenized input (needed for some Roberta models)
+    pub add_prefix_space: Option<bool>,
+    /// Device to place the model on (default: CUDA/GPU when available)
+    pub device: Device,
+    /// Optional labels resource (e.g. labels.txt, one label per line with the line number as label id), overriding the
+    /// label mapping (`id2label`) of the model configuration (default: None)
+    pub labels_resource: Option<Box<dyn ResourceProvider + Send>>,
+    /// Flag indicating if the special tokens (e.g. *CLS* and *SEP*) should be added to the inputs, to be set to `false`
+    /// for models trained without special tokens (default: true). Sentence pairs and long inputs windows always include special tokens.
+    pub add_special_tokens: bool,
+    /// Optional memory budget for the forward pass: if provided, the inputs are split into batches
+    /// sized to fit within the budget given their length (default: None, all inputs processed in a single batch).
+    /// Applies to all the methods classifying a list of texts (e.g. `predict`, `predict_scores_tensor` or
+    /// `predict_multilabel`). Inputs prepared by the caller (`PreparedBatch`, token ids or embeddings), sentence pairs
+    /// and segments, the inputs of `predict_with_ignored_spans` and `predict_long`, and the batches of variants built
+    /// from a single text (e.g. `explain_occlusion`) are classified as a single batch.
+    pub memory_budget: Option<MemoryBudget>,
+    /// Side from which inputs exceeding the maximum model length are truncated (default: `TruncationSide::Right`).
+    /// Left and head+tail truncations are not supported for custom tokenizers, sentence pairs and long inputs windows.
+    /// The truncation reports (`predict_with_truncation_report`) assume a right truncation.
+    pub truncation_side: TruncationSide,
+    /// Optional preprocessing callback applied to each input text before tokenization (e.g. HTML stripping or
+    /// URL normalization), for all prediction methods (default: None). Offsets reported by the pipeline
+    /// (e.g. in truncation reports) refer to the preprocessed text.
+    pub preprocessing: Option<PreprocessingFunction>,
+    /// Optional Unicode normalization applied to each input text before tokenization, after the preprocessing
+    /// callback (default: None, inputs tokenized as is). Should match the normalization of the training data.
+    pub normalization: Option<UnicodeNormalization>,
+    /// Optional precision of the classification head (e.g. `Kind::Float`), kept when the model is cast with
+    /// `SequenceClassificationModel::half`: the encoder runs in half precision while the head inputs are cast to the
+    /// head precision (default: None, the head follows the precision of the model). Only supported for BERT,
+    /// DistilBERT, RoBERTa, XLM-RoBERTa and ALBERT Torch models.
+    pub classifier_dtype: Option<Kind>,
+    /// Optional precision of the model weights and computations (default: None, single precision). The weights are
+    /// cast to this precision when loaded, whatever their precision in the weights file: single precision weights
+    /// loaded in double precision (`Kind::Double`) keep their single precision accuracy, while the activations and
+    /// label scores are computed in double precision. Double precision inference is several times slower than single
+    /// precision on CPU, and much slower on most GPUs (with a limited double precision throughput); it also doubles
+    /// the memory used by the weights. Supported precisions are `Kind::Float`, `Kind::Double`, `Kind::Half` and
+    /// `Kind::BFloat16`, for Torch models only.
+    pub dtype: Option<Kind>,
+    /// Handling of the input characters that cannot be mapped by the tokenizer, applied after the preprocessing
+    /// callback (default: `UnmappableCharacterPolicy::TokenizerDefault`). Not supported for custom tokenizers. Inputs
+    /// containing unmappable characters can be rejected with `SequenceClassificationModel::check_unmappable_characters`.
+    pub unmappable_characters: UnmappableCharacterPolicy,
+    /// Flag indicating if the model should output the attention weights of each layer, required by
+    /// `SequenceClassificationModel::attention_rollout` (default: false). Only supported for BERT, DistilBERT,
+    /// MobileBERT, RoBERTa and XLM-RoBERTa Torch models.
+    pub output_attentions: bool,
+    /// Temperature dividing the logits before the softmax (or sigmoid for multi-label classification), to recalibrate
+    /// the confidence of the scores (default: 1.0, scores unchanged). Values lower than 1.0 sharpen the scores, e.g. for
+    /// models trained with label smoothing that are systematically under-confident. The labels are not affected.
+    ///
+    /// The temperature can be fitted on a labelled validation set: collect the logits of the validation inputs with
+    /// `predict_prepared_logits`, and select the temperature minimizing the negative log-likelihood of the true labels
+    /// (`-log_softmax(logits / temperature)` at the true label index, averaged over the inputs), for example with a grid
+    /// search over values between 0.1 and 10.
+    pub temperature: f64,
+    /// Handling of a pooler layer missing from the weights of a BERT model (default: `MissingPoolerPolicy::Error`).
+    /// A warning is logged when the pooler weights are missing and the pooler is initialized or bypassed. Only
+    /// supported for BERT Torch models.
+    pub missing_pooler: MissingPoolerPolicy,
+    /// Token hidden states fed to the classifier when the pipeline pools manually, i.e. with the
+    /// `MissingPoolerPolicy::ClsPooling` policy (default: `RepresentationStrategy::First`, the *CLS* token). Decoder-style
+    /// models adapted for classification should use `RepresentationStrategy::Last`.
+    pub representation_token: RepresentationStrategy,
+    /// Flag indicating if `predict` should log (with the `log` crate) a warning with the number of inputs truncated
+    /// to the maximum model length (default: false)
+    pub log_truncation: bool,
+    /// Optional limit (in bytes) of the device memory to be used by the model (default: None). The size of the weights
+    /// and the projected activations of the largest batch (the memory budget if provided, otherwise a single sequence of
+    /// the maximum model length) are checked against the limit when the model is loaded, returning an
+    /// `OutOfMemoryError` if they exceed it. Only applied to Torch models placed on a CUDA device. The limit is not
+    /// enforced by libtorch (the per-process memory fraction of the CUDA allocator is not exposed by `tch`): batches
+    /// larger than projected (e.g. several long inputs classified without memory budget) can exceed it.
+    pub device_memory_limit: Option<u64>,
+    /// Flag indicating if a softmax (or sigmoid for multi-label classification) should be applied to the model output
+    /// to obtain the label scores (default: true). To be set to `false` for models already normalizing their output in
+    /// the model graph (e.g. ONNX exports ending with a `Softmax` node), whose output is then used as probabilities;
+    /// the temperature is applied to the corresponding log-probabilities. Such a checkpoint can be recognized by the
+    /// output of `predict_prepared_logits`: all values are between 0 and 1 and the values of each input sum to 1
+    /// (or lie between 0 and 1 for a multi-label model), which is unlikely for raw logits.
+    pub apply_softmax: bool,
+    /// Flag indicating if the variables missing from the model weights or whose shape differs from the weights
+    /// (e.g. the classification head of a base model fine-tuned with a different number of labels) should be randomly
+    /// initialized instead of failing (default: false). A warning listing the initialized variables is logged (with
+    /// the `log` crate). Only supported for Torch models.
+    pub load_partial: bool,
+    /// Optional multiple the padded length of the batches is rounded up to (default: None, batches are padded to
+    /// their longest input). Sequence lengths multiple of 8 make a better use of the tensor cores of recent GPUs in
+    /// half precision. The padding tokens are excluded by the attention mask and do not change the predictions.
+    pub pad_to_multiple_of: Option<usize>,
+    /// Optional on-disk cache of the model graph traced as a TorchScript module, loaded instead of building the model
+    /// on subsequent starts (default: None). See `TracedModelCache` for the tradeoffs and the cache invalidation.
+    pub traced_model_cache: Option<TracedModelCache>,
+    /// Optional tag identifying the model version (e.g. a release name or a checksum of the weights), reported with
+    /// the predictions of `predict_with_provenance` instead of the model weights resource identifier (default: None)
+    pub model_tag: Option<String>,
+}
+
+impl SequenceClassificationConfig {
+    /// Instantiate a new sequence classification configuration of the supplied type.
+    ///
+    /// # Arguments
+    ///
+    /// * `model_type` - `ModelType` indicating the model type to load (must match with the actual data to be loaded!)
+    /// * model - The `ResourceProvider` pointing to the model to load (e.g.  model.ot)
+    /// * config - The `ResourceProvider` pointing to the model configuration to load (e.g. config.json)
+    /// * vocab - The `ResourceProvider` pointing to the tokenizer's vocabulary to load (e.g.  vocab.txt/vocab.json)
+    /// * vocab - An optional `ResourceProvider` pointing to the tokenizer's merge file to load (e.g.  merges.txt), needed only for Roberta.
+    /// * lower_case - A `bool` indicating whether the tokenizer should lower case all input (in case of a lower-cased model)
+    pub fn new<RC, RV>(
+        model_type: ModelType,
+        model_resource: ModelResource,
+        config_resource: RC,
+        vocab_resource: RV,
+        merges_resource: Option<RV>,
+        lower_case: bool,
+        strip_accents: impl Into<Option<bool>>,
+        add_prefix_space: impl Into<Option<bool>>,
+    ) -> SequenceClassificationConfig
+    where
+        RC: ResourceProvider + Send + 'static,
+        RV: ResourceProvider + Send + 'static,
+    {
+        SequenceClassificationConfig {
+            model_type,
+            model_resource,
+            config_resource: Box::new(config_resource),
+            vocab_resource: Box::new(vocab_resource),
+            merges_resource: merges_resource.map(|r| Box::new(r) as Box<_>),
+            lower_case,
+            strip_accents: strip_accents.into(),
+            add_prefix_space: add_prefix_space.into(),
+            device: Device::cuda_if_available(),
+            labels_resource: None,
+            add_special_tokens: true,
+            memory_budget: None,
+            truncation_side: TruncationSide::Right,
+            preprocessing: None,
+            normalization: None,
+            classifier_dtype: None,
+            dtype: None,
+            unmappable_characters: UnmappableCharacterPolicy::TokenizerDefault,
+            output_attentions: false,
+            temperature: 1.0,
+            missing_pooler: MissingPoolerPolicy::Error,
+            representation_token: RepresentationStrategy::First,
+            log_truncation: false,
+            device_memory_limit: None,
+            apply_softmax: true,
+            load_partial: false,
+            pad_to_multiple_of: None,
+            traced_model_cache: None,
+            model_tag: None,
+        }
+    }
+
+    /// Instantiate a new sequence classification configuration, inferring the model type from the configuration
+    /// resource (`model_type` or `architectures` field of config.json, see `ModelType::from_config`).
+    ///
+    /// # Arguments
+    ///
+    /// * model - The `ResourceProvider` pointing to the model to load (e.g.  model.ot)
+    /// * config - The `ResourceProvider` pointing to the model configuration to load (e.g. config.json)
+    /// * vocab - The `ResourceProvider` pointing to the tokenizer's vocabulary to load (e.g.  vocab.txt/vocab.json)
+    /// * vocab - An optional `ResourceProvider` pointing to the tokenizer's merge file to load (e.g.  merges.txt), needed only for Roberta.
+    /// * lower_case - A `bool` indicating whether the tokenizer should lower case all input (in case of a lower-cased model)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::ModelResource;
+    /// use rust_bert::pipelines::sequence_classification::SequenceClassificationConfig;
+    /// use rust_bert::resources::LocalResource;
+    /// use std::path::PathBuf;
+    ///
+    /// let config = SequenceClassificationConfig::auto(
+    ///     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from("path/to/rust_model.ot")))),
+    ///     LocalResource::from(PathBuf::from("path/to/config.json")),
+    ///     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+    ///     None,
+    ///     true,
+    ///     None,
+    ///     None,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn auto<RC, RV>(
+        model_resource: ModelResource,
+        config_resource: RC,
+        vocab_resource: RV,
+        merges_resource: Option<RV>,
+        lower_case: bool,
+        strip_accents: impl Into<Option<bool>>,
+        add_prefix_space: impl Into<Option<bool>>,
+    ) -> Result<SequenceClassificationConfig, RustBertError>
+    where
+        RC: ResourceProvider + Send + 'static,
+        RV: ResourceProvider + Send + 'static,
+    {
+        let model_type = ModelType::from_config(&config_resource.get_local_path()?)?;
+        Ok(SequenceClassificationConfig::new(
+            model_type,
+            model_resource,
+            config_resource,
+            vocab_resource,
+            merges_resource,
+            lower_case,
+            strip_accents,
+            add_prefix_space,
+        ))
+    }
+
+    /// Instantiate a new sequence classification configuration from a model id on the Hugging Face Hub. The
+    /// configuration, vocabulary (and merges if needed) and weights resources point to the files of the model
+    /// repository. The configuration file is downloaded to infer the model type (see `ModelType::from_config`),
+    /// which determines the tokenizer files to use (e.g. `vocab.txt` for BERT, `vocab.json` and `merges.txt` for
+    /// RoBERTa, `spiece.model` for ALBERT).
+    ///
+    /// # Arguments
+    ///
+    /// * model_id - `&str` Model id on the Hub (e.g. `distilbert-base-uncased-finetuned-sst-2-english`)
+    /// * weights_filename - `&str` Name of the weights file in the model repository, either converted weights (`rust_model.ot`) or `model.safetensors`
+    /// * lower_case - A `bool` indicating whether the tokenizer should lower case all input (in case of a lower-cased model)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::{
+    ///     SequenceClassificationConfig, SequenceClassificationModel,
+    /// };
+    ///
+    /// let config = SequenceClassificationConfig::from_hub(
+    ///     "distilbert-base-uncased-finetuned-sst-2-english",
+    ///     "rust_model.ot",
+    ///     true,
+    /// )?;
+    /// let sequence_classification_model = SequenceClassificationModel::new(config)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "remote")]
+    pub fn from_hub(
+        model_id: &str,
+        weights_filename: &str,
+        lower_case: bool,
+    ) -> Result<SequenceClassificationConfig, RustBertError> {
+        let config_resource = RemoteResource::from_hub(model_id, "config.json");
+        let model_type = ModelType::from_config(&config_resource.get_local_path()?)?;
+        let (vocab_filename, merges_filename) = match model_type {
+            ModelType::Bert
+            | ModelType::DistilBert
+            | ModelType::MobileBert
+            | ModelType::FunnelTransformer => ("vocab.txt", None),
+            ModelType::Roberta | ModelType::Longformer | ModelType::Bart | ModelType::Deberta => {
+                ("vocab.json", Some("merges.txt"))
+            }
+            ModelType::DebertaV2 => ("spm.model", None),
+            ModelType::XLMRoberta => ("sentencepiece.bpe.model", None),
+            ModelType::Albert
+            | ModelType::XLNet
+            | ModelType::Reformer
+            | ModelType::FNet
+            | ModelType::T5 => ("spiece.model", None),
+            _ => {
+                return Err(RustBertError::InvalidConfigurationError(format!(
+                    "Sequence Classification not implemented for {model_type:?}!"
+                )));
+            }
+        };
+        Ok(SequenceClassificationConfig::new(
+            model_type,
+            ModelResource::Torch(Box::new(RemoteResource::from_hub(
+                model_id,
+                weights_filename,
+            ))),
+            config_resource,
+            RemoteResource::from_hub(model_id, vocab_filename),
+            merges_filename.map(|filename| RemoteResource::from_hub(model_id, filename)),
+            lower_case,
+            None,
+            None,
+        ))
+    }
+
+    /// Checks that the resources of the configuration are available and consistent with each other, without loading
+    /// the model weights: the configuration resource is downloaded (if needed) and parsed for the configured
+    /// `model_type`, merges must be provided for tokenizers requiring them (e.g. RoBERTa), the vocabulary must not
+    /// exceed the vocabulary size of the model, and the labels (`id2label`, `num_labels` and optional labels resource)
+    /// must match.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::SequenceClassificationConfig;
+    ///
+    /// let config = SequenceClassificationConfig::default();
+    /// config.validate()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Result<(), RustBertError> {
+        let config_path = self.config_resource.get_local_path()?;
+        match self.model_resource {
+            ModelResource::Torch(_) => {
+                if let Ok(config_model_type) = ModelType::from_config(&config_path) {
+                    if config_model_type != self.model_type {
+                        return Err(RustBertError::ConfigurationMismatchError(format!(
+                            "The configuration resource describes a {config_model_type:?} model, but model_type is {:?}",
+                            self.model_type
+                        )));
+                    }
+                }
+            }
+            #[cfg(feature = "onnx")]
+            ModelResource::ONNX(_) => {}
+        }
+        let config_json = std::fs::read_to_string(&config_path)?;
+        let model_config = ConfigOption::from_json_str(self.model_type, &config_json)?;
+        let config_value: serde_json::Value =
+            serde_json::from_str(&config_json).map_err(|error| {
+                RustBertError::InvalidConfigurationError(format!(
+                    "Could not parse configuration: {error}"
+                ))
+            })?;
+
+        let config_num_labels = config_value
+            .get("id2label")
+            .and_then(serde_json::Value::as_object)
+            .map(|id2label| id2label.len());
+        if let (Some(config_num_labels), Some(num_labels)) = (
+            config_num_labels,
+            config_value
+                .get("num_labels")
+                .and_then(serde_json::Value::as_u64),
+        ) {
+            if config_num_labels as u64 != num_labels {
+                return Err(RustBertError::ConfigurationMismatchError(format!(
+                    "The configuration id2label contains {config_num_labels} labels, but num_labels is {num_labels}"
+                )));
+            }
+        }
+        match (config_num_labels, self.read_labels()?) {
+            (None, None) => {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "No label dictionary (id2label) provided in configuration file or labels resource"
+                        .to_string(),
+                ));
+            }
+            (Some(config_num_labels), Some(labels)) if config_num_labels != labels.len() => {
+                return Err(RustBertError::ConfigurationMismatchError(format!(
+                    "The labels resource contains {} labels, but the configuration id2label contains {config_num_labels} labels",
+                    labels.len()
+                )));
+            }
+            _ => {}
+        }
+
+        let requires_merges = matches!(
+            self.model_type,
+            ModelType::Roberta
+                | ModelType::Longformer
+                | ModelType::Bart
+                | ModelType::Deberta
+                | ModelType::Marian
+                | ModelType::GPT2
+                | ModelType::GPTNeo
+                | ModelType::GPTJ
+                | ModelType::OpenAiGpt
+                | ModelType::M2M100
+                | ModelType::NLLB
+        );
+        if requires_merges && self.merges_resource.is_none() {
+            return Err(RustBertError::InvalidConfigurationError(format!(
+                "A merges resource is required for {:?} models",
+                self.model_type
+            )));
+        }
+        let vocab_path = self.vocab_resource.get_local_path()?;
+        let merges_path = self
+            .merges_resource
+            .as_ref()
+            .map(|resource| resource.get_local_path())
+            .transpose()?;
+        let tokenizer = TokenizerOption::from_file(
+            self.model_type,
+            vocab_path.to_str().unwrap(),
+            merges_path.as_deref().map(|path| path.to_str().unwrap()),
+            self.lower_case,
+            self.strip_accents,
+            self.add_prefix_space,
+        )?;
+        if tokenizer.get_vocab_size() > model_config.get_vocab_size() {
+            return Err(RustBertError::ConfigurationMismatchError(format!(
+                "The vocabulary contains {} tokens, but the model vocabulary size is {}",
+                tokenizer.get_vocab_size(),
+                model_config.get_vocab_size()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reads the label mapping from the `labels_resource`, if provided: one label per line, the label id being
+    /// the (0-based) line number.
+    pub(crate) fn read_labels(&self) -> Result<Option<HashMap<i64, String>>, RustBertError> {
+        let labels_resource = match &self.labels_resource {
+            Some(labels_resource) => labels_resource,
+            None => return Ok(None),
+        };
+        let labels = std::fs::read_to_string(labels_resource.get_local_path()?)?
+            .lines()
+            .enumerate()
+            .map(|(label_id, label)| (label_id as i64, label.trim().to_string()))
+            .collect::<HashMap<i64, String>>();
+        if labels.is_empty() {
+            return Err(RustBertError::InvalidConfigurationError(
+                "The labels resource does not contain any label".to_string(),
+            ));
+        }
+        Ok(Some(labels))
+    }
+}
+
+#[cfg(feature = "remote")]
+impl Default for SequenceClassificationConfig {
+    /// Provides a defaultSST-2 sentiment analysis model (English)
+    fn default() -> SequenceClassificationConfig {
+        SequenceClassificationConfig::new(
+            ModelType::DistilBert,
+            ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+                DistilBertModelResources::DISTIL_BERT_SST2,
+            ))),
+            RemoteResource::from_pretrained(DistilBertConfigResources::DISTIL_BERT_SST2),
+            RemoteResource::from_pretrained(DistilBertVocabResources::DISTIL_BERT_SST2),
+            None,
+            true,
+            None,
+            None,
+        )
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+/// # Abstraction that holds one particular sequence classification model, for any of the supported models
+pub enum SequenceClassificationOption {
+    /// Bert for Sequence Classification
+    Bert(BertForSequenceClassification),
+    /// DeBERTa for Sequence Classification
+    Deberta(DebertaForSequenceClassification),
+    /// DeBERTa V2 for Sequence Classification
+    DebertaV2(DebertaV2ForSequenceClassification),
+    /// DistilBert for Sequence Classification
+    DistilBert(DistilBertModelClassifier),
+    /// MobileBert for Sequence Classification
+    MobileBert(MobileBertForSequenceClassification),
+    /// Roberta for Sequence Classification
+    Roberta(RobertaForSequenceClassification),
+    /// XLMRoberta for Sequence Classification
+    XLMRoberta(RobertaForSequenceClassification),
+    /// Albert for Sequence Classification
+    Albert(AlbertForSequenceClassification),
+    /// XLNet for Sequence Classification
+    XLNet(XLNetForSequenceClassification),
+    /// Bart for Sequence Classification
+    Bart(BartForSequenceClassification),
+    /// Reformer for Sequence Classification
+    Reformer(ReformerForSequenceClassification),
+    /// Longformer for Sequence Classification
+    Longformer(LongformerForSequenceClassification),
+    /// FNet for Sequence Classification
+    FNet(FNetForSequenceClassification),
+    /// Funnel Transformer for Sequence Classification
+    FunnelTransformer(FunnelForSequenceClassification),
+    /// T5 for text-to-text classification (scores the label texts defined in the configuration `id2label`)
+    T5(T5ForTextClassification),
+    /// TorchScript module traced from a Torch model (see `TracedModelCache`)
+    TorchScript(TracedSequenceClassifier),
+    /// ONNX Model for Sequence Classification
+    #[cfg(feature = "onnx")]
+    ONNX(ONNXEncoder),
+}
+
+impl SequenceClassificationOption {
+    /// Instantiate a new sequence classification model of the supplied type.
+    ///
+    /// # Arguments
+    ///
+    /// * `SequenceClassificationConfig` - Sequence classification pipeline configuration. The type of model created will be inferred from the
+    ///     `ModelResources` (Torch or ONNX) and `ModelType` (Architecture for Torch models) variants provided and
+    pub fn new(config: &SequenceClassificationConfig) -> Result<Self, RustBertError> {
+        let mut model_config =
+            ConfigOption::from_file(config.model_type, config.config_resource.get_local_path()?);
+        if let Some(labels) = config.read_labels()? {
+            model_config.set_label_mapping(labels)?;
+        }
+        if config.output_attentions {
+            model_config.set_output_attentions(true)?;
+        }
+        Self::new_with_model_config(config, &model_config)
+    }
+
+    /// Instantiate a new sequence classification model of the supplied type from an already loaded model configuration.
+    /// The `config_resource` of the `SequenceClassificationConfig` is not accessed.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Sequence classification pipeline configuration.
+    /// * `model_config` - `ConfigOption` holding the model configuration (e.g. loaded with `ConfigOption::from_json_str`).
+    pub fn new_with_model_config(
+        config: &SequenceClassificationConfig,
+        model_config: &ConfigOption,
+    ) -> Result<Self, RustBertError> {
+        Ok(Self::new_with_var_store(config, model_config, None)?.0)
+    }
+
+    /// Instantiate a new sequence classification model, also returning the `VarStore` holding the weights of
+    /// Torch models (`None` for ONNX models). The variables of Torch models are added to the provided `VarStore`
+    /// if any, or to a new `VarStore` on the configuration device.
+    fn new_with_var_store(
+        config: &SequenceClassificationConfig,
+        model_config: &ConfigOption,
+        var_store: Option<VarStore>,
+    ) -> Result<(Self, Option<VarStore>), RustBertError> {
+        match config.model_resource {
+            ModelResource::Torch(_) => {
+                let var_store = var_store.unwrap_or_else(|| VarStore::new(config.device));
+                let (model, var_store) = Self::new_torch(config, model_config, var_store)?;
+                Ok((model, Some(var_store)))
+            }
+            #[cfg(feature = "onnx")]
+            ModelResource::ONNX(_) => {
+                if var_store.is_some() {
+                    return Err(RustBertError::InvalidConfigurationError(
+                        "A VarStore can only be provided for Torch models".to_string(),
+                    ));
+                }
+                Ok((Self::new_onnx(config)?, None))
+            }
+        }
+    }
+
+    fn new_torch(
+        config: &SequenceClassificationConfig,
+        model_config: &ConfigOption,
+        mut var_store: VarStore,
+    ) -> Result<(Self, VarStore), RustBertError> {
+        // Variables already held by the VarStore do not belong to the model and are not loaded
+        let external_variables = var_store
+            .variables()
+            .into_keys()
+            .collect::<HashSet<String>>();
+        let model_type = config.model_type;
+        let mut model = match model_type {
+            ModelType::Bert => {
+                if let ConfigOption::Bert(config) = model_config {
+                    Ok(Self::Bert(
+                        BertForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a BertConfig for Bert!".to_string(),
+                    ))
+                }
+            }
+            ModelType::Deberta => {
+                if let ConfigOption::Deberta(config) = model_config {
+                    Ok(Self::Deberta(
+                        DebertaForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a DebertaConfig for DeBERTa!".to_string(),
+                    ))
+                }
+            }
+            ModelType::DebertaV2 => {
+                if let ConfigOption::DebertaV2(config) = model_config {
+                    Ok(Self::DebertaV2(
+                        DebertaV2ForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a DebertaV2Config for DeBERTa V2!".to_string(),
+                    ))
+                }
+            }
+            ModelType::DistilBert => {
+                if let ConfigOption::DistilBert(config) = model_config {
+                    Ok(Self::DistilBert(
+                        DistilBertModelClassifier::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a DistilBertConfig for DistilBert!".to_string(),
+                    ))
+                }
+            }
+            ModelType::MobileBert => {
+                if let ConfigOption::MobileBert(config) = model_config {
+                    Ok(Self::MobileBert(
+                        MobileBertForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a MobileBertConfig for MobileBert!".to_string(),
+                    ))
+                }
+            }
+            ModelType::Roberta => {
+                if let ConfigOption::Roberta(config) = model_config {
+                    Ok(Self::Roberta(
+                        RobertaForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a RobertaConfig for Roberta!".to_string(),
+                    ))
+                }
+            }
+            ModelType::XLMRoberta => {
+                if let ConfigOption::Roberta(config) = model_config {
+                    Ok(Self::XLMRoberta(
+                        RobertaForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a RobertaConfig for Roberta!".to_string(),
+                    ))
+                }
+            }
+            ModelType::Albert => {
+                if let ConfigOption::Albert(config) = model_config {
+                    Ok(Self::Albert(
+                        AlbertForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply an AlbertConfig for Albert!".to_string(),
+                    ))
+                }
+            }
+            ModelType::XLNet => {
+                if let ConfigOption::XLNet(config) = model_config {
+                    Ok(Self::XLNet(
+                        XLNetForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply an XLNetConfig for XLNet!".to_string(),
+                    ))
+                }
+            }
+            ModelType::Bart => {
+                if let ConfigOption::Bart(config) = model_config {
+                    Ok(Self::Bart(
+                        BartForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a BertConfig for Bert!".to_string(),
+                    ))
+                }
+            }
+            ModelType::Reformer => {
+                if let ConfigOption::Reformer(config) = model_config {
+                    Ok(Self::Reformer(
+                        ReformerForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a ReformerConfig for Reformer!".to_string(),
+                    ))
+                }
+            }
+            ModelType::Longformer => {
+                if let ConfigOption::Longformer(config) = model_config {
+                    Ok(Self::Longformer(
+                        LongformerForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a LongformerConfig for Longformer!".to_string(),
+                    ))
+                }
+            }
+            ModelType::FNet => {
+                if let ConfigOption::FNet(config) = model_config {
+                    Ok(Self::FNet(
+                        FNetForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a FNetConfig for FNet!".to_string(),
+                    ))
+                }
+            }
+            ModelType::FunnelTransformer => {
+                if let ConfigOption::FunnelTransformer(config) = model_config {
+                    Ok(Self::FunnelTransformer(
+                        FunnelForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a FunnelConfig for Funnel Transformer!".to_string(),
+                    ))
+                }
+            }
+            ModelType::T5 => {
+                if let ConfigOption::T5(t5_config) = model_config {
+                    let label_token_ids = Self::t5_label_token_ids(config, model_config)?;
+                    Ok(Self::T5(T5ForTextClassification::new(
+                        var_store.root(),
+                        t5_config,
+                        &label_token_ids,
+                    )?))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a T5Config for T5!".to_string(),
+                    ))
+                }
+            }
+            #[cfg(feature = "onnx")]
+            ModelType::ONNX => Err(RustBertError::InvalidConfigurationError(
+                "A `ModelType::ONNX` ModelType was provided in the configuration with `ModelResources::TORCH`, these are incompatible".to_string(),
+            )),
+            _ => Err(RustBertError::InvalidConfigurationError(format!(
+                "Sequence Classification not implemented for {model_type:?}!",
+            ))),
+        }?;
+        // The variables are cast before loading, for the weights to be converted from the precision of the file
+        if let Some(dtype) = config.dtype {
+            var_store.set_kind(dtype);
+        }
+        let mut variables = var_store.variables();
+        variables.retain(|name, _| !external_variables.contains(name));
+        let optional_variables: &[&str] = match config.missing_pooler {
+            MissingPoolerPolicy::Error => &[],
+            MissingPoolerPolicy::Initialize | MissingPoolerPolicy::ClsPooling => &POOLER_VARIABLES,
+        };
+        let reinitialized_variables = crate::resources::load_variables(
+            &config.model_resource,
+            &mut variables,
+            optional_variables,
+            config.load_partial,
+        )?;
+        if config.load_partial && !reinitialized_variables.is_empty() {
+            log::warn!(
+                "Variables missing from the model weights or with a different shape, randomly initialized: {}",
+                reinitialized_variables.join(", ")
+            );
+        }
+        let missing_variables = reinitialized_variables
+            .into_iter()
+            .filter(|name| POOLER_VARIABLES.iter().any(|suffix| name.ends_with(suffix)))
+            .collect::<Vec<String>>();
+        if config.missing_pooler != MissingPoolerPolicy::Error && !missing_variables.is_empty() {
+            log::warn!(
+                "Pooler weights missing from the model weights ({}), {}",
+                missing_variables.join(", "),
+                match config.missing_pooler {
+                    MissingPoolerPolicy::ClsPooling => format!(
+                        "falling back to token pooling ({:?})",
+                        config.representation_token
+                    ),
+                    _ => "using a randomly initialized pooler".to_string(),
+                }
+            );
+            if let (MissingPoolerPolicy::ClsPooling, Self::Bert(model)) =
+                (config.missing_pooler, &mut model)
+            {
+                model.set_token_pooling(Some(config.representation_token));
+            }
+        }
+        Ok((model, var_store))
+    }
+
+    /// Tokenizes the label texts of a T5 configuration into the candidate sequences scored by the model, in the order
+    /// of the model outputs (labels sorted by increasing id, see `ConfigOption::get_output_label_ids`).
+    fn t5_label_token_ids(
+        config: &SequenceClassificationConfig,
+        model_config: &ConfigOption,
+    ) -> Result<Vec<Vec<i64>>, RustBertError> {
+        let label_mapping = model_config.get_label_mapping();
+
+        let vocab_path = config.vocab_resource.get_local_path()?;
+        let tokenizer = TokenizerOption::from_file(
+            ModelType::T5,
+            vocab_path.to_str().unwrap(),
+            None,
+            config.lower_case,
+            config.strip_accents,
+            config.add_prefix_space,
+        )?;
+        let eos_token_id = tokenizer.get_eos_id();
+        Ok(model_config
+            .get_output_label_ids()
+            .into_iter()
+            .map(|id| {
+                let mut token_ids =
+                    tokenizer.convert_tokens_to_ids(&tokenizer.tokenize(&label_mapping[&id]));
+                token_ids.extend(eos_token_id);
+                token_ids
+            })
+            .collect())
+    }
+
+    #[cfg(feature = "onnx")]
+    pub fn new_onnx(config: &SequenceClassificationConfig) -> Result<Self, RustBertError> {
+        let onnx_config = ONNXEnvironmentConfig::from_device(config.device);
+        let environment = onnx_config.get_environment()?;
+        let encoder_file = config
+            .model_resource
+            .get_onnx_local_paths()?
+            .encoder_path
+            .ok_or(RustBertError::InvalidConfigurationError(
+                "An encoder file must be provided for sequence classification ONNX models."
+                    .to_string(),
+            ))?;
+
+        Ok(Self::ONNX(ONNXEncoder::new(
+            encoder_file,
+            &environment,
+            &onnx_config,
+        )?))
+    }
+
+    /// Returns the `ModelType` for this SequenceClassificationOption
+    pub fn model_type(&self) -> ModelType {
+        match *self {
+            Self::Bert(_) => ModelType::Bert,
+            Self::Deberta(_) => ModelType::Deberta,
+            Self::DebertaV2(_) => ModelType::DebertaV2,
+            Self::Roberta(_) => ModelType::Roberta,
+            Self::XLMRoberta(_) => ModelType::Roberta,
+            Self::DistilBert(_) => ModelType::DistilBert,
+            Self::MobileBert(_) => ModelType::MobileBert,
+            Self::Albert(_) => ModelType::Albert,
+            Self::XLNet(_) => ModelType::XLNet,
+            Self::Bart(_) => ModelType::Bart,
+            Self::Reformer(_) => ModelType::Reformer,
+            Self::Longformer(_) => ModelType::Longformer,
+            Self::FNet(_) => ModelType::FNet,
+            Self::FunnelTransformer(_) => ModelType::FunnelTransformer,
+            Self::T5(_) => ModelType::T5,
+            Self::TorchScript(ref model) => model.model_type,
+            #[cfg(feature = "onnx")]
+            Self::ONNX(_) => ModelType::ONNX,
+        }
+    }
+
+    /// Interface method to forward_t() of the particular models.
+    pub fn forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        position_ids: Option<&Tensor>,
+        input_embeds: Option<&Tensor>,
+        train: bool,
+    ) -> Tensor {
+        self.f_forward_t(
+            input_ids,
+            mask,
+            token_type_ids,
+            position_ids,
+            input_embeds,
+            train,
+        )
+        .expect("Error in sequence classification forward pass")
+    }
+
+    /// Fallible version of `forward_t`, returning the errors of the models and backends (e.g. TorchScript or ONNX
+    /// runtime errors, missing inputs) instead of panicking. The tensor operations of the BERT, RoBERTa, XLM-RoBERTa
+    /// and DistilBERT models are fallible as well (e.g. a device running out of memory returns a `TchError`), the
+    /// other native Torch models panic on a failed tensor operation.
+    pub fn f_forward_t(
+        &self,
+        input_ids: Option<&Tensor>,
+        mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        position_ids: Option<&Tensor>,
+        input_embeds: Option<&Tensor>,
+        train: bool,
+    ) -> Result<Tensor, RustBertError> {
+        let logits = match *self {
+            Self::Bart(ref model) => {
+                model
+                    .forward_t(
+                        input_ids.ok_or_else(|| {
+                            RustBertError::ValueError(
+                                "`input_ids` must be provided for BART models".to_string(),
+                            )
+                        })?,
+                        mask,
+                        None,
+                        None,
+                        None,
+                        train,
+                    )
+                    .decoder_output
+            }
+            Self::Bert(ref model) => {
+                model
+                    .f_forward_t(
+                        input_ids,
+                        mask,
+                        token_type_ids,
+                        position_ids,
+                        input_embeds,
+                        train,
+                    )?
+                    .logits
+            }
+            Self::Deberta(ref model) => {
+                model
+                    .forward_t(
+                        input_ids,
+                        mask,
+                        token_type_ids,
+                        position_ids,
+                        input_embeds,
+                        train,
+                    )?
+                    .logits
+            }
+            Self::DebertaV2(ref model) => {
+                model
+                    .forward_t(
+                        input_ids,
+                        mask,
+                        token_type_ids,
+                        position_ids,
+                        input_embeds,
+                        train,
+                    )?
+                    .logits
+            }
+            Self::DistilBert(ref model) => {
+                model
+                    .forward_t(input_ids, mask, input_embeds, train)?
+                    .logits
+            }
+            Self::MobileBert(ref model) => {
+                model
+                    .forward_t(
+                        input_ids,
+                        token_type_ids,
+                        position_ids,
+                        input_embeds,
+                        mask,
+                        train,
+                    )?
+                    .logits
+            }
+            Self::Roberta(ref model) | Self::XLMRoberta(ref model) => {
+                model
+                    .f_forward_t(
+                        input_ids,
+                        mask,
+                        token_type_ids,
+                        position_ids,
+                        input_embeds,
+                        train,
+                    )?
+                    .logits
+            }
+            Self::Albert(ref model) => {
+                model
+                    .forward_t(
+                        input_ids,
+                        mask,
+                        token_type_ids,
+                        position_ids,
+                        input_embeds,
+                        train,
+                    )
+                    .logits
+            }
+            Self::XLNet(ref model) => {
+                model
+                    .forward_t(
+                        input_ids,
+                        mask,
+                        None,
+                        None,
+                        None,
+                        token_type_ids,
+                        input_embeds,
+                        train,
+                    )
+                    .logits
+            }
+            Self::Reformer(ref model) => {
+                model
+                    .forward_t(input_ids, None, None, mask, None, train)?
+                    .logits
+            }
+            Self::Longformer(ref model) => {
+                model
+                    .forward_t(
+                        input_ids,
+                        mask,
+                        None,
+                        token_type_ids,
+                        position_ids,
+                        input_embeds,
+                        train,
+                    )?
+                    .logits
+            }
+            Self::FNet(ref model) => {
+                model
+                    .forward_t(input_ids, token_type_ids, position_ids, input_embeds, train)?
+                    .logits
+            }
+            Self::FunnelTransformer(ref model) => {
+                // The Funnel tokenizer assigns a dedicated token type id (2) to the CLS token
+                let token_type_ids = match (token_type_ids, input_ids, input_embeds) {
+                    (Some(token_type_ids), _, _) => token_type_ids.copy(),
+                    (None, Some(input_ids), _) => input_ids.zeros_like(),
+                    (None, None, Some(input_embeds)) => input_embeds.select(-1, 0).zeros_like(),
+                    (None, None, None) => {
+                        return Err(RustBertError::ValueError(
+                            "`input_ids` or `input_embeds` must be provided for Funnel models"
+                                .to_string(),
+                        ));
+                    }
+                }
+                .to_kind(Kind::Int64);
+                let _ = token_type_ids.slice(1, 0, 1, 1).fill_(2);
+                model
+                    .forward_t(input_ids, mask, Some(&token_type_ids), input_embeds, train)?
+                    .logits
+            }
+            Self::T5(ref model) => model.forward_t(
+                input_ids.ok_or_else(|| {
+                    RustBertError::ValueError(
+                        "`input_ids` must be provided for T5 models".to_string(),
+                    )
+                })?,
+                mask,
+                train,
+            ),
+            Self::TorchScript(ref model) => model.forward(
+                input_ids.ok_or_else(|| {
+                    RustBertError::ValueError(
+                        "`input_ids` must be provided for traced models".to_string(),
+                    )
+                })?,
+                mask,
+                token_type_ids,
+            )?,
+            #[cfg(feature = "onnx")]
+            Self::ONNX(ref model) => {
+                let attention_mask = match (mask, input_ids) {
+                    (Some(mask), _) => mask.shallow_clone(),
+                    (None, Some(input_ids)) => input_ids.ones_like(),
+                    (None, None) => {
+                        return Err(RustBertError::ValueError(
+                            "`input_ids` must be provided for ONNX models".to_string(),
+                        ));
+                    }
+                };
+                model
+                    .forward(
+                        input_ids,
+                        Some(&attention_mask),
+                        token_type_ids,
+                        position_ids,
+                        input_embeds,
+                    )?
+                    .logits
+                    .ok_or_else(|| {
+                        RustBertError::ValueError(
+                            "The ONNX model did not return logits".to_string(),
+                        )
+                    })?
+            }
+        };
+        Ok(logits)
+    }
+
+    /// Forward pass returning the logits and the attention weights of each layer, with shape
+    /// (*batch size*, *num_heads*, *sequence_length*, *sequence_length*). The model must have been built with
+    /// `output_attentions` enabled in its configuration.
+    pub fn forward_t_with_attentions(
+        &self,
+        input_ids: &Tensor,
+        mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+        train: bool,
+    ) -> Result<(Tensor, Vec<Tensor>), RustBertError> {
+        let (logits, all_attentions) = match *self {
+            Self::Bert(ref model) => {
+                let output =
+                    model.forward_t(Some(input_ids), mask, token_type_ids, None, None, train);
+                (output.logits, output.all_attentions)
+            }
+            Self::Roberta(ref model) | Self::XLMRoberta(ref model) => {
+                let output =
+                    model.forward_t(Some(input_ids), mask, token_type_ids, None, None, train);
+                (output.logits, output.all_attentions)
+            }
+            Self::DistilBert(ref model) => {
+                let output = model.forward_t(Some(input_ids), mask, None, train)?;
+                (output.logits, output.all_attentions)
+            }
+            Self::MobileBert(ref model) => {
+                let output =
+                    model.forward_t(Some(input_ids), token_type_ids, None, None, mask, train)?;
+                (output.logits, output.all_attentions)
+            }
+            _ => {
+                return Err(RustBertError::UnsupportedError);
+            }
+        };
+        let all_attentions = all_attentions.ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(
+                "The model was built without attentions output, set `output_attentions` in the configuration"
+                    .to_string(),
+            )
+        })?;
+        Ok((logits, all_attentions))
+    }
+}
+
+const CUSTOM_TOKENIZER_UNSUPPORTED: &str =
+    "This feature requires a TokenizerOption and is not supported by the custom tokenizer";
+
+/// # SequenceClassificationModel for Classification (e.g. Sentiment Analysis)
+pub struct SequenceClassificationModel {
+    tokenizer: Box<dyn Tokenize + Send>,
+    sequence_classifier: SequenceClassificationOption,
+    label_mapping: HashMap<i64, String>,
+    output_label_ids: Vec<i64>,
+    device: Device,
+    max_length: usize,
+    embedding_size: Option<i64>,
+    type_vocab_size: Option<i64>,
+    var_store: Option<VarStore>,
+    external_variables: HashSet<String>,
+    add_special_tokens: bool,
+    memory_budget: Option<MemoryBudget>,
+    truncation_side: TruncationSide,
+    preprocessing: Option<PreprocessingFunction>,
+    normalization: Option<UnicodeNormalization>,
+    classifier_dtype: Option<Kind>,
+    dtype: Option<Kind>,
+    unmappable_characters: UnmappableCharacterPolicy,
+    temperature: f64,
+    log_truncation: bool,
+    apply_softmax: bool,
+    pad_to_multiple_of: Option<usize>,
+    metadata: PipelineMetadata,
+}
+
+impl SequenceClassificationModel {
+    /// Build a new `SequenceClassificationModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `SequenceClassificationConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let model = SequenceClassificationModel::new(Default::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        config: SequenceClassificationConfig,
+    ) -> Result<SequenceClassificationModel, RustBertError> {
+        let tokenizer = Self::tokenizer_from_config(&config)?;
+        Self::new_with_tokenizer(config, tokenizer)
+    }
+
+    /// Build a new `SequenceClassificationModel` with its variables added to an existing `VarStore`, e.g. holding the
+    /// variables of custom models sharing the same device. The model is placed on the device of the `VarStore` (the
+    /// `device` of the configuration is ignored), and the variables already held by the `VarStore` are neither loaded
+    /// nor reloaded (see `reload_weights`). Their names must differ from the ones of the model variables
+    /// (e.g. `distilbert.embeddings.word_embeddings.weight`). Only supported for Torch models.
+    ///
+    /// Note that casting the model precision (`half` and `float`) casts all the variables of the `VarStore`.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_store` - `VarStore` to add the model variables to.
+    /// * `config` - `SequenceClassificationConfig` object containing the resource references (model, vocabulary, configuration)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// use tch::{nn, Device};
+    ///
+    /// let var_store = nn::VarStore::new(Device::cuda_if_available());
+    /// let custom_layer = nn::linear(var_store.root() / "custom_head", 2, 1, Default::default());
+    /// let model = SequenceClassificationModel::from_var_store(var_store, Default::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_var_store(
+        var_store: VarStore,
+        config: SequenceClassificationConfig,
+    ) -> Result<SequenceClassificationModel, RustBertError> {
+        let tokenizer = Self::tokenizer_from_config(&config)?;
+        let config_path = config.config_resource.get_local_path()?;
+        let model_config = ConfigOption::from_file(config.model_type, config_path);
+        Self::new_with_boxed_tokenizer(config, Box::new(tokenizer), model_config, Some(var_store))
+    }
+
+    fn tokenizer_from_config(
+        config: &SequenceClassificationConfig,
+    ) -> Result<TokenizerOption, RustBertError> {
+        let vocab_path = config.vocab_resource.get_local_path()?;
+        let merges_path = config
+            .merges_resource
+            .as_ref()
+            .map(|resource| resource.get_local_path())
+            .transpose()?;
+
+        TokenizerOption::from_file(
+            config.model_type,
+            vocab_path.to_str().unwrap(),
+            merges_path.as_deref().map(|path| path.to_str().unwrap()),
+            config.lower_case,
+            config.strip_accents,
+            config.add_prefix_space,
+        )
+    }
+
+    /// Build a new `SequenceClassificationModel` with a provided tokenizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `SequenceClassificationConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    /// * `tokenizer` - `TokenizerOption` tokenizer to use for sequence classification.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::{ModelType, TokenizerOption};
+    /// use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// let tokenizer = TokenizerOption::from_file(
+    ///     ModelType::Bert,
+    ///     "path/to/vocab.txt",
+    ///     None,
+    ///     false,
+    ///     None,
+    ///     None,
+    /// )?;
+    /// let model = SequenceClassificationModel::new_with_tokenizer(Default::default(), tokenizer)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_tokenizer(
+        config: SequenceClassificationConfig,
+        tokenizer: TokenizerOption,
+    ) -> Result<SequenceClassificationModel, RustBertError> {
+        let config_path = config.config_resource.get_local_path()?;
+        let model_config = ConfigOption::from_file(config.model_type, config_path);
+        Self::new_with_model_config(config, tokenizer, model_config)
+    }
+
+    /// Build a new `SequenceClassificationModel` with a provided tokenizer and model configuration.
+    /// The `config_resource` of the `SequenceClassificationConfig` is not accessed: combined with a
+    /// `BufferResource` for the model weights, this allows building the pipeline without disk access.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `SequenceClassificationConfig` object containing the resource references (model, vocabulary) and device placement (CPU/GPU)
+    /// * `tokenizer` - `TokenizerOption` tokenizer to use for sequence classification.
+    /// * `model_config` - `ConfigOption` model configuration (e.g. loaded with `ConfigOption::from_json_str`).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::{ConfigOption, ModelType, TokenizerOption};
+    /// use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// let tokenizer = TokenizerOption::from_file(
+    ///     ModelType::DistilBert,
+    ///     "path/to/vocab.txt",
+    ///     None,
+    ///     true,
+    ///     None,
+    ///     None,
+    /// )?;
+    /// let config_json = std::env::var("MODEL_CONFIG")?;
+    /// let model_config = ConfigOption::from_json_str(ModelType::DistilBert, &config_json)?;
+    /// let model = SequenceClassificationModel::new_with_model_config(
+    ///     Default::default(),
+    ///     tokenizer,
+    ///     model_config,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_model_config(
+        config: SequenceClassificationConfig,
+        tokenizer: TokenizerOption,
+        model_config: ConfigOption,
+    ) -> Result<SequenceClassificationModel, RustBertError> {
+        Self::new_with_boxed_tokenizer(config, Box::new(tokenizer), model_config, None)
+    }
+
+    /// Build a new `SequenceClassificationModel` with a custom tokenizer implementation.
+    ///
+    /// The pipeline only relies on the `Tokenize` interface to encode and pad the inputs. Features depending on the
+    /// specifics of the `TokenizerOption` tokenizers (sentence pairs, segments, long inputs windows and unknown token
+    /// rates) return an `InvalidConfigurationError` if the custom tokenizer does not provide a `TokenizerOption`
+    /// (see `Tokenize::as_tokenizer_option`), and `get_tokenizer` panics. Building the model fails for the options
+    /// relying on these specifics (unmappable characters policy, disabled special tokens and truncation side).
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `SequenceClassificationConfig` object containing the resource references (model, configuration) and device placement (CPU/GPU).
+    ///   The vocabulary and merges resources are not used.
+    /// * `tokenizer` - `Box<dyn Tokenize + Send>` custom tokenizer to use for sequence classification.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::Tokenize;
+    /// use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// use rust_tokenizers::tokenizer::TruncationStrategy;
+    /// use rust_tokenizers::TokenizedInput;
+    ///
+    /// struct DomainTokenizer;
+    ///
+    /// impl Tokenize for DomainTokenizer {
+    ///     fn encode_list(
+    ///         &self,
+    ///         _text_list: &[&str],
+    ///         _max_len: usize,
+    ///         _truncation_strategy: &TruncationStrategy,
+    ///         _stride: usize,
+    ///     ) -> Vec<TokenizedInput> {
+    ///         // Custom tokenization logic
+    /// #       unimplemented!()
+    ///     }
+    ///
+    ///     fn get_pad_id(&self) -> Option<i64> {
+    ///         Some(0)
+    ///     }
+    ///
+    ///     fn decode(&self, _token_ids: &[i64], _: bool, _: bool) -> String {
+    ///         // Custom decoding logic
+    /// #       unimplemented!()
+    ///     }
+    /// }
+    ///
+    /// let model = SequenceClassificationModel::new_with_custom_tokenizer(
+    ///     Default::default(),
+    ///     Box::new(DomainTokenizer),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_custom_tokenizer(
+        config: SequenceClassificationConfig,
+        tokenizer: Box<dyn Tokenize + Send>,
+    ) -> Result<SequenceClassificationModel, RustBertError> {
+        let config_path = config.config_resource.get_local_path()?;
+        let model_config = ConfigOption::from_file(config.model_type, config_path);
+        Self::new_with_boxed_tokenizer(config, tokenizer, model_config, None)
+    }
+
+    /// Build a new `SequenceClassificationModel` with a tokenizer shared with other models (e.g. models
+    /// fine-tuned from the same base checkpoint), so that the tokenizer vocabulary is loaded in memory once.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - `SequenceClassificationConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    /// * `tokenizer` - `Arc<TokenizerOption>` shared tokenizer to use for sequence classification.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::{ModelType, TokenizerOption};
+    /// use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// use std::sync::Arc;
+    ///
+    /// let tokenizer = Arc::new(TokenizerOption::from_file(
+    ///     ModelType::Bert,
+    ///     "path/to/vocab.txt",
+    ///     None,
+    ///     false,
+    ///     None,
+    ///     None,
+    /// )?);
+    /// let first_model =
+    ///     SequenceClassificationModel::new_with_shared_tokenizer(Default::default(), tokenizer.clone())?;
+    /// let second_model =
+    ///     SequenceClassificationModel::new_with_shared_tokenizer(Default::default(), tokenizer)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_shared_tokenizer(
+        config: SequenceClassificationConfig,
+        tokenizer: Arc<TokenizerOption>,
+    ) -> Result<SequenceClassificationModel, RustBertError> {
+        let config_path = config.config_resource.get_local_path()?;
+        let model_config = ConfigOption::from_file(config.model_type, config_path);
+        Self::new_with_boxed_tokenizer(config, Box::new(tokenizer), model_config, None)
+    }
+
+    fn new_with_boxed_tokenizer(
+        config: SequenceClassificationConfig,
+        tokenizer: Box<dyn Tokenize + Send>,
+        mut model_config: ConfigOption,
+        var_store: Option<VarStore>,
+    ) -> Result<SequenceClassificationModel, RustBertError> {
+        if tokenizer.as_tokenizer_option().is_none() {
+            if config.unmappable_characters != UnmappableCharacterPolicy::TokenizerDefault {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "Unmappable characters policy not supported for custom tokenizers".to_string(),
+                ));
+            }
+            if !config.add_special_tokens {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "Disabling the special tokens is not supported for custom tokenizers"
+                        .to_string(),
+                ));
+            }
+            if config.truncation_side != TruncationSide::Right {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "Truncation side not supported for custom tokenizers".to_string(),
+                ));
+            }
+        }
+        if let Some(labels) = config.read_labels()? {
+            model_config.set_label_mapping(labels)?;
+        }
+        if config.output_attentions {
+            model_config.set_output_attentions(true)?;
+        }
+        if config.classifier_dtype.is_some()
+            && !matches!(
+                config.model_type,
+                ModelType::Bert
+                    | ModelType::DistilBert
+                    | ModelType::Roberta
+                    | ModelType::XLMRoberta
+                    | ModelType::Albert
+            )
+        {
+            return Err(RustBertError::InvalidConfigurationError(format!(
+                "Classifier head precision override not supported for {:?}",
+                config.model_type
+            )));
+        }
+        if config.missing_pooler != MissingPoolerPolicy::Error
+            && (config.model_type != ModelType::Bert
+                || !matches!(config.model_resource, ModelResource::Torch(_)))
+        {
+            return Err(RustBertError::InvalidConfigurationError(format!(
+                "Missing pooler handling not supported for {:?}",
+                config.model_type
+            )));
+        }
+        if config.representation_token != RepresentationStrategy::First
+            && config.missing_pooler != MissingPoolerPolicy::ClsPooling
+        {
+            return Err(RustBertError::InvalidConfigurationError(format!(
+                "Representation token {:?} requires manual pooling (`MissingPoolerPolicy::ClsPooling`)",
+                config.representation_token
+            )));
+        }
+        if let Some(dtype) = config.dtype {
+            if !matches!(
+                dtype,
+                Kind::Float | Kind::Double | Kind::Half | Kind::BFloat16
+            ) || !matches!(config.model_resource, ModelResource::Torch(_))
+            {
+                return Err(RustBertError::InvalidConfigurationError(format!(
+                    "Model precision {dtype:?} not supported: expected a floating point precision for a Torch model"
+                )));
+            }
+        }
+        if config.load_partial && !matches!(config.model_resource, ModelResource::Torch(_)) {
+            return Err(RustBertError::InvalidConfigurationError(
+                "Partial weights loading only supported for Torch models".to_string(),
+            ));
+        }
+        if !config.temperature.is_finite() || config.temperature <= 0.0 {
+            return Err(RustBertError::InvalidConfigurationError(format!(
+                "The temperature must be strictly positive and finite, got {}",
+                config.temperature
+            )));
+        }
+        if let Some(traced_model_cache) = &config.traced_model_cache {
+            if !matches!(
+                config.model_type,
+                ModelType::Bert
+                    | ModelType::DistilBert
+                    | ModelType::Roberta
+                    | ModelType::XLMRoberta
+                    | ModelType::Albert
+                    | ModelType::MobileBert
+            ) || !matches!(config.model_resource, ModelResource::Torch(_))
+            {
+                return Err(RustBertError::InvalidConfigurationError(format!(
+                    "Traced model cache not supported for {:?}",
+                    config.model_type
+                )));
+            }
+            if var_store.is_some() || config.output_attentions {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "Traced model cache not supported with a provided VarStore or attentions output"
+                        .to_string(),
+                ));
+            }
+            if traced_model_cache.batch_size == 0 || traced_model_cache.sequence_length == 0 {
+                return Err(RustBertError::InvalidConfigurationError(
+                    "The traced batch size and sequence length must be strictly positive"
+                        .to_string(),
+                ));
+            }
+        }
+        let external_variables: HashSet<String> = var_store
+            .as_ref()
+            .map(|var_store| var_store.variables().into_keys().collect())
+            .unwrap_or_default();
+        let var_store_device = var_store.as_ref().map(VarStore::device);
+        let traced_model = match &config.traced_model_cache {
+            Some(traced_model_cache) => {
+                let pad_id = tokenizer.get_pad_id().ok_or_else(|| {
+                    RustBertError::InvalidConfigurationError(
+                        "Traced model cache requires a tokenizer with a PAD id".to_string(),
+                    )
+                })?;
+                let fingerprint = TracedModelFingerprint::new(&config, traced_model_cache)?;
+                let module = traced_model_cache.load(&fingerprint, config.device)?;
+                Some((traced_model_cache, fingerprint, pad_id, module))
+            }
+            None => None,
+        };
+        let (sequence_classifier, var_store) = match traced_model {
+            Some((traced_model_cache, _, pad_id, Some(module))) => (
+                SequenceClassificationOption::TorchScript(TracedSequenceClassifier::new(
+                    module,
+                    config.model_type,
+                    traced_model_cache,
+                    pad_id,
+                )),
+                None,
+            ),
+            _ => {
+                let (sequence_classifier, var_store) =
+                    SequenceClassificationOption::new_with_var_store(
+                        &config,
+                        &model_config,
+                        var_store,
+                    )?;
+                if let Some(classifier_dtype) = config.classifier_dtype {
+                    let var_store = var_store.as_ref().ok_or_else(|| {
+                        RustBertError::InvalidConfigurationError(
+                            "Classifier head precision override only supported for Torch models"
+                                .to_string(),
+                        )
+                    })?;
+                    set_classifier_kind(var_store, classifier_dtype);
+                }
+                match (traced_model, var_store) {
+                    // Cache miss: the model is traced and replaced by the traced module, for a consistent behaviour
+                    // across loads
+                    (
+                        Some((traced_model_cache, fingerprint, pad_id, None)),
+                        Some(mut var_store),
+                    ) => {
+                        var_store.freeze();
+                        let module = traced_model_cache.trace(
+                            &sequence_classifier,
+                            &fingerprint,
+                            pad_id,
+                            config.device,
+                        )?;
+                        (
+                            SequenceClassificationOption::TorchScript(
+                                TracedSequenceClassifier::new(
+                                    module,
+                                    config.model_type,
+                                    traced_model_cache,
+                                    pad_id,
+                                ),
+                            ),
+                            None,
+                        )
+                    }
+                    (_, var_store) => (sequence_classifier, var_store),
+                }
+            }
+        };
+
+        let mut max_length = model_config
+            .get_max_len()
+            .map(|v| v as usize)
+            .unwrap_or(usize::MAX);
+        let mut embedding_size = model_config.get_embedding_size();
+        if let Some(traced_model_cache) = &config.traced_model_cache {
+            max_length = min(max_length, traced_model_cache.sequence_length);
+            embedding_size = None;
+        }
+        let type_vocab_size = model_config.get_type_vocab_size();
+        let label_mapping = model_config.get_label_mapping().clone();
+        let output_label_ids = model_config.get_output_label_ids();
+        let model_resource = config.model_resource.identifier();
+        let device = match var_store_device {
+            Some(device) => device,
+            None => get_device(config.model_resource, config.device),
+        };
+        let metadata = PipelineMetadata {
+            model_type: config.model_type,
+            model_resource,
+            model_tag: config.model_tag.clone(),
+            config_resource: config.config_resource.identifier(),
+            vocab_resource: config.vocab_resource.identifier(),
+            merges_resource: config
+                .merges_resource
+                .as_ref()
+                .map(|resource| resource.identifier()),
+            labels_resource: config
+                .labels_resource
+                .as_ref()
+                .map(|resource| resource.identifier()),
+            label_mapping: label_mapping
+                .iter()
+                .map(|(id, label)| (*id, label.clone()))
+                .collect(),
+            max_length,
+            device: format!("{device:?}"),
+            lower_case: config.lower_case,
+            strip_accents: config.strip_accents,
+            add_prefix_space: config.add_prefix_space,
+            add_special_tokens: config.add_special_tokens,
+            temperature: config.temperature,
+        };
+        let device_memory_limit = config.device_memory_limit;
+        let model = SequenceClassificationModel {
+            tokenizer,
+            sequence_classifier,
+            label_mapping,
+            output_label_ids,
+            device,
+            max_length,
+            embedding_size,
+            type_vocab_size,
+            var_store,
+            external_variables,
+            add_special_tokens: config.add_special_tokens,
+            memory_budget: config.memory_budget,
+            truncation_side: config.truncation_side,
+            preprocessing: config.preprocessing,
+            normalization: config.normalization,
+            classifier_dtype: config.classifier_dtype,
+            dtype: config.dtype,
+            unmappable_characters: config.unmappable_characters,
+            temperature: config.temperature,
+            log_truncation: config.log_truncation,
+            apply_softmax: config.apply_softmax,
+            pad_to_multiple_of: config.pad_to_multiple_of,
+            metadata,
+        };
+        if let Some(device_memory_limit) = device_memory_limit {
+            if let Device::Cuda(_) = model.device {
+                model.check_device_memory_limit(device_memory_limit)?;
+            }
+        }
+        Ok(model)
+    }
+
+    /// Casts the model weights to half precision (e.g. for faster GPU inference). If a `classifier_dtype` was
+    /// provided in the configuration, the classification head is kept in this precision.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::{
+    ///     SequenceClassificationConfig, SequenceClassificationModel,
+    /// };
+    /// use tch::Kind;
+    ///
+    /// let config = SequenceClassificationConfig {
+    ///     classifier_dtype: Some(Kind::Float),
+    ///     ..Default::default()
+    /// };
+    /// let mut sequence_classification_model = SequenceClassificationModel::new(config)?;
+    /// sequence_classification_model.half()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn half(&mut self) -> Result<(), RustBertError> {
+        self.set_kind(Kind::Half)
+    }
+
+    /// Casts the model weights to single precision. If a `classifier_dtype` was provided in the configuration,
+    /// the classification head is kept in this precision.
+    pub fn float(&mut self) -> Result<(), RustBertError> {
+        self.set_kind(Kind::Float)
+    }
+
+    fn set_kind(&mut self, kind: Kind) -> Result<(), RustBertError> {
+        let var_store = self.var_store.as_mut().ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(
+                "Type casting only supported for Torch models".to_string(),
+            )
+        })?;
+        match kind {
+            Kind::Half => var_store.half(),
+            _ => var_store.float(),
+        }
+        if let Some(classifier_dtype) = self.classifier_dtype {
+            set_classifier_kind(var_store, classifier_dtype);
+        }
+        Ok(())
+    }
+
+    /// Replaces the weights of the model with the weights from the provided resource, without rebuilding
+    /// the tokenizer or the model graph (e.g. to roll out an updated checkpoint of the same architecture).
+    /// The new weights are validated against the existing variables before any of them is modified:
+    /// the model is left unchanged if a variable is missing or has a different shape.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - `ResourceProvider` pointing to the new model weights.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// use rust_bert::resources::LocalResource;
+    /// use std::path::PathBuf;
+    ///
+    /// let mut sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let new_weights = LocalResource::from(PathBuf::from("path/to/rust_model.ot"));
+    /// sequence_classification_model.reload_weights(&new_weights)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reload_weights(
+        &mut self,
+        resource: &(impl ResourceProvider + ?Sized),
+    ) -> Result<(), RustBertError> {
+        let var_store = self.var_store.as_ref().ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(
+                "Weights can only be reloaded for Torch models".to_string(),
+            )
+        })?;
+        let named_tensors = crate::resources::load_named_tensors(resource)?
+            .into_iter()
+            .collect::<HashMap<String, Tensor>>();
+        let mut variables = var_store.variables();
+        variables.retain(|name, _| !self.external_variables.contains(name));
+        for (name, variable) in variables.iter() {
+            match named_tensors.get(name) {
+                None => {
+                    return Err(RustBertError::WeightsMismatchError(format!(
+                        "Variable {name} not found in the provided weights"
+                    )));
+                }
+                Some(tensor) if tensor.size() != variable.size() => {
+                    return Err(RustBertError::TensorShapeError(format!(
+                        "Shape mismatch for variable {name}: expected {:?}, got {:?}",
+                        variable.size(),
+                        tensor.size()
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+        no_grad(|| {
+            for (name, variable) in variables.iter_mut() {
+                variable.f_copy_(&named_tensors[name])?;
+            }
+            Ok::<(), RustBertError>(())
+        })?;
+        self.metadata.model_resource = resource.identifier();
+        self.metadata.model_tag = None;
+        Ok(())
+    }
+
+    /// Adds tokens to the tokenizer vocabulary and resizes the input (word) embeddings of the model to cover them
+    /// (e.g. to adapt a model to domain-specific terms). Tokens already in the vocabulary are left unchanged. The
+    /// embeddings of the new tokens are initialized to the mean of the existing embeddings, and should be fine-tuned
+    /// for the model to make use of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - `&[&str]` Tokens to add to the vocabulary.
+    ///
+    /// # Returns
+    ///
+    /// * `i64` Vocabulary size of the model after the tokens were added.
+    ///
+    /// # Errors
+    ///
+    /// If the model is not a Torch model, if its word embeddings cannot be identified, or if the tokenizer is a
+    /// custom tokenizer or is shared with other models. The model and tokenizer are left unchanged in these cases.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// let mut sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let vocab_size = sequence_classification_model.add_tokens(&["[GENE]", "[PROTEIN]"])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_tokens(&mut self, tokens: &[&str]) -> Result<i64, RustBertError> {
+        let var_store = self.var_store.as_ref().ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(
+                "Tokens can only be added to Torch models".to_string(),
+            )
+        })?;
+        let mut word_embeddings = var_store
+            .variables()
+            .into_iter()
+            .filter(|(name, _)| {
+                name.ends_with("word_embeddings.weight") && !self.external_variables.contains(name)
+            })
+            .map(|(_, variable)| variable)
+            .collect::<Vec<Tensor>>();
+        if word_embeddings.len() != 1 {
+            return Err(RustBertError::InvalidConfigurationError(
+                "Could not identify the word embeddings of the model".to_string(),
+            ));
+        }
+        let mut word_embeddings = word_embeddings.remove(0);
+        let tokenizer = self.tokenizer.as_tokenizer_option_mut().ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(
+                "The tokenizer cannot be modified: it is a custom tokenizer or is shared with other models"
+                    .to_string(),
+            )
+        })?;
+        tokenizer.add_tokens(tokens);
+
+        let current_vocab_size = word_embeddings.size()[0];
+        let new_vocab_size = max(tokenizer.get_vocab_size(), current_vocab_size);
+        if new_vocab_size > current_vocab_size {
+            no_grad(|| {
+                let new_embeddings = word_embeddings
+                    .mean_dim([0].as_slice(), true, word_embeddings.kind())
+                    .expand([new_vocab_size - current_vocab_size, -1], false);
+                let resized_embeddings = Tensor::cat(&[&word_embeddings, &new_embeddings], 0);
+                word_embeddings.set_data(&resized_embeddings);
+            });
+        }
+        Ok(new_vocab_size)
+    }
+
+    /// Get a reference to the model tokenizer.
+    ///
+    /// # Panics
+    ///
+    /// If the model was built with a custom tokenizer not providing a `TokenizerOption`.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        self.tokenizer
+            .as_tokenizer_option()
+            .expect(CUSTOM_TOKENIZER_UNSUPPORTED)
+    }
+
+    /// Get a mutable reference to the model tokenizer.
+    ///
+    /// # Panics
+    ///
+    /// If the model was built with a custom tokenizer not providing a `TokenizerOption`, or with a tokenizer
+    /// currently shared with other models (see `new_with_shared_tokenizer`).
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        self.tokenizer.as_tokenizer_option_mut().expect(
+            "The tokenizer cannot be modified: it is a custom tokenizer or is shared with other models",
+        )
+    }
+
+    fn native_tokenizer(&self) -> Result<&TokenizerOption, RustBertError> {
+        self.tokenizer.as_tokenizer_option().ok_or_else(|| {
+            RustBertError::InvalidConfigurationError(CUSTOM_TOKENIZER_UNSUPPORTED.to_string())
+        })
+    }
+
+    /// Returns the padding token id of the model tokenizer, if defined.
+    pub fn pad_id(&self) -> Option<i64> {
+        self.tokenizer.get_pad_id()
+    }
+
+    /// Returns the classification (CLS) token id of the model tokenizer, if defined.
+    pub fn cls_id(&self) -> Option<i64> {
+        self.tokenizer
+            .as_tokenizer_option()
+            .and_then(|tokenizer| tokenizer.get_cls_id())
+    }
+
+    /// Returns the separator (SEP) token id of the model tokenizer, if defined.
+    pub fn sep_id(&self) -> Option<i64> {
+        self.tokenizer
+            .as_tokenizer_option()
+            .and_then(|tokenizer| tokenizer.get_sep_id())
+    }
+
+    /// Returns the mask token id of the model tokenizer, if defined.
+    pub fn mask_id(&self) -> Option<i64> {
+        self.tokenizer
+            .as_tokenizer_option()
+            .and_then(|tokenizer| tokenizer.get_mask_id())
+    }
+
+    /// Returns the maximum input length (in tokens) supported by the model. Longer inputs are truncated.
+    pub fn model_max_length(&self) -> usize {
+        self.max_length
+    }
+
+    /// Returns the metadata of the pipeline: model type, resource identifiers, label mapping, maximum length, device
+    /// and tokenization settings. The metadata is serializable, e.g. to record the configuration that produced a
+    /// set of predictions.
+    ///
+    /// # Returns
+    ///
+    /// * `PipelineMetadata` metadata of the pipeline
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let metadata = serde_json::to_string(&sequence_classification_model.metadata())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn metadata(&self) -> PipelineMetadata {
+        self.metadata.clone()
+    }
+
+    /// Returns the identifier of the model version: the configured model tag if any, otherwise the identifier
+    /// (path or URL) of the model weights resource.
+    pub fn model_id(&self) -> Option<&str> {
+        self.metadata
+            .model_tag
+            .as_deref()
+            .or(Some(self.metadata.model_resource.as_str()))
+            .filter(|model_id| !model_id.is_empty())
+    }
+
+    /// Classify texts. If a `MemoryBudget` is configured, the texts are processed in successive batches fitting within the budget.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Label>` containing labels for input texts
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input = [
+    ///     "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+    ///     "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+    ///     "If you like original gut wrenching laughter you will like this movie. If you are young or old then you will love this movie, hell even my mom liked it.",
+    /// ];
+    /// let output = sequence_classification_model.predict(&input);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict<'a, S>(&self, input: S) -> Vec<Label>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let tokenized_input = self.encode_inputs(input.as_ref());
+        if self.log_truncation {
+            self.log_truncated_inputs(&tokenized_input);
+        }
+        self.predict_tokenized(tokenized_input)
+    }
+
+    /// Logs a warning with the number of inputs truncated to the maximum model length, if any
+    fn log_truncated_inputs(&self, tokenized_input: &[TokenizedInput]) {
+        let truncated_inputs = tokenized_input
+            .iter()
+            .filter(|input| input.num_truncated_tokens > 0)
+            .count();
+        if truncated_inputs > 0 {
+            log::warn!(
+                "{truncated_inputs} of {} inputs exceeded the maximum model length ({}) and were truncated",
+                tokenized_input.len(),
+                self.max_length
+            );
+        }
+    }
+
+    /// Classify tokenized inputs in batches fitting within the memory budget
+    fn predict_tokenized(&self, tokenized_input: Vec<TokenizedInput>) -> Vec<Label> {
+        let logits = self.forward_within_budget(tokenized_input);
+        self.get_top_labels(&self.calibrate(logits).softmax(-1, self.score_kind()))
+    }
+
+    /// Classify groups of texts (e.g. inputs of several requests merged by a batching server) in a single
+    /// prediction, returning the labels grouped by the original group boundaries. The `sentence` index of each label
+    /// refers to the position of the text within its group.
+    ///
+    /// # Arguments
+    ///
+    /// * `groups` - `&[&[&str]]` Groups of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<Label>>` containing the labels for each group of texts
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let first_request = ["This is a great movie.", "I did not like it at all."];
+    /// let second_request = ["An average film."];
+    /// let output =
+    ///     sequence_classification_model.predict_grouped(&[&first_request, &second_request]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_grouped(&self, groups: &[&[&str]]) -> Vec<Vec<Label>> {
+        let input = groups
+            .iter()
+            .flat_map(|group| group.iter().copied())
+            .collect::<Vec<&str>>();
+        let mut labels = self.predict(&input).into_iter();
+        groups
+            .iter()
+            .map(|group| {
+                labels
+                    .by_ref()
+                    .take(group.len())
+                    .enumerate()
+                    .map(|(sentence, mut label)| {
+                        label.sentence = sentence;
+                        label
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Classify texts, writing the labels to a caller-provided buffer
+    ///
+    /// The buffer is cleared at the start of the call, then filled with one label per input text. Re-using the
+    /// same buffer across calls avoids allocating a new `Vec<Label>` for each prediction (its capacity is retained).
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `out` - `&mut Vec<Label>` buffer receiving the labels for the input texts.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let mut labels = Vec::new();
+    /// for request in [["Great movie!"], ["What a waste of time."]] {
+    ///     sequence_classification_model.predict_into(&request, &mut labels);
+    ///     println!("{:?}", labels);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_into(&self, input: &[&str], out: &mut Vec<Label>) {
+        out.clear();
+        self.get_top_labels_into(&self.probabilities_within_budget(input), out)
+    }
+
+    /// Classify texts and attribute each label to the model version that produced it (e.g. to audit the decisions
+    /// logged by a service running several model versions). The model identifier is the model tag of the
+    /// configuration if provided, otherwise the identifier of the model weights resource (see `model_id`). Reloading
+    /// the weights (`reload_weights`) resets the model tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<LabelWithProvenance>` containing the label and the model identifier for each input text.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::{
+    ///     SequenceClassificationConfig, SequenceClassificationModel,
+    /// };
+    ///
+    /// let config = SequenceClassificationConfig {
+    ///     model_tag: Some("sentiment-v2.1".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let sequence_classification_model = SequenceClassificationModel::new(config)?;
+    /// let input = ["Probably my all-time favorite movie."];
+    /// for prediction in sequence_classification_model.predict_with_provenance(&input) {
+    ///     println!("{} ({:?})", prediction.label.text, prediction.model_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_provenance<'a, S>(&self, input: S) -> Vec<LabelWithProvenance>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let model_id = self.model_id().map(str::to_string);
+        self.predict(input)
+            .into_iter()
+            .map(|label| LabelWithProvenance {
+                label,
+                model_id: model_id.clone(),
+            })
+            .collect()
+    }
+
+    /// Classify texts and report the truncation of inputs exceeding the maximum model length
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(Label, TruncationReport)>` containing the label and the truncation report for each input text.
+    ///   The dropped spans are derived from the tokenizer offset mapping of the retained tokens, according to the
+    ///   configured `TruncationSide`. They refer to the preprocessed text (`preprocessed_text`) if the preprocessing
+    ///   modified the input.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let long_review = "This film tried to be too many things all at once. ".repeat(100);
+    /// let input = [long_review.as_str()];
+    /// for (label, report) in sequence_classification_model.predict_with_truncation_report(&input) {
+    ///     let text = report.preprocessed_text.as_deref().unwrap_or(input[0]);
+    ///     if let Some((start, end)) = report.dropped_byte_span {
+    ///         println!("{} (not classified: {:?})", label.text, &text[start..end]);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_truncation_report<'a, S>(&self, input: S) -> Vec<(Label, TruncationReport)>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let (preprocessed_input, tokenized_input) =
+            self.preprocess_and_encode_inputs(input.as_ref());
+        let reports = tokenized_input
+            .iter()
+            .zip(preprocessed_input)
+            .enumerate()
+            .map(|(sentence, (tokenized, text))| {
+                let truncated_tokens = tokenized.num_truncated_tokens;
+                let dropped_char_span = if truncated_tokens > 0 {
+                    Some(self.dropped_char_span(tokenized, text.chars().count()))
+                } else {
+                    None
+                };
+                let byte_offset = |char_offset: usize| {
+                    text.char_indices()
+                        .nth(char_offset)
+                        .map(|(byte_offset, _)| byte_offset)
+                        .unwrap_or(text.len())
+                };
+                let dropped_byte_span =
+                    dropped_char_span.map(|(start, end)| (byte_offset(start), byte_offset(end)));
+                TruncationReport {
+                    sentence,
+                    retained_tokens: tokenized.token_ids.len(),
+                    truncated_tokens,
+                    dropped_char_span,
+                    dropped_byte_span,
+                    preprocessed_text: match text {
+                        Cow::Owned(text) => Some(text),
+                        Cow::Borrowed(_) => None,
+                    },
+                }
+            })
+            .collect::<Vec<TruncationReport>>();
+
+        let labels = self.predict_tokenized(tokenized_input);
+        labels.into_iter().zip(reports).collect()
+    }
+
+    /// Encode a text as the model input, returning the token ids and the character offsets of each token in the text,
+    /// to align tokens with the source characters. The input is preprocessed, truncated and completed with the special
+    /// tokens as for `predict`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&str` Text to encode.
+    ///
+    /// # Returns
+    ///
+    /// * `(Vec<i64>, Vec<(usize, usize)>)` containing the token ids and the (start, end) character offsets of each
+    ///   token, `(0, 0)` for the special tokens. The offsets refer to the preprocessed text if a preprocessing callback
+    ///   or a normalization is configured.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = "This movie was unwatchable.";
+    /// let (token_ids, offsets) = sequence_classification_model.encode_with_offsets(input);
+    /// for (token_id, (start, end)) in token_ids.iter().zip(offsets) {
+    ///     let token_text = input.chars().skip(start).take(end - start).collect::<String>();
+    ///     println!("{token_id}: {token_text}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encode_with_offsets(&self, input: &str) -> (Vec<i64>, Vec<(usize, usize)>) {
+        let tokenized_input = self.encode_inputs(&[input]).remove(0);
+        let offsets = tokenized_input
+            .token_offsets
+            .iter()
+            .map(|offset| {
+                offset.map_or((0, 0), |offset| {
+                    (offset.begin as usize, offset.end as usize)
+                })
+            })
+            .collect();
+        (tokenized_input.token_ids, offsets)
+    }
+
+    /// Classify texts and return the unknown token rate of each input: the fraction of its tokens (excluding the
+    /// special tokens) mapped to the unknown token of the vocabulary. A high rate indicates a vocabulary mismatch
+    /// silently degrading the accuracy of the model, e.g. out-of-domain inputs (source code or an unexpected language
+    /// fed to an English model). The rates are computed from the tokenized inputs, without additional tokenization.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(Label, f64)>` containing the label and the unknown token rate (between 0 and 1, 0 for inputs without
+    ///   tokens) of each input text, or an `InvalidConfigurationError` if the model was built with a custom tokenizer
+    ///   not providing a `TokenizerOption`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["This is a great movie.", "这部电影很好看"];
+    /// for (label, unknown_token_rate) in
+    ///     sequence_classification_model.predict_with_unknown_token_rate(&input)?
+    /// {
+    ///     if unknown_token_rate > 0.1 {
+    ///         println!(
+    ///             "{} (possibly out-of-domain input, {:.0}% unknown tokens)",
+    ///             label.text,
+    ///             unknown_token_rate * 100.0
+    ///         );
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_unknown_token_rate<'a, S>(
+        &self,
+        input: S,
+    ) -> Result<Vec<(Label, f64)>, RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let unk_id = self.native_tokenizer()?.get_unk_id();
+        let tokenized_input = self.encode_inputs(input.as_ref());
+        let unknown_token_rates = tokenized_input
+            .iter()
+            .map(|tokenized| {
+                let content_ids = tokenized
+                    .token_ids
+                    .iter()
+                    .zip(tokenized.special_tokens_mask.iter())
+                    .filter(|(_, is_special)| **is_special == 0)
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<i64>>();
+                let num_tokens = content_ids.len();
+                let num_unknown_tokens = content_ids.iter().filter(|id| **id == unk_id).count();
+                if num_tokens > 0 {
+                    num_unknown_tokens as f64 / num_tokens as f64
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<f64>>();
+        let labels = self.predict_tokenized(tokenized_input);
+        Ok(labels.into_iter().zip(unknown_token_rates).collect())
+    }
+
+    /// Character range of a truncated input not seen by the model, between the retained head (the content tokens
+    /// kept at the beginning of the input) and the retained tail, given the truncation side
+    fn dropped_char_span(&self, tokenized: &TokenizedInput, num_chars: usize) -> (usize, usize) {
+        let content_offsets = tokenized
+            .token_offsets
+            .iter()
+            .zip(tokenized.special_tokens_mask.iter())
+            .filter(|(_, &special_token)| special_token == 0)
+            .map(|(offset, _)| *offset)
+            .collect::<Vec<Option<Offset>>>();
+        let head_tokens = match self.truncation_side {
+            TruncationSide::Right => content_offsets.len(),
+            TruncationSide::Left => 0,
+            TruncationSide::HeadTail { head } => min(head, content_offsets.len()),
+        };
+        let (head, tail) = content_offsets.split_at(head_tokens);
+        let start = head
+            .iter()
+            .flatten()
+            .last()
+            .map_or(0, |offset| offset.end as usize);
+        let end = tail
+            .iter()
+            .flatten()
+            .next()
+            .map_or(num_chars, |offset| offset.begin as usize);
+        (start, max(start, end))
+    }
+
+    /// Classify texts, recovering from out-of-memory errors
+    ///
+    /// The inputs are first classified as a single batch. If the device runs out of memory, the batch size is halved
+    /// and the prediction resumed from the failed batch (the labels of the batches already classified are kept), down
+    /// to a batch size of 1. If the model still runs out of memory, a `RustBertError::OutOfMemoryError` is returned.
+    ///
+    /// The forward pass goes through the fallible `SequenceClassificationOption::f_forward_t`: the errors it returns
+    /// are mapped to an `OutOfMemoryError` for out-of-memory errors, and returned unchanged otherwise. Running out of
+    /// memory is recovered from for TorchScript, ONNX, BERT, RoBERTa, XLM-RoBERTa and DistilBERT models, the other
+    /// native Torch models panic on a failed tensor operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Label>, RustBertError>` containing labels for input texts
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input = [
+    ///     "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+    ///     "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+    /// ];
+    /// let output = sequence_classification_model.try_predict(&input)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_predict<'a, S>(&self, input: S) -> Result<Vec<Label>, RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let input = input.as_ref();
+        let mut labels = Vec::with_capacity(input.len());
+        let mut batch_size = max(input.len(), 1);
+        let mut start = 0;
+        while start < input.len() {
+            let end = min(start + batch_size, input.len());
+            match self.try_predict_batch(&input[start..end]) {
+                Ok(batch_labels) => {
+                    labels.extend(batch_labels.into_iter().map(|mut label| {
+                        label.sentence += start;
+                        label
+                    }));
+                    start = end;
+                }
+                Err(RustBertError::OutOfMemoryError(_)) if end - start > 1 => {
+                    batch_size = (end - start) / 2;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(labels)
+    }
+
+    /// Check that texts only contain characters mapped by the tokenizer
+    ///
+    /// A character is unmappable if the tokenizer maps it to its unknown token (see `UnmappableCharacterPolicy`). The
+    /// texts are checked after the preprocessing callback and normalization (if any), allowing to reject the inputs
+    /// containing unmappable characters before classifying them.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to check.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), RustBertError>` a `ValueError` listing the unmappable characters of the first invalid text, or an
+    ///   `InvalidConfigurationError` for custom tokenizers
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["What a great film \u{1F980}"];
+    /// sequence_classification_model.check_unmappable_characters(&input)?;
+    /// let output = sequence_classification_model.predict(&input);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check_unmappable_characters<'a, S>(&self, input: S) -> Result<(), RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let tokenizer = self.native_tokenizer()?;
+        for (sentence, text) in input.as_ref().iter().enumerate() {
+            let unmappable_characters =
+                find_unmappable_characters(tokenizer, &self.apply_preprocessing(text));
+            if !unmappable_characters.is_empty() {
+                return Err(RustBertError::ValueError(format!(
+                    "Input {sentence} contains characters not mapped by the tokenizer: {unmappable_characters:?}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Classify texts provided as a JSON array of strings, returning the labels as a JSON array
+    ///
+    /// The (de)serialization is done internally, which is convenient for FFI bindings or for exchanging data
+    /// across process boundaries. The prediction recovers from out-of-memory errors (see `try_predict`).
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&str` JSON array of texts to classify (e.g. `["first text", "second text"]`).
+    ///
+    /// # Returns
+    ///
+    /// * `Result<String, RustBertError>` JSON array of labels (objects with the `text`, `score`, `id` and `sentence` fields),
+    ///   or a `ValueError` if the input is not a JSON array of strings
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let output = sequence_classification_model
+    ///     .predict_json(r#"["Great movie!", "What a waste of time."]"#)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_json(&self, input: &str) -> Result<String, RustBertError> {
+        let input: Vec<String> = serde_json::from_str(input).map_err(|error| {
+            RustBertError::ValueError(format!(
+                "Expected a JSON array of strings as input: {error}"
+            ))
+        })?;
+        let input = input.iter().map(String::as_str).collect::<Vec<&str>>();
+        let labels = self.try_predict(&input)?;
+        serde_json::to_string(&labels).map_err(|error| RustBertError::ValueError(error.to_string()))
+    }
+
+    /// Classify texts and write the predictions to a writer as delimited text (CSV or TSV)
+    ///
+    /// A header row (`sentence`, `label`, `score`, `id`) is written first, followed by one row per input. Labels
+    /// containing the delimiter, quotes or line breaks are quoted, with inner quotes doubled.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `writer` - `Write` destination of the rows (e.g. a file or standard output).
+    /// * `format` - `DelimitedFormat` (CSV or TSV)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(), RustBertError>` an `IOError` if the rows could not be written
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::{DelimitedFormat, SequenceClassificationModel};
+    /// use std::fs::File;
+    /// use std::io::BufWriter;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["Great movie!", "What a waste of time."];
+    /// let mut writer = BufWriter::new(File::create("predictions.csv")?);
+    /// sequence_classification_model.predict_to_writer(&input, &mut writer, DelimitedFormat::Csv)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_to_writer<W: Write>(
+        &self,
+        input: &[&str],
+        mut writer: W,
+        format: DelimitedFormat,
+    ) -> Result<(), RustBertError> {
+        let delimiter = format.delimiter();
+        writeln!(
+            writer,
+            "sentence{delimiter}label{delimiter}score{delimiter}id"
+        )?;
+        for label in self.try_predict(input)? {
+            writeln!(
+                writer,
+                "{}{delimiter}{}{delimiter}{}{delimiter}{}",
+                label.sentence,
+                format.escape(&label.text),
+                label.score,
+                label.id
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn try_predict_batch(&self, input: &[&str]) -> Result<Vec<Label>, RustBertError> {
+        let tokenized_input = self.encode_inputs(input);
+        if self.log_truncation {
+            self.log_truncated_inputs(&tokenized_input);
+        }
+        let logits = self.try_forward_within_budget(tokenized_input)?;
+        Ok(self.get_top_labels(&self.calibrate(logits).softmax(-1, self.score_kind())))
+    }
+
+    /// Fallible forward pass of a batch, mapping the out-of-memory errors to an `OutOfMemoryError` (see `try_predict`)
+    fn try_forward_logits(&self, batch: &PreparedBatch) -> Result<Tensor, RustBertError> {
+        no_grad(|| {
+            self.sequence_classifier.f_forward_t(
+                Some(&batch.input_ids),
+                Some(&batch.attention_mask),
+                Some(&batch.token_type_ids),
+                None,
+                None,
+                false,
+            )
+        })
+        .map(|logits| logits.detach())
+        .map_err(Self::out_of_memory_error)
+    }
+
+    /// Maps the tensor errors reporting an exhausted device memory to an `OutOfMemoryError`
+    fn out_of_memory_error(error: RustBertError) -> RustBertError {
+        match error {
+            RustBertError::TchError(message) if message.contains("out of memory") => {
+                RustBertError::OutOfMemoryError(message)
+            }
+            error => error,
+        }
+    }
+
+    /// Encodes sentence pairs, the token type ids distinguishing the first and second sentence of each pair
+    fn encode_pairs(&self, input: &[(&str, &str)]) -> Result<Vec<TokenizedInput>, RustBertError> {
+        let tokenizer = self.native_tokenizer()?;
+        let preprocessed_input = input
+            .iter()
+            .map(|(first, second)| (self.preprocess(first), self.preprocess(second)))
+            .collect::<Vec<_>>();
+        let preprocessed_input = preprocessed_input
+            .iter()
+            .map(|(first, second)| (first.as_ref(), second.as_ref()))
+            .collect::<Vec<_>>();
+        Ok(tokenizer.encode_pair_list(
+            &preprocessed_input,
+            self.max_length,
+            &TruncationStrategy::LongestFirst,
+            0,
+        ))
+    }
+
+    /// Classify sentence pairs (e.g. paraphrase detection or natural language inference)
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[(&str, &str)]` Array of sentence pairs to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Label>` containing labels for the sentence pairs, or an `InvalidConfigurationError` if the model was
+    ///   built with a custom tokenizer not providing a `TokenizerOption`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input = [(
+    ///     "The company posted record profits this quarter.",
+    ///     "Quarterly earnings reached an all-time high.",
+    /// )];
+    /// let output = sequence_classification_model.predict_pairs(&input)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_pairs(&self, input: &[(&str, &str)]) -> Result<Vec<Label>, RustBertError> {
+        Ok(self.predict_tokenized(self.encode_pairs(input)?))
+    }
+
+    /// Classify inputs made of several segments, each segment being identified by its position in the input
+    /// as token type id (see `prepare_segments_batch`)
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&[&str]]` Array of inputs to classify, each given as its list of segments.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Label>` containing labels for the inputs, or a `ValueError` if an input contains more segments than
+    ///   the token types supported by the model (see `prepare_segments_batch` for the other errors)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// # let config = Default::default();
+    /// // Model fine-tuned with three token types
+    /// let sequence_classification_model = SequenceClassificationModel::new(config)?;
+    /// let question = "Where is the Eiffel tower?";
+    /// let context = "The Eiffel tower is a landmark of Paris.";
+    /// let answer = "Paris";
+    /// let output = sequence_classification_model.predict_segments(&[&[question, context, answer]])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_segments(&self, input: &[&[&str]]) -> Result<Vec<Label>, RustBertError> {
+        Ok(self.predict_prepared(&self.prepare_segments_batch(input)?))
+    }
+
+    /// Returns the score of the last label for sentence pairs classified in batches fitting within the memory budget,
+    /// calibrated as the other predictions (temperature and `apply_softmax`): the sigmoid of the output for models with
+    /// a single output, the probability of the last label otherwise. Used by the cross-encoder pipelines (reranking
+    /// and similarity).
+    pub(crate) fn predict_pairs_last_label_scores(
+        &self,
+        input: &[(&str, &str)],
+    ) -> Result<Tensor, RustBertError> {
+        let logits = self.forward_within_budget(self.encode_pairs(input)?);
+        let scores = if logits.size()[1] == 1 {
+            self.calibrate_multilabel(logits).squeeze_dim(-1).sigmoid()
+        } else {
+            self.calibrate(logits)
+                .softmax(-1, self.score_kind())
+                .select(-1, -1)
+        };
+        Ok(scores.to_kind(Kind::Double).to(Device::Cpu))
+    }
+
+    /// Classify inputs from precomputed input embeddings
+    ///
+    /// The embeddings replace the output of the word embeddings layer of the model (`input_embeds`), allowing to cache
+    /// embeddings and re-run the rest of the model (e.g. after fine-tuning the classification head). Not supported for
+    /// models that require token ids (BART, Reformer, T5 and ONNX models).
+    ///
+    /// # Arguments
+    ///
+    /// * `embeddings` - `Tensor` of shape (*batch size*, *sequence length*, *embedding size*) containing the input embeddings.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Label>, RustBertError>` containing labels for the inputs, or an error if the model does not accept
+    ///   input embeddings or if the embedding dimension does not match the model configuration
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// use tch::{Device, Kind, Tensor};
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let embeddings = Tensor::rand([2, 16, 768], (Kind::Float, Device::Cpu));
+    /// let output = sequence_classification_model.classify_embeddings(&embeddings)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn classify_embeddings(&self, embeddings: &Tensor) -> Result<Vec<Label>, RustBertError> {
+        let model_type = self.sequence_classifier.model_type();
+        let embedding_size = match (model_type, self.embedding_size) {
+            (ModelType::Bart | ModelType::Reformer | ModelType::T5, _) | (_, None) => {
+                return Err(RustBertError::InvalidConfigurationError(format!(
+                    "Classification from input embeddings is not supported for {model_type:?}"
+                )));
+            }
+            (_, Some(embedding_size)) => embedding_size,
+        };
+        let size = embeddings.size();
+        if size.len() != 3 || size[2] != embedding_size {
+            return Err(RustBertError::TensorShapeError(format!(
+                "Expected input embeddings of shape (batch size, sequence length, {embedding_size}), got {size:?}"
+            )));
+        }
+        let embeddings = match self.dtype {
+            Some(dtype) => embeddings.to_kind(dtype).to(self.device),
+            None => embeddings.to(self.device),
+        };
+        let output = no_grad(|| {
+            self.sequence_classifier
+                .forward_t(None, None, None, None, Some(&embeddings), false)
+                .detach()
+        });
+        Ok(self.get_top_labels(&self.calibrate(output).softmax(-1, self.score_kind())))
+    }
+
+    /// Classify a batch of inputs already tokenized and padded by the caller (e.g. by a data loader), skipping all
+    /// the text processing of the pipeline. The inputs must include the special tokens expected by the model.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_ids` - `Tensor` of shape (*batch size*, *sequence length*) containing the token ids of the inputs.
+    /// * `attention_mask` - Optional `Tensor` of shape (*batch size*, *sequence length*), with value 1 for the tokens
+    ///   to attend to and 0 for the padding. If None, all tokens are attended to.
+    /// * `token_type_ids` - Optional `Tensor` of shape (*batch size*, *sequence length*) containing the segment id of
+    ///   each token (e.g. 0 for the first and 1 for the second sentence of a pair). If None, set to 0.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Label>, RustBertError>` containing labels for the inputs, or a `TensorShapeError` if the input ids
+    ///   are not a 2-dimensional tensor or if the attention mask or token type ids shape differs from the input ids
+    ///   shape
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// use tch::Tensor;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input_ids = Tensor::from_slice2(&[[101, 2023, 3185, 2001, 2307, 102], [101, 6659, 102, 0, 0, 0]]);
+    /// let attention_mask = input_ids.ne(0);
+    /// let output = sequence_classification_model.predict_tensor(input_ids, Some(attention_mask), None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_tensor(
+        &self,
+        input_ids: Tensor,
+        attention_mask: Option<Tensor>,
+        token_type_ids: Option<Tensor>,
+    ) -> Result<Vec<Label>, RustBertError> {
+        let size = input_ids.size();
+        if size.len() != 2 {
+            return Err(RustBertError::TensorShapeError(format!(
+                "Expected input ids of shape (batch size, sequence length), got {size:?}"
+            )));
+        }
+        for (name, tensor) in [
+            ("attention mask", &attention_mask),
+            ("token type ids", &token_type_ids),
+        ] {
+            if let Some(tensor) = tensor {
+                if tensor.size() != size {
+                    return Err(RustBertError::TensorShapeError(format!(
+                        "The {name} shape {:?} does not match the input ids shape {size:?}",
+                        tensor.size()
+                    )));
+                }
+            }
+        }
+        let input_ids = input_ids.to(self.device);
+        let attention_mask = match attention_mask {
+            Some(attention_mask) => attention_mask.to_device(self.device).to_kind(Kind::Int64),
+            None => input_ids.ones_like(),
+        };
+        let token_type_ids = match token_type_ids {
+            Some(token_type_ids) => token_type_ids.to_device(self.device).to_kind(Kind::Int64),
+            None => input_ids.zeros_like(),
+        };
+        let batch = PreparedBatch {
+            input_ids,
+            token_type_ids,
+            attention_mask,
+        };
+
+        let output = self.try_forward_logits(&batch)?;
+        Ok(self.get_top_labels(&self.calibrate(output).softmax(-1, self.score_kind())))
+    }
+
+    /// Classify texts with a cost-sensitive decision rule: the probability of each label is weighted by the prior
+    /// (or utility) of the label before selecting the best label, instead of selecting the most probable label.
+    /// Lower weights on over-represented classes or on classes whose false positives are costly shift the
+    /// decisions towards the other classes.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `priors` - `&[f64]` Non-negative weight of each label, in the order of increasing label id.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Label>, RustBertError>` containing the selected label for input texts, or a `ValueError` if the
+    ///   number of priors differs from the number of labels or if a prior is negative or not finite. The score of each
+    ///   label is its (unweighted) probability predicted by the model.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["An average film.", "What a waste of time."];
+    /// // Only predict the positive label (id 1) if it is twice as likely as the negative label
+    /// let output = sequence_classification_model.predict_with_priors(&input, &[2.0, 1.0])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_priors<'a, S>(
+        &self,
+        input: S,
+        priors: &[f64],
+    ) -> Result<Vec<Label>, RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        if priors.len() != self.label_mapping.len() {
+            return Err(RustBertError::ValueError(format!(
+                "One prior must be provided for each label: got {} priors for {} labels",
+                priors.len(),
+                self.label_mapping.len()
+            )));
+        }
+        if let Some(invalid_prior) = priors
+            .iter()
+            .find(|prior| !prior.is_finite() || **prior < 0.0)
+        {
+            return Err(RustBertError::ValueError(format!(
+                "The priors must be non-negative and finite, got {invalid_prior}"
+            )));
+        }
+        let probabilities = self.probabilities_within_budget(input);
+        let priors = Tensor::from_slice(priors)
+            .to_kind(probabilities.kind())
+            .to(probabilities.device());
+        let label_indices = (&probabilities * priors).argmax(-1, true);
+        let scores = probabilities
+            .gather(-1, &label_indices, false)
+            .squeeze_dim(-1)
+            .to(Device::Cpu);
+        let label_indices = label_indices.squeeze_dim(-1).to(Device::Cpu);
+
+        Ok(label_indices
+            .iter::<i64>()
+            .unwrap()
+            .zip(scores.iter::<f64>().unwrap())
+            .enumerate()
+            .map(|(sentence_idx, (label_index, score))| {
+                self.output_label(label_index, score, sentence_idx)
+            })
+            .collect())
+    }
+
+    /// Classify texts, restricting the prediction to a subset of the labels (e.g. the labels valid in a given context
+    /// for hierarchical classification). The logits of the other labels are masked before the softmax, so that the
+    /// scores form a probability distribution over the allowed labels.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `allowed_ids` - `&[i64]` Ids of the labels that can be predicted.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Label>, RustBertError>` containing the best allowed label for input texts, or a `ValueError` if
+    ///   `allowed_ids` is empty or contains an id that is not a label id of the model
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["This is a great movie.", "What a waste of time."];
+    /// let output = sequence_classification_model.predict_restricted(&input, &[0])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_restricted<'a, S>(
+        &self,
+        input: S,
+        allowed_ids: &[i64],
+    ) -> Result<Vec<Label>, RustBertError>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        if allowed_ids.is_empty() {
+            return Err(RustBertError::ValueError(
+                "At least one label must be allowed".to_string(),
+            ));
+        }
+        let allowed_positions = allowed_ids
+            .iter()
+            .map(|label_id| {
+                self.output_position(*label_id).ok_or_else(|| {
+                    RustBertError::ValueError(format!(
+                        "Allowed label id {label_id} is not a label of the model"
+                    ))
+                })
+            })
+            .collect::<Result<Vec<i64>, RustBertError>>()?;
+        let logits = self.calibrate(self.logits_within_budget(input));
+        let num_labels = logits.size()[1];
+        let allowed_positions = Tensor::from_slice(&allowed_positions).to(logits.device());
+        let disallowed_mask = Tensor::ones([num_labels], (Kind::Bool, logits.device())).index_fill(
+            0,
+            &allowed_positions,
+            0,
+        );
+        let output = logits
+            .masked_fill(&disallowed_mask, f64::NEG_INFINITY)
+            .softmax(-1, self.score_kind());
+        Ok(self.get_top_labels(&output))
+    }
+
+    /// Classify texts and return the class probabilities as a tensor
+    ///
+    /// Lower-level variant of `predict` that skips the label post-processing and leaves the output on the requested
+    /// device, avoiding a transfer to the CPU when the probabilities are consumed by a further stage on the GPU.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `output_device` - `Device` on which the output tensor should be placed.
+    ///
+    /// # Returns
+    ///
+    /// * `Tensor` of shape (*batch size*, *number of labels*) containing the softmax probabilities of each label
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    /// use tch::Device;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input = [
+    ///     "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+    ///     "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+    /// ];
+    /// let output = sequence_classification_model.predict_scores_tensor(&input, Device::cuda_if_available());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_scores_tensor<'a, S>(&self, input: S, output_device: Device) -> Tensor
+    where
+        S: AsRef<[&'a str]>,
+    {
+        self.probabilities_within_budget(input).to(output_device)
+    }
+
+    /// Maps the scores of every label (columns of the output) to the label names, for each input (rows of the output)
+    fn scores_to_maps(&self, output: Tensor) -> Vec<HashMap<String, f64>> {
+        let output = output.to(Device::Cpu);
+        let (batch_size, num_labels) = (output.size()[0], output.size()[1]);
+        (0..batch_size)
+            .map(|sentence_idx| {
+                let sentence_scores = output.get(sentence_idx);
+                (0..num_labels)
+                    .map(|position| {
+                        (
+                            self.label_mapping[&self.output_label_ids[position as usize]].clone(),
+                            sentence_scores.double_value(&[position]),
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Classify texts longer than the maximum model input length
+    ///
+    /// Each input is split into overlapping windows fitting the model maximum length. Every window is classified
+    /// and the window logits are aggregated into a single prediction per input text. With `Aggregation::Vote`,
+    /// the majority label of the windows is returned, which can be more robust than averaging the logits for
+    /// documents with mixed content.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `stride` - `usize` number of tokens shared by consecutive windows.
+    /// * `aggregation` - `Aggregation` used to combine the logits of the windows of an input.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Label>` containing a label for each input text, or an `InvalidConfigurationError` if the model was built
+    ///   with a custom tokenizer not providing a `TokenizerOption`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::{Aggregation, SequenceClassificationModel};
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let long_review = std::fs::read_to_string("path/to/review.txt")?;
+    /// let output = sequence_classification_model.predict_long(&[&long_review], 128, Aggregation::Mean)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_long(
+        &self,
+        input: &[&str],
+        stride: usize,
+        aggregation: Aggregation,
+    ) -> Result<Vec<Label>, RustBertError> {
+        let mut windows: Vec<Vec<i64>> = vec![];
+        let mut segment_ids: Vec<Vec<i64>> = vec![];
+        let mut window_boundaries: Vec<(usize, usize)> = Vec::with_capacity(input.len());
+        for text in input {
+            let start = windows.len();
+            for window in self.generate_windows(text, stride)? {
+                windows.push(window.token_ids);
+                segment_ids.push(
+                    window
+                        .segment_ids
+                        .into_iter()
+                        .map(|segment_id| segment_id as i64)
+                        .collect(),
+                );
+            }
+            window_boundaries.push((start, windows.len()));
+        }
+        if windows.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let pad_id = self
+            .tokenizer
+            .get_pad_id()
+            .expect("The Tokenizer used for sequence classification should contain a PAD id");
+        let max_len = windows.iter().map(|window| window.len()).max().unwrap();
+        let input_ids = windows
+            .iter_mut()
+            .map(|window| {
+                window.resize(max_len, pad_id);
+                Tensor::from_slice(window)
+            })
+            .collect::<Vec<_>>();
+        let token_type_ids = segment_ids
+            .iter_mut()
+            .map(|window_segment_ids| {
+                let last_segment_id = *window_segment_ids.last().unwrap_or(&0);
+                window_segment_ids.resize(max_len, last_segment_id);
+                Tensor::from_slice(window_segment_ids)
+            })
+            .collect::<Vec<_>>();
+        let input_ids = Tensor::stack(input_ids.as_slice(), 0).to(self.device);
+        let token_type_ids = Tensor::stack(token_type_ids.as_slice(), 0).to(self.device);
+        let mask = input_ids.ne(pad_id).to_kind(Kind::Int64);
+
+        let logits = no_grad(|| {
+            self.sequence_classifier
+                .forward_t(
+                    Some(&input_ids),
+                    Some(&mask),
+                    Some(&token_type_ids),
+                    None,
+                    None,
+                    false,
+                )
+                .detach()
+        });
+        let logits = self.calibrate(logits);
+
+        let output =
+            aggregate_probabilities(&logits, &window_boundaries, aggregation, self.score_kind());
+        Ok(self.get_top_labels(&output))
+    }
+
+    fn generate_windows(
+        &self,
+        text: &str,
+        stride: usize,
+    ) -> Result<Vec<TokenizedInput>, RustBertError> {
+        let tokenizer = self.native_tokenizer()?;
+        let tokenized_input = tokenizer.tokenize_with_offsets(&self.preprocess(text));
+        let encoded_input = TokenIdsWithOffsets {
+            ids: tokenizer.convert_tokens_to_ids(&tokenized_input.tokens),
+            offsets: tokenized_input.offsets,
+            reference_offsets: tokenized_input.reference_offsets,
+            masks: tokenized_input.masks,
+        };
+
+        let max_content_length = self
+            .max_length
+            .saturating_sub(sequence_added_tokens(tokenizer))
+            .max(1);
+        let stride = min(stride, max_content_length - 1);
+
+        let mut windows = vec![];
+        let mut start_token = 0_usize;
+        let total_length = encoded_input.ids.len();
+        loop {
+            let end_token = min(start_token + max_content_length, total_length);
+            let sub_encoded_input = TokenIdsWithOffsets {
+                ids: encoded_input.ids[start_token..end_token].to_vec(),
+                offsets: encoded_input.offsets[start_token..end_token].to_vec(),
+                reference_offsets: encoded_input.reference_offsets[start_token..end_token].to_vec(),
+                masks: encoded_input.masks[start_token..end_token].to_vec(),
+            };
+            windows.push(tokenizer.build_input_with_special_tokens(sub_encoded_input, None));
+            if end_token == total_length {
+                break;
+            }
+            start_token = end_token - stride;
+        }
+        Ok(windows)
+    }
+
+    fn forward_logits(&self, batch: &PreparedBatch) -> Tensor {
+        no_grad(|| {
+            self.sequence_classifier
+                .forward_t(
+                    Some(&batch.input_ids),
+                    Some(&batch.attention_mask),
+                    Some(&batch.token_type_ids),
+                    None,
+                    None,
+                    false,
+                )
+                .detach()
+        })
+    }
+
+    /// Applies the preprocessing callback and normalization (if any) and the unmappable characters policy to an input
+    /// text
+    fn preprocess<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let text = self.apply_preprocessing(text);
+        // Custom tokenizers are rejected at build time for the policies other than `TokenizerDefault`
+        let tokenizer = match self.tokenizer.as_tokenizer_option() {
+            Some(tokenizer)
+                if self.unmappable_characters != UnmappableCharacterPolicy::TokenizerDefault =>
+            {
+                tokenizer
+            }
+            _ => return text,
+        };
+        let unmappable_characters = find_unmappable_characters(tokenizer, &text);
+        if unmappable_characters.is_empty() {
+            return text;
+        }
+        match self.unmappable_characters {
+            UnmappableCharacterPolicy::TokenizerDefault => text,
+            UnmappableCharacterPolicy::Skip => Cow::Owned(
+                text.chars()
+                    .filter(|character| !unmappable_characters.contains(character))
+                    .collect(),
+            ),
+            UnmappableCharacterPolicy::ReplaceWithUnknown => {
+                let unknown_token = tokenizer.decode(&[tokenizer.get_unk_id()], false, false);
+                let mut replaced_text = String::with_capacity(text.len());
+                for character in text.chars() {
+                    if unmappable_characters.contains(&character) {
+                        replaced_text.push_str(&unknown_token);
+                    } else {
+                        replaced_text.push(character);
+                    }
+                }
+                Cow::Owned(replaced_text)
+            }
+        }
+    }
+
+    /// Applies the preprocessing callback and normalization (if any) to an input text
+    fn apply_preprocessing<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let text = match &self.preprocessing {
+            Some(preprocessing) => Cow::Owned(preprocessing(text)),
+            None => Cow::Borrowed(text),
+        };
+        match &self.normalization {
+            Some(normalization) => normalization.normalize(text),
+            None => text,
+        }
+    }
+
+    fn encode_inputs(&self, input: &[&str]) -> Vec<TokenizedInput> {
+        self.preprocess_and_encode_inputs(input).1
+    }
+
+    /// Encodes the inputs, returning the preprocessed texts (borrowing the inputs left unchanged by the
+    /// preprocessing) along with the tokenized inputs
+    fn preprocess_and_encode_inputs<'a>(
+        &self,
+        input: &[&'a str],
+    ) -> (Vec<Cow<'a, str>>, Vec<TokenizedInput>) {
+        let preprocessed_input = input
+            .iter()
+            .map(|text| self.preprocess(text))
+            .collect::<Vec<Cow<'a, str>>>();
+        let mut tokenized_input = self.encode_preprocessed_inputs(
+            &preprocessed_input
+                .iter()
+                .map(|text| text.as_ref())
+                .collect::<Vec<&str>>(),
+        );
+        // BART uses the hidden state of the EOS token as sentence representation
+        if let SequenceClassificationOption::Bart(ref model) = self.sequence_classifier {
+            for tokenized_input in tokenized_input.iter_mut() {
+                terminate_with_eos(tokenized_input, model.eos_token_id(), self.max_length);
+            }
+        }
+        (preprocessed_input, tokenized_input)
+    }
+
+    fn encode_preprocessed_inputs(&self, input: &[&str]) -> Vec<TokenizedInput> {
+        // Custom tokenizers are rejected at build time for a truncation side other than `Right` or disabled special
+        // tokens
+        match self.tokenizer.as_tokenizer_option() {
+            Some(tokenizer) if self.truncation_side != TruncationSide::Right => input
+                .iter()
+                .map(|text| self.encode_side_truncated(tokenizer, text))
+                .collect(),
+            Some(tokenizer) if !self.add_special_tokens => {
+                tokenizer.encode_list_without_special_tokens(input, self.max_length)
+            }
+            _ => self.tokenizer.encode_list(
+                input,
+                self.max_length,
+                &TruncationStrategy::LongestFirst,
+                0,
+            ),
+        }
+    }
+
+    /// Encodes a text truncated from the configured side if it exceeds the maximum model length. The special tokens
+    /// (if enabled) are added to the retained tokens.
+    fn encode_side_truncated(&self, tokenizer: &TokenizerOption, text: &str) -> TokenizedInput {
+        let tokens = tokenizer.tokenize_with_offsets(text);
+        let token_ids = tokenizer.convert_tokens_to_ids(&tokens.tokens);
+        let max_content_length = if self.add_special_tokens {
+            self.max_length
+                .saturating_sub(sequence_added_tokens(tokenizer))
+                .max(1)
+        } else {
+            self.max_length
+        };
+        // The retained tokens are the tokens before `head_end` and from `tail_start`
+        let num_tokens = token_ids.len();
+        let (head_end, tail_start) = match self.truncation_side {
+            _ if num_tokens <= max_content_length => (num_tokens, num_tokens),
+            TruncationSide::Right => (max_content_length, num_tokens),
+            TruncationSide::Left => (0, num_tokens - max_content_length),
+            TruncationSide::HeadTail { head } => {
+                let head_end = min(head, max_content_length);
+                (head_end, num_tokens - (max_content_length - head_end))
+            }
+        };
+        let retained_input = TokenIdsWithOffsets {
+            ids: retain_head_tail(&token_ids, head_end, tail_start),
+            offsets: retain_head_tail(&tokens.offsets, head_end, tail_start),
+            reference_offsets: retain_head_tail(&tokens.reference_offsets, head_end, tail_start),
+            masks: retain_head_tail(&tokens.masks, head_end, tail_start),
+        };
+        let mut tokenized_input = if self.add_special_tokens {
+            tokenizer.build_input_with_special_tokens(retained_input, None)
+        } else {
+            let num_tokens = retained_input.ids.len();
+            TokenizedInput {
+                token_ids: retained_input.ids,
+                segment_ids: vec![0; num_tokens],
+                special_tokens_mask: vec![0; num_tokens],
+                overflowing_tokens: vec![],
+                num_truncated_tokens: 0,
+                token_offsets: retained_input.offsets,
+                reference_offsets: retained_input.reference_offsets,
+                mask: retained_input.masks,
+            }
+        };
+        tokenized_input.num_truncated_tokens = tail_start - head_end;
+        tokenized_input
+    }
+
+    /// Precision of the label scores: double precision for models running in double precision, single precision
+    /// otherwise
+    fn score_kind(&self) -> Kind {
+        match self.dtype {
+            Some(Kind::Double) => Kind::Double,
+            _ => Kind::Float,
+        }
+    }
+
+    fn forward_probabilities(&self, batch: &PreparedBatch) -> Tensor {
+        self.calibrate(self.forward_logits(batch))
+            .softmax(-1, self.score_kind())
+    }
+
+    /// Returns the logits of the model output, to be normalized with a softmax, divided by the configured temperature.
+    /// The output of models applying the softmax in their graph is converted to log-probabilities.
+    fn calibrate(&self, output: Tensor) -> Tensor {
+        let logits = if self.apply_softmax {
+            output
+        } else {
+            output.clamp_min(1e-12).log()
+        };
+        self.scale_temperature(logits)
+    }
+
+    /// Returns the logits of the model output, to be normalized with a sigmoid, divided by the configured temperature.
+    /// The output of models applying the sigmoid in their graph is converted back to logits.
+    fn calibrate_multilabel(&self, output: Tensor) -> Tensor {
+        let logits = if self.apply_softmax {
+            output
+        } else {
+            output.logit(Some(1e-7))
+        };
+        self.scale_temperature(logits)
+    }
+
+    /// Divides the logits by the configured temperature
+    fn scale_temperature(&self, logits: Tensor) -> Tensor {
+        if self.temperature == 1.0 {
+            logits
+        } else {
+            logits / self.temperature
+        }
+    }
+
+    fn get_top_labels(&self, output: &Tensor) -> Vec<Label> {
+        let mut labels: Vec<Label> = vec![];
+        self.get_top_labels_into(output, &mut labels);
+        labels
+    }
+
+    fn get_top_labels_into(&self, output: &Tensor, labels: &mut Vec<Label>) {
+        // The label selection runs on the device holding the probabilities (e.g. the GPU):
+        // only the best label indices and scores are copied to the CPU.
+        let (scores, label_indices) = output.max_dim(-1, false);
+        let label_indices = label_indices.to(Device::Cpu);
+        let scores = scores.to(Device::Cpu);
+
+        labels.reserve(label_indices.size()[0] as usize);
+        for (sentence_idx, (label_index, score)) in label_indices
+            .iter::<i64>()
+            .unwrap()
+            .zip(scores.iter::<f64>().unwrap())
+            .enumerate()
+        {
+            labels.push(self.output_label(label_index, score, sentence_idx))
+        }
+    }
+
+    /// Returns the label of a model output, `position` being the index of the label in the output tensor
+    fn output_label(&self, position: i64, score: f64, sentence: usize) -> Label {
+        let id = self.output_label_ids[position as usize];
+        Label {
+            text: self.label_mapping[&id].clone(),
+            score,
+            id,
+            sentence,
+        }
+    }
+
+    /// Returns the index in the output tensor of a label id, if it is a label of the model
+    fn output_position(&self, label_id: i64) -> Option<i64> {
+        self.output_label_ids
+            .binary_search(&label_id)
+            .ok()
+            .map(|position| position as i64)
+    }
+
+    /// Multi-label classification of texts
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `threshold` - `f64` threshold above which a label will be considered true by the classifier
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<Label>>` containing a vector of true labels for each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input = [
+    ///     "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+    ///     "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+    ///     "If you like original gut wrenching laughter you will like this movie. If you are young or old then you will love this movie, hell even my mom liked it.",
+    /// ];
+    /// let output = sequence_classification_model.predict_multilabel(&input, 0.5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_multilabel(
+        &self,
+        input: &[&str],
+        threshold: f64,
+    ) -> Result<Vec<Vec<Label>>, RustBertError> {
+        let output = self
+            .calibrate_multilabel(self.logits_within_budget(input))
+            .sigmoid()
+            .to(Device::Cpu);
+        let label_indices = output.as_ref().ge(threshold).nonzero();
+
+        let mut labels: Vec<Vec<Label>> = vec![];
+        let mut sequence_labels: Vec<Label> = vec![];
+
+        for sentence_idx in 0..label_indices.size()[0] {
+            let label_index_tensor = label_indices.get(sentence_idx);
+            let sentence_label = label_index_tensor
+                .iter::<i64>()
+                .unwrap()
+                .collect::<Vec<i64>>();
+            let (sentence, position) = (sentence_label[0], sentence_label[1]);
+            if sentence as usize > labels.len() {
+                labels.push(sequence_labels);
+                sequence_labels = vec![];
+            }
+            let score = output.double_value(sentence_label.as_slice());
+            sequence_labels.push(self.output_label(position, score, sentence as usize));
+        }
+        if !sequence_labels.is_empty() {
+            labels.push(sequence_labels);
+        }
+        Ok(labels)
+    }
+
+    /// Multi-label classification of texts, returning one prediction for each input text (in the input order), even
+    /// if no label exceeds the threshold. In that case, the prediction holds the single top label if
+    /// `fallback_to_top_label` is true (with its `fallback` flag set), and no label otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `threshold` - `f64` threshold above which a label will be considered true by the classifier
+    /// * `fallback_to_top_label` - `bool` flag indicating if the top label should be returned for inputs without any
+    ///   label exceeding the threshold
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<MultiLabelPrediction>` containing the labels of each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["An average film.", "What a waste of time."];
+    /// let output =
+    ///     sequence_classification_model.predict_multilabel_with_fallback(&input, 0.9, true)?;
+    /// for prediction in output {
+    ///     if prediction.fallback {
+    ///         println!("Low confidence: {}", prediction.labels[0].text);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_multilabel_with_fallback(
+        &self,
+        input: &[&str],
+        threshold: f64,
+        fallback_to_top_label: bool,
+    ) -> Result<Vec<MultiLabelPrediction>, RustBertError> {
+        Ok(self
+            .predict_multilabel_all_scores(input)?
+            .into_iter()
+            .map(|all_labels| {
+                let top_label = all_labels
+                    .iter()
+                    .max_by(|label_1, label_2| label_1.score.total_cmp(&label_2.score))
+                    .cloned();
+                let labels = all_labels
+                    .into_iter()
+                    .filter(|label| label.score >= threshold)
+                    .collect::<Vec<Label>>();
+                match top_label {
+                    Some(top_label) if labels.is_empty() && fallback_to_top_label => {
+                        MultiLabelPrediction {
+                            labels: vec![top_label],
+                            fallback: true,
+                        }
+                    }
+                    _ => MultiLabelPrediction {
+                        labels,
+                        fallback: false,
+                    },
+                }
+            })
+            .collect())
+    }
+
+    /// Classify texts, returning the top label of each input along with the outputs requested in the options (decision
+    /// margin, probabilities or log-probabilities of every label, multi-label set), all derived from a single forward
+    /// pass. The texts are classified in batches fitting within the memory budget.
+    ///
+    /// The outputs are normalized differently: the top label, margin and scores use the **softmax** distribution over
+    /// the labels (the scores of all labels summing to one), while the multi-label set is based on the **sigmoid**
+    /// score of each label, computed independently.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    /// * `options` - `&PredictOptions` outputs to compute in addition to the top label.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Prediction>` containing the top label and the requested outputs for each input text (in the input order)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::{
+    ///     Label, PredictOptions, SequenceClassificationModel,
+    /// };
+    ///
+    /// enum Sentiment {
+    ///     Positive,
+    ///     Negative,
+    /// }
+    ///
+    /// impl From<Label> for Sentiment {
+    ///     fn from(label: Label) -> Self {
+    ///         match label.text.as_str() {
+    ///             "POSITIVE" => Sentiment::Positive,
+    ///             _ => Sentiment::Negative,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    /// let input = ["An average film.", "What a waste of time."];
+    /// let options = PredictOptions {
+    ///     margin: true,
+    ///     scores: true,
+    ///     ..Default::default()
+    /// };
+    /// for prediction in sequence_classification_model.predict_with_options(&input, &options) {
+    ///     if prediction.margin.unwrap() < 0.2 {
+    ///         println!("Uncertain prediction: {:?}", prediction.scores.unwrap());
+    ///     }
+    ///     let sentiment = Sentiment::from(prediction.label);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_with_options<'a, S>(&self, input: S, options: &PredictOptions) -> Vec<Prediction>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        if input.as_ref().is_empty() {
+            return vec![];
+        }
+        let logits = self.logits_within_budget(input);
+        let calibrated_logits = self.calibrate(logits.shallow_clone());
+        let probabilities = calibrated_logits.softmax(-1, self.score_kind());
+        let top_labels = self.get_top_labels(&probabilities);
+
+        let mut margins = if options.margin {
+            let margins = if probabilities.size()[1] > 1 {
+                let (top_scores, _) = probabilities.topk(2, -1, true, true);
+                top_scores.select(1, 0) - top_scores.select(1, 1)
+            } else {
+                probabilities.select(1, 0)
+            };
+            Some(
+                margins
+                    .to_kind(Kind::Double)
+                    .to(Device::Cpu)
+                    .iter::<f64>()
+                    .unwrap(),
+            )
+        } else {
+            None
+        };
+        let mut scores = if options.scores {
+            Some(self.scores_to_maps(probabilities).into_iter())
+        } else {
+            None
+        };
+        let mut log_scores = if options.log_scores {
+            Some(
+                self.scores_to_maps(calibrated_logits.log_softmax(-1, self.score_kind()))
+                    .into_iter(),
+            )
+        } else {
+            None
+        };
+        let multilabel_scores = options.multilabel_threshold.map(|threshold| {
+            (
+                threshold,
+                self.calibrate_multilabel(logits)
+                    .sigmoid()
+                    .to_kind(Kind::Double)
+                    .to(Device::Cpu),
+            )
+        });
+
+        top_labels
+            .into_iter()
+            .enumerate()
+            .map(|(sentence_idx, label)| Prediction {
+                label,
+                margin: margins.as_mut().and_then(Iterator::next),
+                scores: scores.as_mut().and_then(Iterator::next),
+                log_scores: log_scores.as_mut().and_then(Iterator::next),
+                multilabel: multilabel_scores
+                    .as_ref()
+                    .map(|(threshold, multilabel_scores)| {
+                        multilabel_scores
+                            .get(sentence_idx as i64)
+                            .iter::<f64>()
+                            .unwrap()
+                            .enumerate()
+                            .filter(|(_, score)| score >= threshold)
+                            .map(|(position, score)| {
+                                self.output_label(position as i64, score, sentence_idx)
+                            })
+                            .collect()
+                    }),
+            })
+            .collect()
+    }
+
+    /// Multi-label classification of texts, returning the score of every class
+    ///
+    /// Unlike `predict_multilabel`, no threshold is applied: the sigmoid score of all classes is
+    /// returned for each input, which is useful for threshold tuning or logging complete score vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Vec<Label>>` containing a vector with a label for every class (ordered by label id) for each input text
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::SequenceClassificationModel;
+    ///
+    /// let sequence_classification_model =  SequenceClassificationModel::new(Default::default())?;
+    /// let input = [
+    ///     "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+    ///     "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+    /// ];
+    /// let output = sequence_classification_model.predict_multilabel_all_scores(&input);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict_multilabel_all_scores(
+        &self,
+        input: &[&str],
+    ) -> Result<Vec<Vec<Label>>, RustBertError> {
+        let output = self
+            .calibrate_multilabel(self.logits_within_budget(input))
+            .sigmoid()
+            .to(Device::Cpu);
+
+        let num_labels = output.size()[1];
+        let mut labels: Vec<Vec<Label>> = Vec::with_capacity(input.len());
+        for sentence_idx in 0..output.size()[0] {
+            let sentence_scores = output.get(sentence_idx);
+            let mut sequence_labels: Vec<Label> = Vec::with_capacity(num_labels as usize);
+            for position in 0..num_labels {
+                if position as usize >= self.output_label_ids.len() {
+                    return Err(RustBertError::InvalidConfigurationError(format!(
+                        "No label found for the output {position} in the label mapping"
+                    )));
+                }
+                sequence_labels.push(self.output_label(
+                    position,
+                    sentence_scores.double_value(&[position]),
+                    sentence_idx as usize,
+                ));
+            }
+            labels.push(sequence_labels);
+        }
+        Ok(labels)
+    }
+}
+
+/// # Weighted ensemble of sequence classification models
+/// Averages the probabilities of several `SequenceClassificationModel`s sharing the same label space
+/// (identical label ids and names) with configurable weights.
+pub struct EnsembleClassificationModel {
+    models: Vec<SequenceClassificationModel>,
+    weights: Vec<f64>,
+}
+
+impl EnsembleClassificationModel {
+    /// Build a new `EnsembleClassificationModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `models` - `Vec<SequenceClassificationModel>` models to ensemble, sharing the same label mapping
+    /// * `weights` - Optional weight of each model (default: None, all models weighted equally). The weights are normalized to sum to one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::sequence_classification::{
+    ///     EnsembleClassificationModel, SequenceClassificationModel,
+    /// };
+    ///
+    /// let first_model = SequenceClassificationModel::new(Default::default())?;
+    /// let second_model = SequenceClassificationModel::new(Default::default())?;
+    /// let ensemble_model =
+    ///     EnsembleClassificationModel::new(vec![first_model, second_model], Some(vec![2.0, 1.0]))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        models: Vec<SequenceClassificationModel>,
+        weights: Option<Vec<f64>>,
+    ) -> Result<EnsembleClassificationModel, RustBertError> {
+        let first_model = models.first().ok_or_else(|| {
+            RustBertError::ValueError("At least one model is required for an ensemble".to_string())
+        })?;
+        if let Some(position) = models
+            .iter()
+            .position(|model| model.label_mapping != first_model.label_mapping)
+        {
+            return Err(RustBertError::InvalidConfigurationError(format!(
+                "The labels of model {position} do not match the labels of model 0"
+            )));
+        }
+        let weights = weights.unwrap_or_else(|| vec![1.0; models.len()]);
+        if weights.len() != models.len() {
+            return Err(RustBertError::ValueError(format!(
+                "Expected {} ensemble weights, got {}",
+                models.len(),
+                weights.len()
+            )));
+        }
+        let total_weight: f64 = weights.iter().sum();
+        if !weights.iter().all(|weight| *weight >= 0.0)
+            || total_weight <= 0.0
+            || !total_weight.is_finite()
+        {
+            return Err(RustBertError::ValueError(
+                "Ensemble weights must be non-negative with a finite positive sum".to_string(),
+            ));
+        }
+        let weights = weights
+            .into_iter()
+            .map(|weight| weight / total_weight)
+            .collect();
+        Ok(EnsembleClassificationModel { models, weights })
+    }
+
+    /// Classify texts with the weighted average of the probabilities of the models
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to classify.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<Label>` containing labels for input texts, with the ensembled probability as score
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// # use rust_bert::pipelines::sequence_classification::{
+    /// #     EnsembleClassificationModel, SequenceClassificationModel,
+    /// # };
+    /// # let first_model = SequenceClassificationModel::new(Default::default())?;
+    /// # let second_model = SequenceClassificationModel::new(Default::default())?;
+    /// let ensemble_model = EnsembleClassificationModel::new(vec![first_model, second_model], None)?;
+    /// let input = ["This is a great movie.", "I did not like it at all."];
+    /// let output = ensemble_model.predict(&input);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict<'a, S>(&self, input: S) -> Vec<Label>
+    where
+        S: AsRef<[&'a str]>,
+    {
+        let input = input.as_ref();
+        if input.is_empty() {
+            return Vec::new();
+        }
+        let scores = self
+            .models
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(model, weight)| model.predict_scores_tensor(input, Device::Cpu) * *weight)
+            .reduce(|scores, model_scores| scores + model_scores)
+            .unwrap();
+        let mut labels = Vec::with_capacity(input.len());
+        self.models[0].get_top_labels_into(&scores, &mut labels);
+        labels
+    }
+}
+
+/// Aggregates the logits of the segments (e.g. windows or sentences) of each input, delimited by `boundaries`,
+/// returning the probabilities of the labels for each input, computed with the provided precision
+fn aggregate_probabilities(
+    logits: &Tensor,
+    boundaries: &[(usize, usize)],
+    aggregation: Aggregation,
+    kind: Kind,
+) -> Tensor {
+    let num_labels = logits.size()[1];
+    let aggregated_scores = boundaries
+        .iter()
+        .map(|&(start, end)| {
+            let segment_logits = logits.slice(0, start as i64, end as i64, 1);
+            match aggregation {
+                Aggregation::Mean => segment_logits.mean_dim([0].as_slice(), false, kind),
+                Aggregation::Max => segment_logits.max_dim(0, false).0,
+                Aggregation::Vote => segment_logits
+                    .argmax(-1, false)
+                    .one_hot(num_labels)
+                    .mean_dim([0].as_slice(), false, kind),
+            }
+        })
+        .collect::<Vec<_>>();
+    let aggregated_scores = Tensor::stack(aggregated_scores.as_slice(), 0);
+    match aggregation {
+        Aggregation::Vote => aggregated_scores,
+        Aggregation::Mean | Aggregation::Max => aggregated_scores.softmax(-1, kind),
+    }
+}
+
+/// Splits a text into sentences, returning their (trimmed) byte offsets. A sentence ends with `.`, `!` or `?`
+/// followed by a white space. A text without any non-white space content is returned as a single sentence.
+fn split_sentences(text: &str) -> Vec<(usize, usize)> {
+    let mut sentences = vec![];
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((position, character)) = chars.next() {
+        let is_boundary = matches!(character, '.' | '!' | '?')
+            && !matches!(chars.peek(), Some((_, next_character)) if !next_character.is_whitespace());
+        if is_boundary {
+            let end = position + character.len_utf8();
+            push_trimmed_sentence(text, start, end, &mut sentences);
+            start = end;
+        }
+    }
+    push_trimmed_sentence(text, start, text.len(), &mut sentences);
+    if sentences.is_empty() {
+        sentences.push((0, text.len()));
+    }
+    sentences
+}
+
+fn push_trimmed_sentence(
+    text: &str,
+    start: usize,
+    end: usize,
+    sentences: &mut Vec<(usize, usize)>,
+) {
+    let sentence = &text[start..end];
+    let trimmed_start = sentence.trim_start();
+    if trimmed_start.is_empty() {
+        return;
+    }
+    let sentence_start = start + sentence.len() - trimmed_start.len();
+    sentences.push((
+        sentence_start,
+        sentence_start + trimmed_start.trim_end().len(),
+    ));
+}
+
+/// Appends an EOS token to an input that is not terminated by one (e.g. encoded without special tokens). The last
+/// token is replaced by EOS if the input already has the maximum length.
+fn terminate_with_eos(tokenized_input: &mut TokenizedInput, eos_token_id: i64, max_length: usize) {
+    if tokenized_input.token_ids.last() == Some(&eos_token_id) {
+        return;
+    }
+    if tokenized_input.token_ids.len() >= max_length.max(1) {
+        tokenized_input.token_ids.pop();
+        tokenized_input.segment_ids.pop();
+        tokenized_input.special_tokens_mask.pop();
+        tokenized_input.token_offsets.pop();
+        tokenized_input.reference_offsets.pop();
+        tokenized_input.mask.pop();
+        tokenized_input.num_truncated_tokens += 1;
+    }
+    let segment_id = tokenized_input.segment_ids.last().copied().unwrap_or(0);
+    tokenized_input.token_ids.push(eos_token_id);
+    tokenized_input.segment_ids.push(segment_id);
+    tokenized_input.special_tokens_mask.push(1);
+    tokenized_input.token_offsets.push(None);
+    tokenized_input.reference_offsets.push(vec![]);
+    tokenized_input.mask.push(Mask::Special);
+}
+
+/// Returns the values before `head_end` and from `tail_start`, dropping the values in between
+fn retain_head_tail<T: Clone>(values: &[T], head_end: usize, tail_start: usize) -> Vec<T> {
+    [&values[..head_end], &values[tail_start..]].concat()
+}
+
+/// Number of special tokens added by the tokenizer to a single input
+fn sequence_added_tokens(tokenizer: &TokenizerOption) -> usize {
+    tokenizer
+        .build_input_with_special_tokens(
+            TokenIdsWithOffsets {
+                ids: vec![],
+                offsets: vec![],
+                reference_offsets: vec![],
+                masks: vec![],
+            },
+            None,
+        )
+        .token_ids
+        .len()
+}
+
+/// Returns the distinct characters of a text mapped to the unknown token by the tokenizer
+fn find_unmappable_characters(tokenizer: &TokenizerOption, text: &str) -> HashSet<char> {
+    let unknown_id = tokenizer.get_unk_id();
+    text.chars()
+        .filter(|character| !character.is_ascii_alphanumeric() && !character.is_whitespace())
+        .collect::<HashSet<char>>()
+        .into_iter()
+        .filter(|character| {
+            let tokens = tokenizer.tokenize(&character.to_string());
+            tokenizer
+                .convert_tokens_to_ids(&tokens)
+                .contains(&unknown_id)
+        })
+        .collect()
+}
+
+/// Casts the variables of the classification head (`classifier` and DistilBERT `pre_classifier` layers)
+fn set_classifier_kind(var_store: &VarStore, kind: Kind) {
+    for head in ["classifier", "pre_classifier"] {
+        let mut path = var_store.root() / head;
+        path.set_kind(kind);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let config = SequenceClassificationConfig::default();
+        let _: Box<dyn Send> = Box::new(SequenceClassificationModel::new(config));
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2019-present, the HuggingFace Inc. team, The Google AI Language Team and Facebook, Inc.
+// Copyright 2019-2020 Guillaume Becquin
+// Copyright 2020 Maarten van Gompel
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! TorchScript tracing of the sequence classification models, cached on disk to shorten cold starts.
+
+use crate::common::error::RustBertError;
+use crate::pipelines::common::ModelType;
+use crate::resources::ResourceProvider;
+use serde::{Deserialize, Serialize};
+use std::cmp::min;
+use std::fs;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+use tch::{no_grad, CModule, Device, Kind, Tensor};
+
+use super::{SequenceClassificationConfig, SequenceClassificationOption};
+
+/// # On-disk cache of the model graph, traced as a TorchScript module
+/// The first load builds the model, loads its weights and traces its forward pass to a TorchScript module saved at
+/// `path`. Later loads with the same fingerprint deserialize the module instead of building the model graph
+/// (skipping the initialization of the variables and the name-based weights loading), which shortens cold starts.
+///
+/// Tradeoffs:
+/// - the traced module is specialized for the traced input shape: every batch is split into chunks of `batch_size`
+///   sequences, and each chunk is padded to `batch_size` x `sequence_length` tokens (the extra positions are masked)
+///   whatever the number and length of its sequences. Inputs are truncated to `sequence_length` tokens, and small
+///   batches or short inputs pay the cost of a full-size forward pass.
+/// - the weights are embedded in the module: `reload_weights`, `half`/`float`, `classify_embeddings` and the
+///   attention outputs are not available for a model loaded from (or traced to) the cache.
+/// - only supported for BERT, DistilBERT, RoBERTa, XLM-RoBERTa, ALBERT and MobileBERT Torch models.
+///
+/// Invalidation: a JSON fingerprint is saved alongside the module (at `path` with a `.json` extension appended),
+/// recording the crate version, model type, resources identifiers (paths or URLs), size and modification time of the
+/// weights file, traced shape and graph-related settings. The module is traced again if the fingerprint differs,
+/// e.g. after upgrading the crate or replacing the weights. Modules traced with a different version of libtorch may
+/// fail to load and should be deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracedModelCache {
+    /// Path of the TorchScript module
+    pub path: PathBuf,
+    /// Number of sequences of the traced batches
+    pub batch_size: usize,
+    /// Number of tokens of the traced sequences
+    pub sequence_length: usize,
+}
+
+impl TracedModelCache {
+    /// Creates a traced model cache at the given path, for batches of `batch_size` sequences of `sequence_length` tokens
+    pub fn new(
+        path: impl Into<PathBuf>,
+        batch_size: usize,
+        sequence_length: usize,
+    ) -> TracedModelCache {
+        TracedModelCache {
+            path: path.into(),
+            batch_size,
+            sequence_length,
+        }
+    }
+
+    fn fingerprint_path(&self) -> PathBuf {
+        let mut fingerprint_path = self.path.clone().into_os_string();
+        fingerprint_path.push(".json");
+        PathBuf::from(fingerprint_path)
+    }
+
+    /// Loads the cached module if it exists and matches the fingerprint
+    pub(super) fn load(
+        &self,
+        fingerprint: &TracedModelFingerprint,
+        device: Device,
+    ) -> Result<Option<CModule>, RustBertError> {
+        let fingerprint_path = self.fingerprint_path();
+        if !self.path.is_file() || !fingerprint_path.is_file() {
+            return Ok(None);
+        }
+        match serde_json::from_str::<TracedModelFingerprint>(&fs::read_to_string(fingerprint_path)?)
+        {
+            Ok(cached_fingerprint) if &cached_fingerprint == fingerprint => {}
+            _ => return Ok(None),
+        }
+        let mut module = CModule::load_on_device(&self.path, device)?;
+        module.set_eval();
+        Ok(Some(module))
+    }
+
+    /// Traces the forward pass of a model and saves the module and its fingerprint
+    pub(super) fn trace(
+        &self,
+        model: &SequenceClassificationOption,
+        fingerprint: &TracedModelFingerprint,
+        pad_id: i64,
+        device: Device,
+    ) -> Result<CModule, RustBertError> {
+        let shape = [self.batch_size as i64, self.sequence_length as i64];
+        let inputs = [
+            Tensor::full(shape, pad_id, (Kind::Int64, device)),
+            Tensor::ones(shape, (Kind::Int64, device)),
+            Tensor::zeros(shape, (Kind::Int64, device)),
+        ];
+        let mut module = no_grad(|| {
+            CModule::create_by_tracing("SequenceClassifier", "forward", &inputs, &mut |inputs| {
+                vec![model.forward_t(
+                    Some(&inputs[0]),
+                    Some(&inputs[1]),
+                    Some(&inputs[2]),
+                    None,
+                    None,
+                    false,
+                )]
+            })
+        })?;
+        module.set_eval();
+        module.save(&self.path)?;
+        let fingerprint = serde_json::to_string_pretty(fingerprint)
+            .map_err(|error| RustBertError::ValueError(error.to_string()))?;
+        fs::write(self.fingerprint_path(), fingerprint)?;
+        Ok(module)
+    }
+}
+
+/// Settings determining the traced module of a `TracedModelCache`
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub(super) struct TracedModelFingerprint {
+    rust_bert_version: String,
+    model_type: ModelType,
+    model_resource: String,
+    #[serde(default)]
+    weights_size: u64,
+    #[serde(default)]
+    weights_modified: Option<u128>,
+    config_resource: String,
+    labels_resource: Option<String>,
+    missing_pooler: String,
+    classifier_dtype: Option<String>,
+    #[serde(default)]
+    dtype: Option<String>,
+    batch_size: usize,
+    sequence_length: usize,
+}
+
+impl TracedModelFingerprint {
+    pub(super) fn new(
+        config: &SequenceClassificationConfig,
+        cache: &TracedModelCache,
+    ) -> Result<Self, RustBertError> {
+        let weights_metadata = fs::metadata(config.model_resource.get_torch_local_path()?)?;
+        // The modification time is not available on all platforms, the size is then the only weights check
+        let weights_modified = weights_metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|modified| modified.as_nanos());
+        Ok(TracedModelFingerprint {
+            rust_bert_version: env!("CARGO_PKG_VERSION").to_string(),
+            model_type: config.model_type,
+            model_resource: config.model_resource.identifier(),
+            weights_size: weights_metadata.len(),
+            weights_modified,
+            config_resource: config.config_resource.identifier(),
+            labels_resource: config
+                .labels_resource
+                .as_ref()
+                .map(|resource| resource.identifier()),
+            missing_pooler: format!("{:?}", config.missing_pooler),
+            classifier_dtype: config
+                .classifier_dtype
+                .map(|classifier_dtype| format!("{classifier_dtype:?}")),
+            dtype: config.dtype.map(|dtype| format!("{dtype:?}")),
+            batch_size: cache.batch_size,
+            sequence_length: cache.sequence_length,
+        })
+    }
+}
+
+/// # Sequence classifier running a TorchScript module traced from one of the Torch models (see `TracedModelCache`)
+pub struct TracedSequenceClassifier {
+    module: CModule,
+    pub(super) model_type: ModelType,
+    batch_size: i64,
+    sequence_length: i64,
+    pad_id: i64,
+}
+
+impl TracedSequenceClassifier {
+    pub(super) fn new(
+        module: CModule,
+        model_type: ModelType,
+        cache: &TracedModelCache,
+        pad_id: i64,
+    ) -> TracedSequenceClassifier {
+        TracedSequenceClassifier {
+            module,
+            model_type,
+            batch_size: cache.batch_size as i64,
+            sequence_length: cache.sequence_length as i64,
+            pad_id,
+        }
+    }
+
+    /// Forward pass, splitting the inputs into chunks padded to the traced shape
+    pub(super) fn forward(
+        &self,
+        input_ids: &Tensor,
+        mask: Option<&Tensor>,
+        token_type_ids: Option<&Tensor>,
+    ) -> Result<Tensor, RustBertError> {
+        let (batch_size, sequence_length) = input_ids.size2()?;
+        if sequence_length > self.sequence_length {
+            return Err(RustBertError::ValueError(format!(
+                "Input sequence length ({sequence_length}) exceeds the traced sequence length ({})",
+                self.sequence_length
+            )));
+        }
+        let mask = mask.map_or_else(|| input_ids.ones_like(), |mask| mask.to_kind(Kind::Int64));
+        let token_type_ids =
+            token_type_ids.map_or_else(|| input_ids.zeros_like(), Tensor::shallow_clone);
+        let pad = |tensor: &Tensor, start: i64, rows: i64, fill_value: i64| {
+            let padded = Tensor::full(
+                [self.batch_size, self.sequence_length],
+                fill_value,
+                (Kind::Int64, input_ids.device()),
+            );
+            padded
+                .narrow(0, 0, rows)
+                .narrow(1, 0, sequence_length)
+                .copy_(&tensor.narrow(0, start, rows));
+            padded
+        };
+        let outputs = (0..batch_size)
+            .step_by(self.batch_size as usize)
+            .map(|start| {
+                let rows = min(self.batch_size, batch_size - start);
+                let padded_mask = pad(&mask, start, rows, 0);
+                // Padding sequences are fully attended, avoiding fully masked attention rows
+                let _ = padded_mask.narrow(0, rows, self.batch_size - rows).fill_(1);
+                Ok(self
+                    .module
+                    .forward_ts(&[
+                        pad(input_ids, start, rows, self.pad_id),
+                        padded_mask,
+                        pad(&token_type_ids, start, rows, 0),
+                    ])?
+                    .narrow(0, 0, rows))
+            })
+            .collect::<Result<Vec<Tensor>, RustBertError>>()?;
+        Ok(Tensor::cat(&outputs, 0))
+    }
+}
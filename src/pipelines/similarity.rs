@@ -0,0 +1,285 @@
+// Copyright 2019-present, the HuggingFace Inc. team, The Google AI Language Team and Facebook, Inc.
+// Copyright 2019 Guillaume Becquin
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//     http://www.apache.org/licenses/LICENSE-2.0
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Semantic similarity pipeline
+//! Scores the semantic similarity (or paraphrase probability) of sentence pairs with a cross-encoder:
+//! both sentences are encoded jointly by a sequence classification model, using token type ids to
+//! distinguish the two segments. The pipeline returns a score between 0 and 1 for each pair:
+//! - for models with a single output (regression head, e.g. fine-tuned on STS-B), the sigmoid of the output
+//! - for models with several outputs (e.g. fine-tuned on MRPC), the probability of the last label (paraphrase)
+//!
+//! The outputs are calibrated with the `temperature` and `apply_softmax` settings of the configuration, as for the
+//! sequence classification pipeline.
+//!
+//! No default resources are provided for this pipeline: the configuration resources should point to a
+//! cross-encoder checkpoint converted to the `.ot` format (e.g. a TinyBERT cross-encoder fine-tuned on STS-B).
+//! A `SequenceClassificationConfig` can also be converted into a `SimilarityConfig`.
+//!
+//! ```no_run
+//! use rust_bert::pipelines::common::{ModelResource, ModelType};
+//! use rust_bert::pipelines::similarity::{SimilarityConfig, SimilarityModel};
+//! use rust_bert::resources::LocalResource;
+//! use std::path::PathBuf;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let config = SimilarityConfig::new(
+//!     ModelType::Bert,
+//!     ModelResource::Torch(Box::new(LocalResource::from(PathBuf::from(
+//!         "path/to/rust_model.ot",
+//!     )))),
+//!     LocalResource::from(PathBuf::from("path/to/config.json")),
+//!     LocalResource::from(PathBuf::from("path/to/vocab.txt")),
+//!     None,
+//!     true,
+//!     None,
+//!     None,
+//! );
+//! let similarity_model = SimilarityModel::new(config)?;
+//! let input = [
+//!     ("A man is playing a guitar.", "A person plays an instrument."),
+//!     ("A man is playing a guitar.", "The stock market fell sharply."),
+//! ];
+//! let output = similarity_model.predict(&input)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::common::error::RustBertError;
+use crate::pipelines::common::{ModelResource, ModelType, TokenizerOption};
+use crate::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
+use crate::resources::ResourceProvider;
+
+/// # Configuration for SimilarityModel
+/// Wraps a `SequenceClassificationConfig` pointing to a cross-encoder checkpoint.
+pub struct SimilarityConfig {
+    sequence_classification_config: SequenceClassificationConfig,
+}
+
+impl SimilarityConfig {
+    /// Instantiate a new similarity configuration of the supplied type.
+    ///
+    /// # Arguments
+    ///
+    /// * `model_type` - `ModelType` indicating the model type to load (must match with the actual data to be loaded!)
+    /// * model - The `ResourceProvider` pointing to the model to load (e.g.  model.ot)
+    /// * config - The `ResourceProvider` pointing to the model configuration to load (e.g. config.json)
+    /// * vocab - The `ResourceProvider` pointing to the tokenizer's vocabulary to load (e.g.  vocab.txt/vocab.json)
+    /// * merges - An optional `ResourceProvider` pointing to the tokenizer's merge file to load (e.g.  merges.txt), needed only for Roberta.
+    /// * lower_case - A `bool` indicating whether the tokenizer should lower case all input (in case of a lower-cased model)
+    pub fn new<RC, RV>(
+        model_type: ModelType,
+        model_resource: ModelResource,
+        config_resource: RC,
+        vocab_resource: RV,
+        merges_resource: Option<RV>,
+        lower_case: bool,
+        strip_accents: impl Into<Option<bool>>,
+        add_prefix_space: impl Into<Option<bool>>,
+    ) -> SimilarityConfig
+    where
+        RC: ResourceProvider + Send + 'static,
+        RV: ResourceProvider + Send + 'static,
+    {
+        SequenceClassificationConfig::new(
+            model_type,
+            model_resource,
+            config_resource,
+            vocab_resource,
+            merges_resource,
+            lower_case,
+            strip_accents,
+            add_prefix_space,
+        )
+        .into()
+    }
+}
+
+impl From<SequenceClassificationConfig> for SimilarityConfig {
+    fn from(sequence_classification_config: SequenceClassificationConfig) -> Self {
+        SimilarityConfig {
+            sequence_classification_config,
+        }
+    }
+}
+
+impl From<SimilarityConfig> for SequenceClassificationConfig {
+    fn from(similarity_config: SimilarityConfig) -> Self {
+        similarity_config.sequence_classification_config
+    }
+}
+
+/// # SimilarityModel to score the semantic similarity of sentence pairs
+pub struct SimilarityModel {
+    sequence_classification_model: SequenceClassificationModel,
+}
+
+impl SimilarityModel {
+    /// Build a new `SimilarityModel`
+    ///
+    /// # Arguments
+    ///
+    /// * `similarity_config` - `SimilarityConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::similarity::{SimilarityConfig, SimilarityModel};
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let resource = |path: &str| LocalResource::from(PathBuf::from(path));
+    /// # let similarity_config = SimilarityConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(resource("path/to/rust_model.ot"))),
+    /// #     resource("path/to/config.json"),
+    /// #     resource("path/to/vocab.txt"),
+    /// #     None,
+    /// #     false,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let similarity_model = SimilarityModel::new(similarity_config)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(similarity_config: SimilarityConfig) -> Result<SimilarityModel, RustBertError> {
+        let sequence_classification_model =
+            SequenceClassificationModel::new(similarity_config.into())?;
+        Ok(SimilarityModel {
+            sequence_classification_model,
+        })
+    }
+
+    /// Build a new `SimilarityModel` with a provided tokenizer.
+    ///
+    /// # Arguments
+    ///
+    /// * `similarity_config` - `SimilarityConfig` object containing the resource references (model, vocabulary, configuration) and device placement (CPU/GPU)
+    /// * `tokenizer` - `TokenizerOption` tokenizer to use for the sentence pairs encoding.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::common::{ModelType, TokenizerOption};
+    /// use rust_bert::pipelines::similarity::{SimilarityConfig, SimilarityModel};
+    /// let tokenizer = TokenizerOption::from_file(
+    ///     ModelType::Bert,
+    ///     "path/to/vocab.txt",
+    ///     None,
+    ///     false,
+    ///     None,
+    ///     None,
+    /// )?;
+    /// # use rust_bert::pipelines::common::ModelResource;
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let resource = |path: &str| LocalResource::from(PathBuf::from(path));
+    /// # let similarity_config = SimilarityConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(resource("path/to/rust_model.ot"))),
+    /// #     resource("path/to/config.json"),
+    /// #     resource("path/to/vocab.txt"),
+    /// #     None,
+    /// #     false,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let similarity_model = SimilarityModel::new_with_tokenizer(similarity_config, tokenizer)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_with_tokenizer(
+        similarity_config: SimilarityConfig,
+        tokenizer: TokenizerOption,
+    ) -> Result<SimilarityModel, RustBertError> {
+        let sequence_classification_model =
+            SequenceClassificationModel::new_with_tokenizer(similarity_config.into(), tokenizer)?;
+        Ok(SimilarityModel {
+            sequence_classification_model,
+        })
+    }
+
+    /// Get a reference to the model tokenizer.
+    pub fn get_tokenizer(&self) -> &TokenizerOption {
+        self.sequence_classification_model.get_tokenizer()
+    }
+
+    /// Get a mutable reference to the model tokenizer.
+    pub fn get_tokenizer_mut(&mut self) -> &mut TokenizerOption {
+        self.sequence_classification_model.get_tokenizer_mut()
+    }
+
+    /// Score the similarity of sentence pairs
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[(&str, &str)]` Array of sentence pairs to score.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<f64>` Similarity score in [0, 1] for each sentence pair.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::similarity::{SimilarityConfig, SimilarityModel};
+    /// # use rust_bert::pipelines::common::{ModelResource, ModelType};
+    /// # use rust_bert::resources::LocalResource;
+    /// # use std::path::PathBuf;
+    /// # let resource = |path: &str| LocalResource::from(PathBuf::from(path));
+    /// # let similarity_config = SimilarityConfig::new(
+    /// #     ModelType::Bert,
+    /// #     ModelResource::Torch(Box::new(resource("path/to/rust_model.ot"))),
+    /// #     resource("path/to/config.json"),
+    /// #     resource("path/to/vocab.txt"),
+    /// #     None,
+    /// #     false,
+    /// #     None,
+    /// #     None,
+    /// # );
+    /// let similarity_model = SimilarityModel::new(similarity_config)?;
+    /// let input = [
+    ///     ("A man is playing a guitar.", "A person plays an instrument."),
+    ///     ("A man is playing a guitar.", "The stock market fell sharply."),
+    /// ];
+    /// let output = similarity_model.predict(&input)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn predict(&self, input: &[(&str, &str)]) -> Result<Vec<f64>, RustBertError> {
+        if input.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .sequence_classification_model
+            .predict_pairs_last_label_scores(input)?
+            .iter::<f64>()?
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[ignore] // no need to run, compilation is enough to verify it is Send
+    fn test() {
+        let config = SimilarityConfig::from(SequenceClassificationConfig::default());
+        let _: Box<dyn Send> = Box::new(SimilarityModel::new(config));
+    }
+}
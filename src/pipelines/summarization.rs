@@ -68,7 +68,9 @@ use crate::bart::BartGenerator;
 use crate::common::error::RustBertError;
 use crate::pegasus::PegasusConditionalGenerator;
 use crate::pipelines::common::{ModelResource, ModelType, TokenizerOption};
-use crate::pipelines::generation_utils::{GenerateConfig, LanguageGenerator};
+use crate::pipelines::generation_utils::{
+    GenerateConfig, GenerateOptions, LanguageGenerator, LogitsProcessor,
+};
 use crate::prophetnet::ProphetNetConditionalGenerator;
 use crate::resources::ResourceProvider;
 use crate::t5::T5Generator;
@@ -126,6 +128,8 @@ pub struct SummarizationConfig {
     pub diversity_penalty: Option<f64>,
     /// Device to place the model on (default: CUDA/GPU when available)
     pub device: Device,
+    /// Logits processors applied in order to the next token scores at each generation step (default: none)
+    pub logits_processors: Vec<Box<dyn LogitsProcessor>>,
 }
 
 impl SummarizationConfig {
@@ -170,6 +174,7 @@ impl SummarizationConfig {
             num_beam_groups: None,
             diversity_penalty: None,
             device: Device::cuda_if_available(),
+            logits_processors: Vec::new(),
         }
     }
 }
@@ -214,6 +219,7 @@ impl From<SummarizationConfig> for GenerateConfig {
             num_beam_groups: config.num_beam_groups,
             diversity_penalty: config.diversity_penalty,
             device: config.device,
+            logits_processors: config.logits_processors,
         }
     }
 }
@@ -335,38 +341,51 @@ impl SummarizationOption {
 
     /// Interface method to generate() of the particular models.
     pub fn generate<S>(&self, prompt_texts: Option<&[S]>) -> Vec<String>
+    where
+        S: AsRef<str> + Sync,
+    {
+        self.generate_with_options(prompt_texts, None)
+    }
+
+    /// Interface method to generate() of the particular models, with optional generation options overriding the
+    /// configuration of the model for this call.
+    pub fn generate_with_options<S>(
+        &self,
+        prompt_texts: Option<&[S]>,
+        generate_options: Option<GenerateOptions>,
+    ) -> Vec<String>
     where
         S: AsRef<str> + Sync,
     {
         match *self {
             Self::Bart(ref model) => model
-                .generate(prompt_texts, None)
+                .generate(prompt_texts, generate_options)
                 .into_iter()
                 .map(|output| output.text)
                 .collect(),
             Self::T5(ref model) => model
-                .generate(prompt_texts, None)
+                .generate(prompt_texts, generate_options)
                 .into_iter()
                 .map(|output| output.text)
                 .collect(),
             Self::LongT5(ref model) => model
-                .generate(prompt_texts, None)
+                .generate(prompt_texts, generate_options)
                 .into_iter()
                 .map(|output| output.text)
                 .collect(),
             Self::ProphetNet(ref model) => model
-                .generate(prompt_texts, None)
+                .generate(prompt_texts, generate_options)
                 .into_iter()
                 .map(|output| output.text)
                 .collect(),
             Self::Pegasus(ref model) => model
-                .generate(prompt_texts, None)
+                .generate(prompt_texts, generate_options)
                 .into_iter()
                 .map(|output| output.text)
                 .collect(),
             #[cfg(feature = "onnx")]
             Self::ONNX(ref model) => model
-                .generate(prompt_texts, None)
+                .generate(prompt_texts, generate_options)
                 .into_iter()
                 .map(|output| output.text)
                 .collect(),
@@ -503,17 +522,64 @@ impl SummarizationModel {
     /// ```
     /// (New sample credits: [WikiNews](https://en.wikinews.org/wiki/Astronomers_find_water_vapour_in_atmosphere_of_exoplanet_K2-18b))
     pub fn summarize<S>(&self, texts: &[S]) -> Vec<String>
+    where
+        S: AsRef<str> + Sync,
+    {
+        self.summarize_with_options(texts, None)
+    }
+
+    /// Summarize texts provided, with generation options (e.g. `num_beams` or `length_penalty`) overriding the
+    /// pipeline configuration for this call only.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to summarize.
+    /// * `generate_options` - `Option<GenerateOptions>` Optional generation options. The options not provided (`None`) use the settings of the pipeline configuration.
+    ///
+    /// # Returns
+    /// * `Vec<String>` Summarized texts
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::generation_utils::GenerateOptions;
+    /// use rust_bert::pipelines::summarization::SummarizationModel;
+    /// let model = SummarizationModel::new(Default::default())?;
+    ///
+    /// let input = ["In findings published Tuesday in Cornell University's arXiv by a team of scientists
+    /// from the University of Montreal and a separate report published Wednesday in Nature Astronomy by a team
+    /// from University College London (UCL), the presence of water vapour was confirmed in the atmosphere of K2-18b,
+    /// a planet circling a star in the constellation Leo."];
+    ///
+    /// let generate_options = GenerateOptions {
+    ///     num_beams: Some(2),
+    ///     length_penalty: Some(0.5),
+    ///     ..Default::default()
+    /// };
+    /// let output = model.summarize_with_options(&input, Some(generate_options));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn summarize_with_options<S>(
+        &self,
+        texts: &[S],
+        generate_options: Option<GenerateOptions>,
+    ) -> Vec<String>
     where
         S: AsRef<str> + Sync,
     {
         match &self.prefix {
-            None => self.model.generate(Some(texts)),
+            None => self
+                .model
+                .generate_with_options(Some(texts), generate_options),
             Some(prefix) => {
                 let texts = texts
                     .iter()
                     .map(|text| format!("{}{}", prefix, text.as_ref()))
                     .collect::<Vec<String>>();
-                self.model.generate(Some(&texts))
+                self.model
+                    .generate_with_options(Some(&texts), generate_options)
             }
         }
     }
@@ -39,7 +39,9 @@ use crate::gpt_j::GptJGenerator;
 use crate::gpt_neo::GptNeoGenerator;
 use crate::openai_gpt::OpenAIGenerator;
 use crate::pipelines::common::{ModelResource, ModelType, TokenizerOption};
-use crate::pipelines::generation_utils::{GenerateConfig, GenerateOptions, LanguageGenerator};
+use crate::pipelines::generation_utils::{
+    GenerateConfig, GenerateOptions, LanguageGenerator, LogitsProcessor, TokenCallback,
+};
 use crate::reformer::ReformerGenerator;
 use crate::resources::ResourceProvider;
 use crate::t5::T5Generator;
@@ -97,6 +99,8 @@ pub struct TextGenerationConfig {
     pub diversity_penalty: Option<f64>,
     /// Device to place the model on (default: CUDA/GPU when available)
     pub device: Device,
+    /// Logits processors applied in order to the next token scores at each generation step (default: none)
+    pub logits_processors: Vec<Box<dyn LogitsProcessor>>,
 }
 
 impl TextGenerationConfig {
@@ -141,6 +145,7 @@ impl TextGenerationConfig {
             num_beam_groups: None,
             diversity_penalty: None,
             device: Device::cuda_if_available(),
+            logits_processors: Vec::new(),
         }
     }
 }
@@ -185,6 +190,7 @@ impl From<TextGenerationConfig> for GenerateConfig {
             num_beam_groups: config.num_beam_groups,
             diversity_penalty: config.diversity_penalty,
             device: config.device,
+            logits_processors: config.logits_processors,
         }
     }
 }
@@ -335,11 +341,27 @@ impl TextGenerationOption {
     where
         S: AsRef<str> + Sync,
     {
-        let generate_options = Some(GenerateOptions {
-            min_length,
-            max_length,
-            ..Default::default()
-        });
+        self.generate_indices_with_options(
+            prompt_texts,
+            GenerateOptions {
+                min_length,
+                max_length,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Interface method to generate() of the particular models, with generation options overriding the
+    /// configuration of the model for this call.
+    pub fn generate_indices_with_options<S>(
+        &self,
+        prompt_texts: Option<&[S]>,
+        generate_options: GenerateOptions,
+    ) -> Vec<Vec<i64>>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let generate_options = Some(generate_options);
         match *self {
             Self::GPT(ref model) => model
                 .generate_indices(prompt_texts, generate_options)
@@ -599,6 +621,96 @@ with people, even a bishop, begging for his blessing. <eod> </s> <eos>"
     where
         S: AsRef<str> + Sync,
     {
+        self.generate_with_options(texts, prefix, None)
+    }
+
+    /// Generate texts from provided prompts, streaming the generated text as it is produced (e.g. to forward it
+    /// to a client as server-sent events). The callback is only called for greedy and sampling decoding: the
+    /// pipeline must be configured with `num_beams` = 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of prompts.
+    /// * `prefix` - `impl Into<Option<&'a str>>`: Optional string to pass as a prefix for generation. Will be excluded from generated sequences.
+    /// * `token_callback` - `&dyn Fn(i64, &str, &str)` Function called at each generation step with the index of the generated sequence, the new text and the text generated so far.
+    ///
+    /// # Returns
+    /// * `Vec<String>` Generated texts
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::text_generation::{TextGenerationConfig, TextGenerationModel};
+    /// use std::io::Write;
+    ///
+    /// let model = TextGenerationModel::new(TextGenerationConfig {
+    ///     num_beams: 1,
+    ///     ..Default::default()
+    /// })?;
+    ///
+    /// let output = model.generate_streaming(&["The dog"], None, &|_, new_text, _| {
+    ///     print!("{new_text}");
+    ///     std::io::stdout().flush().unwrap();
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generate_streaming<'a, S>(
+        &self,
+        texts: &[S],
+        prefix: impl Into<Option<&'a str>>,
+        token_callback: TokenCallback,
+    ) -> Vec<String>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let generate_options = GenerateOptions {
+            token_callback: Some(token_callback),
+            ..Default::default()
+        };
+        self.generate_with_options(texts, prefix, Some(generate_options))
+    }
+
+    /// Generate texts from provided prompts, with generation options (e.g. `num_beams` or `length_penalty`)
+    /// overriding the pipeline configuration for this call only.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of prompts.
+    /// * `prefix` - `impl Into<Option<&'a str>>`: Optional string to pass as a prefix for generation. Will be excluded from generated sequences.
+    /// * `generate_options` - `Option<GenerateOptions>` Optional generation options. The options not provided (`None`) use the settings of the pipeline configuration.
+    ///
+    /// # Returns
+    /// * `Vec<String>` Generated texts
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::generation_utils::GenerateOptions;
+    /// use rust_bert::pipelines::text_generation::TextGenerationModel;
+    ///
+    /// let model = TextGenerationModel::new(Default::default())?;
+    /// let generate_options = GenerateOptions {
+    ///     num_beams: Some(3),
+    ///     length_penalty: Some(2.0),
+    ///     ..Default::default()
+    /// };
+    /// let output = model.generate_with_options(&["The dog"], None, Some(generate_options));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generate_with_options<'a, S>(
+        &self,
+        texts: &[S],
+        prefix: impl Into<Option<&'a str>>,
+        generate_options: Option<GenerateOptions>,
+    ) -> Vec<String>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let generate_options = generate_options.unwrap_or_default();
         let (prefix, prefix_length) = match (prefix.into(), &self.prefix) {
             (Some(query_prefix), _) => (
                 Some(query_prefix),
@@ -608,18 +720,27 @@ with people, even a bishop, begging for his blessing. <eod> </s> <eos>"
             (None, None) => (None, None),
         };
         let generated_indices = match (prefix, prefix_length) {
-            (None, _) => self.model.generate_indices(Some(texts), None, None),
+            (None, _) => self
+                .model
+                .generate_indices_with_options(Some(texts), generate_options),
             (Some(prefix), Some(prefix_length)) => {
                 let texts = texts
                     .as_ref()
                     .iter()
                     .map(|text| format!("{} {}", prefix, text.as_ref()))
                     .collect::<Vec<String>>();
-                self.model.generate_indices(
-                    Some(&texts),
-                    Some(self.min_length + prefix_length),
-                    self.max_length.map(|max_length| max_length + prefix_length),
-                )
+                let generate_options = GenerateOptions {
+                    min_length: Some(
+                        generate_options.min_length.unwrap_or(self.min_length) + prefix_length,
+                    ),
+                    max_length: generate_options
+                        .max_length
+                        .or(self.max_length)
+                        .map(|max_length| max_length + prefix_length),
+                    ..generate_options
+                };
+                self.model
+                    .generate_indices_with_options(Some(&texts), generate_options)
             }
             _ => panic!("Prefix length not defined but prefix provided!"),
         };
@@ -372,7 +372,7 @@ impl TokenClassificationOption {
                         BertForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Bert!".to_string(),
                     ))
                 }
@@ -383,7 +383,7 @@ impl TokenClassificationOption {
                         DebertaForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DebertaConfig for DeBERTa!".to_string(),
                     ))
                 }
@@ -394,7 +394,7 @@ impl TokenClassificationOption {
                         DebertaV2ForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DebertaConfig for DeBERTa V2!".to_string(),
                     ))
                 }
@@ -405,7 +405,7 @@ impl TokenClassificationOption {
                         DistilBertForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DistilBertConfig for DistilBert!".to_string(),
                     ))
                 }
@@ -416,7 +416,7 @@ impl TokenClassificationOption {
                         MobileBertForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a MobileBertConfig for MobileBert!".to_string(),
                     ))
                 }
@@ -427,7 +427,7 @@ impl TokenClassificationOption {
                         RobertaForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a RobertaConfig for Roberta!".to_string(),
                     ))
                 }
@@ -438,7 +438,7 @@ impl TokenClassificationOption {
                         RobertaForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a RobertaConfig for XLMRoberta!".to_string(),
                     ))
                 }
@@ -449,7 +449,7 @@ impl TokenClassificationOption {
                         ElectraForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Roberta!".to_string(),
                     ))
                 }
@@ -460,7 +460,7 @@ impl TokenClassificationOption {
                         AlbertForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply an AlbertConfig for Albert!".to_string(),
                     ))
                 }
@@ -471,7 +471,7 @@ impl TokenClassificationOption {
                         XLNetForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply an AlbertConfig for Albert!".to_string(),
                     ))
                 }
@@ -482,7 +482,7 @@ impl TokenClassificationOption {
                         LongformerForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a LongformerConfig for Longformer!".to_string(),
                     ))
                 }
@@ -493,7 +493,7 @@ impl TokenClassificationOption {
                         FNetForTokenClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply an FNetConfig for FNet!".to_string(),
                     ))
                 }
@@ -695,6 +695,7 @@ pub struct TokenClassificationModel {
     tokenizer: TokenizerOption,
     token_sequence_classifier: TokenClassificationOption,
     label_mapping: HashMap<i64, String>,
+    output_label_ids: Vec<i64>,
     device: Device,
     label_aggregation_function: LabelAggregationOption,
     max_length: usize,
@@ -779,12 +780,14 @@ impl TokenClassificationModel {
             .map(|v| v as usize)
             .unwrap_or(usize::MAX);
         let label_mapping = model_config.get_label_mapping().clone();
+        let output_label_ids = model_config.get_output_label_ids();
         let batch_size = config.batch_size;
         let device = get_device(config.model_resource, config.device);
         Ok(TokenClassificationModel {
             tokenizer,
             token_sequence_classifier,
             label_mapping,
+            output_label_ids,
             device,
             label_aggregation_function,
             max_length,
@@ -1097,7 +1100,8 @@ impl TokenClassificationModel {
         position_idx: i64,
         word_index: u16,
     ) -> Token {
-        let label_id = labels.int64_value(&[position_idx]);
+        let label_position = labels.int64_value(&[position_idx]);
+        let label_id = self.output_label_ids[label_position as usize];
         let token_id = input_tensor.int64_value(&[sentence_idx, position_idx]);
 
         let offsets = &sentence_tokens.offsets[position_idx as usize];
@@ -1136,7 +1140,7 @@ impl TokenClassificationModel {
 
         Token {
             text,
-            score: score.double_value(&[sentence_idx, position_idx, label_id]),
+            score: score.double_value(&[sentence_idx, position_idx, label_position]),
             label: self
                 .label_mapping
                 .get(&label_id)
@@ -19,7 +19,9 @@ use crate::marian::MarianGenerator;
 use crate::mbart::MBartGenerator;
 use crate::nllb::NLLBGenerator;
 use crate::pipelines::common::{ModelResource, ModelType, TokenizerOption};
-use crate::pipelines::generation_utils::{GenerateConfig, GenerateOptions, LanguageGenerator};
+use crate::pipelines::generation_utils::{
+    GenerateConfig, GenerateOptions, LanguageGenerator, LogitsProcessor,
+};
 #[cfg(feature = "onnx")]
 use crate::pipelines::onnx::ONNXConditionalGenerator;
 use crate::resources::ResourceProvider;
@@ -978,6 +980,8 @@ pub struct TranslationConfig {
     pub num_beam_groups: Option<i64>,
     /// Diversity penalty for diverse beam search. High values will enforce more difference between beam groups (default: 5.5)
     pub diversity_penalty: Option<f64>,
+    /// Logits processors applied in order to the next token scores at each generation step (default: none)
+    pub logits_processors: Vec<Box<dyn LogitsProcessor>>,
 }
 
 impl TranslationConfig {
@@ -1065,6 +1069,7 @@ impl TranslationConfig {
             num_return_sequences: 1,
             num_beam_groups: None,
             diversity_penalty: None,
+            logits_processors: Vec::new(),
         }
     }
 }
@@ -1092,6 +1097,7 @@ impl From<TranslationConfig> for GenerateConfig {
             num_beam_groups: config.num_beam_groups,
             diversity_penalty: config.diversity_penalty,
             device: config.device,
+            logits_processors: config.logits_processors,
         }
     }
 }
@@ -1219,52 +1225,55 @@ impl TranslationOption {
     where
         S: AsRef<str> + Sync,
     {
+        self.generate_with_options(prompt_texts, forced_bos_token_id, None)
+    }
+
+    /// Interface method to generate() of the particular models, with optional generation options overriding the
+    /// configuration of the model for this call. The forced BOS token id (target language), if any, takes priority
+    /// over the one of the generation options.
+    pub fn generate_with_options<S>(
+        &self,
+        prompt_texts: Option<&[S]>,
+        forced_bos_token_id: Option<i64>,
+        generate_options: Option<GenerateOptions>,
+    ) -> Vec<String>
+    where
+        S: AsRef<str> + Sync,
+    {
+        let generate_options_with_forced_bos = match (generate_options, forced_bos_token_id) {
+            (generate_options, None) => generate_options,
+            (generate_options, Some(forced_bos_token_id)) => Some(GenerateOptions {
+                forced_bos_token_id: Some(forced_bos_token_id),
+                ..generate_options.unwrap_or_default()
+            }),
+        };
         match *self {
             Self::Marian(ref model) => model
-                .generate(prompt_texts, None)
+                .generate(prompt_texts, generate_options)
                 .into_iter()
                 .map(|output| output.text)
                 .collect(),
             Self::T5(ref model) => model
-                .generate(prompt_texts, None)
+                .generate(prompt_texts, generate_options)
+                .into_iter()
+                .map(|output| output.text)
+                .collect(),
+            Self::MBart(ref model) => model
+                .generate(prompt_texts, generate_options_with_forced_bos)
+                .into_iter()
+                .map(|output| output.text)
+                .collect(),
+            Self::M2M100(ref model) | Self::NLLB(ref model) => model
+                .generate(prompt_texts, generate_options_with_forced_bos)
                 .into_iter()
                 .map(|output| output.text)
                 .collect(),
-            Self::MBart(ref model) => {
-                let generate_options = GenerateOptions {
-                    forced_bos_token_id,
-                    ..Default::default()
-                };
-                model
-                    .generate(prompt_texts, Some(generate_options))
-                    .into_iter()
-                    .map(|output| output.text)
-                    .collect()
-            }
-            Self::M2M100(ref model) | Self::NLLB(ref model) => {
-                let generate_options = GenerateOptions {
-                    forced_bos_token_id,
-                    ..Default::default()
-                };
-                model
-                    .generate(prompt_texts, Some(generate_options))
-                    .into_iter()
-                    .map(|output| output.text)
-                    .collect()
-            }
             #[cfg(feature = "onnx")]
-            Self::ONNX(ref model) => {
-                let generate_options =
-                    forced_bos_token_id.map(|forced_bos_token_id| GenerateOptions {
-                        forced_bos_token_id: Some(forced_bos_token_id),
-                        ..Default::default()
-                    });
-                model
-                    .generate(prompt_texts, generate_options)
-                    .into_iter()
-                    .map(|output| output.text)
-                    .collect()
-            }
+            Self::ONNX(ref model) => model
+                .generate(prompt_texts, generate_options_with_forced_bos)
+                .into_iter()
+                .map(|output| output.text)
+                .collect(),
         }
     }
 }
@@ -1469,6 +1478,54 @@ impl TranslationModel {
         source_language: impl Into<Option<Language>>,
         target_language: impl Into<Option<Language>>,
     ) -> Result<Vec<String>, RustBertError>
+    where
+        S: AsRef<str> + Sync,
+    {
+        self.translate_with_options(texts, source_language, target_language, None)
+    }
+
+    /// Translates texts provided, with generation options (e.g. `num_beams` or `length_penalty`) overriding the
+    /// pipeline configuration for this call only.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - `&[&str]` Array of texts to translate.
+    /// * `source_language` - Optional source language of the texts.
+    /// * `target_language` - Optional target language of the translation.
+    /// * `generate_options` - `Option<GenerateOptions>` Optional generation options. The options not provided (`None`) use the settings of the pipeline configuration.
+    ///
+    /// # Returns
+    /// * `Vec<String>` Translated texts
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> anyhow::Result<()> {
+    /// use rust_bert::pipelines::generation_utils::GenerateOptions;
+    /// use rust_bert::pipelines::translation::{Language, TranslationModelBuilder};
+    ///
+    /// let model = TranslationModelBuilder::new().create_model()?;
+    /// let generate_options = GenerateOptions {
+    ///     num_beams: Some(2),
+    ///     length_penalty: Some(1.2),
+    ///     ..Default::default()
+    /// };
+    /// let output = model.translate_with_options(
+    ///     &["This is a sentence to be translated"],
+    ///     None,
+    ///     Language::French,
+    ///     Some(generate_options),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn translate_with_options<S>(
+        &self,
+        texts: &[S],
+        source_language: impl Into<Option<Language>>,
+        target_language: impl Into<Option<Language>>,
+        generate_options: Option<GenerateOptions>,
+    ) -> Result<Vec<String>, RustBertError>
     where
         S: AsRef<str> + Sync,
     {
@@ -1486,9 +1543,16 @@ impl TranslationModel {
                     .iter()
                     .map(|v| format!("{}{}", value, v.as_ref()))
                     .collect::<Vec<String>>();
-                self.model.generate(Some(&texts), forced_bos_token_id)
+                self.model.generate_with_options(
+                    Some(&texts),
+                    forced_bos_token_id,
+                    generate_options,
+                )
+            }
+            None => {
+                self.model
+                    .generate_with_options(Some(texts), forced_bos_token_id, generate_options)
             }
-            None => self.model.generate(Some(texts), forced_bos_token_id),
         })
     }
 }
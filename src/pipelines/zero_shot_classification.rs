@@ -102,6 +102,7 @@ use crate::albert::AlbertForSequenceClassification;
 use crate::bart::BartForSequenceClassification;
 use crate::bert::BertForSequenceClassification;
 use crate::deberta::DebertaForSequenceClassification;
+use crate::deberta_v2::DebertaV2ForSequenceClassification;
 use crate::distilbert::DistilBertModelClassifier;
 use crate::longformer::LongformerForSequenceClassification;
 use crate::mobilebert::MobileBertForSequenceClassification;
@@ -214,6 +215,7 @@ impl Default for ZeroShotClassificationConfig {
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 /// # Abstraction that holds one particular zero shot classification model, for any of the supported models
 /// The models are using a classification architecture that should be trained on Natural Language Inference.
 /// The models should output a Tensor of size > 2 in the label dimension, with the first logit corresponding
@@ -223,6 +225,8 @@ pub enum ZeroShotClassificationOption {
     Bart(BartForSequenceClassification),
     /// DeBERTa for Sequence Classification
     Deberta(DebertaForSequenceClassification),
+    /// DeBERTa V2 for Sequence Classification
+    DebertaV2(DebertaV2ForSequenceClassification),
     /// Bert for Sequence Classification
     Bert(BertForSequenceClassification),
     /// DistilBert for Sequence Classification
@@ -273,7 +277,7 @@ impl ZeroShotClassificationOption {
                         BartForSequenceClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BartConfig for Bart!".to_string(),
                     ))
                 }
@@ -284,18 +288,29 @@ impl ZeroShotClassificationOption {
                         DebertaForSequenceClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DebertaConfig for DeBERTa!".to_string(),
                     ))
                 }
             }
+            ModelType::DebertaV2 => {
+                if let ConfigOption::DebertaV2(config) = model_config {
+                    Ok(Self::DebertaV2(
+                        DebertaV2ForSequenceClassification::new(var_store.root(), config)?,
+                    ))
+                } else {
+                    Err(RustBertError::ConfigurationMismatchError(
+                        "You can only supply a DebertaV2Config for DeBERTa V2!".to_string(),
+                    ))
+                }
+            }
             ModelType::Bert => {
                 if let ConfigOption::Bert(config) = model_config {
                     Ok(Self::Bert(
                         BertForSequenceClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Bert!".to_string(),
                     ))
                 }
@@ -306,7 +321,7 @@ impl ZeroShotClassificationOption {
                         DistilBertModelClassifier::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a DistilBertConfig for DistilBert!".to_string(),
                     ))
                 }
@@ -317,7 +332,7 @@ impl ZeroShotClassificationOption {
                         MobileBertForSequenceClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a MobileBertConfig for MobileBert!".to_string(),
                     ))
                 }
@@ -328,7 +343,7 @@ impl ZeroShotClassificationOption {
                         RobertaForSequenceClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Roberta!".to_string(),
                     ))
                 }
@@ -339,7 +354,7 @@ impl ZeroShotClassificationOption {
                         RobertaForSequenceClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a BertConfig for Roberta!".to_string(),
                     ))
                 }
@@ -350,7 +365,7 @@ impl ZeroShotClassificationOption {
                         AlbertForSequenceClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply an AlbertConfig for Albert!".to_string(),
                     ))
                 }
@@ -361,7 +376,7 @@ impl ZeroShotClassificationOption {
                         XLNetForSequenceClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply an AlbertConfig for Albert!".to_string(),
                     ))
                 }
@@ -372,7 +387,7 @@ impl ZeroShotClassificationOption {
                         LongformerForSequenceClassification::new(var_store.root(), config)?,
                     ))
                 } else {
-                    Err(RustBertError::InvalidConfigurationError(
+                    Err(RustBertError::ConfigurationMismatchError(
                         "You can only supply a LongformerConfig for Longformer!".to_string(),
                     ))
                 }
@@ -414,6 +429,7 @@ impl ZeroShotClassificationOption {
         match *self {
             Self::Bart(_) => ModelType::Bart,
             Self::Deberta(_) => ModelType::Deberta,
+            Self::DebertaV2(_) => ModelType::DebertaV2,
             Self::Bert(_) => ModelType::Bert,
             Self::Roberta(_) => ModelType::Roberta,
             Self::XLMRoberta(_) => ModelType::Roberta,
@@ -475,6 +491,19 @@ impl ZeroShotClassificationOption {
                     .expect("Error in DeBERTa forward_t")
                     .logits
             }
+            Self::DebertaV2(ref model) => {
+                model
+                    .forward_t(
+                        input_ids,
+                        mask,
+                        token_type_ids,
+                        position_ids,
+                        input_embeds,
+                        train,
+                    )
+                    .expect("Error in DeBERTa V2 forward_t")
+                    .logits
+            }
             Self::DistilBert(ref model) => {
                 model
                     .forward_t(input_ids, mask, input_embeds, train)
@@ -6,11 +6,16 @@ use rust_bert::albert::{
     AlbertForQuestionAnswering, AlbertForSequenceClassification, AlbertForTokenClassification,
     AlbertModelResources, AlbertVocabResources,
 };
-use rust_bert::resources::{load_weights, RemoteResource, ResourceProvider};
+use rust_bert::pipelines::common::{ModelResource, ModelType};
+use rust_bert::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
+use rust_bert::resources::{load_weights, LocalResource, RemoteResource, ResourceProvider};
 use rust_bert::Config;
 use rust_tokenizers::tokenizer::{AlbertTokenizer, MultiThreadedTokenizer, TruncationStrategy};
 use rust_tokenizers::vocab::Vocab;
 use std::collections::HashMap;
+use std::io::Write;
 use tch::{nn, no_grad, Device, Tensor};
 
 #[test]
@@ -149,6 +154,72 @@ fn albert_for_sequence_classification() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn albert_sequence_classification_shared_layer_groups() -> anyhow::Result<()> {
+    //    Save a randomly initialized checkpoint with several groups of shared layers
+    let device = Device::Cpu;
+    let vs = nn::VarStore::new(device);
+    let config = AlbertConfig {
+        embedding_size: 16,
+        hidden_size: 32,
+        intermediate_size: 64,
+        num_attention_heads: 2,
+        num_hidden_layers: 5,
+        num_hidden_groups: 2,
+        inner_group_num: 2,
+        id2label: Some(HashMap::from([
+            (0, String::from("entailment")),
+            (1, String::from("neutral")),
+            (2, String::from("contradiction")),
+        ])),
+        ..Default::default()
+    };
+    let _ = AlbertForSequenceClassification::new(vs.root(), &config)?;
+
+    //    Variables follow the naming of the Hugging Face checkpoints: one entry per group and shared layer
+    let variables = vs.variables();
+    for name in [
+        "albert.embeddings.word_embeddings.weight",
+        "albert.encoder.embedding_hidden_mapping_in.weight",
+        "albert.encoder.albert_layer_groups.1.albert_layers.1.attention.query.weight",
+        "albert.encoder.albert_layer_groups.1.albert_layers.1.full_layer_layer_norm.weight",
+        "albert.pooler.weight",
+        "classifier.weight",
+    ] {
+        assert!(variables.contains_key(name), "{} not found", name);
+    }
+    assert!(!variables
+        .keys()
+        .any(|name| name.contains("albert_layer_groups.2")));
+
+    let weights_path = tempfile::NamedTempFile::new()?.into_temp_path();
+    vs.save(&weights_path)?;
+    let mut config_file = tempfile::NamedTempFile::new()?;
+    config_file.write_all(serde_json::to_string(&config)?.as_bytes())?;
+    let config_path = config_file.into_temp_path();
+
+    //    Load the checkpoint end-to-end through the classification pipeline
+    let model = SequenceClassificationModel::new(SequenceClassificationConfig {
+        device,
+        ..SequenceClassificationConfig::new(
+            ModelType::Albert,
+            ModelResource::Torch(Box::new(LocalResource::from(weights_path.to_path_buf()))),
+            LocalResource::from(config_path.to_path_buf()),
+            RemoteResource::from_pretrained(AlbertVocabResources::ALBERT_BASE_V2),
+            None,
+            true,
+            None,
+            None,
+        )
+    })?;
+    let output =
+        model.predict_pairs(&[("A man is playing a guitar.", "Somebody is making music.")])?;
+    assert_eq!(output.len(), 1);
+    assert!(["entailment", "neutral", "contradiction"].contains(&output[0].text.as_str()));
+
+    Ok(())
+}
+
 #[test]
 fn albert_for_multiple_choice() -> anyhow::Result<()> {
     //    Resources paths
@@ -2,7 +2,11 @@ use rust_bert::bart::{
     BartConfig, BartConfigResources, BartMergesResources, BartModel, BartModelResources,
     BartVocabResources,
 };
-use rust_bert::pipelines::common::ModelResource;
+use rust_bert::pipelines::common::{ModelResource, ModelType};
+use rust_bert::pipelines::reranking::{RerankingConfig, RerankingModel};
+use rust_bert::pipelines::sequence_classification::{
+    MemoryBudget, SequenceClassificationConfig, SequenceClassificationModel,
+};
 use rust_bert::pipelines::summarization::{SummarizationConfig, SummarizationModel};
 use rust_bert::pipelines::zero_shot_classification::{
     ZeroShotClassificationConfig, ZeroShotClassificationModel,
@@ -333,3 +337,114 @@ fn bart_zero_shot_classification_multilabel_try_error() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[cfg_attr(not(feature = "all-tests"), ignore)]
+fn bart_reranking_mnli() -> anyhow::Result<()> {
+    //    Set-up model, the last label of the MNLI model (entailment) scoring the relevance
+    let config = RerankingConfig {
+        device: Device::Cpu,
+        memory_budget: Some(MemoryBudget::from_bytes(1)),
+        ..RerankingConfig::new(
+            ModelType::Bart,
+            ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+                BartModelResources::BART_MNLI,
+            ))),
+            RemoteResource::from_pretrained(BartConfigResources::BART_MNLI),
+            RemoteResource::from_pretrained(BartVocabResources::BART_MNLI),
+            Some(RemoteResource::from_pretrained(
+                BartMergesResources::BART_MNLI,
+            )),
+            false,
+            None,
+            None,
+        )
+    };
+    let reranking_model = RerankingModel::new(config)?;
+
+    //    The memory budget splits the pairs into batches of a single pair
+    let output = reranking_model.rerank(
+        "A man is playing a guitar.",
+        &[
+            "Nobody is playing an instrument.",
+            "A person is playing music.",
+        ],
+    )?;
+
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0].0, 1);
+    assert_eq!(output[1].0, 0);
+    assert!(output[0].1 > 0.5);
+    assert!(output[1].1 < 0.5);
+
+    Ok(())
+}
+
+#[test]
+#[cfg_attr(not(feature = "all-tests"), ignore)]
+fn bart_sequence_classification_mnli() -> anyhow::Result<()> {
+    //    Set-up model
+    let config = SequenceClassificationConfig {
+        device: Device::Cpu,
+        ..SequenceClassificationConfig::new(
+            ModelType::Bart,
+            ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+                BartModelResources::BART_MNLI,
+            ))),
+            RemoteResource::from_pretrained(BartConfigResources::BART_MNLI),
+            RemoteResource::from_pretrained(BartVocabResources::BART_MNLI),
+            Some(RemoteResource::from_pretrained(
+                BartMergesResources::BART_MNLI,
+            )),
+            false,
+            None,
+            None,
+        )
+    };
+    let sequence_classification_model = SequenceClassificationModel::new(config)?;
+
+    let output = sequence_classification_model.predict_pairs(&[
+        ("A man is playing a guitar.", "A person is playing music."),
+        (
+            "A man is playing a guitar.",
+            "Nobody is playing an instrument.",
+        ),
+    ])?;
+
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0].text, "entailment");
+    assert_eq!(output[1].text, "contradiction");
+
+    //    Inputs encoded without special tokens are terminated with EOS
+    let config = SequenceClassificationConfig {
+        device: Device::Cpu,
+        add_special_tokens: false,
+        ..SequenceClassificationConfig::new(
+            ModelType::Bart,
+            ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+                BartModelResources::BART_MNLI,
+            ))),
+            RemoteResource::from_pretrained(BartConfigResources::BART_MNLI),
+            RemoteResource::from_pretrained(BartVocabResources::BART_MNLI),
+            Some(RemoteResource::from_pretrained(
+                BartMergesResources::BART_MNLI,
+            )),
+            false,
+            None,
+            None,
+        )
+    };
+    let sequence_classification_model = SequenceClassificationModel::new(config)?;
+
+    let output = sequence_classification_model.predict([
+        "A man is playing a guitar.",
+        "The prime minister has announced a stimulus package which was widely criticized by the opposition.",
+    ]);
+
+    assert_eq!(output.len(), 2);
+    assert!(output
+        .iter()
+        .all(|label| label.score > 0.0 && label.score <= 1.0));
+
+    Ok(())
+}
@@ -6,18 +6,26 @@ use rust_bert::bert::{
     BertForQuestionAnswering, BertForSequenceClassification, BertForTokenClassification,
     BertModelResources, BertVocabResources,
 };
-use rust_bert::pipelines::common::{ModelResource, ModelType};
+use rust_bert::pipelines::common::{ConfigOption, ModelResource, ModelType};
 use rust_bert::pipelines::masked_language::{MaskedLanguageConfig, MaskedLanguageModel};
 use rust_bert::pipelines::ner::NERModel;
 use rust_bert::pipelines::question_answering::{
     QaInput, QuestionAnsweringConfig, QuestionAnsweringModel,
 };
-use rust_bert::resources::{RemoteResource, ResourceProvider};
-use rust_bert::Config;
+use rust_bert::pipelines::sequence_classification::{
+    MissingPoolerPolicy, RepresentationStrategy, SequenceClassificationConfig,
+    SequenceClassificationModel, TracedModelCache,
+};
+use rust_bert::resources::{LocalResource, RemoteResource, ResourceProvider};
+use rust_bert::{Config, RustBertError};
 use rust_tokenizers::tokenizer::{BertTokenizer, MultiThreadedTokenizer, TruncationStrategy};
 use rust_tokenizers::vocab::Vocab;
 use std::collections::HashMap;
-use tch::{nn, no_grad, Device, Tensor};
+use std::convert::TryFrom;
+use std::io::Write;
+use std::path::Path;
+use tch::{nn, no_grad, Device, Kind, Tensor};
+use tempfile::TempPath;
 
 #[test]
 fn bert_masked_lm() -> anyhow::Result<()> {
@@ -143,6 +151,47 @@ fn bert_masked_lm_pipeline() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn bert_masked_lm_whole_words() -> anyhow::Result<()> {
+    //    Set-up model
+    let config = MaskedLanguageConfig::new(
+        ModelType::Bert,
+        ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+            BertModelResources::BERT,
+        ))),
+        RemoteResource::from_pretrained(BertConfigResources::BERT),
+        RemoteResource::from_pretrained(BertVocabResources::BERT),
+        None,
+        true,
+        None,
+        None,
+        Some(String::from("<mask>")),
+    );
+    let mask_language_model = MaskedLanguageModel::new(config)?;
+
+    //    Run model
+    let input = [
+        "Paris is the <mask> of France.",
+        "The <mask> is a large marine mammal living in the Arctic. It is <mask> in Europe.",
+    ];
+    let output = mask_language_model.predict_whole_words(input, 3)?;
+
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0].len(), 1);
+    assert_eq!(output[0][0].text, "capital");
+    assert_eq!(output[1].len(), 2);
+    let tokenizer = mask_language_model.get_tokenizer();
+    for word in output.iter().flatten() {
+        assert!(!word.token_ids.is_empty() && word.token_ids.len() <= 3);
+        assert!(word.score > 0.0 && word.score <= 1.0);
+        //    A word starts with a word-initial token, followed by continuation subwords
+        let tokens = tokenizer.convert_ids_to_tokens(&word.token_ids);
+        assert!(!tokens[0].starts_with("##"));
+        assert!(tokens[1..].iter().all(|token| token.starts_with("##")));
+    }
+    Ok(())
+}
+
 #[test]
 fn bert_for_sequence_classification() -> anyhow::Result<()> {
     //    Resources paths
@@ -205,6 +254,348 @@ fn bert_for_sequence_classification() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Small BERT configuration with two labels, fast to initialize randomly
+fn tiny_bert_config() -> BertConfig {
+    BertConfig {
+        hidden_size: 32,
+        intermediate_size: 64,
+        num_attention_heads: 2,
+        num_hidden_layers: 2,
+        id2label: Some(HashMap::from([
+            (0, String::from("Negative")),
+            (1, String::from("Positive")),
+        ])),
+        ..Default::default()
+    }
+}
+
+/// Saves a randomly initialized BERT sequence classifier and its configuration to temporary files, returning
+/// their paths and a pipeline configuration loading them
+fn random_bert_classifier(
+    config: &BertConfig,
+) -> anyhow::Result<(TempPath, TempPath, SequenceClassificationConfig)> {
+    let vs = nn::VarStore::new(Device::Cpu);
+    let _ = BertForSequenceClassification::new(vs.root(), config)?;
+    let weights_path = tempfile::NamedTempFile::new()?.into_temp_path();
+    vs.save(&weights_path)?;
+    let mut config_file = tempfile::NamedTempFile::new()?;
+    config_file.write_all(serde_json::to_string(config)?.as_bytes())?;
+    let config_path = config_file.into_temp_path();
+    let pipeline_config = bert_classifier_config(&weights_path, &config_path);
+    Ok((weights_path, config_path, pipeline_config))
+}
+
+/// CPU sequence classification configuration loading a local BERT checkpoint
+fn bert_classifier_config(weights_path: &Path, config_path: &Path) -> SequenceClassificationConfig {
+    SequenceClassificationConfig {
+        device: Device::Cpu,
+        ..SequenceClassificationConfig::new(
+            ModelType::Bert,
+            ModelResource::Torch(Box::new(LocalResource::from(weights_path.to_path_buf()))),
+            LocalResource::from(config_path.to_path_buf()),
+            RemoteResource::from_pretrained(BertVocabResources::BERT),
+            None,
+            true,
+            None,
+            None,
+        )
+    }
+}
+
+#[test]
+fn bert_sequence_classification_missing_pooler() -> anyhow::Result<()> {
+    //    Save a randomly initialized checkpoint without the pooler weights
+    let (weights_path, config_path, _) = random_bert_classifier(&tiny_bert_config())?;
+    let variables = Tensor::load_multi(&weights_path)?;
+    let named_tensors = variables
+        .iter()
+        .filter(|(name, _)| !name.contains("pooler"))
+        .map(|(name, tensor)| (name.clone(), tensor.shallow_clone()))
+        .collect::<Vec<(String, Tensor)>>();
+    assert_eq!(named_tensors.len() + 2, variables.len());
+    Tensor::save_multi(&named_tensors, &weights_path)?;
+    let pipeline_config = |missing_pooler| SequenceClassificationConfig {
+        missing_pooler,
+        ..bert_classifier_config(&weights_path, &config_path)
+    };
+
+    //    Loading fails by default, and succeeds with the fallback policies
+    let error = SequenceClassificationModel::new(pipeline_config(MissingPoolerPolicy::Error));
+    assert!(matches!(
+        error,
+        Err(RustBertError::WeightsMismatchError(message)) if message.contains("pooler")
+    ));
+    for missing_pooler in [
+        MissingPoolerPolicy::Initialize,
+        MissingPoolerPolicy::ClsPooling,
+    ] {
+        let model = SequenceClassificationModel::new(pipeline_config(missing_pooler))?;
+        let output = model.predict(["Looks like one thing is missing"]);
+        assert_eq!(output.len(), 1);
+        assert!(output[0].text == "Negative" || output[0].text == "Positive");
+    }
+
+    //    Last token pooling requires manual pooling
+    let model = SequenceClassificationModel::new(SequenceClassificationConfig {
+        representation_token: RepresentationStrategy::Last,
+        ..pipeline_config(MissingPoolerPolicy::ClsPooling)
+    })?;
+    assert_eq!(model.predict(["Looks like one thing is missing"]).len(), 1);
+    let error = SequenceClassificationModel::new(SequenceClassificationConfig {
+        representation_token: RepresentationStrategy::Last,
+        ..pipeline_config(MissingPoolerPolicy::Initialize)
+    });
+    assert!(matches!(
+        error,
+        Err(RustBertError::InvalidConfigurationError(_))
+    ));
+
+    Ok(())
+}
+#[test]
+fn bert_sequence_classification_token_pooling_ignores_padding() -> anyhow::Result<()> {
+    let vs = nn::VarStore::new(Device::Cpu);
+    let mut model = BertForSequenceClassification::new(vs.root(), &tiny_bert_config())?;
+
+    //    The same input, alone and padded with two positions
+    let input = Tensor::from_slice(&[101i64, 2023, 2003, 102]).unsqueeze(0);
+    let padded_input = Tensor::from_slice(&[101i64, 2023, 2003, 102, 0, 0]).unsqueeze(0);
+    let padded_mask = Tensor::from_slice(&[1i64, 1, 1, 1, 0, 0]).unsqueeze(0);
+
+    for strategy in [
+        RepresentationStrategy::First,
+        RepresentationStrategy::Last,
+        RepresentationStrategy::Mean,
+    ] {
+        model.set_token_pooling(Some(strategy));
+        let logits =
+            no_grad(|| model.forward_t(Some(&input), None, None, None, None, false)).logits;
+        let padded_logits = no_grad(|| {
+            model.forward_t(
+                Some(&padded_input),
+                Some(&padded_mask),
+                None,
+                None,
+                None,
+                false,
+            )
+        })
+        .logits;
+        assert!(
+            logits.allclose(&padded_logits, 1e-5, 1e-5, false),
+            "{:?} pooling depends on the padding",
+            strategy
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn bert_sequence_classification_non_contiguous_label_ids() -> anyhow::Result<()> {
+    let config = BertConfig {
+        id2label: Some(HashMap::from([
+            (2, String::from("A")),
+            (5, String::from("B")),
+        ])),
+        ..tiny_bert_config()
+    };
+    let (_weights_path, _config_path, pipeline_config) = random_bert_classifier(&config)?;
+
+    //    Hugging Face configurations store the label ids as strings
+    let mut config_value = serde_json::to_value(&config)?;
+    config_value["id2label"] = serde_json::json!({"2": "A", "5": "B"});
+    let config_json = serde_json::to_string(&config_value)?;
+    let parsed_config = BertConfig::from_json_str(&config_json)?;
+    assert_eq!(parsed_config.id2label, config.id2label);
+    let model_config = ConfigOption::from_json_str(ModelType::Bert, &config_json)?;
+    assert_eq!(model_config.get_output_label_ids(), [2, 5]);
+
+    //    Labels are assigned to the model outputs by increasing id, and keep their configured id
+    let model = SequenceClassificationModel::new(pipeline_config)?;
+    let input = ["One label or the other"];
+    let output = model.predict_multilabel_all_scores(&input)?;
+    let labels = output[0]
+        .iter()
+        .map(|label| (label.id, label.text.as_str()))
+        .collect::<Vec<(i64, &str)>>();
+    assert_eq!(labels, [(2, "A"), (5, "B")]);
+
+    let output = model.predict_restricted(input, &[5])?;
+    assert_eq!((output[0].id, output[0].text.as_str()), (5, "B"));
+    assert!((output[0].score - 1.0).abs() < 1e-6);
+    assert!(model.predict_restricted(input, &[1]).is_err());
+
+    let best_id = model.predict(input)[0].id;
+    assert_eq!(model.true_label_rank(input[0], best_id)?, 0);
+    assert!(model.true_label_rank(input[0], 0).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn bert_sequence_classification_add_tokens() -> anyhow::Result<()> {
+    let config = tiny_bert_config();
+    let (_weights_path, _config_path, pipeline_config) = random_bert_classifier(&config)?;
+    let mut model = SequenceClassificationModel::new(pipeline_config)?;
+
+    //    New tokens extend the vocabulary and the word embeddings
+    let vocab_size = model.add_tokens(&["[GENE]", "[PROTEIN]"])?;
+    assert_eq!(vocab_size, config.vocab_size + 2);
+    assert_eq!(
+        model.get_tokenizer().convert_tokens_to_ids(&["[GENE]"]),
+        [config.vocab_size]
+    );
+
+    //    Tokens already in the vocabulary are not added again
+    let vocab_size = model.add_tokens(&["[GENE]", "the"])?;
+    assert_eq!(vocab_size, config.vocab_size + 2);
+
+    let output = model.predict(["[GENE] and [PROTEIN] expression"]);
+    assert_eq!(output.len(), 1);
+    assert!(output[0].score.is_finite());
+
+    Ok(())
+}
+
+#[test]
+fn bert_sequence_classification_double_precision() -> anyhow::Result<()> {
+    //    Randomly initialized single precision checkpoint
+    let (weights_path, config_path, pipeline_config) = random_bert_classifier(&tiny_bert_config())?;
+    let classification_config = |dtype: Option<Kind>| SequenceClassificationConfig {
+        dtype,
+        ..bert_classifier_config(&weights_path, &config_path)
+    };
+
+    //    The weights are cast on load: both precisions predict the same labels
+    let model = SequenceClassificationModel::new(pipeline_config)?;
+    let double_model = SequenceClassificationModel::new(classification_config(Some(Kind::Double)))?;
+    let input = ["One label or the other", "Another input"];
+    let output = model.predict(input);
+    let double_output = double_model.predict(input);
+    for (label, double_label) in output.iter().zip(double_output.iter()) {
+        assert_eq!(label.id, double_label.id);
+        assert!((label.score - double_label.score).abs() < 1e-5);
+    }
+
+    //    Only floating point precisions are supported
+    assert!(matches!(
+        SequenceClassificationModel::new(classification_config(Some(Kind::Int64))),
+        Err(RustBertError::InvalidConfigurationError(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn bert_sequence_classification_load_partial() -> anyhow::Result<()> {
+    //    Checkpoint with a two labels classification head, fine-tune configuration with three labels
+    let mut config = tiny_bert_config();
+    let (weights_path, config_path, _) = random_bert_classifier(&config)?;
+    config.id2label = Some(HashMap::from([
+        (0, String::from("Negative")),
+        (1, String::from("Neutral")),
+        (2, String::from("Positive")),
+    ]));
+    std::fs::write(&config_path, serde_json::to_string(&config)?)?;
+    let pipeline_config = |load_partial| SequenceClassificationConfig {
+        load_partial,
+        ..bert_classifier_config(&weights_path, &config_path)
+    };
+
+    //    Loading fails on the classifier shape mismatch, unless partial loading is enabled
+    let error = SequenceClassificationModel::new(pipeline_config(false));
+    assert!(matches!(error, Err(RustBertError::TensorShapeError(_))));
+    let model = SequenceClassificationModel::new(pipeline_config(true))?;
+    let output = model.predict(["A new head for a new task"]);
+    assert_eq!(output.len(), 1);
+    assert!(output[0].id < 3);
+
+    Ok(())
+}
+
+#[test]
+fn bert_sequence_classification_segments() -> anyhow::Result<()> {
+    let config = BertConfig {
+        type_vocab_size: 3,
+        ..tiny_bert_config()
+    };
+    let (_weights_path, _config_path, pipeline_config) = random_bert_classifier(&config)?;
+    let model = SequenceClassificationModel::new(pipeline_config)?;
+
+    //    Each segment is identified by its token type id
+    let batch = model.prepare_segments_batch(&[&["hello world", "hello", "world"]])?;
+    let token_type_ids = Vec::<i64>::try_from(batch.token_type_ids().get(0))?;
+    assert_eq!(token_type_ids, [0, 0, 0, 0, 1, 1, 2, 2]);
+    let output = model.predict_segments(&[&["hello world", "hello", "world"], &["hello"]])?;
+    assert_eq!(output.len(), 2);
+
+    //    Inputs with more segments than token types are rejected
+    let error = model.predict_segments(&[&["one", "two", "three", "four"]]);
+    assert!(matches!(error, Err(RustBertError::ValueError(_))));
+
+    Ok(())
+}
+
+#[test]
+fn bert_sequence_classification_traced_model_cache() -> anyhow::Result<()> {
+    let (weights_path, config_path, pipeline_config) = random_bert_classifier(&tiny_bert_config())?;
+    let cache_dir = tempfile::tempdir()?;
+    let cache_path = cache_dir.path().join("bert_classifier.pt");
+    let input = [
+        "Looks like one thing is missing",
+        "It's like comparing oranges to apples",
+        "Short",
+    ];
+    let expected_output = SequenceClassificationModel::new(pipeline_config)?.predict(input);
+
+    //    The first load traces the model, the second one loads the cached module
+    let traced_model_cache = TracedModelCache::new(&cache_path, 2, 16);
+    for _ in 0..2 {
+        let model = SequenceClassificationModel::new(SequenceClassificationConfig {
+            traced_model_cache: Some(traced_model_cache.clone()),
+            ..bert_classifier_config(&weights_path, &config_path)
+        })?;
+        assert!(cache_path.is_file());
+        assert_eq!(model.model_max_length(), 16);
+        let output = model.predict(input);
+        assert_eq!(output.len(), 3);
+        for (label, expected_label) in output.iter().zip(expected_output.iter()) {
+            assert_eq!(label.id, expected_label.id);
+            assert!((label.score - expected_label.score).abs() < 1e-4);
+        }
+    }
+
+    //    Weights replaced at the same location invalidate the cached module
+    let variables = Tensor::load_multi(&weights_path)?
+        .into_iter()
+        .map(|(name, tensor)| {
+            let tensor = if name == "classifier.bias" {
+                Tensor::from_slice(&[10f32, -10f32])
+            } else {
+                tensor
+            };
+            (name, tensor)
+        })
+        .collect::<Vec<(String, Tensor)>>();
+    Tensor::save_multi(&variables, &weights_path)?;
+    let model = SequenceClassificationModel::new(SequenceClassificationConfig {
+        traced_model_cache: Some(traced_model_cache),
+        ..bert_classifier_config(&weights_path, &config_path)
+    })?;
+    let expected_output =
+        SequenceClassificationModel::new(bert_classifier_config(&weights_path, &config_path))?
+            .predict(input);
+    let output = model.predict(input);
+    for (label, expected_label) in output.iter().zip(expected_output.iter()) {
+        assert_eq!(label.id, 0);
+        assert_eq!(label.id, expected_label.id);
+        assert!((label.score - expected_label.score).abs() < 1e-4);
+    }
+
+    Ok(())
+}
+
 #[test]
 fn bert_for_multiple_choice() -> anyhow::Result<()> {
     //    Resources paths
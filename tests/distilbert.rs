@@ -3,14 +3,22 @@ use rust_bert::distilbert::{
     DistilBertForTokenClassification, DistilBertModelMaskedLM, DistilBertModelResources,
     DistilBertVocabResources,
 };
+use rust_bert::pipelines::common::{ModelType, Tokenize, TokenizerOption};
 use rust_bert::pipelines::question_answering::{QaInput, QuestionAnsweringModel};
 use rust_bert::pipelines::sentiment::{SentimentModel, SentimentPolarity};
+use rust_bert::pipelines::sequence_classification::{
+    Aggregation, BatchAccumulator, PredictOptions, SequenceClassificationConfig,
+    SequenceClassificationModel, TruncationSide, UnicodeNormalization, UnmappableCharacterPolicy,
+};
 use rust_bert::resources::{RemoteResource, ResourceProvider};
 use rust_bert::Config;
+use rust_bert::RustBertError;
 use rust_tokenizers::tokenizer::{BertTokenizer, MultiThreadedTokenizer, TruncationStrategy};
 use rust_tokenizers::vocab::Vocab;
+use rust_tokenizers::TokenizedInput;
 use std::collections::HashMap;
-use tch::{nn, no_grad, Device, Tensor};
+use std::convert::TryFrom;
+use tch::{nn, no_grad, Device, Kind, Tensor};
 
 extern crate anyhow;
 
@@ -39,6 +47,806 @@ fn distilbert_sentiment_classifier() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn distilbert_sequence_classification_all_scores() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Get scores for all classes
+    let input = [
+        "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+        "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+    ];
+
+    let output = sequence_classification_model.predict_multilabel_all_scores(&input)?;
+
+    assert_eq!(output.len(), 2usize);
+    for (sentence_idx, sentence_labels) in output.iter().enumerate() {
+        assert_eq!(sentence_labels.len(), 2usize);
+        assert_eq!(sentence_labels[0].text, "NEGATIVE");
+        assert_eq!(sentence_labels[1].text, "POSITIVE");
+        assert!(sentence_labels
+            .iter()
+            .all(|label| label.sentence == sentence_idx));
+    }
+    assert!(output[0][1].score > output[0][0].score);
+    assert!(output[1][0].score > output[1][1].score);
+
+    Ok(())
+}
+
+/// Custom tokenizer delegating to a `TokenizerOption` without exposing it
+struct OpaqueTokenizer(TokenizerOption);
+
+impl Tokenize for OpaqueTokenizer {
+    fn encode_list(
+        &self,
+        text_list: &[&str],
+        max_len: usize,
+        truncation_strategy: &TruncationStrategy,
+        stride: usize,
+    ) -> Vec<TokenizedInput> {
+        self.0
+            .encode_list(text_list, max_len, truncation_strategy, stride)
+    }
+
+    fn get_pad_id(&self) -> Option<i64> {
+        self.0.get_pad_id()
+    }
+
+    fn decode(
+        &self,
+        token_ids: &[i64],
+        skip_special_tokens: bool,
+        clean_up_tokenization_spaces: bool,
+    ) -> String {
+        self.0
+            .decode(token_ids, skip_special_tokens, clean_up_tokenization_spaces)
+    }
+}
+
+#[test]
+fn distilbert_sequence_classification_custom_tokenizer() -> anyhow::Result<()> {
+    let opaque_tokenizer = || -> anyhow::Result<Box<OpaqueTokenizer>> {
+        let vocab_path =
+            RemoteResource::from_pretrained(DistilBertVocabResources::DISTIL_BERT_SST2)
+                .get_local_path()?;
+        Ok(Box::new(OpaqueTokenizer(TokenizerOption::from_file(
+            ModelType::DistilBert,
+            vocab_path.to_str().unwrap(),
+            None,
+            true,
+            None,
+            None,
+        )?)))
+    };
+
+    //    Options relying on the tokenizer specifics are rejected when building the model
+    for config in [
+        SequenceClassificationConfig {
+            add_special_tokens: false,
+            ..Default::default()
+        },
+        SequenceClassificationConfig {
+            truncation_side: TruncationSide::Left,
+            ..Default::default()
+        },
+    ] {
+        assert!(matches!(
+            SequenceClassificationModel::new_with_custom_tokenizer(config, opaque_tokenizer()?),
+            Err(RustBertError::InvalidConfigurationError(_))
+        ));
+    }
+
+    //    Features relying on the tokenizer specifics return an error instead of panicking
+    let model = SequenceClassificationModel::new_with_custom_tokenizer(
+        Default::default(),
+        opaque_tokenizer()?,
+    )?;
+    let reference_model = SequenceClassificationModel::new(Default::default())?;
+    let input = ["This is a great movie."];
+    assert_eq!(
+        model.predict(input)[0].text,
+        reference_model.predict(input)[0].text
+    );
+    assert!(matches!(
+        model.predict_pairs(&[("This is a great movie.", "I loved it.")]),
+        Err(RustBertError::InvalidConfigurationError(_))
+    ));
+    assert!(matches!(
+        model.predict_long(&input, 16, Aggregation::Mean),
+        Err(RustBertError::InvalidConfigurationError(_))
+    ));
+    assert!(matches!(
+        model.predict_with_unknown_token_rate(input),
+        Err(RustBertError::InvalidConfigurationError(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_unmappable_characters() -> anyhow::Result<()> {
+    //    Set-up classifiers
+    let skip_model = SequenceClassificationModel::new(SequenceClassificationConfig {
+        unmappable_characters: UnmappableCharacterPolicy::Skip,
+        ..Default::default()
+    })?;
+
+    //    Emoji, control characters and characters surrounding the surrogates range
+    let input = [
+        "What a great film \u{1F980}",
+        "What a great film\u{0007}\u{0000}",
+        "What a great \u{D7FF}film\u{E000}",
+    ];
+    let reference = skip_model.predict(["What a great film"]);
+    let output = skip_model.predict(input);
+
+    assert_eq!(output.len(), 3);
+    for label in output.iter() {
+        assert_eq!(label.text, reference[0].text);
+        assert!((label.score - reference[0].score).abs() < 1e-6);
+    }
+
+    //    Control characters are removed by the tokenizer normalization
+    assert!(skip_model
+        .check_unmappable_characters(["What a great film\u{0007}"])
+        .is_ok());
+    assert!(matches!(
+        skip_model.check_unmappable_characters(["What a great film \u{1F980}"]),
+        Err(RustBertError::ValueError(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_compare() -> anyhow::Result<()> {
+    //    Set-up classifiers
+    let reference_model = SequenceClassificationModel::new(Default::default())?;
+    let other_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Compare the predictions of identical models
+    let input = [
+        "Probably my all-time favorite movie, a story of selflessness, sacrifice and dedication to a noble cause, but it's not preachy or boring.",
+        "This film tried to be too many things all at once: stinging political satire, Hollywood blockbuster, sappy romantic comedy, family values promo...",
+    ];
+    let output = reference_model.compare(&other_model, input)?;
+
+    assert_eq!(output.len(), 2);
+    for (sentence, diff) in output.iter().enumerate() {
+        assert_eq!(diff.sentence, sentence);
+        assert!(!diff.label_changed);
+        assert_eq!(diff.label.text, diff.other_label.text);
+        assert!(diff.score_delta.abs() < 1e-6);
+        assert!(diff.max_score_delta < 1e-6);
+    }
+    assert_eq!(output[0].label.text, "POSITIVE");
+    assert_eq!(output[1].label.text, "NEGATIVE");
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_from_var_store() -> anyhow::Result<()> {
+    //    Set-up classifier in a VarStore holding a custom layer
+    let var_store = nn::VarStore::new(Device::Cpu);
+    let custom_layer = nn::linear(var_store.root() / "custom_head", 2, 1, Default::default());
+    let custom_weights = custom_layer.ws.copy();
+    let mut sequence_classification_model =
+        SequenceClassificationModel::from_var_store(var_store, Default::default())?;
+
+    //    Classify and reload the weights, leaving the custom layer unchanged
+    let input = ["This is a great movie.", "This movie was a waste of time."];
+    let output = sequence_classification_model.predict(input);
+    assert_eq!(output[0].text, "POSITIVE");
+    assert_eq!(output[1].text, "NEGATIVE");
+
+    let weights = RemoteResource::from_pretrained(DistilBertModelResources::DISTIL_BERT_SST2);
+    sequence_classification_model.reload_weights(&weights)?;
+    assert!(custom_layer.ws.equal(&custom_weights));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_predict_tensor() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Tokenize and pad the inputs outside of the pipeline
+    let input = ["This is a great movie.", "What a waste of time."];
+    let tokenized_input = sequence_classification_model.get_tokenizer().encode_list(
+        &input,
+        128,
+        &TruncationStrategy::LongestFirst,
+        0,
+    );
+    let max_len = tokenized_input
+        .iter()
+        .map(|input| input.token_ids.len())
+        .max()
+        .unwrap();
+    let (input_ids, masks): (Vec<Tensor>, Vec<Tensor>) = tokenized_input
+        .into_iter()
+        .map(|input| {
+            let mut token_ids = input.token_ids;
+            let mut mask = vec![1; token_ids.len()];
+            token_ids.resize(max_len, 0);
+            mask.resize(max_len, 0);
+            (Tensor::from_slice(&token_ids), Tensor::from_slice(&mask))
+        })
+        .unzip();
+    let input_ids = Tensor::stack(&input_ids, 0);
+    let attention_mask = Tensor::stack(&masks, 0);
+
+    let output = sequence_classification_model.predict_tensor(
+        input_ids.shallow_clone(),
+        Some(attention_mask),
+        Some(input_ids.zeros_like()),
+    )?;
+    let expected_output = sequence_classification_model.predict(input);
+
+    assert_eq!(output.len(), 2);
+    for (label, expected_label) in output.iter().zip(expected_output.iter()) {
+        assert_eq!(label.text, expected_label.text);
+        assert!((label.score - expected_label.score).abs() < 1e-4);
+    }
+
+    //    Invalid shapes are reported as errors
+    assert!(matches!(
+        sequence_classification_model.predict_tensor(input_ids.get(0), None, None),
+        Err(RustBertError::TensorShapeError(_))
+    ));
+    assert!(matches!(
+        sequence_classification_model.predict_tensor(
+            input_ids.shallow_clone(),
+            None,
+            Some(input_ids.get(0))
+        ),
+        Err(RustBertError::TensorShapeError(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_predict_verbose() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Get the predictions and tokens
+    let input = ["This is a great movie.", "What a waste of time."];
+    let output = sequence_classification_model.predict_verbose(&input);
+    let expected_output = sequence_classification_model.predict(input);
+
+    assert_eq!(output.len(), 2);
+    assert_eq!(
+        output[0].1,
+        ["[CLS]", "this", "is", "a", "great", "movie", ".", "[SEP]"]
+    );
+    assert_eq!(output[1].1.first().map(String::as_str), Some("[CLS]"));
+    assert_eq!(output[1].1.last().map(String::as_str), Some("[SEP]"));
+    for ((label, _), expected_label) in output.iter().zip(expected_output.iter()) {
+        assert_eq!(label.text, expected_label.text);
+        assert!((label.score - expected_label.score).abs() < 1e-4);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_encode_with_offsets() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Encode a text and align the tokens to its characters
+    let input = "A great movie";
+    let (token_ids, offsets) = sequence_classification_model.encode_with_offsets(input);
+
+    assert_eq!(token_ids.len(), 5);
+    assert_eq!(offsets, [(0, 0), (0, 1), (2, 7), (8, 13), (0, 0)]);
+    assert_eq!(&input[offsets[2].0..offsets[2].1], "great");
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_pad_to_multiple_of() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model =
+        SequenceClassificationModel::new(SequenceClassificationConfig {
+            pad_to_multiple_of: Some(8),
+            ..Default::default()
+        })?;
+
+    //    The batch is padded to a multiple of 8 tokens
+    let input = ["This is a great movie.", "What a waste of time, really."];
+    let batch = sequence_classification_model.prepare_batch(input);
+    assert_eq!(batch.input_ids().size(), [2, 16]);
+    assert_eq!(batch.attention_mask().size(), [2, 16]);
+
+    //    The padding is masked and does not change the predictions
+    let output = sequence_classification_model.predict(input);
+    let unpadded_model = SequenceClassificationModel::new(Default::default())?;
+    let unpadded_output = unpadded_model.predict(input);
+    assert_eq!(output.len(), 2);
+    for (label, unpadded_label) in output.iter().zip(unpadded_output.iter()) {
+        assert_eq!(label.text, unpadded_label.text);
+        assert!((label.score - unpadded_label.score).abs() < 1e-4);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_ignored_spans() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Without ignored spans, the prediction is unchanged
+    let input = ["Terrible sponsored banner. I really enjoyed this movie."];
+    let output = sequence_classification_model.predict_with_ignored_spans(&input, &[&[]])?;
+    let expected_output = sequence_classification_model.predict(input);
+    assert_eq!(output[0].text, expected_output[0].text);
+    assert!((output[0].score - expected_output[0].score).abs() < 1e-4);
+
+    //    Ignoring the boilerplate
+    let output = sequence_classification_model.predict_with_ignored_spans(&input, &[&[(0, 26)]])?;
+    assert_eq!(output.len(), 1);
+    assert_eq!(output[0].text, "POSITIVE");
+
+    let error = sequence_classification_model.predict_with_ignored_spans(&input, &[]);
+    assert!(matches!(error, Err(RustBertError::ValueError(_))));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_priors() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Uniform priors select the most probable label
+    let input = ["This is a great movie.", "What a waste of time."];
+    let output = sequence_classification_model.predict_with_priors(input, &[1.0, 1.0])?;
+    let expected_output = sequence_classification_model.predict(input);
+    for (label, expected_label) in output.iter().zip(expected_output.iter()) {
+        assert_eq!(label.id, expected_label.id);
+        assert!((label.score - expected_label.score).abs() < 1e-4);
+    }
+
+    //    A null prior prevents the selection of a label
+    let output = sequence_classification_model.predict_with_priors(input, &[1.0, 0.0])?;
+    assert!(output.iter().all(|label| label.text == "NEGATIVE"));
+    assert!(output[0].score < 0.5);
+
+    //    Invalid priors are rejected
+    let error = sequence_classification_model.predict_with_priors(input, &[1.0]);
+    assert!(matches!(error, Err(RustBertError::ValueError(_))));
+    let error = sequence_classification_model.predict_with_priors(input, &[1.0, -1.0]);
+    assert!(matches!(error, Err(RustBertError::ValueError(_))));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_restricted() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Restricting the prediction to a single label
+    let input = ["This is a great movie.", "What a waste of time."];
+    let output = sequence_classification_model.predict_restricted(input, &[0])?;
+    assert_eq!(output.len(), 2);
+    for label in output {
+        assert_eq!(label.text, "NEGATIVE");
+        assert!((label.score - 1.0).abs() < 1e-6);
+    }
+
+    //    Allowing all labels is equivalent to an unrestricted prediction
+    let output = sequence_classification_model.predict_restricted(input, &[0, 1])?;
+    let expected_output = sequence_classification_model.predict(input);
+    for (label, expected_label) in output.iter().zip(expected_output.iter()) {
+        assert_eq!(label.id, expected_label.id);
+        assert!((label.score - expected_label.score).abs() < 1e-4);
+    }
+
+    //    Unknown or missing allowed labels are rejected
+    let error = sequence_classification_model.predict_restricted(input, &[2]);
+    assert!(matches!(error, Err(RustBertError::ValueError(_))));
+    let error = sequence_classification_model.predict_restricted(input, &[]);
+    assert!(matches!(error, Err(RustBertError::ValueError(_))));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_explain_occlusion() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Occlude each token, then spans of two tokens
+    let output = sequence_classification_model.explain_occlusion("This is a great movie.", 1);
+    assert_eq!(output.len(), 6);
+    assert_eq!(output[3].0, "great");
+    assert!(output.iter().all(|(_, score)| score.abs() <= 1.0));
+
+    let output = sequence_classification_model.explain_occlusion("This is a great movie.", 2);
+    assert_eq!(output.len(), 3);
+    assert_eq!(output[1].0, "a great");
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_multilabel_fallback() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    No label can exceed a threshold above 1
+    let input = ["This is a great movie.", "What a waste of time."];
+    let output =
+        sequence_classification_model.predict_multilabel_with_fallback(&input, 1.1, false)?;
+    assert_eq!(output.len(), 2);
+    assert!(output
+        .iter()
+        .all(|prediction| prediction.labels.is_empty() && !prediction.fallback));
+
+    let output =
+        sequence_classification_model.predict_multilabel_with_fallback(&input, 1.1, true)?;
+    assert_eq!(output.len(), 2);
+    assert!(output
+        .iter()
+        .all(|prediction| prediction.labels.len() == 1 && prediction.fallback));
+    assert_eq!(output[0].labels[0].text, "POSITIVE");
+    assert_eq!(output[1].labels[0].text, "NEGATIVE");
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_calibration_report() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Compute the calibration on a small labelled set
+    let input = [
+        "This is a great movie.",
+        "What a waste of time.",
+        "I loved every minute of it.",
+        "The plot made no sense at all.",
+    ];
+    let report = sequence_classification_model.calibration_report(&input, &[1, 0, 1, 0], 5)?;
+
+    assert_eq!(report.bins.len(), 5);
+    assert_eq!(report.bins.iter().map(|bin| bin.count).sum::<usize>(), 4);
+    assert!((report.accuracy - 1.0).abs() < 1e-9);
+    assert!(report.expected_calibration_error >= 0.0 && report.expected_calibration_error < 0.5);
+
+    //    Invalid arguments are reported as errors
+    assert!(matches!(
+        sequence_classification_model.calibration_report(&input, &[1, 0], 5),
+        Err(RustBertError::ValueError(_))
+    ));
+    assert!(matches!(
+        sequence_classification_model.calibration_report(&input, &[1, 0, 1, 0], 0),
+        Err(RustBertError::ValueError(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_true_label_rank() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    The predicted label is ranked first, the other label second
+    let input = ["This is a great movie.", "What a waste of time."];
+    let ranks = sequence_classification_model.true_label_ranks(&input, &[1, 1])?;
+    assert_eq!(ranks, [0, 1]);
+    assert_eq!(
+        sequence_classification_model.true_label_rank("What a waste of time.", 0)?,
+        0
+    );
+
+    //    Mismatched or unknown true label ids are rejected
+    let error = sequence_classification_model.true_label_ranks(&input, &[1]);
+    assert!(matches!(error, Err(RustBertError::ValueError(_))));
+    let error = sequence_classification_model.true_label_ranks(&input, &[1, 5]);
+    assert!(matches!(error, Err(RustBertError::ValueError(_))));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_normalization() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model =
+        SequenceClassificationModel::new(SequenceClassificationConfig {
+            normalization: Some(UnicodeNormalization::Nfkc),
+            ..Default::default()
+        })?;
+
+    //    Full-width characters are mapped to their ASCII equivalents before tokenization
+    let (token_ids, _) = sequence_classification_model.encode_with_offsets("ａ ｇｒｅａｔ movie");
+    let (expected_token_ids, _) =
+        sequence_classification_model.encode_with_offsets("a great movie");
+    assert_eq!(token_ids, expected_token_ids);
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_metadata() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Get the metadata of the pipeline
+    let metadata = sequence_classification_model.metadata();
+    assert_eq!(metadata.model_type, ModelType::DistilBert);
+    assert!(metadata.model_resource.ends_with("rust_model.ot"));
+    assert!(metadata.vocab_resource.ends_with("vocab.txt"));
+    assert_eq!(metadata.merges_resource, None);
+    assert_eq!(metadata.label_mapping[&0], "NEGATIVE");
+    assert_eq!(metadata.label_mapping[&1], "POSITIVE");
+    assert_eq!(metadata.max_length, 512);
+
+    let serialized_metadata = serde_json::to_string(&metadata)?;
+    assert!(serialized_metadata.contains("\"model_type\":\"DistilBert\""));
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_provenance() -> anyhow::Result<()> {
+    //    Without tag, the predictions are attributed to the model weights resource
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    let input = ["This is a great movie.", "What a waste of time."];
+    let output = sequence_classification_model.predict_with_provenance(input);
+    let expected_labels = sequence_classification_model.predict(input);
+    assert_eq!(output.len(), 2);
+    for (prediction, expected_label) in output.iter().zip(expected_labels.iter()) {
+        assert_eq!(prediction.label.id, expected_label.id);
+        assert!(prediction
+            .model_id
+            .as_deref()
+            .unwrap()
+            .ends_with("rust_model.ot"));
+    }
+
+    //    A model tag takes precedence over the resource identifier
+    let sequence_classification_model =
+        SequenceClassificationModel::new(SequenceClassificationConfig {
+            model_tag: Some(String::from("sst2-v1")),
+            ..Default::default()
+        })?;
+    assert_eq!(sequence_classification_model.model_id(), Some("sst2-v1"));
+    let output = sequence_classification_model.predict_with_provenance(input);
+    assert!(output
+        .iter()
+        .all(|prediction| prediction.model_id.as_deref() == Some("sst2-v1")));
+    assert_eq!(
+        sequence_classification_model
+            .metadata()
+            .model_tag
+            .as_deref(),
+        Some("sst2-v1")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_head_tail_truncation() -> anyhow::Result<()> {
+    //    Set-up classifier keeping the 100 first tokens of long inputs
+    let sequence_classification_model =
+        SequenceClassificationModel::new(SequenceClassificationConfig {
+            truncation_side: TruncationSide::HeadTail { head: 100 },
+            ..Default::default()
+        })?;
+    let tokenizer = sequence_classification_model.get_tokenizer();
+    let expected_ids = tokenizer.convert_tokens_to_ids(&["start", "middle", "end"]);
+
+    //    The beginning and end of the input are retained, between the special tokens
+    let long_input = format!(
+        "start {}middle {}end",
+        "movie ".repeat(500),
+        "movie ".repeat(500)
+    );
+    let (token_ids, _) = sequence_classification_model.encode_with_offsets(&long_input);
+    assert_eq!(token_ids.len(), 512);
+    assert_eq!(token_ids[0], tokenizer.get_cls_id().unwrap());
+    assert_eq!(token_ids[1], expected_ids[0]);
+    assert!(!token_ids.contains(&expected_ids[1]));
+    assert_eq!(token_ids[510], expected_ids[2]);
+    assert_eq!(token_ids[511], tokenizer.get_sep_id().unwrap());
+
+    //    The truncation report spans the text between the retained head and tail
+    let (_, report) = sequence_classification_model
+        .predict_with_truncation_report([long_input.as_str()])
+        .remove(0);
+    assert_eq!(report.truncated_tokens, 493);
+    assert_eq!(report.dropped_char_span, Some((599, 3559)));
+    assert_eq!(report.dropped_byte_span, Some((599, 3559)));
+    assert_eq!(report.preprocessed_text, None);
+    assert!(!long_input[599..3559].contains("start"));
+    assert!(long_input[599..3559].contains("middle"));
+
+    //    Short inputs are not truncated
+    let (token_ids, _) = sequence_classification_model.encode_with_offsets("start middle end");
+    assert_eq!(token_ids.len(), 5);
+    assert_eq!(&token_ids[1..4], expected_ids.as_slice());
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_predict_options() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    Without options, only the top label (matching `predict`) is returned
+    let input = ["This is a great movie.", "What a waste of time."];
+    let expected_labels = sequence_classification_model.predict(input);
+    let output = sequence_classification_model.predict_with_options(input, &Default::default());
+    assert_eq!(output.len(), 2);
+    for (prediction, expected_label) in output.iter().zip(expected_labels.iter()) {
+        assert_eq!(prediction.label.id, expected_label.id);
+        assert!((prediction.label.score - expected_label.score).abs() < 1e-4);
+        assert!(prediction.margin.is_none());
+        assert!(prediction.scores.is_none());
+        assert!(prediction.log_scores.is_none());
+        assert!(prediction.multilabel.is_none());
+    }
+
+    //    With two labels, the margin is the difference between the scores of the top and other label. The multi-label
+    //    sets match `predict_multilabel_all_scores`
+    let options = PredictOptions {
+        margin: true,
+        scores: true,
+        log_scores: true,
+        multilabel_threshold: Some(0.5),
+    };
+    let output = sequence_classification_model.predict_with_options(input, &options);
+    let expected_multilabel_scores =
+        sequence_classification_model.predict_multilabel_all_scores(&input)?;
+    assert_eq!(output.len(), 2);
+    for ((prediction, expected_label), expected_multilabel_scores) in output
+        .iter()
+        .zip(expected_labels.iter())
+        .zip(expected_multilabel_scores.iter())
+    {
+        let label = &prediction.label;
+        assert_eq!(label.id, expected_label.id);
+        assert!((prediction.margin.unwrap() - (2.0 * label.score - 1.0)).abs() < 1e-4);
+        let scores = prediction.scores.as_ref().unwrap();
+        let log_scores = prediction.log_scores.as_ref().unwrap();
+        assert_eq!(scores.len(), 2);
+        assert!((scores.values().sum::<f64>() - 1.0).abs() < 1e-4);
+        assert!((scores[&label.text] - label.score).abs() < 1e-4);
+        for (label_name, score) in scores {
+            assert!((log_scores[label_name].exp() - score).abs() < 1e-4);
+        }
+        let expected_ids = expected_multilabel_scores
+            .iter()
+            .filter(|label| label.score >= 0.5)
+            .map(|label| label.id)
+            .collect::<Vec<i64>>();
+        assert_eq!(
+            prediction
+                .multilabel
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(|label| label.id)
+                .collect::<Vec<i64>>(),
+            expected_ids
+        );
+    }
+    assert!(sequence_classification_model
+        .predict_with_options(&[] as &[&str], &options)
+        .is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_batch_accumulator() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+    let mut accumulator = BatchAccumulator::new(&sequence_classification_model);
+
+    //    Flushing an empty accumulator does not run the model
+    assert!(accumulator.flush().is_empty());
+
+    //    The labels are keyed by request id
+    accumulator.push("request-b", "What a waste of time.");
+    accumulator.push("request-a", "This is a great movie.");
+    assert_eq!(accumulator.len(), 2);
+    let labels = accumulator.flush();
+    assert!(accumulator.is_empty());
+    assert_eq!(labels.len(), 2);
+    assert_eq!(labels["request-a"].text, "POSITIVE");
+    assert_eq!(labels["request-b"].text, "NEGATIVE");
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_unknown_token_rate() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    The rate is the share of the input tokens (excluding special tokens) mapped to the unknown token
+    let input = ["This is a great movie.", "A great 🦀 movie 🦀"];
+    let output = sequence_classification_model.predict_with_unknown_token_rate(input)?;
+    let expected_labels = sequence_classification_model.predict(input);
+    assert_eq!(output.len(), 2);
+    for ((label, unknown_token_rate), (text, expected_label)) in
+        output.iter().zip(input.iter().zip(expected_labels.iter()))
+    {
+        assert_eq!(label.id, expected_label.id);
+        let tokens = sequence_classification_model.get_tokenizer().tokenize(text);
+        let expected_rate =
+            tokens.iter().filter(|token| *token == "[UNK]").count() as f64 / tokens.len() as f64;
+        assert!((unknown_token_rate - expected_rate).abs() < 1e-9);
+    }
+    assert_eq!(output[0].1, 0.0);
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_ood_score() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model = SequenceClassificationModel::new(Default::default())?;
+
+    //    The energy score is the log-sum-exp of the logits
+    let input = ["This is a great movie.", "What a waste of time."];
+    let scores = sequence_classification_model.ood_score(&input);
+    let logits = sequence_classification_model
+        .predict_prepared_logits(&sequence_classification_model.prepare_batch(input));
+    assert_eq!(scores.len(), 2);
+    for (sentence_idx, score) in scores.iter().enumerate() {
+        let sentence_logits =
+            Vec::<f64>::try_from(logits.get(sentence_idx as i64).to_kind(Kind::Double))?;
+        let max_logit = sentence_logits.iter().cloned().fold(f64::MIN, f64::max);
+        let expected_score = max_logit
+            + sentence_logits
+                .iter()
+                .map(|logit| (logit - max_logit).exp())
+                .sum::<f64>()
+                .ln();
+        assert!((score - expected_score).abs() < 1e-4);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn distilbert_sequence_classification_attention_rollout() -> anyhow::Result<()> {
+    //    Set-up classifier
+    let sequence_classification_model =
+        SequenceClassificationModel::new(SequenceClassificationConfig {
+            output_attentions: true,
+            ..Default::default()
+        })?;
+
+    //    Get the token importances
+    let output = sequence_classification_model.attention_rollout("This is a great movie.")?;
+
+    assert_eq!(output.len(), 8);
+    assert_eq!(output[0].0, "[CLS]");
+    assert_eq!(output[7].0, "[SEP]");
+    let total_importance: f32 = output.iter().map(|(_, importance)| importance).sum();
+    assert!((total_importance - 1.0).abs() < 1e-4);
+    assert!(output.iter().all(|(_, importance)| *importance > 0.0));
+
+    Ok(())
+}
+
 #[test]
 fn distilbert_masked_lm() -> anyhow::Result<()> {
     //    Resources paths
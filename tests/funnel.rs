@@ -0,0 +1,96 @@
+extern crate anyhow;
+
+use rust_bert::funnel::{FunnelBaseModel, FunnelConfig, FunnelForSequenceClassification};
+use rust_bert::RustBertError;
+use std::collections::HashMap;
+use tch::{nn, no_grad, Device, Kind, Tensor};
+
+fn tiny_funnel_config() -> FunnelConfig {
+    FunnelConfig {
+        vocab_size: 100,
+        block_sizes: vec![1, 1],
+        num_decoder_layers: 1,
+        d_model: 32,
+        n_head: 2,
+        d_head: 16,
+        d_inner: 64,
+        id2label: Some(HashMap::from([
+            (0, String::from("A")),
+            (1, String::from("B")),
+            (2, String::from("C")),
+        ])),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn funnel_sequence_classification_head() -> anyhow::Result<()> {
+    //    Randomly initialized classifier, and a base model sharing its weights
+    let device = Device::Cpu;
+    let config = tiny_funnel_config();
+    let vs = nn::VarStore::new(device);
+    let model = FunnelForSequenceClassification::new(vs.root(), &config)?;
+    let mut base_vs = nn::VarStore::new(device);
+    let base_model = FunnelBaseModel::new(base_vs.root() / "funnel", &config);
+    base_vs.copy(&vs)?;
+
+    //    Define input, the CLS token having a token type id of 2
+    let (batch_size, sequence_length) = (2, 9);
+    let input_ids = Tensor::randint(
+        config.vocab_size,
+        [batch_size, sequence_length],
+        (Kind::Int64, device),
+    );
+    let mut token_type_ids = vec![0i64; sequence_length as usize];
+    token_type_ids[0] = 2;
+    let token_type_ids = Tensor::from_slice(&token_type_ids)
+        .unsqueeze(0)
+        .repeat([batch_size, 1])
+        .to(device);
+
+    //    Forward pass
+    let (model_output, base_model_output) = no_grad(|| {
+        let model_output =
+            model.forward_t(Some(&input_ids), None, Some(&token_type_ids), None, false)?;
+        let base_model_output =
+            base_model.forward_t(Some(&input_ids), None, Some(&token_type_ids), None, false)?;
+        Ok::<_, RustBertError>((model_output, base_model_output))
+    })?;
+
+    //    The head classifies the hidden state of the first (CLS) position of the compressed sequence
+    let variables = vs.variables();
+    let expected_logits = no_grad(|| {
+        base_model_output
+            .hidden_state
+            .select(1, 0)
+            .linear(
+                &variables["classifier.linear_hidden.weight"],
+                Some(&variables["classifier.linear_hidden.bias"]),
+            )
+            .tanh()
+            .linear(
+                &variables["classifier.linear_out.weight"],
+                Some(&variables["classifier.linear_out.bias"]),
+            )
+    });
+    assert_eq!(model_output.logits.size(), [batch_size, 3]);
+    assert!(model_output
+        .logits
+        .allclose(&expected_logits, 1e-5, 1e-6, false));
+
+    Ok(())
+}
+
+#[test]
+fn funnel_sequence_classification_requires_labels() {
+    let vs = nn::VarStore::new(Device::Cpu);
+    let config = FunnelConfig {
+        id2label: None,
+        ..tiny_funnel_config()
+    };
+
+    assert!(matches!(
+        FunnelForSequenceClassification::new(vs.root(), &config),
+        Err(RustBertError::InvalidConfigurationError(_))
+    ));
+}
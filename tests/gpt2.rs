@@ -7,12 +7,16 @@ use rust_bert::pipelines::conversation::{
     ConversationConfig, ConversationManager, ConversationModel,
 };
 use rust_bert::pipelines::generation_utils::{
-    Cache, GenerateConfig, GenerateOptions, LanguageGenerator,
+    BadWordsLogitsProcessor, Cache, ForcedEosTokenLogitsProcessor, GenerateConfig, GenerateOptions,
+    LanguageGenerator,
 };
 use rust_bert::pipelines::text_generation::{TextGenerationConfig, TextGenerationModel};
 use rust_bert::resources::{RemoteResource, ResourceProvider};
 use rust_bert::Config;
 use rust_tokenizers::tokenizer::{Gpt2Tokenizer, Tokenizer, TruncationStrategy};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tch::{nn, Device, Tensor};
 
 #[test]
@@ -129,6 +133,47 @@ fn gpt2_generation_greedy() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn gpt2_generation_greedy_streaming() -> anyhow::Result<()> {
+    //    Resources definition
+    let config_resource = Box::new(RemoteResource::from_pretrained(Gpt2ConfigResources::GPT2));
+    let vocab_resource = Box::new(RemoteResource::from_pretrained(Gpt2VocabResources::GPT2));
+    let merges_resource = Box::new(RemoteResource::from_pretrained(Gpt2MergesResources::GPT2));
+    let model_resource = Box::new(RemoteResource::from_pretrained(Gpt2ModelResources::GPT2));
+
+    let generate_config = TextGenerationConfig {
+        model_type: ModelType::GPT2,
+        model_resource: ModelResource::Torch(model_resource),
+        config_resource,
+        vocab_resource,
+        merges_resource: Some(merges_resource),
+        max_length: Some(40),
+        do_sample: false,
+        num_beams: 1,
+        temperature: 1.1,
+        repetition_penalty: 1.1,
+        ..Default::default()
+    };
+    let model = TextGenerationModel::new(generate_config)?;
+
+    let input_context = "The cat";
+    let streamed_text = RefCell::new(String::new());
+    let output = model.generate_streaming(&[input_context], None, &|index, new_text, text| {
+        assert_eq!(index, 0);
+        let mut streamed_text = streamed_text.borrow_mut();
+        streamed_text.push_str(new_text);
+        assert_eq!(streamed_text.as_str(), text);
+    });
+
+    assert_eq!(output.len(), 1);
+    assert_eq!(
+        format!("{}{}", input_context, streamed_text.into_inner()),
+        output[0]
+    );
+
+    Ok(())
+}
+
 #[test]
 fn gpt2_generation_beam_search() -> anyhow::Result<()> {
     //    Resources definition
@@ -173,6 +218,59 @@ fn gpt2_generation_beam_search() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn gpt2_generation_beam_search_call_options() -> anyhow::Result<()> {
+    //    Resources definition
+    let config_resource = Box::new(RemoteResource::from_pretrained(Gpt2ConfigResources::GPT2));
+    let vocab_resource = Box::new(RemoteResource::from_pretrained(Gpt2VocabResources::GPT2));
+    let merges_resource = Box::new(RemoteResource::from_pretrained(Gpt2MergesResources::GPT2));
+    let model_resource = Box::new(RemoteResource::from_pretrained(Gpt2ModelResources::GPT2));
+
+    //    Greedy pipeline configuration, overridden with beam search settings at call time
+    let generate_config = TextGenerationConfig {
+        model_type: ModelType::GPT2,
+        model_resource: ModelResource::Torch(model_resource),
+        config_resource,
+        vocab_resource,
+        merges_resource: Some(merges_resource),
+        max_length: Some(20),
+        do_sample: false,
+        num_beams: 1,
+        temperature: 1.2,
+        device: Device::Cpu,
+        ..Default::default()
+    };
+    let model = TextGenerationModel::new(generate_config)?;
+
+    let input_context = "The dog";
+    let generate_options = GenerateOptions {
+        num_beams: Some(5),
+        num_return_sequences: Some(3),
+        ..Default::default()
+    };
+    let output = model.generate_with_options(&[input_context], None, Some(generate_options));
+
+    assert_eq!(output.len(), 3);
+    assert_eq!(
+        output[0],
+        "The dog was found in the backyard of a home in the 6200 block of South Main Street."
+    );
+    assert_eq!(
+        output[1],
+        "The dog was found in the backyard of a home in the 6500 block of South Main Street."
+    );
+    assert_eq!(
+        output[2],
+        "The dog was found in the backyard of a home in the 6200 block of South Main Street,"
+    );
+
+    //    The pipeline configuration applies to the following calls
+    let output = model.generate(&[input_context], None);
+    assert_eq!(output.len(), 1);
+
+    Ok(())
+}
+
 #[test]
 fn gpt2_generation_beam_search_multiple_prompts_without_padding() -> anyhow::Result<()> {
     //    Resources definition
@@ -475,6 +573,113 @@ fn gpt2_bad_tokens_greedy() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn gpt2_logits_processors_greedy() -> anyhow::Result<()> {
+    //    Resources definition
+    let generate_config = || GenerateConfig {
+        max_length: Some(36),
+        model_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+            Gpt2ModelResources::GPT2,
+        ))),
+        config_resource: Box::new(RemoteResource::from_pretrained(Gpt2ConfigResources::GPT2)),
+        vocab_resource: Box::new(RemoteResource::from_pretrained(Gpt2VocabResources::GPT2)),
+        merges_resource: Some(Box::new(RemoteResource::from_pretrained(
+            Gpt2MergesResources::GPT2,
+        ))),
+        do_sample: false,
+        num_beams: 1,
+        device: Device::Cpu,
+        ..Default::default()
+    };
+    let model = GPT2Generator::new(generate_config())?;
+
+    let bad_words = vec![" honeybees", " a writer"];
+    let bad_word_ids = model
+        .get_tokenizer()
+        .encode_list(
+            bad_words.as_slice(),
+            512,
+            &TruncationStrategy::DoNotTruncate,
+            0,
+        )
+        .into_iter()
+        .map(|tokenized_input| tokenized_input.token_ids)
+        .collect::<Vec<Vec<i64>>>();
+    let eos_token_id = model.get_tokenizer().get_eos_id().unwrap();
+
+    let processor_model = GPT2Generator::new(GenerateConfig {
+        logits_processors: vec![
+            Box::new(BadWordsLogitsProcessor::new(bad_word_ids.clone())),
+            Box::new(ForcedEosTokenLogitsProcessor {
+                token_id: eos_token_id,
+            }),
+        ],
+        ..generate_config()
+    })?;
+
+    let input_context_1 = "Hello, my name is";
+    let generate_options = GenerateOptions {
+        bad_word_ids: Some(&bad_word_ids),
+        ..Default::default()
+    };
+
+    //    The processors ban the same words as the generation options, and force EOS as last token
+    let reference_output = model.generate_indices(Some(&[input_context_1]), Some(generate_options));
+    let output = processor_model.generate_indices(Some(&[input_context_1]), None);
+
+    assert_eq!(output.len(), 1);
+    assert_eq!(output[0].indices.len(), 36);
+    assert_eq!(output[0].indices[..35], reference_output[0].indices[..35]);
+    assert_eq!(output[0].indices[35], eos_token_id);
+
+    Ok(())
+}
+
+#[test]
+fn gpt2_text_generation_logits_processors() -> anyhow::Result<()> {
+    //    Resources definition
+    let generate_config = || TextGenerationConfig {
+        model_type: ModelType::GPT2,
+        model_resource: ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+            Gpt2ModelResources::GPT2,
+        ))),
+        config_resource: Box::new(RemoteResource::from_pretrained(Gpt2ConfigResources::GPT2)),
+        vocab_resource: Box::new(RemoteResource::from_pretrained(Gpt2VocabResources::GPT2)),
+        merges_resource: Some(Box::new(RemoteResource::from_pretrained(
+            Gpt2MergesResources::GPT2,
+        ))),
+        max_length: Some(40),
+        do_sample: false,
+        num_beams: 1,
+        temperature: 1.1,
+        repetition_penalty: 1.1,
+        ..Default::default()
+    };
+    let model = TextGenerationModel::new(generate_config())?;
+    let bad_word_ids = model
+        .get_tokenizer()
+        .encode_list(&[" field"], 512, &TruncationStrategy::DoNotTruncate, 0)
+        .into_iter()
+        .map(|tokenized_input| tokenized_input.token_ids)
+        .collect::<Vec<Vec<i64>>>();
+
+    //    The processors of the pipeline configuration are passed to the generator
+    let processor_model = TextGenerationModel::new(TextGenerationConfig {
+        logits_processors: vec![Box::new(BadWordsLogitsProcessor::new(bad_word_ids))],
+        ..generate_config()
+    })?;
+
+    let input_context = "The cat";
+    let reference_output = model.generate(&[input_context], None);
+    let output = processor_model.generate(&[input_context], None);
+
+    assert!(reference_output[0].contains(" field"));
+    assert_eq!(output.len(), 1);
+    assert!(!output[0].contains(" field"));
+
+    Ok(())
+}
+
 #[test]
 fn gpt2_bad_tokens_beam_search() -> anyhow::Result<()> {
     //    Resources definition
@@ -662,6 +867,47 @@ fn gpt2_greedy_token_scores() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn gpt2_greedy_cancellation() -> anyhow::Result<()> {
+    //    Resources definition
+    let config_resource = Box::new(RemoteResource::from_pretrained(Gpt2ConfigResources::GPT2));
+    let vocab_resource = Box::new(RemoteResource::from_pretrained(Gpt2VocabResources::GPT2));
+    let merges_resource = Box::new(RemoteResource::from_pretrained(Gpt2MergesResources::GPT2));
+    let model_resource = Box::new(RemoteResource::from_pretrained(Gpt2ModelResources::GPT2));
+
+    let generate_config = GenerateConfig {
+        max_length: Some(16),
+        model_resource: ModelResource::Torch(model_resource),
+        config_resource,
+        vocab_resource,
+        merges_resource: Some(merges_resource),
+        do_sample: false,
+        num_beams: 1,
+        device: Device::Cpu,
+        ..Default::default()
+    };
+    let model = GPT2Generator::new(generate_config)?;
+
+    let input_context = "Hello, my name is";
+    let cancellation_token = Arc::new(AtomicBool::new(false));
+    let generate_options = GenerateOptions {
+        cancellation_token: Some(cancellation_token.as_ref()),
+        ..Default::default()
+    };
+
+    let output = model.generate_indices(Some(&[input_context]), Some(generate_options));
+    assert_eq!(output[0].indices.len(), 16);
+    assert!(!output[0].cancelled);
+
+    //    A generation cancelled before it starts stops after its first step
+    cancellation_token.store(true, Ordering::Relaxed);
+    let output = model.generate_indices(Some(&[input_context]), Some(generate_options));
+    assert_eq!(output[0].indices, vec![15496, 11, 616, 1438, 318, 1757]);
+    assert!(output[0].cancelled);
+
+    Ok(())
+}
+
 #[test]
 fn gpt2_beam_search_token_scores() -> anyhow::Result<()> {
     //    Resources definition
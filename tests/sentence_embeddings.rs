@@ -1,9 +1,13 @@
 use rust_bert::pipelines::keywords_extraction::{
     KeywordExtractionConfig, KeywordExtractionModel, KeywordScorerType,
 };
+use rust_bert::pipelines::sentence_embeddings::layers::{l2_normalize, Pooling, PoolingConfig};
 use rust_bert::pipelines::sentence_embeddings::{
     SentenceEmbeddingsBuilder, SentenceEmbeddingsConfig, SentenceEmbeddingsModelType,
 };
+use rust_bert::RustBertError;
+use std::convert::TryFrom;
+use tch::{Kind, Tensor};
 
 #[test]
 fn sbert_distilbert() -> anyhow::Result<()> {
@@ -83,6 +87,103 @@ fn sbert_bert_small() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn sbert_bert_small_layer_embeddings() -> anyhow::Result<()> {
+    let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL12V2)
+        .with_hidden_states()
+        .create_model()?;
+
+    let sentences = ["this is an example sentence", "each sentence is converted"];
+    let embeddings = model.encode_as_tensor(&sentences)?.embeddings;
+
+    //    The last layer gives the sentence embeddings before normalization
+    let last_layer_embeddings = model.encode_layer_as_tensor(&sentences, 12)?;
+    assert!(l2_normalize(&last_layer_embeddings).allclose(&embeddings, 1e-5, 1e-5, false));
+    let embeddings_layer = model.encode_layer(&sentences, 0)?;
+    assert_eq!(embeddings_layer.len(), 2);
+    assert_eq!(embeddings_layer[0].len(), 384);
+    assert!(matches!(
+        model.encode_layer(&sentences, 13),
+        Err(RustBertError::ValueError(_))
+    ));
+
+    let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL12V2)
+        .create_model()?;
+    assert!(matches!(
+        model.encode_layer(&sentences, 6),
+        Err(RustBertError::InvalidConfigurationError(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn sbert_bert_small_similarity_matrix() -> anyhow::Result<()> {
+    let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL12V2)
+        .create_model()?;
+
+    let sentences = [
+        "the cat sits on the mat",
+        "a cat is sitting on the mat",
+        "stock markets fell sharply today",
+    ];
+    let similarities = model.similarity_matrix(&sentences)?;
+    let embeddings = model.encode(&sentences)?;
+
+    assert_eq!(similarities.len(), 3);
+    for (i, row) in similarities.iter().enumerate() {
+        assert_eq!(row.len(), 3);
+        assert!((row[i] - 1.0).abs() < 1e-4);
+        for (j, similarity) in row.iter().enumerate() {
+            assert!((similarity - similarities[j][i]).abs() < 1e-5);
+            //    The embeddings of this model are normalized: the cosine similarity is their dot product
+            let dot_product = embeddings[i]
+                .iter()
+                .zip(embeddings[j].iter())
+                .map(|(a, b)| a * b)
+                .sum::<f32>();
+            assert!((similarity - dot_product).abs() < 1e-4);
+        }
+    }
+    assert!(similarities[0][1] > similarities[0][2]);
+
+    Ok(())
+}
+
+#[test]
+fn sbert_bert_small_without_special_tokens() -> anyhow::Result<()> {
+    let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllMiniLmL12V2)
+        .without_special_tokens()
+        .create_model()?;
+
+    //    The inputs are tokenized without special tokens, the masks only cover the input tokens
+    let sentences = ["short", "this is a much longer example sentence"];
+    let tokenized_input = model.tokenize(&sentences);
+    let tokens = model.get_tokenizer().tokenize(sentences[1]);
+    assert_eq!(tokenized_input.tokens_ids[1].size(), [tokens.len() as i64]);
+    assert_eq!(
+        tokenized_input.tokens_masks[0]
+            .sum(Kind::Int64)
+            .int64_value(&[]),
+        1
+    );
+    assert_eq!(
+        tokenized_input.tokens_masks[1]
+            .sum(Kind::Int64)
+            .int64_value(&[]),
+        tokens.len() as i64
+    );
+
+    //    The padding is excluded from the mean pooling: batched and single embeddings match
+    let embeddings = model.encode_as_tensor(&sentences)?.embeddings;
+    let single_embeddings = model.encode_as_tensor(&sentences[..1])?.embeddings;
+    assert!(embeddings
+        .get(0)
+        .allclose(&single_embeddings.get(0), 1e-4, 1e-4, false));
+
+    Ok(())
+}
+
 #[test]
 fn sbert_distilroberta() -> anyhow::Result<()> {
     let model = SentenceEmbeddingsBuilder::remote(SentenceEmbeddingsModelType::AllDistilrobertaV1)
@@ -159,6 +260,38 @@ fn sbert_t5() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn sentence_embeddings_pooling() -> anyhow::Result<()> {
+    //    Two sequences of 3 tokens with hidden size 2, the last token of the second sequence is padding
+    let token_embeddings = Tensor::from_slice(&[
+        1f32, -2.0, 3.0, 4.0, 5.0, 0.0, //
+        2.0, 2.0, 4.0, -4.0, 100.0, 100.0,
+    ])
+    .view([2, 3, 2]);
+    let attention_mask = Tensor::from_slice(&[1i64, 1, 1, 1, 1, 0]).view([2, 3]);
+    let pooling = Pooling::new(PoolingConfig {
+        word_embedding_dimension: 2,
+        pooling_mode_cls_token: true,
+        pooling_mode_max_tokens: true,
+        pooling_mode_mean_tokens: true,
+        pooling_mode_mean_sqrt_len_tokens: false,
+    });
+
+    let pooled = pooling.forward(token_embeddings, &attention_mask);
+    assert_eq!(pooled.size(), [2, 6]);
+    let pooled = Vec::<Vec<f32>>::try_from(pooled)?;
+    //    CLS token, max and mean over the unpadded tokens
+    assert_eq!(pooled[0], [1.0, -2.0, 5.0, 4.0, 3.0, 2.0 / 3.0]);
+    assert_eq!(pooled[1], [2.0, 2.0, 4.0, 2.0, 3.0, -1.0]);
+
+    let normalized =
+        Vec::<Vec<f32>>::try_from(l2_normalize(&Tensor::from_slice(&[3f32, 4.0]).view([1, 2])))?;
+    assert!((normalized[0][0] - 0.6).abs() < 1e-6);
+    assert!((normalized[0][1] - 0.8).abs() < 1e-6);
+
+    Ok(())
+}
+
 #[test]
 fn keyword_extraction_cosine_similarity() -> anyhow::Result<()> {
     let keyword_extraction_config = KeywordExtractionConfig {
@@ -210,6 +343,44 @@ fn keyword_extraction_cosine_similarity() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn keyword_extraction_single_text() -> anyhow::Result<()> {
+    let keyword_extraction_model = KeywordExtractionModel::new(Default::default())?;
+
+    let input = "Rust is a multi-paradigm, general-purpose programming language. \
+ Rust emphasizes performance, type safety, and concurrency.";
+
+    let keywords = keyword_extraction_model.extract(input, 3)?;
+
+    assert_eq!(keywords.len(), 3);
+    assert_eq!(keywords[0].0, "rust");
+    assert!(keywords[0].1 >= keywords[1].1);
+    assert!(keywords[1].1 >= keywords[2].1);
+
+    Ok(())
+}
+
+#[test]
+fn keyword_extraction_no_keywords() -> anyhow::Result<()> {
+    let keyword_extraction_model = KeywordExtractionModel::new(KeywordExtractionConfig {
+        scorer_type: KeywordScorerType::MaximalMarginRelevance,
+        ..Default::default()
+    })?;
+
+    let input = "Rust is a multi-paradigm, general-purpose programming language.";
+
+    //    No keyword is requested
+    assert!(keyword_extraction_model.extract(input, 0)?.is_empty());
+
+    //    An input of the batch contains no keyword candidate
+    let keywords = keyword_extraction_model.predict(&[input, ""])?;
+    assert_eq!(keywords.len(), 2);
+    assert!(!keywords[0].is_empty());
+    assert!(keywords[1].is_empty());
+
+    Ok(())
+}
+
 #[test]
 fn keyword_extraction_maximal_margin_relevance() -> anyhow::Result<()> {
     let keyword_extraction_config = KeywordExtractionConfig {
@@ -1,9 +1,44 @@
 use rust_bert::pipelines::common::{ModelResource, ModelType};
+use rust_bert::pipelines::sequence_classification::{
+    SequenceClassificationConfig, SequenceClassificationModel,
+};
 use rust_bert::pipelines::summarization::{SummarizationConfig, SummarizationModel};
 use rust_bert::pipelines::translation::{Language, TranslationConfig, TranslationModel};
-use rust_bert::resources::RemoteResource;
+use rust_bert::resources::{LocalResource, RemoteResource, ResourceProvider};
 use rust_bert::t5::{T5ConfigResources, T5ModelResources, T5VocabResources};
+use std::io::Write;
 use tch::Device;
+use tempfile::TempPath;
+
+/// Builds a T5-small text classifier scoring the label texts of `id2label` (e.g. `{"0": "negative", "1": "positive"}`)
+fn t5_small_classifier(
+    id2label: serde_json::Value,
+) -> anyhow::Result<(TempPath, SequenceClassificationModel)> {
+    let config_resource = RemoteResource::from_pretrained(T5ConfigResources::T5_SMALL);
+    let mut config_value: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(config_resource.get_local_path()?)?)?;
+    config_value["id2label"] = id2label;
+    let mut config_file = tempfile::NamedTempFile::new()?;
+    config_file.write_all(serde_json::to_string(&config_value)?.as_bytes())?;
+    let config_path = config_file.into_temp_path();
+
+    let model = SequenceClassificationModel::new(SequenceClassificationConfig {
+        device: Device::cuda_if_available(),
+        ..SequenceClassificationConfig::new(
+            ModelType::T5,
+            ModelResource::Torch(Box::new(RemoteResource::from_pretrained(
+                T5ModelResources::T5_SMALL,
+            ))),
+            LocalResource::from(config_path.to_path_buf()),
+            RemoteResource::from_pretrained(T5VocabResources::T5_SMALL),
+            None,
+            false,
+            None,
+            None,
+        )
+    })?;
+    Ok((config_path, model))
+}
 
 #[test]
 fn test_translation_t5() -> anyhow::Result<()> {
@@ -113,3 +148,46 @@ about exoplanets like K2-18b."];
 
     Ok(())
 }
+
+#[test]
+fn test_sequence_classification_t5_non_contiguous_label_ids() -> anyhow::Result<()> {
+    //    The label ids 2 and 5 are assigned to the model outputs 0 and 1, and kept in the labels
+    let (_config_path, model) =
+        t5_small_classifier(serde_json::json!({"2": "negative", "5": "positive"}))?;
+
+    let output = model.predict([
+        "sst2 sentence: This is a great movie, I loved every minute of it.",
+        "sst2 sentence: What a waste of time, the plot makes no sense.",
+    ]);
+    assert_eq!(output[0].text, "positive");
+    assert_eq!(output[0].id, 5);
+    assert_eq!(output[1].text, "negative");
+    assert_eq!(output[1].id, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_sequence_classification_t5() -> anyhow::Result<()> {
+    //    T5-small was trained on SST-2 with the `sst2 sentence:` prefix, generating `negative` or `positive`
+    let (_config_path, model) =
+        t5_small_classifier(serde_json::json!({"0": "negative", "1": "positive"}))?;
+
+    let output = model.predict([
+        "sst2 sentence: This is a great movie, I loved every minute of it.",
+        "sst2 sentence: What a waste of time, the plot makes no sense.",
+        "sst2 sentence: A wonderful cast and a moving story.",
+    ]);
+    assert_eq!(output.len(), 3);
+    assert_eq!(output[0].text, "positive");
+    assert_eq!(output[0].id, 1);
+    assert_eq!(output[1].text, "negative");
+    assert_eq!(output[1].id, 0);
+    assert_eq!(output[2].text, "positive");
+    for (sentence, label) in output.iter().enumerate() {
+        assert_eq!(label.sentence, sentence);
+        assert!(label.score > 0.5 && label.score <= 1.0);
+    }
+
+    Ok(())
+}